@@ -1,6 +1,7 @@
 //! Consensus protocols for multi-agent agreement
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// A vote from an agent
@@ -87,6 +88,9 @@ pub struct Consensus {
     /// Super-majority thresholds (configurable)
     #[serde(default)]
     pub supermajority_config: SuperMajorityConfig,
+    /// Per-voter weights (voters without an entry weigh 1.0)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub weights: HashMap<Uuid, f64>,
 }
 
 impl Consensus {
@@ -99,6 +103,7 @@ impl Consensus {
             decision: None,
             confidence: 0.0,
             supermajority_config: SuperMajorityConfig::default(),
+            weights: HashMap::new(),
         }
     }
 
@@ -113,14 +118,29 @@ impl Consensus {
         self.votes.push(vote);
     }
 
+    /// Set how much a voter's votes count (e.g. a more trusted model)
+    pub fn set_weight(&mut self, agent_id: Uuid, weight: f64) {
+        self.weights.insert(agent_id, weight.max(0.0));
+    }
+
+    /// Weight of a voter's votes
+    pub fn weight(&self, agent_id: Uuid) -> f64 {
+        self.weights.get(&agent_id).copied().unwrap_or(1.0)
+    }
+
     /// Evaluate the votes and determine consensus
     pub fn evaluate(&mut self) {
         if self.votes.is_empty() {
             return;
         }
 
-        let total = self.votes.len() as f64;
-        let agrees: f64 = self.votes.iter().filter(|v| v.agrees).count() as f64;
+        let total: f64 = self.votes.iter().map(|v| self.weight(v.agent_id)).sum();
+        let agrees: f64 = self
+            .votes
+            .iter()
+            .filter(|v| v.agrees)
+            .map(|v| self.weight(v.agent_id))
+            .sum();
         if total == 0.0 {
             self.reached = false;
             self.decision = None;
@@ -155,13 +175,13 @@ impl Consensus {
                     .votes
                     .iter()
                     .filter(|v| v.agrees)
-                    .map(|v| v.confidence)
+                    .map(|v| v.confidence * self.weight(v.agent_id))
                     .sum();
                 let weighted_disagree: f64 = self
                     .votes
                     .iter()
                     .filter(|v| !v.agrees)
-                    .map(|v| v.confidence)
+                    .map(|v| v.confidence * self.weight(v.agent_id))
                     .sum();
                 let total_confidence = weighted_agree + weighted_disagree;
 
@@ -179,7 +199,12 @@ impl Consensus {
         if total == 0.0 {
             self.confidence = 0.0;
         } else {
-            self.confidence = self.votes.iter().map(|v| v.confidence).sum::<f64>() / total;
+            self.confidence = self
+                .votes
+                .iter()
+                .map(|v| v.confidence * self.weight(v.agent_id))
+                .sum::<f64>()
+                / total;
         }
     }
 }
@@ -310,4 +335,33 @@ mod tests {
         assert!(!consensus.reached);
         assert_eq!(consensus.decision, None);
     }
+
+    #[test]
+    fn test_weighted_voters() {
+        let trusted = Uuid::new_v4();
+        let mut consensus = Consensus::new(ConsensusProtocol::Majority);
+        consensus.set_weight(trusted, 3.0);
+
+        consensus.add_vote(Vote {
+            agent_id: trusted,
+            agrees: true,
+            confidence: 0.9,
+            reasoning: None,
+        });
+        for _ in 0..2 {
+            consensus.add_vote(Vote {
+                agent_id: Uuid::new_v4(),
+                agrees: false,
+                confidence: 0.6,
+                reasoning: None,
+            });
+        }
+
+        consensus.evaluate();
+
+        // 3.0 agreeing weight outvotes 2.0 disagreeing
+        assert!(consensus.reached);
+        assert_eq!(consensus.decision, Some(true));
+        assert!((consensus.confidence - (0.9 * 3.0 + 0.6 * 2.0) / 5.0).abs() < 1e-9);
+    }
 }
@@ -16,6 +16,23 @@ pub struct DebateRound {
     pub blue_rebuttal: Option<String>,
 }
 
+/// A vote cast by one model on a provider panel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelVote {
+    /// Debate round the vote was cast in
+    pub round: u32,
+    /// Provider name (e.g. "ollama", "openai")
+    pub provider: String,
+    /// Model that produced the vote
+    pub model: String,
+    /// Whether the model agreed with the claim
+    pub agrees: bool,
+    /// Model's confidence (0.0 - 1.0)
+    pub confidence: f64,
+    /// Weight of the vote in consensus
+    pub weight: f64,
+}
+
 /// A complete debate between Blue and Red agents.
 ///
 /// This is a **data-only** struct that records debate rounds, verdicts, and confidence.
@@ -37,6 +54,9 @@ pub struct Debate {
     pub verdict: Option<bool>,
     /// Confidence in the verdict (0.0 - 1.0)
     pub confidence: f64,
+    /// Per-model votes when the claim was checked by a provider panel
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub model_votes: Vec<ModelVote>,
 }
 
 impl Debate {
@@ -50,6 +70,7 @@ impl Debate {
             rounds: Vec::new(),
             verdict: None,
             confidence: 0.0,
+            model_votes: Vec::new(),
         }
     }
 
@@ -73,6 +94,16 @@ impl Debate {
     pub fn round_count(&self) -> usize {
         self.rounds.len()
     }
+
+    /// Models that agreed with the claim in the final panel round
+    pub fn agreeing_models(&self) -> Vec<&str> {
+        let last_round = self.model_votes.iter().map(|v| v.round).max();
+        self.model_votes
+            .iter()
+            .filter(|v| Some(v.round) == last_round && v.agrees)
+            .map(|v| v.model.as_str())
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -98,4 +129,25 @@ mod tests {
         assert!(debate.is_concluded());
         assert_eq!(debate.verdict, Some(true));
     }
+
+    #[test]
+    fn test_agreeing_models() {
+        let mut debate = Debate::new(Uuid::new_v4(), Uuid::new_v4(), "2 + 2 = 4");
+        let vote = |round, model: &str, agrees| ModelVote {
+            round,
+            provider: "mock".to_string(),
+            model: model.to_string(),
+            agrees,
+            confidence: 0.9,
+            weight: 1.0,
+        };
+        debate.model_votes = vec![
+            vote(1, "llama3", false),
+            vote(1, "gpt-4o", true),
+            vote(2, "llama3", true),
+            vote(2, "gpt-4o", true),
+        ];
+
+        assert_eq!(debate.agreeing_models(), vec!["llama3", "gpt-4o"]);
+    }
 }
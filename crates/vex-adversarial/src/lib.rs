@@ -48,6 +48,6 @@ pub mod reflection;
 pub mod shadow;
 
 pub use consensus::{Consensus, ConsensusProtocol, SuperMajorityConfig, Vote};
-pub use debate::{Debate, DebateRound};
+pub use debate::{Debate, DebateRound, ModelVote};
 pub use reflection::{ReflectionAgent, ReflectionConfig, ReflectionResult};
 pub use shadow::{ShadowAgent, ShadowConfig};
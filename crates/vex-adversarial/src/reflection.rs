@@ -5,10 +5,11 @@
 
 use std::sync::Arc;
 
+use serde_json::json;
 use vex_core::{
     Agent, EvolutionMemory, Genome, GenomeExperiment, OptimizationRule, TraitAdjustment,
 };
-use vex_llm::LlmProvider;
+use vex_llm::{LlmProvider, LlmRequest, ResponseFormat, StructuredOutput};
 
 /// Result of reflection analysis
 #[derive(Debug, Clone)]
@@ -55,6 +56,65 @@ struct ExtractedRule {
     confidence: f64,
 }
 
+#[derive(Debug, serde::Deserialize)]
+struct LlmAdjustment {
+    #[serde(rename = "trait")]
+    trait_name: String,
+    delta: f64,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct LlmAdjustments {
+    adjustments: Vec<LlmAdjustment>,
+    #[serde(default)]
+    reasoning: String,
+}
+
+/// Schema for LLM trait adjustment suggestions
+fn adjustments_format() -> ResponseFormat {
+    ResponseFormat::new(
+        "trait_adjustments",
+        json!({
+            "type": "object",
+            "properties": {
+                "adjustments": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "trait": { "type": "string" },
+                            "delta": { "type": "number", "minimum": -1, "maximum": 1 },
+                            "reasoning": { "type": "string" }
+                        },
+                        "required": ["trait", "delta"]
+                    }
+                },
+                "reasoning": { "type": "string" }
+            },
+            "required": ["adjustments"]
+        }),
+    )
+}
+
+/// Schema for consolidated optimization rules
+fn rules_format() -> ResponseFormat {
+    ResponseFormat::new(
+        "optimization_rules",
+        json!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "rule": { "type": "string" },
+                    "traits": { "type": "array", "items": { "type": "string" } },
+                    "confidence": { "type": "number", "minimum": 0, "maximum": 1 }
+                },
+                "required": ["rule", "traits", "confidence"]
+            }
+        }),
+    )
+}
+
 impl Default for ReflectionConfig {
     fn default() -> Self {
         Self {
@@ -206,49 +266,19 @@ INSTRUCTIONS:
                 .join("\n")
         );
 
-        let llm_response = self.llm.ask(&prompt).await.map_err(|e| e.to_string())?;
-
-        // Parse JSON response
-        let adjustments = self.parse_llm_response(&llm_response);
+        let structured = StructuredOutput::new(adjustments_format())
+            .complete::<LlmAdjustments, _>(self.llm.as_ref(), LlmRequest::simple(&prompt))
+            .await
+            .map_err(|e| e.to_string())?;
 
-        Ok((adjustments, llm_response))
-    }
-
-    /// Parse JSON response into trait adjustments
-    fn parse_llm_response(&self, response: &str) -> Vec<(String, f64)> {
-        #[derive(Debug, serde::Serialize, serde::Deserialize)]
-        struct LlmAdjustment {
-            #[serde(rename = "trait")]
-            trait_name: String,
-            delta: f64,
-        }
-        #[derive(serde::Deserialize)]
-        struct LlmResponse {
-            adjustments: Vec<LlmAdjustment>,
-        }
-
-        // Find JSON block if it's wrapped in other text
-        let json_str = if let Some(start) = response.find('{') {
-            if let Some(end) = response.rfind('}') {
-                &response[start..=end]
-            } else {
-                response
-            }
-        } else {
-            response
-        };
+        let adjustments = structured
+            .value
+            .adjustments
+            .into_iter()
+            .map(|a| (a.trait_name, a.delta))
+            .collect();
 
-        match serde_json::from_str::<LlmResponse>(json_str) {
-            Ok(res) => res
-                .adjustments
-                .into_iter()
-                .map(|a| (a.trait_name, a.delta))
-                .collect(),
-            Err(e) => {
-                tracing::warn!("Failed to parse JSON adjustments: {}", e);
-                Vec::new()
-            }
-        }
+        Ok((adjustments, structured.value.reasoning))
     }
 
     /// Merge statistical and LLM suggestions
@@ -336,8 +366,19 @@ If no clear patterns, return empty list [].
             summaries.join("\n")
         );
 
-        match self.llm.ask(&prompt).await {
-            Ok(response) => Ok(self.parse_consolidation_response(&response, experiments.len())),
+        match StructuredOutput::new(rules_format())
+            .complete::<Vec<ExtractedRule>, _>(self.llm.as_ref(), LlmRequest::simple(&prompt))
+            .await
+        {
+            Ok(structured) => Ok(structured
+                .value
+                .into_iter()
+                .map(|r| OptimizationRule::new(r.rule, r.traits, r.confidence, experiments.len()))
+                .collect()),
+            Err(vex_llm::StructuredError::Invalid { errors, .. }) => {
+                tracing::warn!("Failed to parse rules from LLM: {}", errors.join("; "));
+                Ok(Vec::new())
+            }
             Err(e) => Err(format!("LLM request failed: {}", e)),
         }
     }
@@ -354,34 +395,6 @@ If no clear patterns, return empty list [].
     }
 }
 
-impl<L: LlmProvider + ?Sized> ReflectionAgent<L> {
-    // ... (rest of impl)
-
-    fn parse_consolidation_response(&self, response: &str, count: usize) -> Vec<OptimizationRule> {
-        // Extract JSON block if needed
-        let json_str = if let Some(start) = response.find('[') {
-            if let Some(end) = response.rfind(']') {
-                &response[start..=end]
-            } else {
-                response
-            }
-        } else {
-            response
-        };
-
-        match serde_json::from_str::<Vec<ExtractedRule>>(json_str) {
-            Ok(extracted) => extracted
-                .into_iter()
-                .map(|r| OptimizationRule::new(r.rule, r.traits, r.confidence, count))
-                .collect(),
-            Err(e) => {
-                tracing::warn!("Failed to parse rules from LLM: {}", e);
-                Vec::new()
-            }
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -395,19 +408,24 @@ mod tests {
         assert_eq!(result.expected_improvement, 0.0);
     }
 
-    #[test]
-    fn test_parse_llm_response() {
-        let llm = Arc::new(MockProvider::new(vec!["mock response".to_string()]));
-        let agent = ReflectionAgent::new(llm);
-
-        let response = r#"{
+    #[tokio::test]
+    async fn test_parse_llm_response() {
+        let llm = Arc::new(MockProvider::new(vec![r#"Here are my suggestions: {
             "adjustments": [
                 { "trait": "exploration", "delta": 0.1, "reasoning": "more creative" },
                 { "trait": "precision", "delta": -0.05, "reasoning": "too focused" }
-            ]
-        }"#;
-        let adjustments = agent.parse_llm_response(response);
+            ],
+            "reasoning": "balance"
+        }"#
+        .to_string()]));
+        let agent = ReflectionAgent::new(llm);
+
+        let (adjustments, reasoning) = agent
+            .get_llm_suggestions(&Agent::new(Default::default()), "task", "answer", 0.5, &[])
+            .await
+            .unwrap();
 
+        assert_eq!(reasoning, "balance");
         assert_eq!(adjustments.len(), 2);
         assert!(adjustments
             .iter()
@@ -417,15 +435,22 @@ mod tests {
             .any(|(n, d)| n == "precision" && *d == -0.05));
     }
 
-    #[test]
-    fn test_parse_no_changes() {
-        let llm = Arc::new(MockProvider::new(vec!["mock response".to_string()]));
+    #[tokio::test]
+    async fn test_parse_no_changes() {
+        // The first reply is rejected by the schema and repaired
+        let llm = Arc::new(MockProvider::new(vec![
+            r#"{ "adjustments": "none" }"#.to_string(),
+            r#"{ "adjustments": [], "reasoning": "optimal" }"#.to_string(),
+        ]));
         let agent = ReflectionAgent::new(llm);
 
-        let response = r#"{ "adjustments": [], "reasoning": "optimal" }"#;
-        let adjustments = agent.parse_llm_response(response);
+        let (adjustments, reasoning) = agent
+            .get_llm_suggestions(&Agent::new(Default::default()), "task", "answer", 0.9, &[])
+            .await
+            .unwrap();
 
         assert!(adjustments.is_empty());
+        assert_eq!(reasoning, "optimal");
     }
 
     #[tokio::test]
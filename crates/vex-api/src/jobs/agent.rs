@@ -10,6 +10,9 @@ use vex_llm::LlmProvider;
 use vex_persist::StorageBackend;
 use vex_queue::job::BackoffStrategy;
 use vex_queue::{Job, JobResult};
+use vex_runtime::RunContext;
+
+use super::stream::JobStreams;
 
 /// Payload for agent execution job
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +29,9 @@ pub struct AgentJobPayload {
     pub tenant_id: Option<String>,
     #[serde(default)]
     pub capabilities: Vec<vex_llm::Capability>,
+    /// Job stream the execution's token deltas are forwarded to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_id: Option<Uuid>,
 }
 
 fn default_max_rounds() -> u32 {
@@ -58,6 +64,9 @@ pub struct AgentJobResult {
     pub new_context_id: Option<String>,
     /// CHORA Evidence Capsule for this execution
     pub evidence: Option<vex_core::audit::EvidenceCapsule>,
+    /// Escalation the execution is suspended on, awaiting human approval
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalation_id: Option<String>,
 }
 
 /// Shared storage for job results
@@ -79,6 +88,7 @@ pub struct AgentExecutionJob {
     pub evolution_store: Arc<dyn vex_persist::EvolutionStore>,
     pub gate: Arc<dyn vex_runtime::Gate>,
     pub orchestrator: Arc<vex_runtime::Orchestrator<dyn vex_llm::LlmProvider>>,
+    pub streams: Option<JobStreams>,
 }
 
 impl AgentExecutionJob {
@@ -104,8 +114,15 @@ impl AgentExecutionJob {
             evolution_store,
             gate,
             orchestrator,
+            streams: None,
         }
     }
+
+    /// Forward token deltas to the payload's job stream while running
+    pub fn with_streams(mut self, streams: JobStreams) -> Self {
+        self.streams = Some(streams);
+        self
+    }
 }
 
 #[async_trait]
@@ -123,18 +140,46 @@ impl Job for AgentExecutionJob {
 
         let tenant_id = self.payload.tenant_id.as_deref().unwrap_or("default");
 
+        let stream = self
+            .payload
+            .stream_id
+            .zip(self.streams.as_ref())
+            .and_then(|(stream_id, streams)| streams.sender(stream_id));
+
         // Use the unified Orchestrator for full cognitive cycle (includes Hardware Signing)
-        let orchestration_result = match self
-            .orchestrator
-            .process(
-                tenant_id,
-                &self.payload.prompt,
-                None, // Initial intent data usually None for jobs
-                self.payload.capabilities.clone(),
-            )
-            .await
-        {
+        let orchestration_result = {
+            let ctx = match stream {
+                Some(sink) => RunContext::new().with_stream(sink),
+                None => RunContext::new(),
+            };
+            self.orchestrator
+                .process_with_context(
+                    &ctx,
+                    tenant_id,
+                    &self.payload.prompt,
+                    None, // Initial intent data usually None for jobs
+                    self.payload.capabilities.clone(),
+                )
+                .await
+        };
+        // Subscribers fall back to status polling (a retry does not stream)
+        if let (Some(stream_id), Some(streams)) = (self.payload.stream_id, &self.streams) {
+            streams.close(stream_id);
+        }
+
+        let orchestration_result = match orchestration_result {
             Ok(res) => res,
+            Err(e) if vex_runtime::suspended_escalation_id(&e).is_some() => {
+                info!(job_id = %self.job_id, "{}", e);
+                return store_suspended(
+                    &self.result_store,
+                    self.job_id,
+                    self.payload.agent_id.clone(),
+                    self.payload.prompt.clone(),
+                    e,
+                )
+                .await;
+            }
             Err(e) => {
                 error!(job_id = %self.job_id, error = %e, "Orchestrator execution failed");
                 return store_error(
@@ -164,6 +209,7 @@ impl Job for AgentExecutionJob {
             merkle_root: Some(hex::encode(orchestration_result.merkle_root.0)),
             new_context_id: None,
             evidence: None, // Evidence is stored in audit logs
+            escalation_id: None,
         };
 
         self.result_store
@@ -185,6 +231,105 @@ impl Job for AgentExecutionJob {
     }
 }
 
+/// Payload for resuming an execution suspended on an escalation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentResumePayload {
+    pub tenant_id: String,
+    pub escalation_id: String,
+    pub continuation_token: vex_core::ContinuationToken,
+}
+
+/// Resumes a suspended execution once its escalation has been resolved
+#[derive(Debug)]
+pub struct AgentResumeJob {
+    pub job_id: Uuid,
+    /// Raw payload, parsed as [`AgentResumePayload`] when the job runs
+    pub payload: serde_json::Value,
+    pub result_store: JobResultStore,
+    pub orchestrator: Arc<vex_runtime::Orchestrator<dyn vex_llm::LlmProvider>>,
+}
+
+impl AgentResumeJob {
+    pub fn new(
+        job_id: Uuid,
+        payload: serde_json::Value,
+        result_store: JobResultStore,
+        orchestrator: Arc<vex_runtime::Orchestrator<dyn vex_llm::LlmProvider>>,
+    ) -> Self {
+        Self {
+            job_id,
+            payload,
+            result_store,
+            orchestrator,
+        }
+    }
+}
+
+#[async_trait]
+impl Job for AgentResumeJob {
+    fn name(&self) -> &str {
+        "agent_resume"
+    }
+
+    async fn execute(&mut self) -> JobResult {
+        let payload: AgentResumePayload = match serde_json::from_value(self.payload.clone()) {
+            Ok(payload) => payload,
+            Err(e) => return JobResult::Fatal(format!("Invalid resume payload: {}", e)),
+        };
+        info!(
+            job_id = %self.job_id,
+            escalation_id = %payload.escalation_id,
+            "Resuming suspended VEX agent execution"
+        );
+
+        let resumed = self
+            .orchestrator
+            .resume(
+                &payload.tenant_id,
+                &payload.escalation_id,
+                payload.continuation_token,
+            )
+            .await;
+        let execution = match resumed {
+            Ok(execution) => execution,
+            Err(e) => {
+                // A rejected token will not become valid on retry
+                error!(job_id = %self.job_id, error = %e, "Resume failed");
+                return JobResult::Fatal(e);
+            }
+        };
+
+        let result = AgentJobResult {
+            job_id: self.job_id,
+            agent_id: execution.agent_id.to_string(),
+            prompt: String::new(),
+            response: execution.response,
+            tokens_used: None,
+            completed_at: Utc::now(),
+            success: true,
+            error: None,
+            verified: execution.verified,
+            confidence: execution.confidence,
+            context_hash: Some(execution.context.hash.to_hex()),
+            debate_rounds: execution
+                .debate
+                .as_ref()
+                .map_or(0, |d| d.rounds.len() as u32),
+            merkle_root: None,
+            new_context_id: Some(execution.context.id.to_string()),
+            evidence: execution.evidence,
+            escalation_id: Some(payload.escalation_id),
+        };
+
+        self.result_store
+            .write()
+            .await
+            .insert(self.job_id, result.clone());
+
+        JobResult::Success(Some(serde_json::to_value(&result).unwrap()))
+    }
+}
+
 // ── Helpers ────────────────────────────────────────────────────────────────
 
 async fn store_error(
@@ -210,7 +355,39 @@ async fn store_error(
         merkle_root: None,
         new_context_id: None,
         evidence: None,
+        escalation_id: None,
     };
     result_store.write().await.insert(job_id, result);
     JobResult::Retry(error)
 }
+
+/// Record a suspended execution; it is resumed by an `agent_resume` job, not retried
+async fn store_suspended(
+    result_store: &JobResultStore,
+    job_id: Uuid,
+    agent_id: String,
+    prompt: String,
+    error: String,
+) -> JobResult {
+    let result = AgentJobResult {
+        job_id,
+        agent_id,
+        prompt,
+        response: String::new(),
+        tokens_used: None,
+        completed_at: Utc::now(),
+        success: false,
+        escalation_id: vex_runtime::suspended_escalation_id(&error).map(str::to_string),
+        error: Some(error),
+        verified: false,
+        confidence: 0.0,
+        context_hash: None,
+        debate_rounds: 0,
+        merkle_root: None,
+        new_context_id: None,
+        evidence: None,
+    };
+    let value = serde_json::to_value(&result).ok();
+    result_store.write().await.insert(job_id, result);
+    JobResult::Success(value)
+}
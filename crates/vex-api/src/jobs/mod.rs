@@ -1,5 +1,8 @@
 pub mod agent;
+pub mod stream;
 
 pub use agent::{
-    new_result_store, AgentExecutionJob, AgentJobPayload, AgentJobResult, JobResultStore,
+    new_result_store, AgentExecutionJob, AgentJobPayload, AgentJobResult, AgentResumeJob,
+    AgentResumePayload, JobResultStore,
 };
+pub use stream::JobStreams;
@@ -0,0 +1,180 @@
+//! Live token streams for running jobs
+//!
+//! A stream is opened under a fresh stream ID before its job is enqueued (the
+//! queue assigns the job ID, and the worker may pick the job up before the
+//! enqueue call returns), then linked to the job ID for subscribers. The job
+//! claims the stream when it starts and holds it while it runs; once it
+//! finishes the stream closes and `/api/v1/jobs/{id}/stream` falls back to
+//! status polling. Streams no job claims within the TTL (the job was lost or is
+//! still queued) are dropped the next time a stream is opened.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+use vex_llm::StreamEvent;
+
+/// Events buffered per stream before slow subscribers start skipping
+const STREAM_CAPACITY: usize = 256;
+
+/// How long a stream waits for its job to start before it is dropped
+const DEFAULT_STREAM_TTL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug)]
+struct OpenStream {
+    tx: broadcast::Sender<StreamEvent>,
+    opened_at: Instant,
+    claimed: bool,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    streams: HashMap<Uuid, OpenStream>,
+    jobs: HashMap<Uuid, Uuid>,
+}
+
+/// Registry of open job streams
+#[derive(Debug, Clone)]
+pub struct JobStreams {
+    inner: Arc<Mutex<Registry>>,
+    ttl: Duration,
+}
+
+impl Default for JobStreams {
+    fn default() -> Self {
+        Self {
+            inner: Arc::default(),
+            ttl: DEFAULT_STREAM_TTL,
+        }
+    }
+}
+
+impl JobStreams {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop streams whose job has not started within `ttl`
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Open a stream, returning its ID
+    pub fn open(&self) -> Uuid {
+        let stream_id = Uuid::new_v4();
+        let (tx, _) = broadcast::channel(STREAM_CAPACITY);
+        let mut registry = self.lock();
+        let expired: Vec<Uuid> = registry
+            .streams
+            .iter()
+            .filter(|(_, s)| !s.claimed && s.opened_at.elapsed() >= self.ttl)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in expired {
+            registry.remove(id);
+        }
+        registry.streams.insert(
+            stream_id,
+            OpenStream {
+                tx,
+                opened_at: Instant::now(),
+                claimed: false,
+            },
+        );
+        stream_id
+    }
+
+    /// Make a stream reachable by its job's ID
+    pub fn link(&self, job_id: Uuid, stream_id: Uuid) {
+        let mut registry = self.lock();
+        if registry.streams.contains_key(&stream_id) {
+            registry.jobs.insert(job_id, stream_id);
+        }
+    }
+
+    /// Claim an open stream for the job publishing to it, returning its sender
+    pub fn sender(&self, stream_id: Uuid) -> Option<broadcast::Sender<StreamEvent>> {
+        let mut registry = self.lock();
+        let stream = registry.streams.get_mut(&stream_id)?;
+        stream.claimed = true;
+        Some(stream.tx.clone())
+    }
+
+    /// Subscribe to a job's stream, if it is still open
+    pub fn subscribe(&self, job_id: Uuid) -> Option<broadcast::Receiver<StreamEvent>> {
+        let registry = self.lock();
+        let stream_id = registry.jobs.get(&job_id)?;
+        registry.streams.get(stream_id).map(|s| s.tx.subscribe())
+    }
+
+    /// Close a stream; subscribers see the end once the job drops its sender
+    pub fn close(&self, stream_id: Uuid) {
+        self.lock().remove(stream_id);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Registry> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Registry {
+    fn remove(&mut self, stream_id: Uuid) {
+        self.streams.remove(&stream_id);
+        self.jobs.retain(|_, id| *id != stream_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_streams_lifecycle() {
+        let streams = JobStreams::new();
+        let stream_id = streams.open();
+        let job_id = Uuid::new_v4();
+        assert!(streams.subscribe(job_id).is_none());
+
+        streams.link(job_id, stream_id);
+        let mut rx = streams.subscribe(job_id).unwrap();
+        let tx = streams.sender(stream_id).unwrap();
+        tx.send(StreamEvent::Delta {
+            text: "hi".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            StreamEvent::Delta {
+                text: "hi".to_string()
+            }
+        );
+
+        streams.close(stream_id);
+        drop(tx);
+        assert!(streams.subscribe(job_id).is_none());
+        assert!(streams.sender(stream_id).is_none());
+        assert!(matches!(
+            rx.try_recv(),
+            Err(broadcast::error::TryRecvError::Closed)
+        ));
+    }
+
+    #[test]
+    fn test_unclaimed_streams_expire() {
+        let streams = JobStreams::new().with_ttl(Duration::ZERO);
+        let lost = streams.open();
+        let lost_job = Uuid::new_v4();
+        streams.link(lost_job, lost);
+        let running = streams.open();
+        let _tx = streams.sender(running).unwrap();
+
+        // Opening another stream sweeps the unclaimed one but keeps the running one
+        streams.open();
+        assert!(streams.subscribe(lost_job).is_none());
+        assert!(streams.sender(lost).is_none());
+        assert!(streams.sender(running).is_some());
+    }
+}
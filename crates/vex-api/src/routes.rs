@@ -6,7 +6,7 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
-use futures::stream::{self, Stream};
+use futures::stream::{self, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
 use uuid::Uuid;
@@ -438,6 +438,9 @@ pub async fn execute_agent(
         }
     }
 
+    // Token deltas are published here while the job runs
+    let stream_id = state.job_streams().open();
+
     // Create job payload with sanitized prompt and adversarial config
     let payload = serde_json::json!({
         "agent_id": agent_id,
@@ -452,6 +455,7 @@ pub async fn execute_agent(
         "witness_receipt": witness_receipt,
         "authority_data": capsule.authority,
         "witness_data": capsule.witness,
+        "stream_id": stream_id,
     });
 
     // Enqueue job with explicit type checks
@@ -461,10 +465,17 @@ pub async fn execute_agent(
     // For dynamic dispatch, we access the backend. It's Arc<dyn QueueBackend>.
     let backend = &pool.backend;
 
-    let job_id = backend
+    let job_id = match backend
         .enqueue(&claims.sub, "agent_execution", payload, None)
         .await
-        .map_err(|e| ApiError::Internal(format!("Queue error: {}", e)))?;
+    {
+        Ok(job_id) => job_id,
+        Err(e) => {
+            state.job_streams().close(stream_id);
+            return Err(ApiError::Internal(format!("Queue error: {}", e)));
+        }
+    };
+    state.job_streams().link(job_id, stream_id);
 
     // Record metrics
     state.metrics().record_llm_call(0, false); // Just counting requests for now
@@ -551,6 +562,11 @@ pub struct JobUpdate {
 }
 
 /// SSE Stream handler for job updates
+///
+/// While an agent job runs, the root agent's answer is streamed as `token` events
+/// (one [`vex_llm::StreamEvent`] each, ending with a single `finish`); status
+/// updates are polled after the token stream ends, or straight away for jobs that
+/// are not streaming.
 #[utoipa::path(
     get,
     path = "/api/v1/jobs/{id}/stream",
@@ -558,7 +574,7 @@ pub struct JobUpdate {
         ("id" = Uuid, Path, description = "Job ID")
     ),
     responses(
-        (status = 200, description = "SSE stream of job updates")
+        (status = 200, description = "SSE stream of token deltas and job updates")
     ),
     security(
         ("jwt" = [])
@@ -576,6 +592,28 @@ pub async fn get_job_stream(
         .to_string();
     let backend = state.queue().backend.clone();
 
+    let tokens = match backend.get_job(&tenant_id, job_id).await {
+        Ok(_) => state.job_streams().subscribe(job_id),
+        Err(_) => None,
+    };
+    let tokens = stream::unfold(tokens, |rx| async move {
+        let mut rx = rx?;
+        loop {
+            match rx.recv().await {
+                Ok(token) => {
+                    let event = ax_sse::Event::default()
+                        .event("token")
+                        .json_data(&token)
+                        .unwrap_or_else(|_| ax_sse::Event::default().data("error"));
+                    return Some((Ok(event), Some(rx)));
+                }
+                // Slow client: skip ahead (the final result has the full answer)
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
     let stream = stream::unfold(
         (backend, tenant_id, job_id, false),
         |(backend, tid, jid, finished)| async move {
@@ -626,7 +664,7 @@ pub async fn get_job_stream(
         },
     );
 
-    ax_sse::Sse::new(stream).keep_alive(ax_sse::KeepAlive::default())
+    ax_sse::Sse::new(tokens.chain(stream)).keep_alive(ax_sse::KeepAlive::default())
 }
 
 /// Metrics response
@@ -884,12 +922,30 @@ pub async fn get_prometheus_metrics(
     Ok(snapshot.to_prometheus())
 }
 
+/// Reviewer decision on an escalated action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EscalationDecision {
+    /// Resume the suspended execution
+    Approve,
+    /// Discard the suspended execution
+    Deny,
+}
+
 /// Escalation resolution request
 #[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct ResolveEscalationRequest {
     pub escalation_id: String,
     pub resolution_vep_hash: String,
     pub rationale: String,
+    /// Approve or deny an execution suspended on the escalation. Without a decision
+    /// or a token the escalation is closed and the execution stays suspended.
+    #[serde(default)]
+    pub decision: Option<EscalationDecision>,
+    /// Token approving the escalated action (implies approval); with `approve` and
+    /// no token, the one recorded on the coordination record is used.
+    #[serde(default)]
+    pub continuation_token: Option<ContinuationToken>,
 }
 
 /// List active escalations handler
@@ -939,6 +995,11 @@ pub async fn resolve_escalation(
     if !claims.has_role("user") {
         return Err(ApiError::Forbidden("Reviewer access required".to_string()));
     }
+    if req.decision == Some(EscalationDecision::Deny) && req.continuation_token.is_some() {
+        return Err(ApiError::BadRequest(
+            "A denied escalation cannot carry a continuation token".to_string(),
+        ));
+    }
 
     // 1. Log the HumanOverride event in AuditStore
     // This will trigger the Auto-Resolve logic in AuditStore::log
@@ -947,6 +1008,12 @@ pub async fn resolve_escalation(
     let data = serde_json::json!({
         "resolves_escalation_id": req.escalation_id,
         "rationale": req.rationale,
+        "decision": match req.decision {
+            Some(EscalationDecision::Approve) => "approve",
+            Some(EscalationDecision::Deny) => "deny",
+            None if req.continuation_token.is_some() => "approve",
+            None => "none",
+        },
     });
 
     // Log the resolution event
@@ -972,11 +1039,85 @@ pub async fn resolve_escalation(
         capsule.resolution_vep_hash = Some(req.resolution_vep_hash.clone());
     }
 
+    // 2. Close the escalation in the Coordination Ledger
+    let coordination = PersistentCoordinationStore::new(state.db());
+    let record = coordination
+        .get_record(&claims.sub, &req.escalation_id)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Coordination Ledger error: {}", e)))?;
+    if record.is_some() {
+        coordination
+            .resolve_escalation(
+                &claims.sub,
+                &req.escalation_id,
+                event.id,
+                req.resolution_vep_hash.clone(),
+            )
+            .await
+            .map_err(|e| ApiError::Internal(format!("Coordination Ledger error: {}", e)))?;
+    }
+
+    // 3. Resume (or discard) an execution suspended on this escalation, only on an
+    // explicit reviewer decision
+    let suspensions = vex_runtime::SuspensionStore::new(state.db());
+    if req.decision == Some(EscalationDecision::Deny) {
+        let discarded = suspensions
+            .claim(&claims.sub, &req.escalation_id)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Suspension store error: {}", e)))?;
+        return Ok(Json(serde_json::json!({
+            "status": "denied",
+            "event_id": event.id,
+            "escalation_id": req.escalation_id,
+            "resolution_vep_hash": req.resolution_vep_hash,
+            "discarded_execution": discarded.is_some(),
+        })));
+    }
+    let token = match (req.continuation_token, req.decision) {
+        (Some(token), _) => Some(token),
+        (None, Some(EscalationDecision::Approve)) => record.and_then(|r| r.continuation_token),
+        (None, _) => None,
+    };
+    let suspended = suspensions
+        .get(&claims.sub, &req.escalation_id)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Suspension store error: {}", e)))?;
+    let resume_job_id = match (token, suspended) {
+        (Some(token), Some(_)) => {
+            let verified = state
+                .bridge()
+                .verify_continuation_token(&token, None, None, None)
+                .await
+                .map_err(|e| ApiError::Internal(format!("Token Verification failed: {}", e)))?;
+            if !verified {
+                return Err(ApiError::Forbidden(
+                    "Invalid or forged continuation token".to_string(),
+                ));
+            }
+
+            let payload = serde_json::to_value(crate::jobs::AgentResumePayload {
+                tenant_id: claims.sub.clone(),
+                escalation_id: req.escalation_id.clone(),
+                continuation_token: token,
+            })
+            .map_err(|e| ApiError::Internal(format!("Serialization error: {}", e)))?;
+            let job_id = state
+                .queue()
+                .backend
+                .enqueue(&claims.sub, "agent_resume", payload, None)
+                .await
+                .map_err(|e| ApiError::Internal(format!("Queue error: {}", e)))?;
+            Some(job_id)
+        }
+        _ => None,
+    };
+
     Ok(Json(serde_json::json!({
         "status": "resolved",
         "event_id": event.id,
         "escalation_id": req.escalation_id,
-        "resolution_vep_hash": req.resolution_vep_hash
+        "resolution_vep_hash": req.resolution_vep_hash,
+        "resume_job_id": resume_job_id,
     })))
 }
 
@@ -1019,6 +1160,7 @@ pub async fn resolve_escalation(
             CoordinationRecord,
             CoordinationStatus,
             ResolveEscalationRequest,
+            EscalationDecision,
         )
     ),
     modifiers(&SecurityAddon)
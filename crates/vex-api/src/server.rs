@@ -116,7 +116,7 @@ pub struct VexServer {
 impl VexServer {
     /// Create a new server
     pub async fn new(config: ServerConfig) -> Result<Self, ApiError> {
-        use crate::jobs::agent::{AgentExecutionJob, AgentJobPayload};
+        use crate::jobs::agent::{AgentExecutionJob, AgentJobPayload, AgentResumeJob};
         use crate::tenant_rate_limiter::{RateLimitTier, TenantRateLimiter};
         use vex_llm::{
             CachedProvider, DeepSeekProvider, LlmProvider, MockProvider, ResilientProvider,
@@ -209,6 +209,7 @@ impl VexServer {
 
         // Create shared result store for job results
         let result_store = crate::jobs::new_result_store();
+        let job_streams = crate::jobs::JobStreams::new();
 
         // --- Phase 3: Hardware-Rooted Trust Layer ---
         let hardware_keystore = vex_hardware::api::HardwareKeystore::new()
@@ -250,12 +251,14 @@ impl VexServer {
             vex_runtime::OrchestratorConfig::default(),
             Some(evolution_store.clone()),
             gate.clone(),
-        );
+        )
+        .map_err(|e| ApiError::Internal(format!("Invalid orchestration plan: {}", e)))?;
 
         let orchestrator = Arc::new(
             base_orchestrator
                 .with_identity(identity.clone(), audit_store.clone())
-                .with_verifier(verifier.clone()),
+                .with_verifier(verifier.clone())
+                .with_suspensions(Arc::new(vex_runtime::SuspensionStore::new(db.clone()))),
         );
 
         // Register Agent Job
@@ -265,6 +268,7 @@ impl VexServer {
         let evolution_store_clone = evolution_store.clone();
         let gate_clone = gate.clone();
         let orchestrator_clone = orchestrator.clone();
+        let job_streams_clone = job_streams.clone();
 
         worker_pool.register_job_factory("agent_execution", move |payload| {
             let job_payload: AgentJobPayload =
@@ -277,20 +281,35 @@ impl VexServer {
                     max_debate_rounds: 3,
                     tenant_id: None,
                     capabilities: vec![],
+                    stream_id: None,
                 });
             let job_id = uuid::Uuid::new_v4();
             let db_concrete = db_for_factory.clone();
             let evo_store = evolution_store_clone.clone();
 
-            Box::new(AgentExecutionJob::new(
-                job_id,
-                job_payload,
-                llm_clone.clone(),
+            Box::new(
+                AgentExecutionJob::new(
+                    job_id,
+                    job_payload,
+                    llm_clone.clone(),
+                    result_store_clone.clone(),
+                    db_concrete as Arc<dyn vex_persist::StorageBackend>,
+                    None, // Anchor handled by AuditStore now
+                    evo_store,
+                    gate_clone.clone(),
+                    orchestrator_clone.clone(),
+                )
+                .with_streams(job_streams_clone.clone()),
+            )
+        });
+
+        let result_store_clone = result_store.clone();
+        let orchestrator_clone = orchestrator.clone();
+        worker_pool.register_job_factory("agent_resume", move |payload| {
+            Box::new(AgentResumeJob::new(
+                uuid::Uuid::new_v4(),
+                payload,
                 result_store_clone.clone(),
-                db_concrete as Arc<dyn vex_persist::StorageBackend>,
-                None, // Anchor handled by AuditStore now
-                evo_store,
-                gate_clone.clone(),
                 orchestrator_clone.clone(),
             ))
         });
@@ -310,7 +329,8 @@ impl VexServer {
             gate.clone(),
             orchestrator.clone(),
             bridge,
-        );
+        )
+        .with_job_streams(job_streams);
 
         Ok(Self { config, app_state })
     }
@@ -4,6 +4,7 @@
 
 use crate::a2a::handler::A2aState;
 use crate::auth::JwtAuth;
+use crate::jobs::JobStreams;
 use crate::tenant_rate_limiter::TenantRateLimiter;
 use std::sync::Arc;
 use vex_chora::AuthorityBridge;
@@ -26,6 +27,7 @@ pub struct AppState {
     gate: Arc<dyn vex_runtime::Gate>,
     orchestrator: Arc<vex_runtime::Orchestrator<dyn vex_llm::LlmProvider>>,
     bridge: Arc<AuthorityBridge>,
+    job_streams: JobStreams,
 }
 
 impl AppState {
@@ -58,9 +60,16 @@ impl AppState {
             gate,
             orchestrator,
             bridge,
+            job_streams: JobStreams::new(),
         }
     }
 
+    /// Share `job_streams` with the job workers that publish to it
+    pub fn with_job_streams(mut self, job_streams: JobStreams) -> Self {
+        self.job_streams = job_streams;
+        self
+    }
+
     /// Get JWT auth service
     pub fn jwt_auth(&self) -> &JwtAuth {
         &self.jwt_auth
@@ -120,4 +129,9 @@ impl AppState {
     pub fn bridge(&self) -> Arc<AuthorityBridge> {
         self.bridge.clone()
     }
+
+    /// Get live job streams
+    pub fn job_streams(&self) -> &JobStreams {
+        &self.job_streams
+    }
 }
@@ -49,6 +49,7 @@ async fn setup_gatekeeping_state(llm_responses: Vec<String>) -> AppState {
             Some(evolution_store.clone()),
             gate.clone(),
         )
+        .unwrap()
         .with_identity(identity, audit_store),
     );
 
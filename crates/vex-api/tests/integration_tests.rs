@@ -63,6 +63,7 @@ async fn setup_state() -> AppState {
             Some(evolution_store.clone()),
             gate.clone(),
         )
+        .unwrap()
         .with_identity(identity, audit_store),
     );
 
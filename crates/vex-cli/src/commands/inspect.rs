@@ -2,8 +2,9 @@ use anyhow::{Context, Result};
 use clap::Args;
 use colored::Colorize;
 use std::path::PathBuf;
-use vex_core::vep::VepPacket;
 use vex_core::vep::VepSegmentType;
+use vex_core::vep::{VepError, VepPacket};
+use vex_core::vep_stream::{is_vep_archive, VepArchiveReader};
 
 /// Arguments for the inspect command
 #[derive(Args)]
@@ -15,6 +16,10 @@ pub struct InspectArgs {
     /// Show detailed binary information (hex segments)
     #[arg(long, short = 'x')]
     pub hex: bool,
+
+    /// Inspect a single packet of a VEP archive by its index
+    #[arg(long, short = 'p')]
+    pub packet: Option<usize>,
 }
 
 /// Run the inspect command
@@ -23,12 +28,91 @@ pub async fn run(args: InspectArgs) -> Result<()> {
     println!("{}", "═".repeat(45).cyan());
     println!();
 
+    let mut file = std::fs::File::open(&args.path)
+        .with_context(|| format!("Failed to read VEP file: {}", args.path.display()))?;
+
+    // Archives are streamed packet by packet instead of being loaded whole
+    if is_vep_archive(&mut file)? {
+        let mut archive = VepArchiveReader::open(std::io::BufReader::new(file))
+            .map_err(|e| anyhow::anyhow!("Failed to open VEP archive: {}", e))?;
+
+        return match args.packet {
+            Some(index) => {
+                let bytes = archive
+                    .read_packet(index)
+                    .map_err(|e| anyhow::anyhow!("Failed to read archive packet: {}", e))?;
+                inspect_packet(&args, &bytes)
+            }
+            None => inspect_archive(&args, &mut archive),
+        };
+    }
+
     // Read binary file
     let bytes = std::fs::read(&args.path)
         .with_context(|| format!("Failed to read VEP file: {}", args.path.display()))?;
 
+    inspect_packet(&args, &bytes)
+}
+
+/// Summarize every packet of a VEP archive without buffering segment data
+fn inspect_archive<R: std::io::Read + std::io::Seek>(
+    args: &InspectArgs,
+    archive: &mut VepArchiveReader<R>,
+) -> Result<()> {
+    println!("  {} {}", "File:".dimmed(), args.path.display());
+    println!("  {} {}", "Packets:".dimmed(), archive.len());
+    println!();
+
+    for index in 0..archive.len() {
+        let entry = archive.entries()[index];
+        let reader = archive
+            .packet(index)
+            .map_err(|e| anyhow::anyhow!("Failed to read archive packet {}: {}", index, e))?;
+        let header = *reader.header();
+
+        println!(
+            "  {} {} {} {}",
+            format!("[{}]", index).bold(),
+            "Root:".dimmed(),
+            hex::encode(header.capsule_root).magenta(),
+            format!("(v{}, {} bytes)", header.version, entry.len).dimmed()
+        );
+
+        match reader.validate() {
+            Ok(segments) => {
+                for segment in segments {
+                    let label = match (segment.kind(), segment.is_sealed()) {
+                        (Some(kind), true) => format!("{:?} (sealed)", kind),
+                        (Some(kind), false) => format!("{:?}", kind),
+                        (None, _) => "Unknown".to_string(),
+                    };
+                    println!(
+                        "     {} [Type: {:02X}] [{} bytes]",
+                        label.yellow(),
+                        segment.segment_type,
+                        segment.len
+                    );
+                }
+            }
+            Err(e) => println!("     {} {}", "Error:".red().bold(), e.to_string().dimmed()),
+        }
+    }
+
+    println!();
+    println!(
+        "  {}",
+        "Use --packet <N> to reconstruct and verify a single capsule.".dimmed()
+    );
+    println!();
+    println!("{} Read complete.", "✓".green().bold());
+
+    Ok(())
+}
+
+/// Inspect a single in-memory VEP packet
+fn inspect_packet(args: &InspectArgs, bytes: &[u8]) -> Result<()> {
     // Parse VEP using the core VepPacket logic
-    let packet = VepPacket::new(&bytes)
+    let packet = VepPacket::new(bytes)
         .map_err(|e| anyhow::anyhow!("Failed to parse binary VEP header: {}", e))?;
 
     let header = packet.header();
@@ -70,6 +154,14 @@ pub async fn run(args: InspectArgs) -> Result<()> {
 
     // Try to reconstruct the capsule for more detail
     println!("{}", "🛡️ Verification Status:".bold());
+    match packet.validate_strict() {
+        Ok(()) => println!("  {} {}", "Strict Structure:".dimmed(), "OK".green().bold()),
+        Err(e) => println!(
+            "  {} {}",
+            "Strict Structure:".dimmed(),
+            e.to_string().red().bold()
+        ),
+    }
     match packet.to_capsule() {
         Ok(capsule) => {
             let outcome_color = match capsule.authority.outcome.to_uppercase().as_str() {
@@ -154,6 +246,13 @@ pub async fn run(args: InspectArgs) -> Result<()> {
                 }
             }
         }
+        Err(VepError::Sealed(segment)) => {
+            println!(
+                "  {} {:?} segment is sealed; the root commits to its ciphertext",
+                "Sealed:".cyan().bold(),
+                segment
+            );
+        }
         Err(e) => {
             println!(
                 "  {} {}",
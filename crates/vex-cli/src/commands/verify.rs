@@ -45,6 +45,14 @@ pub struct VerifyArgs {
     /// Authority public key (hex) for local signature verification
     #[arg(long, short = 'p', value_name = "HEX")]
     public_key: Option<String>,
+
+    /// Signing key history JSON (overrides the history embedded in the audit file)
+    #[arg(long, short = 'k', value_name = "FILE")]
+    key_history: Option<PathBuf>,
+
+    /// Additional trusted public key (hex) anchoring the key history; repeatable
+    #[arg(long = "trusted-key", value_name = "HEX")]
+    trusted_keys: Vec<String>,
 }
 
 /// Run the verify command
@@ -78,7 +86,14 @@ pub async fn run(args: VerifyArgs) -> Result<()> {
 
     // Handle audit file verification
     if let Some(audit_path) = &args.audit {
-        verify_audit_file(audit_path, args.detailed).await?;
+        verify_audit_file(
+            audit_path,
+            args.key_history.as_deref(),
+            args.public_key.as_deref(),
+            &args.trusted_keys,
+            args.detailed,
+        )
+        .await?;
     }
 
     // Handle database verification
@@ -261,10 +276,29 @@ async fn verify_live_handshake(gate_url: Option<&str>, api_key: Option<&str>) ->
     Ok(())
 }
 
+/// Parse a hex Ed25519 public key given on the command line
+fn parse_verifying_key(pk_hex: &str) -> Result<ed25519_dalek::VerifyingKey> {
+    let pk: [u8; 32] = hex::decode(pk_hex)
+        .context("Invalid public key hex")?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Public key must be 32 bytes"))?;
+    ed25519_dalek::VerifyingKey::from_bytes(&pk).context("Invalid Ed25519 public key")
+}
+
 /// Verify an exported audit JSON file
-async fn verify_audit_file(path: &std::path::PathBuf, detailed: bool) -> Result<()> {
+///
+/// The key history embedded in an export is self-signed, so it is only trusted when
+/// every introduced key is one of the pinned keys (`--public-key` / `--trusted-key`).
+/// A history passed with `--key-history` is supplied by the operator and trusted as is.
+async fn verify_audit_file(
+    path: &Path,
+    key_history_path: Option<&Path>,
+    public_key_hex: Option<&str>,
+    trusted_keys: &[String],
+    detailed: bool,
+) -> Result<()> {
     use vex_core::audit::AuditEvent;
-    use vex_core::{Hash, MerkleTree};
+    use vex_core::{Hash, KeyEvent, KeyEventKind, KeyHistory, MerkleTree};
     use vex_persist::audit_store::AuditExport;
 
     println!("{}", "🔐 VEX Audit Verification".bold().cyan());
@@ -281,6 +315,49 @@ async fn verify_audit_file(path: &std::path::PathBuf, detailed: bool) -> Result<
     let events = &audit_data.events;
     let event_count = events.len();
 
+    // Resolve signing keys: a history file overrides the one embedded in the export
+    let key_entries: Vec<KeyEvent> = match key_history_path {
+        Some(history_path) => {
+            let content = std::fs::read_to_string(history_path).with_context(|| {
+                format!("Failed to read key history: {}", history_path.display())
+            })?;
+            serde_json::from_str(&content).with_context(|| "Failed to parse key history JSON")?
+        }
+        None => audit_data.key_history.clone(),
+    };
+    let key_history = KeyHistory::from_entries(key_entries)
+        .map_err(|e| anyhow::anyhow!("Invalid key history: {}", e))?;
+
+    let pinned_key = public_key_hex.map(parse_verifying_key).transpose()?;
+    let mut anchors = Vec::new();
+    for pk_hex in public_key_hex
+        .into_iter()
+        .chain(trusted_keys.iter().map(String::as_str))
+    {
+        anchors.push(hex::encode(parse_verifying_key(pk_hex)?.to_bytes()));
+    }
+
+    // Every introduced key must chain to a pinned anchor; rotations are signed by them
+    if key_history_path.is_none() && !key_history.is_empty() && anchors.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Embedded key history is self-signed and unauthenticated: pin its keys with \
+             --public-key/--trusted-key or pass a trusted --key-history"
+        ));
+    }
+    if !anchors.is_empty() {
+        for entry in key_history.entries() {
+            if entry.kind == KeyEventKind::Introduced
+                && !anchors.contains(&entry.public_key.to_ascii_lowercase())
+            {
+                return Err(anyhow::anyhow!(
+                    "Key history introduces an unpinned key for signer {}: {}",
+                    entry.signer_id,
+                    entry.public_key
+                ));
+            }
+        }
+    }
+
     // Summary info
     println!("  {} {}", "File:".dimmed(), path.display());
     println!("  {} {}", "Events:".dimmed(), event_count);
@@ -289,6 +366,16 @@ async fn verify_audit_file(path: &std::path::PathBuf, detailed: bool) -> Result<
         "Merkle Root (File):".dimmed(),
         audit_data.merkle_root.as_deref().unwrap_or("None")
     );
+    println!(
+        "  {} {}",
+        "Hash Algorithm:".dimmed(),
+        audit_data.hash_algorithm
+    );
+    println!(
+        "  {} {} entries",
+        "Key History:".dimmed(),
+        key_history.entries().len()
+    );
     println!();
 
     if events.is_empty() {
@@ -300,21 +387,7 @@ async fn verify_audit_file(path: &std::path::PathBuf, detailed: bool) -> Result<
     let mut last_hash: Option<Hash> = None;
     for (i, event) in events.iter().enumerate() {
         // Re-calculate the "individual" hash (Centralized in vex-core)
-        use vex_core::audit::HashParams;
-        let base_hash = AuditEvent::compute_hash(HashParams {
-            event_type: &event.event_type,
-            timestamp: event.timestamp.timestamp(),
-            sequence_number: event.sequence_number,
-            data: &event.data,
-            actor: &event.actor,
-            rationale: &event.rationale,
-            policy_version: &event.policy_version,
-            data_provenance_hash: &event.data_provenance_hash,
-            human_review_required: event.human_review_required,
-            approval_count: event.approval_signatures.len(),
-            evidence_capsule: &event.evidence_capsule,
-            schema_version: &event.schema_version,
-        });
+        let base_hash = AuditEvent::compute_hash(event.hash_params());
 
         // Calculate expected final hash (including chain link if applicable)
         let expected_hash = if let Some(prev) = &event.previous_hash {
@@ -360,15 +433,38 @@ async fn verify_audit_file(path: &std::path::PathBuf, detailed: bool) -> Result<
             ));
         }
 
+        // Enforce M-of-N co-signatures committed in the event
+        event
+            .verify_threshold()
+            .map_err(|e| anyhow::anyhow!("Co-signature failure: {}", e))?;
+
+        // Check approval signatures with the key valid at this sequence number
+        let verified = if !key_history.is_empty() {
+            event.verify_approval_signatures(|signer_id| {
+                key_history.key_for(signer_id, event.sequence_number)
+            })
+        } else if let Some(key) = &pinned_key {
+            event.verify_approval_signatures(|_| Some(*key))
+        } else {
+            Ok(0)
+        };
+        verified.map_err(|e| anyhow::anyhow!("Signature failure: {}", e))?;
+
         last_hash = Some(event.hash.clone());
     }
 
-    // 2. Build Merkle tree from verified hashes
+    // 2. Build Merkle tree from verified hashes, with the algorithm recorded in the export
+    let hasher = audit_data.hash_algorithm.builtin_hasher().ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unsupported Merkle hash algorithm '{}': no built-in verifier",
+            audit_data.hash_algorithm
+        )
+    })?;
     let leaves: Vec<(String, Hash)> = events
         .iter()
         .map(|e| (e.id.to_string(), e.hash.clone()))
         .collect();
-    let tree = MerkleTree::from_leaves(leaves);
+    let tree = MerkleTree::from_leaves_with_hasher(leaves, hasher);
     let calculated_root = tree.root_hash().map(|h| h.to_string());
 
     // 3. Compare roots
@@ -522,8 +618,8 @@ async fn verify_capsule_file(
     let capsule = if bytes.starts_with(&vex_core::vep::VEP_MAGIC) || bytes.starts_with(b"EPH") {
         print!("  Detecting Format: ");
         println!("{}", "BINARY (V1.0)".green().bold());
-        let packet =
-            VepPacket::new(&bytes).map_err(|e| anyhow::anyhow!("Binary parse error: {}", e))?;
+        let packet = VepPacket::new_strict(&bytes)
+            .map_err(|e| anyhow::anyhow!("Binary parse error: {}", e))?;
         packet
             .to_capsule()
             .map_err(|e| anyhow::anyhow!("Capsule reconstruction failed: {}", e))?
@@ -660,6 +756,8 @@ mod tests {
         AuditExport {
             events,
             merkle_root: tree.root_hash().map(|h| h.to_string()),
+            hash_algorithm: Default::default(),
+            key_history: Vec::new(),
             exported_at: Utc::now(),
             verified: true,
         }
@@ -672,7 +770,7 @@ mod tests {
         let json = serde_json::to_string(&export).unwrap();
         std::fs::write(&path, json).unwrap();
 
-        let result = verify_audit_file(&path, false).await;
+        let result = verify_audit_file(&path, None, None, &[], false).await;
         assert!(
             result.is_ok(),
             "Valid audit should verify! Error: {:?}",
@@ -690,7 +788,7 @@ mod tests {
         let json = serde_json::to_string(&export).unwrap();
         std::fs::write(&path, json).unwrap();
 
-        let result = verify_audit_file(&path, false).await;
+        let result = verify_audit_file(&path, None, None, &[], false).await;
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
@@ -708,7 +806,7 @@ mod tests {
         let json = serde_json::to_string(&export).unwrap();
         std::fs::write(&path, json).unwrap();
 
-        let result = verify_audit_file(&path, false).await;
+        let result = verify_audit_file(&path, None, None, &[], false).await;
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
@@ -716,6 +814,130 @@ mod tests {
             .contains("Merkle root mismatch"));
     }
 
+    #[tokio::test]
+    async fn test_verify_unsupported_hash_algorithm() {
+        let mut export = create_test_audit();
+        export.hash_algorithm = vex_core::HashAlgorithm::Poseidon;
+
+        let path = std::env::temp_dir().join("audit_poseidon.json");
+        let json = serde_json::to_string(&export).unwrap();
+        std::fs::write(&path, json).unwrap();
+
+        let result = verify_audit_file(&path, None, None, &[], false).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Unsupported Merkle hash algorithm 'poseidon'"));
+    }
+
+    /// Sign an unchained event the way `AuditEvent::sign_hardware` does and rehash it
+    fn sign_event(event: &mut AuditEvent, signer_id: &str, key: &ed25519_dalek::SigningKey) {
+        let jcs = serde_jcs::to_vec(&event.hash_params()).unwrap();
+        event
+            .approval_signatures
+            .push(vex_core::Signature::create(signer_id, &jcs, key));
+        event.hash = AuditEvent::compute_hash(event.hash_params());
+    }
+
+    #[tokio::test]
+    async fn test_verify_audit_with_rotated_keys() {
+        use vex_core::KeySigner;
+
+        let old_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+        let new_key = ed25519_dalek::SigningKey::from_bytes(&[8u8; 32]);
+        let mut history = vex_core::KeyHistory::new();
+        history.introduce("agent-1", &old_key, 0).unwrap();
+        history
+            .rotate("agent-1", &old_key, &new_key.public_key_hex(), 1)
+            .unwrap();
+
+        let mut e1 = AuditEvent::new(
+            AuditEventType::AgentCreated,
+            None,
+            serde_json::json!({"role": "Root"}),
+            0,
+        );
+        sign_event(&mut e1, "agent-1", &old_key);
+        let mut e2 = AuditEvent::new(
+            AuditEventType::AgentExecuted,
+            None,
+            serde_json::json!({"prompt": "Hello"}),
+            1,
+        );
+        sign_event(&mut e2, "agent-1", &new_key);
+        e2.hash = AuditEvent::compute_chained_hash(&e2.hash, &e1.hash, 1);
+        e2.previous_hash = Some(e1.hash.clone());
+
+        let events = vec![e1, e2];
+        let tree = MerkleTree::from_leaves(
+            events
+                .iter()
+                .map(|e| (e.id.to_string(), e.hash.clone()))
+                .collect(),
+        );
+        let mut export = AuditExport {
+            events,
+            merkle_root: tree.root_hash().map(|h| h.to_string()),
+            hash_algorithm: Default::default(),
+            key_history: history.entries().to_vec(),
+            exported_at: Utc::now(),
+            verified: true,
+        };
+
+        let path = std::env::temp_dir().join("audit_rotated_keys.json");
+        std::fs::write(&path, serde_json::to_string(&export).unwrap()).unwrap();
+        // The embedded history alone is not trusted
+        assert!(verify_audit_file(&path, None, None, &[], false)
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("unauthenticated"));
+
+        let anchor = old_key.public_key_hex();
+        let result = verify_audit_file(&path, None, Some(&anchor), &[], false).await;
+        assert!(
+            result.is_ok(),
+            "Rotated keys should verify: {:?}",
+            result.err()
+        );
+
+        // A key from outside the history is not a valid trust anchor
+        let stranger = hex::encode([9u8; 32]);
+        assert!(verify_audit_file(&path, None, Some(&stranger), &[], false)
+            .await
+            .is_err());
+
+        // A second signer introduced without its own anchor is rejected
+        let other_key = ed25519_dalek::SigningKey::from_bytes(&[10u8; 32]);
+        let mut two_signers = history.clone();
+        two_signers.introduce("agent-2", &other_key, 1).unwrap();
+        export.key_history = two_signers.entries().to_vec();
+        std::fs::write(&path, serde_json::to_string(&export).unwrap()).unwrap();
+        assert!(verify_audit_file(&path, None, Some(&anchor), &[], false)
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("unpinned key for signer agent-2"));
+        let trusted = [other_key.public_key_hex()];
+        assert!(
+            verify_audit_file(&path, None, Some(&anchor), &trusted, false)
+                .await
+                .is_ok()
+        );
+
+        // Revoking the new key from sequence 1 invalidates the second event
+        history
+            .revoke("agent-1", &new_key.public_key_hex(), &new_key, 1, None)
+            .unwrap();
+        export.key_history = history.entries().to_vec();
+        std::fs::write(&path, serde_json::to_string(&export).unwrap()).unwrap();
+        let result = verify_audit_file(&path, None, Some(&anchor), &[], false).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("No valid key for signer agent-1 at sequence 1"));
+    }
+
     #[tokio::test]
     async fn test_verify_capsule_offline() {
         use ed25519_dalek::{Signer, SigningKey};
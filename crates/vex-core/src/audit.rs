@@ -33,6 +33,10 @@ pub enum AuditEventType {
     GateDecision,
     /// Phase 2: AI Escalation for Human Review
     Escalation,
+    /// Tool invocation inside an agent's reasoning loop
+    ToolCall,
+    /// Run stopped early (cancellation, deadline or budget)
+    RunStopped,
     #[serde(untagged)]
     Custom(String),
 }
@@ -145,6 +149,54 @@ impl Signature {
     }
 }
 
+/// M-of-N co-signing requirement recorded in an event (ISO 42001 A.6.1.3)
+///
+/// At least `threshold` distinct keys from `signers` (hex Ed25519 public keys) must
+/// co-sign the final event hash.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ThresholdPolicy {
+    pub threshold: usize,
+    pub signers: Vec<String>,
+}
+
+impl ThresholdPolicy {
+    pub fn new(threshold: usize, signers: &[ed25519_dalek::VerifyingKey]) -> Result<Self, String> {
+        let policy = Self {
+            threshold,
+            signers: signers.iter().map(|k| hex::encode(k.to_bytes())).collect(),
+        };
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    /// Reject policies that are trivially met or can never be met
+    pub fn validate(&self) -> Result<(), String> {
+        if self.threshold == 0 {
+            return Err("Threshold must be at least 1".to_string());
+        }
+        if self.threshold > self.signers.len() {
+            return Err(format!(
+                "Threshold {} exceeds the {} allowed signers",
+                self.threshold,
+                self.signers.len()
+            ));
+        }
+        for (i, signer) in self.signers.iter().enumerate() {
+            if self.signers[..i].contains(signer) {
+                return Err(format!("Duplicate signer {} in threshold policy", signer));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether this policy is at least as strict as `minimum`: same or higher threshold,
+    /// drawn from a subset of the allowed signers.
+    pub fn satisfies(&self, minimum: &ThresholdPolicy) -> bool {
+        self.threshold >= minimum.threshold
+            && self.signers.iter().all(|s| minimum.signers.contains(s))
+    }
+}
+
 /// Single audit event (ISO 42001 / EU AI Act compliant)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditEvent {
@@ -164,6 +216,12 @@ pub struct AuditEvent {
     pub data_provenance_hash: Option<Hash>,
     pub human_review_required: bool,
     pub approval_signatures: Vec<Signature>,
+    /// Required co-signatures (committed in the event hash)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold_policy: Option<ThresholdPolicy>,
+    /// Co-signatures over the final event hash; `signer_id` is the hex public key
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub co_signatures: Vec<Signature>,
 
     // CHORA Alignment
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -193,6 +251,8 @@ pub struct HashParams<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub evidence_capsule: &'a Option<EvidenceCapsule>,
     pub schema_version: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold_policy: &'a Option<ThresholdPolicy>,
 }
 
 impl AuditEvent {
@@ -232,6 +292,7 @@ impl AuditEvent {
         let human_review_required = false;
         let approval_signatures: Vec<Signature> = Vec::new();
         let evidence_capsule: Option<EvidenceCapsule> = None;
+        let threshold_policy: Option<ThresholdPolicy> = None;
         let schema_version = "1.0".to_string();
 
         // Compute hash including ALL fields (Centralized in vex-core)
@@ -248,6 +309,7 @@ impl AuditEvent {
             approval_count: approval_signatures.len(),
             evidence_capsule: &evidence_capsule,
             schema_version: &schema_version,
+            threshold_policy: &threshold_policy,
         });
 
         Self {
@@ -265,12 +327,33 @@ impl AuditEvent {
             data_provenance_hash,
             human_review_required,
             approval_signatures,
+            threshold_policy,
+            co_signatures: Vec::new(),
             evidence_capsule,
             vep_blob: None,
             schema_version,
         }
     }
 
+    /// Hash parameters for this event's current fields
+    pub fn hash_params(&self) -> HashParams<'_> {
+        HashParams {
+            event_type: &self.event_type,
+            timestamp: self.timestamp.timestamp(),
+            sequence_number: self.sequence_number,
+            data: &self.data,
+            actor: &self.actor,
+            rationale: &self.rationale,
+            policy_version: &self.policy_version,
+            data_provenance_hash: &self.data_provenance_hash,
+            human_review_required: self.human_review_required,
+            approval_count: self.approval_signatures.len(),
+            evidence_capsule: &self.evidence_capsule,
+            schema_version: &self.schema_version,
+            threshold_policy: &self.threshold_policy,
+        }
+    }
+
     /// Patterns that indicate a secret value (checked against string values)
     const SECRET_VALUE_PREFIXES: &'static [&'static str] = &[
         "sk-",     // OpenAI/Stripe keys
@@ -395,20 +478,7 @@ impl AuditEvent {
         &mut self,
         agent_identity: &vex_hardware::api::AgentIdentity,
     ) -> Result<(), String> {
-        let params = HashParams {
-            event_type: &self.event_type,
-            timestamp: self.timestamp.timestamp(),
-            sequence_number: self.sequence_number,
-            data: &self.data,
-            actor: &self.actor,
-            rationale: &self.rationale,
-            policy_version: &self.policy_version,
-            data_provenance_hash: &self.data_provenance_hash,
-            human_review_required: self.human_review_required,
-            approval_count: self.approval_signatures.len(),
-            evidence_capsule: &self.evidence_capsule,
-            schema_version: &self.schema_version,
-        };
+        let params = self.hash_params();
 
         // 1. Serialize parameters to JCS bytes deterministically
         let jcs_bytes =
@@ -427,6 +497,100 @@ impl AuditEvent {
         self.approval_signatures.push(final_signature);
         Ok(())
     }
+
+    /// Verify every hardware approval signature (see `sign_hardware`) with the key
+    /// `key_for` resolves for its signer, returning the number verified.
+    ///
+    /// Signature `i` covers the JCS hash parameters as they were with `i` approvals.
+    pub fn verify_approval_signatures(
+        &self,
+        key_for: impl Fn(&str) -> Option<ed25519_dalek::VerifyingKey>,
+    ) -> Result<usize, String> {
+        for (i, signature) in self.approval_signatures.iter().enumerate() {
+            let verifying_key = key_for(&signature.signer_id).ok_or_else(|| {
+                format!(
+                    "No valid key for signer {} at sequence {}",
+                    signature.signer_id, self.sequence_number
+                )
+            })?;
+
+            let mut params = self.hash_params();
+            params.approval_count = i;
+            let jcs_bytes = serde_jcs::to_vec(&params)
+                .map_err(|e| format!("JCS serialization failed: {}", e))?;
+            if !signature.verify(&jcs_bytes, &verifying_key)? {
+                return Err(format!(
+                    "Malformed approval signature from {} on event {}",
+                    signature.signer_id, self.id
+                ));
+            }
+        }
+        Ok(self.approval_signatures.len())
+    }
+
+    /// Add an Ed25519 co-signature over the final event hash.
+    /// Must be called after the hash (including chaining) is final.
+    pub fn co_sign(&mut self, signing_key: &ed25519_dalek::SigningKey) -> Signature {
+        let signer_id = hex::encode(signing_key.verifying_key().to_bytes());
+        let signature = Signature::create(signer_id, &self.hash.0, signing_key);
+        self.co_signatures.push(signature.clone());
+        signature
+    }
+
+    /// Check a co-signature against the event hash and the signers allowed by the policy
+    pub fn verify_co_signature(&self, signature: &Signature) -> Result<(), String> {
+        let policy = self
+            .threshold_policy
+            .as_ref()
+            .ok_or_else(|| "Event has no threshold policy".to_string())?;
+        if !policy.signers.contains(&signature.signer_id) {
+            return Err(format!(
+                "Signer {} is not authorized by the threshold policy",
+                signature.signer_id
+            ));
+        }
+
+        let key_bytes: [u8; 32] = hex::decode(&signature.signer_id)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| format!("Invalid signer public key: {}", signature.signer_id))?;
+        let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&key_bytes)
+            .map_err(|e| format!("Invalid signer public key: {}", e))?;
+
+        match signature.verify(&self.hash.0, &verifying_key)? {
+            true => Ok(()),
+            false => Err(format!("Malformed signature from {}", signature.signer_id)),
+        }
+    }
+
+    /// Enforce the event's threshold policy, returning the number of valid distinct signers.
+    /// Events without a policy trivially pass with 0.
+    pub fn verify_threshold(&self) -> Result<usize, String> {
+        let Some(policy) = &self.threshold_policy else {
+            return Ok(0);
+        };
+        policy.validate()?;
+
+        let mut signers: Vec<&str> = Vec::new();
+        for signature in &self.co_signatures {
+            if signers.contains(&signature.signer_id.as_str()) {
+                continue;
+            }
+            if self.verify_co_signature(signature).is_ok() {
+                signers.push(&signature.signer_id);
+            }
+        }
+
+        if signers.len() < policy.threshold {
+            return Err(format!(
+                "Threshold not met for event {}: {} of {} required signatures",
+                self.id,
+                signers.len(),
+                policy.threshold
+            ));
+        }
+        Ok(signers.len())
+    }
 }
 
 #[cfg(test)]
@@ -644,6 +808,7 @@ mod tests {
             approval_count: 0, // It was 0 when signed
             evidence_capsule: &event.evidence_capsule,
             schema_version: &event.schema_version,
+            threshold_policy: &event.threshold_policy,
         };
         let expected_jcs_bytes = serde_jcs::to_vec(&params).unwrap();
 
@@ -659,4 +824,62 @@ mod tests {
             "Raw Dalek verification failed: The generated signature does not mathematically match the JCS payload."
         );
     }
+
+    #[test]
+    fn test_threshold_co_signatures() {
+        let alice = ed25519_dalek::SigningKey::from_bytes(&[1u8; 32]);
+        let bob = ed25519_dalek::SigningKey::from_bytes(&[2u8; 32]);
+        let mallory = ed25519_dalek::SigningKey::from_bytes(&[3u8; 32]);
+
+        let mut event = AuditEvent::new(
+            AuditEventType::HumanOverride,
+            None,
+            serde_json::json!({"override": "deny -> allow"}),
+            0,
+        );
+        let unbound_hash = event.hash.clone();
+        event.threshold_policy =
+            Some(ThresholdPolicy::new(2, &[alice.verifying_key(), bob.verifying_key()]).unwrap());
+        event.hash = AuditEvent::compute_hash(event.hash_params());
+        assert_ne!(
+            event.hash, unbound_hash,
+            "policy must be committed in the hash"
+        );
+
+        event.co_sign(&alice);
+        event.co_sign(&alice);
+        event.co_sign(&mallory);
+        assert!(event.verify_threshold().is_err());
+
+        event.co_sign(&bob);
+        assert_eq!(event.verify_threshold().unwrap(), 2);
+
+        // Tampering with the hash invalidates every co-signature
+        event.hash = Hash::digest(b"tampered");
+        assert!(event.verify_threshold().is_err());
+    }
+
+    #[test]
+    fn test_threshold_policy_validation() {
+        let alice = ed25519_dalek::SigningKey::from_bytes(&[1u8; 32]).verifying_key();
+        let bob = ed25519_dalek::SigningKey::from_bytes(&[2u8; 32]).verifying_key();
+
+        assert!(ThresholdPolicy::new(0, &[alice, bob]).is_err());
+        assert!(ThresholdPolicy::new(3, &[alice, bob]).is_err());
+        assert!(ThresholdPolicy::new(2, &[alice, alice]).is_err());
+        assert!(ThresholdPolicy::new(2, &[alice, bob]).is_ok());
+
+        // A tampered policy deserialized from storage fails verification
+        let mut event = AuditEvent::new(
+            AuditEventType::HumanOverride,
+            None,
+            serde_json::json!({}),
+            0,
+        );
+        event.threshold_policy = Some(ThresholdPolicy {
+            threshold: 0,
+            signers: Vec::new(),
+        });
+        assert!(event.verify_threshold().is_err());
+    }
 }
@@ -150,11 +150,7 @@ impl FitnessEvaluator for HeuristicEvaluator {
         // Coherence: check sentence structure (simple heuristic)
         let sentences = response.matches('.').count();
         let words = response.split_whitespace().count();
-        let avg_sentence_len = if sentences > 0 {
-            words / sentences
-        } else {
-            words
-        };
+        let avg_sentence_len = words.checked_div(sentences).unwrap_or(words);
         let coherence = if (10..40).contains(&avg_sentence_len) {
             0.8
         } else if avg_sentence_len < 60 {
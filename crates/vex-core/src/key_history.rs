@@ -0,0 +1,469 @@
+//! Signing key history for audit chains
+//!
+//! An append-only, hash-linked log of key lifecycle events (introduced, rotated,
+//! revoked). Every entry records the audit sequence number from which it takes
+//! effect, so a verifier can pick the key that was valid for each event and a
+//! compromised key can be rotated out without breaking older signatures.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::audit::Signature;
+use crate::merkle::Hash;
+
+/// Key lifecycle transition
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyEventKind {
+    /// First key for a signer (self-signed)
+    Introduced,
+    /// Signer moves to a new key (signed by the outgoing key)
+    Rotated,
+    /// Key is invalid from `effective_sequence` on (signed by the key or a successor)
+    Revoked,
+}
+
+/// Single entry in a tenant's key history
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub kind: KeyEventKind,
+    /// Signer the key belongs to (matches `Signature::signer_id` on audit events)
+    pub signer_id: String,
+    /// Hex Ed25519 public key introduced, rotated in, or revoked
+    pub public_key: String,
+    /// First audit sequence number this entry applies to
+    pub effective_sequence: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub previous_hash: Option<Hash>,
+    pub hash: Hash,
+    /// Signature over `hash`; `signer_id` holds the authorizing hex public key
+    pub signature: Signature,
+}
+
+/// Fields committed in a key event hash (RFC 8785 JCS)
+#[derive(Serialize)]
+struct KeyEventBody<'a> {
+    kind: KeyEventKind,
+    signer_id: &'a str,
+    public_key: &'a str,
+    effective_sequence: u64,
+    reason: &'a Option<String>,
+    timestamp: i64,
+    previous_hash: &'a Option<Hash>,
+}
+
+impl KeyEvent {
+    /// Recompute the hash over this entry's committed fields
+    pub fn compute_hash(&self) -> Result<Hash, String> {
+        let body = KeyEventBody {
+            kind: self.kind,
+            signer_id: &self.signer_id,
+            public_key: &self.public_key,
+            effective_sequence: self.effective_sequence,
+            reason: &self.reason,
+            timestamp: self.timestamp.timestamp(),
+            previous_hash: &self.previous_hash,
+        };
+        serde_jcs::to_vec(&body)
+            .map(|bytes| Hash::digest(&bytes))
+            .map_err(|e| format!("JCS serialization failed: {}", e))
+    }
+}
+
+/// Ed25519 key able to authorize key history entries
+pub trait KeySigner {
+    fn public_key_hex(&self) -> String;
+    fn sign(&self, message: &[u8]) -> Vec<u8>;
+}
+
+impl KeySigner for ed25519_dalek::SigningKey {
+    fn public_key_hex(&self) -> String {
+        hex::encode(self.verifying_key().to_bytes())
+    }
+
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        ed25519_dalek::Signer::sign(self, message)
+            .to_bytes()
+            .to_vec()
+    }
+}
+
+impl KeySigner for vex_hardware::api::AgentIdentity {
+    fn public_key_hex(&self) -> String {
+        vex_hardware::api::AgentIdentity::public_key_hex(self)
+    }
+
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        vex_hardware::api::AgentIdentity::sign(self, message)
+    }
+}
+
+/// Validity window of one key, derived by replaying the history
+#[derive(Debug, Clone)]
+struct KeyRecord {
+    signer_id: String,
+    public_key: String,
+    valid_from: u64,
+    /// Set when the key is rotated out
+    valid_until: Option<u64>,
+    /// Set when the key is revoked
+    revoked_from: Option<u64>,
+}
+
+impl KeyRecord {
+    fn covers(&self, sequence: u64) -> bool {
+        sequence >= self.valid_from
+            && self.valid_until.is_none_or(|until| sequence < until)
+            && self.revoked_from.is_none_or(|revoked| sequence < revoked)
+    }
+}
+
+/// Validated key history for one audit chain
+#[derive(Debug, Clone, Default)]
+pub struct KeyHistory {
+    entries: Vec<KeyEvent>,
+    keys: Vec<KeyRecord>,
+}
+
+impl KeyHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replay and validate a stored history
+    pub fn from_entries(entries: Vec<KeyEvent>) -> Result<Self, String> {
+        let mut history = Self::new();
+        for entry in entries {
+            history.push(entry)?;
+        }
+        Ok(history)
+    }
+
+    pub fn entries(&self) -> &[KeyEvent] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn last_hash(&self) -> Option<&Hash> {
+        self.entries.last().map(|e| &e.hash)
+    }
+
+    /// Whether `signer_id` has ever had a key in this history
+    pub fn knows_signer(&self, signer_id: &str) -> bool {
+        self.keys.iter().any(|k| k.signer_id == signer_id)
+    }
+
+    /// Whether `public_key` (hex) was ever introduced or rotated in
+    pub fn contains_key(&self, public_key: &str) -> bool {
+        self.keys.iter().any(|k| k.public_key == public_key)
+    }
+
+    /// Key valid for `signer_id` at audit sequence number `sequence`
+    pub fn key_for(&self, signer_id: &str, sequence: u64) -> Option<ed25519_dalek::VerifyingKey> {
+        self.keys
+            .iter()
+            .find(|k| k.signer_id == signer_id && k.covers(sequence))
+            .and_then(|k| parse_public_key(&k.public_key).ok())
+    }
+
+    /// Introduce the first key for a new signer, effective from `effective_sequence`
+    pub fn introduce(
+        &mut self,
+        signer_id: impl Into<String>,
+        key: &impl KeySigner,
+        effective_sequence: u64,
+    ) -> Result<&KeyEvent, String> {
+        let public_key = key.public_key_hex();
+        self.sign_and_push(
+            KeyEventKind::Introduced,
+            signer_id.into(),
+            public_key,
+            effective_sequence,
+            None,
+            key,
+        )
+    }
+
+    /// Rotate `signer_id` from its current key to `next_public_key` (hex)
+    pub fn rotate(
+        &mut self,
+        signer_id: impl Into<String>,
+        current: &impl KeySigner,
+        next_public_key: &str,
+        effective_sequence: u64,
+    ) -> Result<&KeyEvent, String> {
+        self.sign_and_push(
+            KeyEventKind::Rotated,
+            signer_id.into(),
+            next_public_key.to_string(),
+            effective_sequence,
+            None,
+            current,
+        )
+    }
+
+    /// Revoke `public_key` (hex) for events from `effective_sequence` on
+    ///
+    /// `authorizer` must be the revoked key itself or a key that superseded it.
+    pub fn revoke(
+        &mut self,
+        signer_id: impl Into<String>,
+        public_key: &str,
+        authorizer: &impl KeySigner,
+        effective_sequence: u64,
+        reason: Option<String>,
+    ) -> Result<&KeyEvent, String> {
+        self.sign_and_push(
+            KeyEventKind::Revoked,
+            signer_id.into(),
+            public_key.to_string(),
+            effective_sequence,
+            reason,
+            authorizer,
+        )
+    }
+
+    fn sign_and_push(
+        &mut self,
+        kind: KeyEventKind,
+        signer_id: String,
+        public_key: String,
+        effective_sequence: u64,
+        reason: Option<String>,
+        authorizer: &impl KeySigner,
+    ) -> Result<&KeyEvent, String> {
+        let mut entry = KeyEvent {
+            kind,
+            signer_id,
+            public_key,
+            effective_sequence,
+            reason,
+            timestamp: Utc::now(),
+            previous_hash: self.last_hash().cloned(),
+            hash: Hash([0u8; 32]),
+            signature: Signature {
+                signer_id: authorizer.public_key_hex(),
+                signed_at: Utc::now(),
+                signature_hex: String::new(),
+            },
+        };
+        entry.hash = entry.compute_hash()?;
+        entry.signature.signature_hex = hex::encode(authorizer.sign(&entry.hash.0));
+
+        self.push(entry)?;
+        Ok(self.entries.last().expect("entry was just pushed"))
+    }
+
+    /// Validate and append an entry
+    ///
+    /// Checks the hash link, the entry hash, the authorizing key for the transition
+    /// and the signature before the entry is accepted.
+    pub fn push(&mut self, entry: KeyEvent) -> Result<(), String> {
+        if entry.previous_hash.as_ref() != self.last_hash() {
+            return Err("Key history link broken: previous_hash mismatch".to_string());
+        }
+        if entry.compute_hash()? != entry.hash {
+            return Err(format!(
+                "Key history entry hash mismatch for signer {}",
+                entry.signer_id
+            ));
+        }
+        parse_public_key(&entry.public_key)?;
+
+        let authorizer = &entry.signature.signer_id;
+        let seq = entry.effective_sequence;
+        match entry.kind {
+            KeyEventKind::Introduced => {
+                if self.knows_signer(&entry.signer_id) {
+                    return Err(format!(
+                        "Signer {} already has a key history; rotate instead",
+                        entry.signer_id
+                    ));
+                }
+                if self.contains_key(&entry.public_key) {
+                    return Err("Key reuse is not allowed".to_string());
+                }
+                if authorizer != &entry.public_key {
+                    return Err("Introduced keys must be self-signed".to_string());
+                }
+            }
+            KeyEventKind::Rotated => {
+                let current = self
+                    .active_key(&entry.signer_id)
+                    .ok_or_else(|| format!("Signer {} has no active key", entry.signer_id))?;
+                if authorizer != &current.public_key {
+                    return Err("Rotation must be signed by the outgoing key".to_string());
+                }
+                if seq < current.valid_from {
+                    return Err(format!(
+                        "Rotation at sequence {} precedes current key (from {})",
+                        seq, current.valid_from
+                    ));
+                }
+                if self.contains_key(&entry.public_key) {
+                    return Err("Key reuse is not allowed".to_string());
+                }
+            }
+            KeyEventKind::Revoked => {
+                let revoked = self
+                    .keys
+                    .iter()
+                    .find(|k| k.signer_id == entry.signer_id && k.public_key == entry.public_key)
+                    .ok_or_else(|| format!("Unknown key for signer {}", entry.signer_id))?;
+                if revoked.revoked_from.is_some() {
+                    return Err("Key is already revoked".to_string());
+                }
+                if seq < revoked.valid_from {
+                    return Err(format!(
+                        "Revocation at sequence {} precedes key introduction ({})",
+                        seq, revoked.valid_from
+                    ));
+                }
+                let authorized = self.keys.iter().any(|k| {
+                    k.signer_id == entry.signer_id
+                        && &k.public_key == authorizer
+                        && k.valid_from >= revoked.valid_from
+                });
+                if !authorized {
+                    return Err(
+                        "Revocation must be signed by the revoked key or a successor".to_string(),
+                    );
+                }
+            }
+        }
+
+        let authorizer_key = parse_public_key(authorizer)?;
+        if !entry.signature.verify(&entry.hash.0, &authorizer_key)? {
+            return Err(format!(
+                "Malformed key history signature for signer {}",
+                entry.signer_id
+            ));
+        }
+
+        match entry.kind {
+            KeyEventKind::Introduced => self.keys.push(KeyRecord {
+                signer_id: entry.signer_id.clone(),
+                public_key: entry.public_key.clone(),
+                valid_from: seq,
+                valid_until: None,
+                revoked_from: None,
+            }),
+            KeyEventKind::Rotated => {
+                if let Some(current) = self.active_key_mut(&entry.signer_id) {
+                    current.valid_until = Some(seq);
+                }
+                self.keys.push(KeyRecord {
+                    signer_id: entry.signer_id.clone(),
+                    public_key: entry.public_key.clone(),
+                    valid_from: seq,
+                    valid_until: None,
+                    revoked_from: None,
+                });
+            }
+            KeyEventKind::Revoked => {
+                if let Some(revoked) = self
+                    .keys
+                    .iter_mut()
+                    .find(|k| k.signer_id == entry.signer_id && k.public_key == entry.public_key)
+                {
+                    revoked.revoked_from = Some(seq);
+                }
+            }
+        }
+
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    fn active_key(&self, signer_id: &str) -> Option<&KeyRecord> {
+        self.keys
+            .iter()
+            .find(|k| k.signer_id == signer_id && k.valid_until.is_none())
+    }
+
+    fn active_key_mut(&mut self, signer_id: &str) -> Option<&mut KeyRecord> {
+        self.keys
+            .iter_mut()
+            .find(|k| k.signer_id == signer_id && k.valid_until.is_none())
+    }
+}
+
+fn parse_public_key(public_key: &str) -> Result<ed25519_dalek::VerifyingKey, String> {
+    let bytes: [u8; 32] = hex::decode(public_key)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| format!("Invalid public key: {}", public_key))?;
+    ed25519_dalek::VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| format!("Invalid public key: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+
+    #[test]
+    fn test_key_rotation_selects_key_by_sequence() {
+        let old = SigningKey::from_bytes(&[1u8; 32]);
+        let new = SigningKey::from_bytes(&[2u8; 32]);
+
+        let mut history = KeyHistory::new();
+        history.introduce("agent-1", &old, 0).unwrap();
+        history
+            .rotate("agent-1", &old, &new.public_key_hex(), 10)
+            .unwrap();
+
+        assert_eq!(history.key_for("agent-1", 9), Some(old.verifying_key()));
+        assert_eq!(history.key_for("agent-1", 10), Some(new.verifying_key()));
+        assert_eq!(history.key_for("agent-2", 10), None);
+
+        // Old key compromised since sequence 5: revoked by its successor
+        history
+            .revoke(
+                "agent-1",
+                &old.public_key_hex(),
+                &new,
+                5,
+                Some("compromised".to_string()),
+            )
+            .unwrap();
+        assert_eq!(history.key_for("agent-1", 4), Some(old.verifying_key()));
+        assert_eq!(history.key_for("agent-1", 7), None);
+
+        let replayed = KeyHistory::from_entries(history.entries().to_vec()).unwrap();
+        assert_eq!(replayed.key_for("agent-1", 12), Some(new.verifying_key()));
+    }
+
+    #[test]
+    fn test_key_history_rejects_unauthorized_entries() {
+        let old = SigningKey::from_bytes(&[1u8; 32]);
+        let new = SigningKey::from_bytes(&[2u8; 32]);
+        let attacker = SigningKey::from_bytes(&[3u8; 32]);
+
+        let mut history = KeyHistory::new();
+        history.introduce("agent-1", &old, 0).unwrap();
+
+        assert!(history
+            .rotate("agent-1", &attacker, &attacker.public_key_hex(), 3)
+            .is_err());
+        assert!(history.introduce("agent-1", &attacker, 3).is_err());
+
+        history
+            .rotate("agent-1", &old, &new.public_key_hex(), 10)
+            .unwrap();
+        // A superseded key cannot revoke its successor
+        assert!(history
+            .revoke("agent-1", &new.public_key_hex(), &old, 12, None)
+            .is_err());
+
+        // Tampering with a stored entry breaks replay
+        let mut entries = history.entries().to_vec();
+        entries[1].effective_sequence = 1;
+        assert!(KeyHistory::from_entries(entries).is_err());
+    }
+}
@@ -54,17 +54,21 @@ pub mod evolution;
 pub mod evolution_memory;
 pub mod fitness;
 pub mod genome_experiment;
+pub mod key_history;
 pub mod merkle;
+pub mod merkle_log;
 pub mod rule;
 pub mod segment;
 pub mod vep;
+pub mod vep_seal;
+pub mod vep_stream;
 pub mod zk;
 
 #[cfg(test)]
 mod jcs_fuzz;
 
 pub use agent::{Agent, AgentConfig, AgentHandle, AgentId};
-pub use audit::{ActorType, AuditEvent, AuditEventType, HashParams, Signature};
+pub use audit::{ActorType, AuditEvent, AuditEventType, HashParams, Signature, ThresholdPolicy};
 pub use context::{CompressionLevel, ContextPacket};
 pub use evolution::{
     tournament_select, Fitness, GeneticOperator, Genome, LlmParams, StandardOperator,
@@ -72,9 +76,24 @@ pub use evolution::{
 pub use evolution_memory::{EvolutionMemory, TraitAdjustment};
 pub use fitness::{EvaluationContext, FitnessEvaluator, FitnessReport, HeuristicEvaluator};
 pub use genome_experiment::GenomeExperiment;
-pub use merkle::{Hash, MerkleNode, MerkleProof, MerkleTree, ProofDirection, ProofStep};
+pub use key_history::{KeyEvent, KeyEventKind, KeyHistory, KeySigner};
+pub use merkle::{
+    Blake3Hasher, Hash, HashAlgorithm, MerkleHasher, MerkleMultiProof, MerkleNode, MerkleProof,
+    MerkleTree, MultiProofLeaf, ProofDirection, ProofStep, Sha256Hasher, Sha512_256Hasher,
+    UnsupportedHashAlgorithm,
+};
+pub use merkle_log::{
+    ConsistencyProof, IncrementalMerkleTree, MerkleFrontier, MerkleLogView, MerkleNodes, NodeId,
+    NodeRecorder,
+};
 pub use rule::OptimizationRule;
 pub use segment::{
-    AuthorityData, ContinuationPayload, ContinuationToken, IdentityData, IntentData,
+    AuthorityData, CapsulePillar, ContinuationPayload, ContinuationToken, IdentityData, IntentData,
+    PillarDisclosure,
+};
+pub use vep::{
+    VepBuilder, VepError, VepHeader, VepPacket, VepSegmentType, VEP_MAGIC, VEP_VERSION_V2,
+    VEP_VERSION_V3,
 };
-pub use vep::{VepHeader, VepPacket, VepSegmentType, VEP_MAGIC, VEP_VERSION_V2};
+pub use vep_seal::{SealRecipient, SealedSegment, SEALED_SEGMENT_FLAG};
+pub use vep_stream::{VepArchiveReader, VepArchiveWriter, VepReader, VepSegmentHeader};
@@ -3,10 +3,13 @@
 // Removed unused import
 
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha512_256};
 use std::fmt;
 
-/// A SHA-256 hash (32 bytes)
+/// A 32-byte hash.
+///
+/// [`Hash::digest`] and [`Hash::combine`] are SHA-256; trees built with another
+/// [`MerkleHasher`] hold that hasher's output in the same type.
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Hash(pub [u8; 32]);
 
@@ -51,6 +54,161 @@ impl fmt::Display for Hash {
     }
 }
 
+/// Hash function used for Merkle leaves and internal nodes.
+///
+/// Carried in proofs and exports so verifiers know which function to use. SHA-256 is the
+/// default for all existing chains.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HashAlgorithm {
+    #[default]
+    #[serde(rename = "sha256")]
+    Sha256,
+    #[serde(rename = "sha512_256")]
+    Sha512_256,
+    #[serde(rename = "blake3")]
+    Blake3,
+    /// ZK-friendly hash for trees proven in-circuit. vex-core has no implementation;
+    /// the hasher comes from attest-rs.
+    #[serde(rename = "poseidon")]
+    Poseidon,
+}
+
+impl HashAlgorithm {
+    /// The implementation shipped with vex-core, if any.
+    /// Poseidon is only provided by attest-rs.
+    pub fn builtin_hasher(self) -> Option<&'static dyn MerkleHasher> {
+        match self {
+            HashAlgorithm::Sha256 => Some(&Sha256Hasher),
+            HashAlgorithm::Sha512_256 => Some(&Sha512_256Hasher),
+            HashAlgorithm::Blake3 => Some(&Blake3Hasher),
+            HashAlgorithm::Poseidon => None,
+        }
+    }
+
+    /// Like [`HashAlgorithm::builtin_hasher`], but an error for downstream-only algorithms
+    pub fn require_builtin(self) -> Result<&'static dyn MerkleHasher, UnsupportedHashAlgorithm> {
+        self.builtin_hasher().ok_or(UnsupportedHashAlgorithm(self))
+    }
+}
+
+/// Verification was asked of an algorithm vex-core does not implement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("no built-in hasher for '{0}'; verify with the downstream implementation")]
+pub struct UnsupportedHashAlgorithm(pub HashAlgorithm);
+
+/// Unwrap a verification result, logging an unsupported algorithm instead of failing silently
+pub(crate) fn verified_or_warn(result: Result<bool, UnsupportedHashAlgorithm>) -> bool {
+    result.unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Merkle proof not verified");
+        false
+    })
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha512_256 => "sha512_256",
+            HashAlgorithm::Blake3 => "blake3",
+            HashAlgorithm::Poseidon => "poseidon",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Interface for Merkle hash functions.
+/// Implementations must keep the 0x00 leaf / 0x01 node domain separation (or an
+/// equivalent for algebraic hashes) and produce 32-byte outputs.
+pub trait MerkleHasher: Send + Sync + fmt::Debug {
+    /// The algorithm identifier recorded in proofs
+    fn algorithm(&self) -> HashAlgorithm;
+
+    /// Hash leaf data
+    fn hash_leaf(&self, data: &[u8]) -> Hash;
+
+    /// Hash two child nodes
+    fn hash_node(&self, left: &Hash, right: &Hash) -> Hash;
+
+    /// Leaf for a SHA-256 digest such as an event or context hash, which trees and logs
+    /// are built from. Other hashers hash the digest bytes as leaf data.
+    fn hash_digest_leaf(&self, digest: &Hash) -> Hash {
+        self.hash_leaf(&digest.0)
+    }
+}
+
+/// SHA-256 hasher, equivalent to [`Hash::digest`] and [`Hash::combine`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha256Hasher;
+
+impl MerkleHasher for Sha256Hasher {
+    fn algorithm(&self) -> HashAlgorithm {
+        HashAlgorithm::Sha256
+    }
+
+    fn hash_leaf(&self, data: &[u8]) -> Hash {
+        Hash::digest(data)
+    }
+
+    fn hash_node(&self, left: &Hash, right: &Hash) -> Hash {
+        Hash::combine(left, right)
+    }
+
+    /// A [`Hash::digest`] output is already a SHA-256 leaf
+    fn hash_digest_leaf(&self, digest: &Hash) -> Hash {
+        digest.clone()
+    }
+}
+
+/// SHA-512/256 hasher (faster than SHA-256 on 64-bit CPUs without SHA extensions)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha512_256Hasher;
+
+impl MerkleHasher for Sha512_256Hasher {
+    fn algorithm(&self) -> HashAlgorithm {
+        HashAlgorithm::Sha512_256
+    }
+
+    fn hash_leaf(&self, data: &[u8]) -> Hash {
+        let mut hasher = Sha512_256::new();
+        hasher.update([0x00]); // Leaf prefix
+        hasher.update(data);
+        Hash(hasher.finalize().into())
+    }
+
+    fn hash_node(&self, left: &Hash, right: &Hash) -> Hash {
+        let mut hasher = Sha512_256::new();
+        hasher.update([0x01]); // Internal prefix
+        hasher.update(left.0);
+        hasher.update(right.0);
+        Hash(hasher.finalize().into())
+    }
+}
+
+/// BLAKE3 hasher
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Blake3Hasher;
+
+impl MerkleHasher for Blake3Hasher {
+    fn algorithm(&self) -> HashAlgorithm {
+        HashAlgorithm::Blake3
+    }
+
+    fn hash_leaf(&self, data: &[u8]) -> Hash {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&[0x00]); // Leaf prefix
+        hasher.update(data);
+        Hash(hasher.finalize().into())
+    }
+
+    fn hash_node(&self, left: &Hash, right: &Hash) -> Hash {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&[0x01]); // Internal prefix
+        hasher.update(&left.0);
+        hasher.update(&right.0);
+        Hash(hasher.finalize().into())
+    }
+}
+
 /// A node in the Merkle tree
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MerkleNode {
@@ -104,12 +262,29 @@ pub struct MerkleProof {
     pub path: Vec<ProofStep>,
     /// The expected root hash
     pub root_hash: Hash,
+    /// Hash function used for internal nodes (absent in pre-v1.7 proofs: SHA-256)
+    #[serde(default)]
+    pub algorithm: HashAlgorithm,
 }
 
 impl MerkleProof {
     /// Verify this proof against a root hash
+    /// Returns false (and logs a warning) if the proof's algorithm has no built-in
+    /// implementation; use [`MerkleProof::try_verify`] to get that as an error, or
+    /// [`MerkleProof::verify_with`] with the downstream hasher.
     pub fn verify(&self, expected_root: &Hash) -> bool {
-        if &self.root_hash != expected_root {
+        verified_or_warn(self.try_verify(expected_root))
+    }
+
+    /// Verify this proof with the built-in hasher for its algorithm
+    pub fn try_verify(&self, expected_root: &Hash) -> Result<bool, UnsupportedHashAlgorithm> {
+        let hasher = self.algorithm.require_builtin()?;
+        Ok(self.verify_with(expected_root, hasher))
+    }
+
+    /// Verify this proof against a root hash using `hasher`
+    pub fn verify_with(&self, expected_root: &Hash, hasher: &dyn MerkleHasher) -> bool {
+        if &self.root_hash != expected_root || hasher.algorithm() != self.algorithm {
             return false;
         }
 
@@ -117,8 +292,8 @@ impl MerkleProof {
 
         for step in &self.path {
             current_hash = match step.direction {
-                ProofDirection::Left => Hash::combine(&step.sibling_hash, &current_hash),
-                ProofDirection::Right => Hash::combine(&current_hash, &step.sibling_hash),
+                ProofDirection::Left => hasher.hash_node(&step.sibling_hash, &current_hash),
+                ProofDirection::Right => hasher.hash_node(&current_hash, &step.sibling_hash),
             };
         }
 
@@ -161,11 +336,134 @@ impl MerkleProof {
     }
 }
 
+/// Largest power of two strictly smaller than `n` (`n >= 2`): the number of leaves in the
+/// left subtree of an RFC 6962 tree of `n` leaves.
+pub(crate) fn split_point(n: u64) -> u64 {
+    1 << (63 - (n - 1).leading_zeros())
+}
+
+/// A disclosed leaf in a [`MerkleMultiProof`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultiProofLeaf {
+    /// Position of the leaf in the tree
+    pub index: usize,
+    /// The leaf's data ID
+    pub leaf_id: String,
+    /// The leaf hash being proven
+    pub leaf_hash: Hash,
+}
+
+/// A compact inclusion proof for several leaves of the same tree.
+///
+/// Only the roots of subtrees that contain none of the proven leaves are included,
+/// so siblings shared between leaves appear once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MerkleMultiProof {
+    /// Number of leaves in the tree, which fixes its shape
+    pub leaf_count: usize,
+    /// The proven leaves, sorted by index
+    pub leaves: Vec<MultiProofLeaf>,
+    /// Subtree hashes needed to rebuild the root, in depth-first left-to-right order
+    pub siblings: Vec<Hash>,
+    /// The expected root hash
+    pub root_hash: Hash,
+    /// Hash function used for internal nodes
+    #[serde(default)]
+    pub algorithm: HashAlgorithm,
+}
+
+impl MerkleMultiProof {
+    /// Verify this multiproof against a root hash
+    /// Returns false (and logs a warning) if the proof's algorithm has no built-in
+    /// implementation; use [`MerkleMultiProof::try_verify`] to get that as an error, or
+    /// [`MerkleMultiProof::verify_with`] with the downstream hasher.
+    pub fn verify(&self, expected_root: &Hash) -> bool {
+        verified_or_warn(self.try_verify(expected_root))
+    }
+
+    /// Verify this multiproof with the built-in hasher for its algorithm
+    pub fn try_verify(&self, expected_root: &Hash) -> Result<bool, UnsupportedHashAlgorithm> {
+        let hasher = self.algorithm.require_builtin()?;
+        Ok(self.verify_with(expected_root, hasher))
+    }
+
+    /// Verify this multiproof against a root hash using `hasher`
+    pub fn verify_with(&self, expected_root: &Hash, hasher: &dyn MerkleHasher) -> bool {
+        if &self.root_hash != expected_root
+            || self.leaves.is_empty()
+            || hasher.algorithm() != self.algorithm
+        {
+            return false;
+        }
+
+        let sorted = self
+            .leaves
+            .windows(2)
+            .all(|pair| pair[0].index < pair[1].index);
+        let in_range = self
+            .leaves
+            .last()
+            .is_some_and(|leaf| leaf.index < self.leaf_count);
+        if !sorted || !in_range {
+            return false;
+        }
+
+        let mut siblings = self.siblings.iter();
+        let root = Self::rebuild(hasher, 0, self.leaf_count, &self.leaves, &mut siblings);
+
+        siblings.next().is_none() && root.as_ref() == Some(expected_root)
+    }
+
+    fn rebuild<'a>(
+        hasher: &dyn MerkleHasher,
+        lo: usize,
+        hi: usize,
+        leaves: &[MultiProofLeaf],
+        siblings: &mut impl Iterator<Item = &'a Hash>,
+    ) -> Option<Hash> {
+        if leaves.is_empty() {
+            return siblings.next().cloned();
+        }
+        if hi - lo == 1 {
+            return Some(leaves[0].leaf_hash.clone());
+        }
+
+        let mid = lo + split_point((hi - lo) as u64) as usize;
+        let split = leaves.partition_point(|leaf| leaf.index < mid);
+        let left = Self::rebuild(hasher, lo, mid, &leaves[..split], siblings)?;
+        let right = Self::rebuild(hasher, mid, hi, &leaves[split..], siblings)?;
+        Some(hasher.hash_node(&left, &right))
+    }
+
+    /// Export proof as a compact JSON string for transmission
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Import proof from JSON string with size limit
+    pub fn from_json_with_limit(json: &str, max_size: usize) -> Result<Self, String> {
+        if json.len() > max_size {
+            return Err(format!(
+                "Proof JSON too large: {} bytes exceeds limit of {} bytes",
+                json.len(),
+                max_size
+            ));
+        }
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    /// Import proof from JSON string with the default 1MB limit
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        Self::from_json_with_limit(json, MerkleProof::MAX_PROOF_JSON_SIZE)
+    }
+}
+
 /// A Merkle tree for verifying context packet integrity
 #[derive(Debug, Clone)]
 pub struct MerkleTree {
     root: Option<MerkleNode>,
     leaf_count: usize,
+    algorithm: HashAlgorithm,
 }
 
 impl MerkleTree {
@@ -174,6 +472,7 @@ impl MerkleTree {
         Self {
             root: None,
             leaf_count: 0,
+            algorithm: HashAlgorithm::Sha256,
         }
     }
 
@@ -184,14 +483,27 @@ impl MerkleTree {
     /// Bitcoin's Merkle tree construction but avoids the vulnerability of duplicating
     /// the last hash (which can create ambiguous proofs).
     pub fn from_leaves(leaves: Vec<(String, Hash)>) -> Self {
+        Self::from_leaves_with_hasher(leaves, &Sha256Hasher)
+    }
+
+    /// Build a Merkle tree hashed with `hasher`.
+    /// The given leaf hashes are turned into leaves with [`MerkleHasher::hash_digest_leaf`],
+    /// which keeps them as they are for SHA-256.
+    pub fn from_leaves_with_hasher(leaves: Vec<(String, Hash)>, hasher: &dyn MerkleHasher) -> Self {
         if leaves.is_empty() {
-            return Self::new();
+            return Self {
+                algorithm: hasher.algorithm(),
+                ..Self::new()
+            };
         }
 
         let leaf_count = leaves.len();
         let mut nodes: Vec<MerkleNode> = leaves
             .into_iter()
-            .map(|(data_id, hash)| MerkleNode::Leaf { hash, data_id })
+            .map(|(data_id, hash)| MerkleNode::Leaf {
+                hash: hasher.hash_digest_leaf(&hash),
+                data_id,
+            })
             .collect();
 
         // Build tree bottom-up using move semantics (no cloning)
@@ -201,7 +513,7 @@ impl MerkleTree {
 
             while let Some(left_node) = iter.next() {
                 if let Some(right_node) = iter.next() {
-                    let combined_hash = Hash::combine(left_node.hash(), right_node.hash());
+                    let combined_hash = hasher.hash_node(left_node.hash(), right_node.hash());
                     next_level.push(MerkleNode::Internal {
                         hash: combined_hash,
                         left: Box::new(left_node),
@@ -219,9 +531,15 @@ impl MerkleTree {
         Self {
             root: nodes.into_iter().next(),
             leaf_count,
+            algorithm: hasher.algorithm(),
         }
     }
 
+    /// Hash function used for internal nodes
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.algorithm
+    }
+
     /// Get the root hash (None if tree is empty)
     pub fn root_hash(&self) -> Option<&Hash> {
         self.root.as_ref().map(|n| n.hash())
@@ -295,8 +613,115 @@ impl MerkleTree {
         }
     }
 
+    /// Generate an inclusion proof for the leaf at `index` in O(log n)
+    /// Returns None if the index is out of range
+    pub fn get_proof(&self, index: usize) -> Option<MerkleProof> {
+        if index >= self.leaf_count {
+            return None;
+        }
+
+        let mut node = self.root.as_ref()?;
+        let mut lo = 0;
+        let mut hi = self.leaf_count;
+        let mut path = Vec::new();
+
+        loop {
+            match node {
+                MerkleNode::Leaf { hash, data_id } => {
+                    // Steps were collected root to leaf
+                    path.reverse();
+                    return Some(MerkleProof {
+                        leaf_hash: hash.clone(),
+                        leaf_id: data_id.clone(),
+                        path,
+                        root_hash: self.root_hash()?.clone(),
+                        algorithm: self.algorithm,
+                    });
+                }
+                MerkleNode::Internal { left, right, .. } => {
+                    let mid = lo + split_point((hi - lo) as u64) as usize;
+                    if index < mid {
+                        path.push(ProofStep {
+                            sibling_hash: right.hash().clone(),
+                            direction: ProofDirection::Right,
+                        });
+                        node = left;
+                        hi = mid;
+                    } else {
+                        path.push(ProofStep {
+                            sibling_hash: left.hash().clone(),
+                            direction: ProofDirection::Left,
+                        });
+                        node = right;
+                        lo = mid;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generate a compact multiproof for the leaves at `indices`
+    /// Returns None if any index is out of range or no index is given
+    pub fn get_multiproof(&self, indices: &[usize]) -> Option<MerkleMultiProof> {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        if indices.is_empty() || indices.last()? >= &self.leaf_count {
+            return None;
+        }
+
+        let mut leaves = Vec::with_capacity(indices.len());
+        let mut siblings = Vec::new();
+        Self::collect_multiproof(
+            self.root.as_ref()?,
+            0,
+            self.leaf_count,
+            &indices,
+            &mut leaves,
+            &mut siblings,
+        );
+
+        Some(MerkleMultiProof {
+            leaf_count: self.leaf_count,
+            leaves,
+            siblings,
+            root_hash: self.root_hash()?.clone(),
+            algorithm: self.algorithm,
+        })
+    }
+
+    /// Helper: Depth-first walk emitting proven leaves and untouched subtree hashes
+    fn collect_multiproof(
+        node: &MerkleNode,
+        lo: usize,
+        hi: usize,
+        indices: &[usize],
+        leaves: &mut Vec<MultiProofLeaf>,
+        siblings: &mut Vec<Hash>,
+    ) {
+        if indices.is_empty() {
+            siblings.push(node.hash().clone());
+            return;
+        }
+
+        match node {
+            MerkleNode::Leaf { hash, data_id } => leaves.push(MultiProofLeaf {
+                index: lo,
+                leaf_id: data_id.clone(),
+                leaf_hash: hash.clone(),
+            }),
+            MerkleNode::Internal { left, right, .. } => {
+                let mid = lo + split_point((hi - lo) as u64) as usize;
+                let split = indices.partition_point(|&i| i < mid);
+                Self::collect_multiproof(left, lo, mid, &indices[..split], leaves, siblings);
+                Self::collect_multiproof(right, mid, hi, &indices[split..], leaves, siblings);
+            }
+        }
+    }
+
     /// Generate an inclusion proof for a leaf by its hash
-    /// Returns None if the hash is not found in the tree
+    /// Returns None if the hash is not found in the tree.
+    /// If several leaves share the hash the first one is used; prefer [`MerkleTree::get_proof`].
     pub fn get_proof_by_hash(&self, target_hash: &Hash) -> Option<MerkleProof> {
         let root = self.root.as_ref()?;
         let root_hash = root.hash().clone();
@@ -309,6 +734,7 @@ impl MerkleTree {
             leaf_id,
             path,
             root_hash,
+            algorithm: self.algorithm,
         })
     }
 
@@ -570,6 +996,176 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_proof_by_index_with_duplicate_hashes() {
+        let duplicate = Hash::digest(b"same_event");
+        let leaves: Vec<(String, Hash)> = (0..11)
+            .map(|i| {
+                let hash = if i % 3 == 0 {
+                    duplicate.clone()
+                } else {
+                    Hash::digest(format!("event_{}", i).as_bytes())
+                };
+                (format!("event_{}", i), hash)
+            })
+            .collect();
+
+        let tree = MerkleTree::from_leaves(leaves.clone());
+        let root = tree.root_hash().unwrap();
+
+        for (index, (id, hash)) in leaves.iter().enumerate() {
+            let proof = tree.get_proof(index).expect("index in range");
+            assert_eq!(&proof.leaf_id, id);
+            assert_eq!(&proof.leaf_hash, hash);
+            assert!(proof.verify(root));
+        }
+        assert!(tree.get_proof(11).is_none());
+    }
+
+    #[test]
+    fn test_multiproof_shares_siblings() {
+        let leaves: Vec<(String, Hash)> = (0..13)
+            .map(|i| {
+                (
+                    format!("event_{}", i),
+                    Hash::digest(format!("data_{}", i).as_bytes()),
+                )
+            })
+            .collect();
+        let tree = MerkleTree::from_leaves(leaves);
+        let root = tree.root_hash().unwrap();
+
+        let indices = [0, 1, 2, 3, 7, 12];
+        let multiproof = tree.get_multiproof(&[12, 3, 0, 7, 2, 1, 3]).unwrap();
+        assert_eq!(
+            multiproof
+                .leaves
+                .iter()
+                .map(|l| l.index)
+                .collect::<Vec<_>>(),
+            indices
+        );
+        assert!(multiproof.verify(root));
+
+        let individual: usize = indices
+            .iter()
+            .map(|&i| tree.get_proof(i).unwrap().path.len())
+            .sum();
+        assert!(multiproof.siblings.len() < individual);
+
+        let restored = MerkleMultiProof::from_json(&multiproof.to_json().unwrap()).unwrap();
+        assert!(restored.verify(root));
+
+        let mut tampered = multiproof.clone();
+        tampered.leaves[4].leaf_hash = Hash::digest(b"tampered");
+        assert!(!tampered.verify(root));
+
+        let mut extra = multiproof.clone();
+        extra.siblings.push(Hash::digest(b"extra"));
+        assert!(!extra.verify(root));
+
+        let mut reshaped = multiproof;
+        reshaped.leaf_count = 14;
+        assert!(!reshaped.verify(root));
+
+        assert!(tree.get_multiproof(&[]).is_none());
+        assert!(tree.get_multiproof(&[13]).is_none());
+    }
+
+    /// Stand-in for a downstream hasher such as Poseidon
+    #[derive(Debug)]
+    struct TestPoseidon;
+
+    impl MerkleHasher for TestPoseidon {
+        fn algorithm(&self) -> HashAlgorithm {
+            HashAlgorithm::Poseidon
+        }
+
+        fn hash_leaf(&self, data: &[u8]) -> Hash {
+            Sha512_256Hasher.hash_leaf(data)
+        }
+
+        fn hash_node(&self, left: &Hash, right: &Hash) -> Hash {
+            Sha512_256Hasher.hash_node(right, left)
+        }
+    }
+
+    #[test]
+    fn test_pluggable_hash_algorithms() {
+        let leaves: Vec<(String, Hash)> = (0..6)
+            .map(|i| {
+                (
+                    format!("event_{}", i),
+                    Hash::digest(format!("data_{}", i).as_bytes()),
+                )
+            })
+            .collect();
+
+        let sha256 = MerkleTree::from_leaves(leaves.clone());
+        let sha512 = MerkleTree::from_leaves_with_hasher(leaves.clone(), &Sha512_256Hasher);
+        let blake3 = MerkleTree::from_leaves_with_hasher(leaves.clone(), &Blake3Hasher);
+        let poseidon = MerkleTree::from_leaves_with_hasher(leaves.clone(), &TestPoseidon);
+        assert_eq!(sha512.algorithm(), HashAlgorithm::Sha512_256);
+        assert_ne!(sha256.root_hash(), sha512.root_hash());
+        assert_ne!(sha512.root_hash(), blake3.root_hash());
+        assert_ne!(sha512.root_hash(), poseidon.root_hash());
+
+        // Leaves are hashed with the tree's hasher too; SHA-256 keeps the digests as given
+        assert_eq!(sha256.get_proof(4).unwrap().leaf_hash, leaves[4].1);
+        assert_eq!(
+            sha512.get_proof(4).unwrap().leaf_hash,
+            Sha512_256Hasher.hash_leaf(&leaves[4].1 .0)
+        );
+
+        let proof = blake3.get_proof(2).unwrap();
+        assert_eq!(proof.algorithm, HashAlgorithm::Blake3);
+        assert_eq!(proof.leaf_hash, Blake3Hasher.hash_leaf(&leaves[2].1 .0));
+        assert_eq!(proof.try_verify(blake3.root_hash().unwrap()), Ok(true));
+
+        let proof = sha512.get_proof(4).unwrap();
+        assert_eq!(proof.algorithm, HashAlgorithm::Sha512_256);
+        assert!(proof.verify(sha512.root_hash().unwrap()));
+        assert!(!proof.verify_with(sha512.root_hash().unwrap(), &Sha256Hasher));
+
+        let mut relabelled = proof.clone();
+        relabelled.algorithm = HashAlgorithm::Sha256;
+        assert!(!relabelled.verify(sha512.root_hash().unwrap()));
+
+        // No built-in Poseidon: verification needs the downstream hasher
+        let root = poseidon.root_hash().unwrap();
+        let proof = poseidon.get_proof(1).unwrap();
+        assert!(!proof.verify(root));
+        assert_eq!(
+            proof.try_verify(root),
+            Err(UnsupportedHashAlgorithm(HashAlgorithm::Poseidon))
+        );
+        assert!(proof.verify_with(root, &TestPoseidon));
+        let multiproof = poseidon.get_multiproof(&[0, 5]).unwrap();
+        assert!(!multiproof.verify(root));
+        assert!(multiproof.try_verify(root).is_err());
+        assert!(multiproof.verify_with(root, &TestPoseidon));
+
+        let json = proof.to_json().unwrap();
+        assert!(json.contains("\"algorithm\":\"poseidon\""));
+    }
+
+    #[test]
+    fn test_legacy_proof_defaults_to_sha256() {
+        let leaves = vec![
+            ("a".to_string(), Hash::digest(b"a")),
+            ("b".to_string(), Hash::digest(b"b")),
+        ];
+        let tree = MerkleTree::from_leaves(leaves);
+        let proof = tree.get_proof(0).unwrap();
+
+        let mut json: serde_json::Value = serde_json::from_str(&proof.to_json().unwrap()).unwrap();
+        json.as_object_mut().unwrap().remove("algorithm");
+        let legacy = MerkleProof::from_json(&json.to_string()).unwrap();
+
+        assert_eq!(legacy.algorithm, HashAlgorithm::Sha256);
+        assert!(legacy.verify(tree.root_hash().unwrap()));
+    }
+
     #[test]
     fn test_merkle_proof_tamper_detection() {
         let leaves = vec![
@@ -0,0 +1,790 @@
+//! Append-only Merkle log (RFC 6962 / RFC 9162)
+//!
+//! [`IncrementalMerkleTree`] supports O(log n) appends, inclusion proofs by leaf index
+//! and consistency proofs between two tree sizes. [`MerkleFrontier`] is the compact
+//! representation (one hash per perfect subtree) for callers that only need appends
+//! and the current root.
+//!
+//! Logs kept outside memory (e.g. one storage key per node) implement [`MerkleNodes`]
+//! and are read through a [`MerkleLogView`]; a [`NodeRecorder`] lists the nodes a
+//! proof reads so they can be fetched up front.
+//!
+//! Both produce the same roots as [`MerkleTree::from_leaves`](crate::merkle::MerkleTree::from_leaves)
+//! for the same leaf hashes, since carrying odd nodes up yields the RFC 6962 tree shape.
+//! The incremental tree accepts any [`MerkleHasher`]; the frontier is SHA-256 only.
+
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use crate::merkle::{
+    split_point, verified_or_warn, Hash, HashAlgorithm, MerkleHasher, MerkleMultiProof,
+    MerkleProof, MultiProofLeaf, ProofDirection, ProofStep, Sha256Hasher, UnsupportedHashAlgorithm,
+};
+
+/// Position of a perfect subtree: `2^height` leaves starting at leaf `index << height`
+pub type NodeId = (u32, u64);
+
+/// Perfect subtree hashes of an append-only Merkle log
+pub trait MerkleNodes {
+    /// Root of the perfect subtree at `id`, if known (height 0 holds the leaves)
+    fn node(&self, id: NodeId) -> Option<Hash>;
+}
+
+impl MerkleNodes for HashMap<NodeId, Hash> {
+    fn node(&self, id: NodeId) -> Option<Hash> {
+        self.get(&id).cloned()
+    }
+}
+
+/// Records the nodes a computation reads, answering each with a placeholder.
+///
+/// Node positions never depend on hash values, so running a [`MerkleLogView`]
+/// method over a recorder lists exactly the nodes the real call will need.
+#[derive(Debug, Default)]
+pub struct NodeRecorder {
+    read: RefCell<BTreeSet<NodeId>>,
+}
+
+impl NodeRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The nodes read so far, in order
+    pub fn into_nodes(self) -> Vec<NodeId> {
+        self.read.into_inner().into_iter().collect()
+    }
+}
+
+impl MerkleNodes for NodeRecorder {
+    fn node(&self, id: NodeId) -> Option<Hash> {
+        self.read.borrow_mut().insert(id);
+        Some(Hash([0; 32]))
+    }
+}
+
+/// The first `size` leaves of a log whose perfect subtree hashes are [`MerkleNodes`].
+///
+/// Roots and proofs read O(log n) nodes (O(log^2 n) for consistency proofs), and
+/// return None if a node they need is missing.
+#[derive(Debug)]
+pub struct MerkleLogView<'a, N: ?Sized> {
+    nodes: &'a N,
+    size: u64,
+    hasher: &'a dyn MerkleHasher,
+}
+
+impl<'a, N: MerkleNodes + ?Sized> MerkleLogView<'a, N> {
+    pub fn new(nodes: &'a N, size: u64, hasher: &'a dyn MerkleHasher) -> Self {
+        Self {
+            nodes,
+            size,
+            hasher,
+        }
+    }
+
+    /// Number of leaves in view
+    pub fn len(&self) -> u64 {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Root hash (None if empty)
+    pub fn root(&self) -> Option<Hash> {
+        if self.size == 0 {
+            return None;
+        }
+        self.range_hash(0, self.size)
+    }
+
+    /// Nodes completed by appending `leaf` as leaf `size`, the leaf itself first.
+    /// `leaf` goes through [`MerkleHasher::hash_digest_leaf`], as in [`IncrementalMerkleTree::append`].
+    pub fn append(&self, leaf: Hash) -> Option<Vec<(NodeId, Hash)>> {
+        let mut position = self.size;
+        let mut height = 0;
+        let mut hash = self.hasher.hash_digest_leaf(&leaf);
+        let mut completed = vec![((height, position), hash.clone())];
+        while position & 1 == 1 {
+            let left = self.nodes.node((height, position - 1))?;
+            hash = self.hasher.hash_node(&left, &hash);
+            position >>= 1;
+            height += 1;
+            completed.push(((height, position), hash.clone()));
+        }
+        Some(completed)
+    }
+
+    /// Inclusion proof for leaf `index`
+    pub fn inclusion_proof(&self, index: u64) -> Option<MerkleProof> {
+        if index >= self.size {
+            return None;
+        }
+
+        let mut path = Vec::new();
+        self.inclusion_path(index, 0, self.size, &mut path)?;
+
+        Some(MerkleProof {
+            leaf_hash: self.nodes.node((0, index))?,
+            leaf_id: index.to_string(),
+            path,
+            root_hash: self.root()?,
+            algorithm: self.hasher.algorithm(),
+        })
+    }
+
+    /// Proof that the first `old_size` leaves are a prefix of this log
+    pub fn consistency_proof(&self, old_size: u64) -> Option<ConsistencyProof> {
+        if old_size > self.size {
+            return None;
+        }
+
+        let mut path = Vec::new();
+        if old_size > 0 && old_size < self.size {
+            self.consistency_path(old_size, 0, self.size, true, &mut path)?;
+        }
+
+        Some(ConsistencyProof {
+            old_size,
+            new_size: self.size,
+            path,
+            algorithm: self.hasher.algorithm(),
+        })
+    }
+
+    /// Multiproof for the given `(index, leaf_id)` leaves
+    /// Returns None if no leaf is given or an index is out of range.
+    pub fn multiproof(&self, leaves: &[(u64, String)]) -> Option<MerkleMultiProof> {
+        let mut leaves = leaves.to_vec();
+        leaves.sort_unstable_by_key(|(index, _)| *index);
+        leaves.dedup_by_key(|(index, _)| *index);
+        if leaves.is_empty() || leaves.last()?.0 >= self.size {
+            return None;
+        }
+
+        let mut proven = Vec::with_capacity(leaves.len());
+        let mut siblings = Vec::new();
+        self.multiproof_walk(0, self.size, &leaves, &mut proven, &mut siblings)?;
+
+        Some(MerkleMultiProof {
+            leaf_count: self.size as usize,
+            leaves: proven,
+            siblings,
+            root_hash: self.root()?,
+            algorithm: self.hasher.algorithm(),
+        })
+    }
+
+    /// MTH(D[lo:hi]) per RFC 6962, from the stored perfect subtrees
+    fn range_hash(&self, lo: u64, hi: u64) -> Option<Hash> {
+        let n = hi - lo;
+        if n.is_power_of_two() && lo.is_multiple_of(n) {
+            let height = n.trailing_zeros();
+            return self.nodes.node((height, lo >> height));
+        }
+        let k = split_point(n);
+        Some(
+            self.hasher
+                .hash_node(&self.range_hash(lo, lo + k)?, &self.range_hash(lo + k, hi)?),
+        )
+    }
+
+    /// PATH(m, D[lo:hi]), collected from leaf to root
+    fn inclusion_path(
+        &self,
+        index: u64,
+        lo: u64,
+        hi: u64,
+        path: &mut Vec<ProofStep>,
+    ) -> Option<()> {
+        if hi - lo == 1 {
+            return Some(());
+        }
+        let k = split_point(hi - lo);
+        if index < lo + k {
+            self.inclusion_path(index, lo, lo + k, path)?;
+            path.push(ProofStep {
+                sibling_hash: self.range_hash(lo + k, hi)?,
+                direction: ProofDirection::Right,
+            });
+        } else {
+            self.inclusion_path(index, lo + k, hi, path)?;
+            path.push(ProofStep {
+                sibling_hash: self.range_hash(lo, lo + k)?,
+                direction: ProofDirection::Left,
+            });
+        }
+        Some(())
+    }
+
+    /// SUBPROOF(m, D[lo:hi], b), with `m` relative to `lo`
+    fn consistency_path(
+        &self,
+        m: u64,
+        lo: u64,
+        hi: u64,
+        complete: bool,
+        path: &mut Vec<Hash>,
+    ) -> Option<()> {
+        if lo + m == hi {
+            if !complete {
+                path.push(self.range_hash(lo, hi)?);
+            }
+            return Some(());
+        }
+        let k = split_point(hi - lo);
+        if m <= k {
+            self.consistency_path(m, lo, lo + k, complete, path)?;
+            path.push(self.range_hash(lo + k, hi)?);
+        } else {
+            self.consistency_path(m - k, lo + k, hi, false, path)?;
+            path.push(self.range_hash(lo, lo + k)?);
+        }
+        Some(())
+    }
+
+    /// Depth-first walk emitting proven leaves and untouched subtree hashes
+    fn multiproof_walk(
+        &self,
+        lo: u64,
+        hi: u64,
+        leaves: &[(u64, String)],
+        proven: &mut Vec<MultiProofLeaf>,
+        siblings: &mut Vec<Hash>,
+    ) -> Option<()> {
+        if leaves.is_empty() {
+            siblings.push(self.range_hash(lo, hi)?);
+            return Some(());
+        }
+        if hi - lo == 1 {
+            proven.push(MultiProofLeaf {
+                index: lo as usize,
+                leaf_id: leaves[0].1.clone(),
+                leaf_hash: self.nodes.node((0, lo))?,
+            });
+            return Some(());
+        }
+        let mid = lo + split_point(hi - lo);
+        let split = leaves.partition_point(|(index, _)| *index < mid);
+        self.multiproof_walk(lo, mid, &leaves[..split], proven, siblings)?;
+        self.multiproof_walk(mid, hi, &leaves[split..], proven, siblings)
+    }
+}
+
+/// Roots of the perfect subtrees covering an append-only SHA-256 log, largest first.
+///
+/// Deserialization rejects a node count that does not match the size (one node per
+/// set bit), so appends never see a truncated frontier.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "FrontierParts")]
+pub struct MerkleFrontier {
+    size: u64,
+    nodes: Vec<Hash>,
+}
+
+/// Unvalidated serialized form of [`MerkleFrontier`]
+#[derive(Deserialize)]
+struct FrontierParts {
+    size: u64,
+    nodes: Vec<Hash>,
+}
+
+impl TryFrom<FrontierParts> for MerkleFrontier {
+    type Error = String;
+
+    fn try_from(parts: FrontierParts) -> Result<Self, Self::Error> {
+        if parts.nodes.len() != parts.size.count_ones() as usize {
+            return Err(format!(
+                "frontier of size {} needs {} nodes, got {}",
+                parts.size,
+                parts.size.count_ones(),
+                parts.nodes.len()
+            ));
+        }
+        Ok(Self {
+            size: parts.size,
+            nodes: parts.nodes,
+        })
+    }
+}
+
+impl MerkleFrontier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of leaves appended so far
+    pub fn len(&self) -> u64 {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// The perfect subtree roots, ordered from the left of the tree
+    pub fn nodes(&self) -> &[Hash] {
+        &self.nodes
+    }
+
+    /// Append a leaf hash in O(log n)
+    pub fn append(&mut self, leaf: Hash) {
+        let mut hash = leaf;
+        let mut size = self.size;
+        while size & 1 == 1 {
+            let left = self.nodes.pop().expect("frontier matches size");
+            hash = Hash::combine(&left, &hash);
+            size >>= 1;
+        }
+        self.nodes.push(hash);
+        self.size += 1;
+    }
+
+    /// Current root hash (None if empty)
+    pub fn root(&self) -> Option<Hash> {
+        let mut nodes = self.nodes.iter().rev();
+        let last = nodes.next()?.clone();
+        Some(nodes.fold(last, |acc, node| Hash::combine(node, &acc)))
+    }
+}
+
+impl Extend<Hash> for MerkleFrontier {
+    fn extend<I: IntoIterator<Item = Hash>>(&mut self, leaves: I) {
+        for leaf in leaves {
+            self.append(leaf);
+        }
+    }
+}
+
+/// Proof that a tree of `old_size` leaves is a prefix of a tree of `new_size` leaves
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsistencyProof {
+    pub old_size: u64,
+    pub new_size: u64,
+    pub path: Vec<Hash>,
+    /// Hash function used for internal nodes
+    #[serde(default)]
+    pub algorithm: HashAlgorithm,
+}
+
+impl ConsistencyProof {
+    /// Verify this proof against both roots (RFC 9162, section 2.1.4.2)
+    /// Returns false (and logs a warning) if the proof's algorithm has no built-in
+    /// implementation; use [`ConsistencyProof::try_verify`] to get that as an error, or
+    /// [`ConsistencyProof::verify_with`] with the downstream hasher.
+    pub fn verify(&self, old_root: &Hash, new_root: &Hash) -> bool {
+        verified_or_warn(self.try_verify(old_root, new_root))
+    }
+
+    /// Verify this proof with the built-in hasher for its algorithm
+    pub fn try_verify(
+        &self,
+        old_root: &Hash,
+        new_root: &Hash,
+    ) -> Result<bool, UnsupportedHashAlgorithm> {
+        let hasher = self.algorithm.require_builtin()?;
+        Ok(self.verify_with(old_root, new_root, hasher))
+    }
+
+    /// Verify this proof against both roots using `hasher`
+    pub fn verify_with(&self, old_root: &Hash, new_root: &Hash, hasher: &dyn MerkleHasher) -> bool {
+        let (first, second) = (self.old_size, self.new_size);
+
+        if first > second || hasher.algorithm() != self.algorithm {
+            return false;
+        }
+        if first == second {
+            return self.path.is_empty() && old_root == new_root;
+        }
+        if first == 0 {
+            // Every tree is consistent with the empty tree
+            return self.path.is_empty();
+        }
+
+        let mut path: Vec<&Hash> = Vec::with_capacity(self.path.len() + 1);
+        if first.is_power_of_two() {
+            path.push(old_root);
+        }
+        path.extend(self.path.iter());
+
+        let Some((seed, rest)) = path.split_first() else {
+            return false;
+        };
+
+        let mut fn_ = first - 1;
+        let mut sn = second - 1;
+        while fn_ & 1 == 1 {
+            fn_ >>= 1;
+            sn >>= 1;
+        }
+
+        let mut fr = (*seed).clone();
+        let mut sr = (*seed).clone();
+
+        for c in rest {
+            if sn == 0 {
+                return false;
+            }
+            if fn_ & 1 == 1 || fn_ == sn {
+                fr = hasher.hash_node(c, &fr);
+                sr = hasher.hash_node(c, &sr);
+                while fn_ & 1 == 0 && fn_ != 0 {
+                    fn_ >>= 1;
+                    sn >>= 1;
+                }
+            } else {
+                sr = hasher.hash_node(&sr, c);
+            }
+            fn_ >>= 1;
+            sn >>= 1;
+        }
+
+        &fr == old_root && &sr == new_root && sn == 0
+    }
+}
+
+/// An append-only Merkle tree that keeps every perfect subtree hash.
+///
+/// `levels[h][i]` is the root of the perfect subtree of `2^h` leaves starting at leaf
+/// `i * 2^h`, so any subtree hash needed by a proof is found in O(log n).
+#[derive(Debug, Clone)]
+pub struct IncrementalMerkleTree {
+    levels: Vec<Vec<Hash>>,
+    hasher: Arc<dyn MerkleHasher>,
+}
+
+impl Default for IncrementalMerkleTree {
+    fn default() -> Self {
+        Self::with_hasher(Arc::new(Sha256Hasher))
+    }
+}
+
+impl IncrementalMerkleTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty tree hashed with `hasher`
+    pub fn with_hasher(hasher: Arc<dyn MerkleHasher>) -> Self {
+        Self {
+            levels: Vec::new(),
+            hasher,
+        }
+    }
+
+    /// Hash function used for internal nodes
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.hasher.algorithm()
+    }
+
+    /// Number of leaves
+    pub fn len(&self) -> u64 {
+        self.levels.first().map_or(0, |leaves| leaves.len() as u64)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Leaf hash at `index`
+    pub fn leaf(&self, index: u64) -> Option<&Hash> {
+        self.levels.first()?.get(index as usize)
+    }
+
+    /// Append a leaf hash in O(log n), returning its index.
+    /// Hashers other than SHA-256 store [`MerkleHasher::hash_digest_leaf`] of it.
+    pub fn append(&mut self, leaf: Hash) -> u64 {
+        if self.levels.is_empty() {
+            self.levels.push(Vec::new());
+        }
+        let leaf = self.hasher.hash_digest_leaf(&leaf);
+        self.levels[0].push(leaf);
+
+        let index = self.levels[0].len() - 1;
+        let mut position = index;
+        let mut height = 0;
+        while position & 1 == 1 {
+            let level = &self.levels[height];
+            let parent = self
+                .hasher
+                .hash_node(&level[position - 1], &level[position]);
+            if self.levels.len() == height + 1 {
+                self.levels.push(Vec::new());
+            }
+            self.levels[height + 1].push(parent);
+            position >>= 1;
+            height += 1;
+        }
+
+        index as u64
+    }
+
+    /// Current root hash (None if empty)
+    pub fn root(&self) -> Option<Hash> {
+        self.root_at(self.len())
+    }
+
+    /// The log as it was after `size` leaves
+    pub fn view(&self, size: u64) -> Option<MerkleLogView<'_, Self>> {
+        (size <= self.len()).then(|| MerkleLogView::new(self, size, self.hasher.as_ref()))
+    }
+
+    /// Root hash of the tree as it was after `size` leaves
+    pub fn root_at(&self, size: u64) -> Option<Hash> {
+        self.view(size)?.root()
+    }
+
+    /// Compact frontier of the current tree
+    /// Only meaningful for SHA-256 trees, since [`MerkleFrontier`] appends with SHA-256.
+    pub fn frontier(&self) -> MerkleFrontier {
+        let size = self.len();
+        let mut nodes = Vec::new();
+        let mut start = 0;
+        for height in (0..64).rev() {
+            if size & (1 << height) != 0 {
+                nodes.push(self.levels[height][(start >> height) as usize].clone());
+                start += 1 << height;
+            }
+        }
+        MerkleFrontier { size, nodes }
+    }
+
+    /// Every perfect subtree hash, for persisting the log node by node
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &Hash)> + '_ {
+        self.levels.iter().enumerate().flat_map(|(height, level)| {
+            level
+                .iter()
+                .enumerate()
+                .map(move |(index, hash)| ((height as u32, index as u64), hash))
+        })
+    }
+
+    /// Inclusion proof for leaf `index` in the tree of `tree_size` leaves
+    pub fn inclusion_proof(&self, index: u64, tree_size: u64) -> Option<MerkleProof> {
+        self.view(tree_size)?.inclusion_proof(index)
+    }
+
+    /// Consistency proof between the trees of `old_size` and `new_size` leaves
+    pub fn consistency_proof(&self, old_size: u64, new_size: u64) -> Option<ConsistencyProof> {
+        self.view(new_size)?.consistency_proof(old_size)
+    }
+}
+
+impl MerkleNodes for IncrementalMerkleTree {
+    fn node(&self, (height, index): NodeId) -> Option<Hash> {
+        self.levels
+            .get(height as usize)?
+            .get(index as usize)
+            .cloned()
+    }
+}
+
+impl Extend<Hash> for IncrementalMerkleTree {
+    fn extend<I: IntoIterator<Item = Hash>>(&mut self, leaves: I) {
+        for leaf in leaves {
+            self.append(leaf);
+        }
+    }
+}
+
+impl FromIterator<Hash> for IncrementalMerkleTree {
+    fn from_iter<I: IntoIterator<Item = Hash>>(leaves: I) -> Self {
+        let mut tree = Self::new();
+        tree.extend(leaves);
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::MerkleTree;
+
+    fn leaves(n: u64) -> Vec<Hash> {
+        (0..n)
+            .map(|i| Hash::digest(format!("leaf_{}", i).as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    fn test_roots_match_merkle_tree() {
+        let all = leaves(40);
+        let tree: IncrementalMerkleTree = all.iter().cloned().collect();
+        let mut frontier = MerkleFrontier::new();
+
+        for size in 1..=all.len() {
+            frontier.append(all[size - 1].clone());
+            let reference = MerkleTree::from_leaves(
+                all[..size]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, h)| (i.to_string(), h.clone()))
+                    .collect(),
+            );
+            let expected = reference.root_hash().cloned();
+
+            assert_eq!(tree.root_at(size as u64), expected, "size {}", size);
+            assert_eq!(frontier.root(), expected, "frontier size {}", size);
+        }
+
+        assert_eq!(tree.frontier(), frontier);
+        assert_eq!(frontier.nodes().len(), 40u64.count_ones() as usize);
+    }
+
+    #[test]
+    fn test_frontier_deserialize_checks_size() {
+        let mut frontier = MerkleFrontier::new();
+        frontier.extend(leaves(5));
+        let json = serde_json::to_value(&frontier).unwrap();
+        let decoded: MerkleFrontier = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(decoded, frontier);
+
+        // Size 7 needs three subtree roots, not two
+        let mut tampered = json;
+        tampered["size"] = serde_json::json!(7);
+        assert!(serde_json::from_value::<MerkleFrontier>(tampered).is_err());
+    }
+
+    #[test]
+    fn test_inclusion_proofs() {
+        let tree: IncrementalMerkleTree = leaves(13).into_iter().collect();
+
+        for size in 1..=13 {
+            let root = tree.root_at(size).unwrap();
+            for index in 0..size {
+                let proof = tree.inclusion_proof(index, size).unwrap();
+                assert!(proof.verify(&root), "leaf {} in size {}", index, size);
+            }
+        }
+
+        assert!(tree.inclusion_proof(13, 13).is_none());
+        assert!(tree.inclusion_proof(0, 14).is_none());
+    }
+
+    #[test]
+    fn test_consistency_proofs() {
+        let tree: IncrementalMerkleTree = leaves(17).into_iter().collect();
+
+        for new_size in 1..=17 {
+            let new_root = tree.root_at(new_size).unwrap();
+            for old_size in 1..=new_size {
+                let old_root = tree.root_at(old_size).unwrap();
+                let proof = tree.consistency_proof(old_size, new_size).unwrap();
+                assert!(
+                    proof.verify(&old_root, &new_root),
+                    "{} -> {}",
+                    old_size,
+                    new_size
+                );
+
+                if old_size < new_size {
+                    let forged = Hash::digest(b"forged");
+                    assert!(!proof.verify(&forged, &new_root));
+                    assert!(!proof.verify(&old_root, &forged));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_hasher() {
+        use crate::merkle::Sha512_256Hasher;
+
+        let tree = {
+            let mut tree = IncrementalMerkleTree::with_hasher(Arc::new(Sha512_256Hasher));
+            tree.extend(leaves(11));
+            tree
+        };
+        let reference = MerkleTree::from_leaves_with_hasher(
+            leaves(11)
+                .into_iter()
+                .enumerate()
+                .map(|(i, h)| (i.to_string(), h))
+                .collect(),
+            &Sha512_256Hasher,
+        );
+        assert_eq!(tree.root().as_ref(), reference.root_hash());
+
+        let proof = tree.consistency_proof(6, 11).unwrap();
+        assert_eq!(proof.algorithm, HashAlgorithm::Sha512_256);
+        assert!(proof.verify(&tree.root_at(6).unwrap(), &tree.root().unwrap()));
+        assert!(!proof.verify_with(
+            &tree.root_at(6).unwrap(),
+            &tree.root().unwrap(),
+            &Sha256Hasher
+        ));
+    }
+
+    #[test]
+    fn test_view_over_stored_nodes() {
+        let all = leaves(21);
+        let tree: IncrementalMerkleTree = all.iter().cloned().collect();
+
+        // Build the stored nodes one append at a time, as a persistent log would
+        let mut stored: HashMap<NodeId, Hash> = HashMap::new();
+        for (size, leaf) in all.iter().enumerate() {
+            let completed = MerkleLogView::new(&stored, size as u64, &Sha256Hasher)
+                .append(leaf.clone())
+                .unwrap();
+            stored.extend(completed);
+        }
+        let expected: HashMap<NodeId, Hash> =
+            tree.nodes().map(|(id, hash)| (id, hash.clone())).collect();
+        assert_eq!(stored, expected);
+
+        let view = MerkleLogView::new(&stored, 21, &Sha256Hasher);
+        assert_eq!(view.root(), tree.root());
+        let json = |proof: Option<MerkleProof>| serde_json::to_value(proof.unwrap()).unwrap();
+        assert_eq!(
+            json(view.inclusion_proof(9)),
+            json(tree.inclusion_proof(9, 21))
+        );
+        assert_eq!(view.consistency_proof(6), tree.consistency_proof(6, 21));
+
+        let reference = MerkleTree::from_leaves(
+            all.iter()
+                .enumerate()
+                .map(|(i, h)| (i.to_string(), h.clone()))
+                .collect(),
+        )
+        .get_multiproof(&[2, 3, 17])
+        .unwrap();
+        let multiproof = view
+            .multiproof(&[(17, "17".into()), (2, "2".into()), (3, "3".into())])
+            .unwrap();
+        assert_eq!(multiproof.leaves, reference.leaves);
+        assert_eq!(multiproof.siblings, reference.siblings);
+        assert!(multiproof.verify(&tree.root().unwrap()));
+
+        // A proof reads O(log n) nodes, which the recorder lists up front
+        let recorder = NodeRecorder::new();
+        MerkleLogView::new(&recorder, 21, &Sha256Hasher).inclusion_proof(9);
+        let needed = recorder.into_nodes();
+        assert!(needed.len() <= 8, "{:?}", needed);
+        let partial: HashMap<NodeId, Hash> =
+            needed.iter().map(|id| (*id, stored[id].clone())).collect();
+        let proof = MerkleLogView::new(&partial, 21, &Sha256Hasher).inclusion_proof(9);
+        assert_eq!(json(proof), json(tree.inclusion_proof(9, 21)));
+
+        // Missing nodes give no proof rather than a wrong one
+        let empty: HashMap<NodeId, Hash> = HashMap::new();
+        assert!(MerkleLogView::new(&empty, 21, &Sha256Hasher)
+            .inclusion_proof(9)
+            .is_none());
+    }
+
+    #[test]
+    fn test_consistency_rejects_rewritten_history() {
+        let original: IncrementalMerkleTree = leaves(8).into_iter().collect();
+        let mut rewritten = leaves(12);
+        rewritten[3] = Hash::digest(b"tampered");
+        let rewritten: IncrementalMerkleTree = rewritten.into_iter().collect();
+
+        let proof = rewritten.consistency_proof(8, 12).unwrap();
+        assert!(!proof.verify(&original.root().unwrap(), &rewritten.root().unwrap()));
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Provides the data structures and JCS canonicalization for the v0.1.0 "Hardened" Commitment model.
 
-use crate::merkle::Hash;
+use crate::merkle::{Hash, HashAlgorithm, MerkleProof, ProofDirection, ProofStep};
 use crate::zk::{ZkError, ZkVerifier};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -310,6 +310,30 @@ impl Capsule {
     /// Compute the canonical "capsule_root" using the Binary Merkle Tree model.
     /// This enables ZK-Explorer partial disclosure proofs for "Shadow Intents".
     pub fn to_composite_hash(&self) -> Result<Hash, String> {
+        composite_root(self.pillar_leaves()?)
+    }
+
+    /// Export one pillar's JCS bytes with a Merkle proof against the capsule root.
+    ///
+    /// The other three pillars are only represented by their leaf hashes, e.g. the
+    /// Authority decision can be shared without revealing the Intent.
+    pub fn disclose(&self, pillar: CapsulePillar) -> Result<PillarDisclosure, String> {
+        let jcs = match pillar {
+            CapsulePillar::Intent => serde_jcs::to_string(&self.intent),
+            CapsulePillar::Authority => serde_jcs::to_string(&self.authority),
+            CapsulePillar::Identity => serde_jcs::to_string(&self.identity),
+            CapsulePillar::Witness => serde_jcs::to_string(&self.witness),
+        }
+        .map_err(|e| format!("JCS serialization failed: {}", e))?;
+
+        Ok(PillarDisclosure {
+            pillar,
+            jcs,
+            proof: composite_proof(&self.pillar_leaves()?, pillar),
+        })
+    }
+
+    fn pillar_leaves(&self) -> Result<[Hash; 4], String> {
         let intent_h = self.intent.to_jcs_hash()?;
 
         // Authority and Identity are hashed as "Naked" leaves for byte-level interop with CHORA.
@@ -325,22 +349,150 @@ impl Capsule {
 
         let witness_h = self.witness.to_jcs_hash()?;
 
-        // Build 4-leaf Merkle Tree (RFC 6962 compatible structure)
-        let leaves = vec![
-            ("intent".to_string(), intent_h),
-            ("authority".to_string(), authority_h),
-            ("identity".to_string(), identity_h),
-            ("witness".to_string(), witness_h),
-        ];
+        Ok([intent_h, authority_h, identity_h, witness_h])
+    }
+}
+
+/// One of the four pillars committed in a capsule root, in leaf order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CapsulePillar {
+    Intent,
+    Authority,
+    Identity,
+    Witness,
+}
+
+impl CapsulePillar {
+    pub const ALL: [CapsulePillar; 4] = [
+        CapsulePillar::Intent,
+        CapsulePillar::Authority,
+        CapsulePillar::Identity,
+        CapsulePillar::Witness,
+    ];
+
+    /// Position of the pillar's leaf in the capsule Merkle tree.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The leaf ID used in the capsule Merkle tree.
+    pub fn leaf_id(self) -> &'static str {
+        match self {
+            CapsulePillar::Intent => "intent",
+            CapsulePillar::Authority => "authority",
+            CapsulePillar::Identity => "identity",
+            CapsulePillar::Witness => "witness",
+        }
+    }
+
+    /// Recompute the leaf hash committed for this pillar from its JCS bytes.
+    ///
+    /// The Witness leaf only commits the minimal witness (see [`WitnessData::to_commitment_hash`]).
+    pub fn leaf_hash(self, jcs: &[u8]) -> Result<Hash, String> {
+        match self {
+            CapsulePillar::Witness => serde_json::from_slice::<WitnessData>(jcs)
+                .map_err(|e| format!("Invalid witness JSON: {}", e))?
+                .to_commitment_hash(),
+            _ => Ok(Hash::digest(jcs)),
+        }
+    }
+}
+
+/// A single capsule pillar disclosed with its inclusion proof.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PillarDisclosure {
+    pub pillar: CapsulePillar,
+    /// The pillar serialized with JCS, as hashed into the capsule root.
+    pub jcs: String,
+    pub proof: MerkleProof,
+}
+
+impl PillarDisclosure {
+    /// Check that the disclosed bytes hash to the proven leaf, that the proof sits at the
+    /// pillar's position in the 4-leaf tree, and that it resolves to `root`.
+    ///
+    /// Packets with sealed Payload or MagpieAst segments add one final step whose sibling
+    /// is the commitment to those envelopes.
+    pub fn verify_root(&self, root: &Hash) -> bool {
+        let expected_path = composite_directions(self.pillar);
+        let directions: Vec<_> = self.proof.path.iter().map(|s| s.direction).collect();
+        let path_matches = match directions.split_at_checked(expected_path.len()) {
+            Some((pillar_path, [] | [ProofDirection::Right])) => pillar_path == expected_path,
+            _ => false,
+        };
+
+        self.proof.leaf_id == self.pillar.leaf_id()
+            && path_matches
+            && self
+                .pillar
+                .leaf_hash(self.jcs.as_bytes())
+                .is_ok_and(|leaf| leaf == self.proof.leaf_hash)
+            && self.proof.verify(root)
+    }
+}
 
-        let tree = crate::merkle::MerkleTree::from_leaves(leaves);
+/// Sibling directions from leaf to root for a pillar in the 4-leaf capsule tree.
+fn composite_directions(pillar: CapsulePillar) -> [ProofDirection; 2] {
+    let index = pillar.index();
+    let direction = |bit: usize| {
+        if index & bit == 0 {
+            ProofDirection::Right
+        } else {
+            ProofDirection::Left
+        }
+    };
+    [direction(1), direction(2)]
+}
 
-        tree.root_hash()
-            .cloned()
-            .ok_or_else(|| "Failed to calculate Merkle root".to_string())
+pub(crate) fn composite_proof(leaves: &[Hash; 4], pillar: CapsulePillar) -> MerkleProof {
+    let index = pillar.index();
+    let sibling_pair = if index < 2 {
+        Hash::combine(&leaves[2], &leaves[3])
+    } else {
+        Hash::combine(&leaves[0], &leaves[1])
+    };
+    let [leaf_direction, pair_direction] = composite_directions(pillar);
+
+    MerkleProof {
+        leaf_hash: leaves[index].clone(),
+        leaf_id: pillar.leaf_id().to_string(),
+        path: vec![
+            ProofStep {
+                sibling_hash: leaves[index ^ 1].clone(),
+                direction: leaf_direction,
+            },
+            ProofStep {
+                sibling_hash: sibling_pair,
+                direction: pair_direction,
+            },
+        ],
+        root_hash: Hash::combine(
+            &Hash::combine(&leaves[0], &leaves[1]),
+            &Hash::combine(&leaves[2], &leaves[3]),
+        ),
+        algorithm: HashAlgorithm::Sha256,
     }
 }
 
+/// Build the 4-leaf capsule Merkle tree (RFC 6962 compatible structure) over the
+/// intent, authority, identity and witness leaf hashes, in that order.
+pub(crate) fn composite_root(leaves: [Hash; 4]) -> Result<Hash, String> {
+    let [intent_h, authority_h, identity_h, witness_h] = leaves;
+    let leaves = vec![
+        ("intent".to_string(), intent_h),
+        ("authority".to_string(), authority_h),
+        ("identity".to_string(), identity_h),
+        ("witness".to_string(), witness_h),
+    ];
+
+    let tree = crate::merkle::MerkleTree::from_leaves(leaves);
+
+    tree.root_hash()
+        .cloned()
+        .ok_or_else(|| "Failed to calculate Merkle root".to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,8 +2,13 @@
 //!
 //! A zero-copy, high-performance binary envelope for segmented VEX audit data.
 
+use thiserror::Error;
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned};
 
+use crate::merkle::Hash;
+use crate::segment::{CapsulePillar, PillarDisclosure};
+use crate::vep_seal::{SealedSegment, SEALED_SEGMENT_FLAG};
+
 /// VEP Magic bytes: "VEP" (3 bytes)
 pub const VEP_MAGIC: [u8; 3] = *b"VEP";
 pub const VEP_VERSION_V2: u8 = 2; // CHORA Capsule v1 uses v2 wire
@@ -22,6 +27,86 @@ pub struct VepHeader {
     pub nonce: [u8; 8], // u64 BE
 }
 
+impl VepHeader {
+    /// Checks a [`PillarDisclosure`] against this header and the signature over its
+    /// `capsule_root`, without access to the other pillars.
+    ///
+    /// Returns `Ok(false)` if the signature or the inclusion proof does not verify.
+    pub fn verify_disclosure(
+        &self,
+        disclosure: &PillarDisclosure,
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VepError> {
+        if !verify_root_signature(&self.capsule_root, signature, public_key)? {
+            return Ok(false);
+        }
+
+        Ok(disclosure.verify_root(&Hash(self.capsule_root)))
+    }
+}
+
+/// Errors produced while decoding, validating or encoding VEP packets.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum VepError {
+    #[error("Buffer too small for VEP header")]
+    HeaderTooSmall,
+    #[error("Invalid VEP magic bytes")]
+    InvalidMagic,
+    #[error("Unsupported VEP version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Truncated TLV segment at offset {offset}: declared {len} bytes")]
+    TruncatedSegment { offset: usize, len: u32 },
+    #[error("Trailing bytes after last segment: {0}")]
+    TrailingBytes(usize),
+    #[error("Unknown segment type: {0:#04x}")]
+    UnknownSegment(u8),
+    #[error("Duplicate {0:?} segment")]
+    DuplicateSegment(VepSegmentType),
+    #[error("{segment:?} segment is not allowed in VEP v{version}")]
+    SegmentVersionMismatch {
+        segment: VepSegmentType,
+        version: u8,
+    },
+    #[error("Missing {0:?} segment")]
+    MissingSegment(VepSegmentType),
+    #[error("Segment length {len} exceeds limit of {max} bytes")]
+    SegmentTooLarge { len: u64, max: u64 },
+    #[error("Failed to parse {segment:?} segment: {reason}")]
+    InvalidSegment {
+        segment: VepSegmentType,
+        reason: String,
+    },
+    #[error("VEP Integrity Failure: Capsule root mismatch (header {header}, computed {computed})")]
+    CapsuleRootMismatch { header: String, computed: String },
+    #[error("Invalid public key: {0}")]
+    InvalidPublicKey(String),
+    #[error("Invalid signature format: {0}")]
+    InvalidSignature(String),
+    #[error("Capsule hashing failed: {0}")]
+    Hashing(String),
+    #[error("Encoding error: {0}")]
+    Encoding(String),
+    #[error("Invalid VEP archive: {0}")]
+    InvalidArchive(String),
+    #[error("I/O error: {0}")]
+    Io(String),
+    #[error("{0:?} segment is sealed")]
+    Sealed(VepSegmentType),
+    #[error("Sealing error: {0}")]
+    Seal(String),
+    #[error("Key is not a recipient of the sealed {0:?} segment")]
+    NotRecipient(VepSegmentType),
+    #[error("MagpieAst segment must be sealed when the Intent segment is sealed")]
+    PlaintextIntentSource,
+}
+
+impl From<std::io::Error> for VepError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
 // Note: In VEP v3 (v0.2 spec), we use a 5-byte TLV-5 format:
 // [type(1U)] [length(4U BE)]
 
@@ -36,34 +121,94 @@ pub enum VepSegmentType {
     MagpieAst = 7, // Bundled formal intent source
 }
 
+impl TryFrom<u8> for VepSegmentType {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Intent),
+            2 => Ok(Self::Authority),
+            3 => Ok(Self::Identity),
+            4 => Ok(Self::Payload),
+            5 => Ok(Self::Witness),
+            6 => Ok(Self::Signature),
+            7 => Ok(Self::MagpieAst),
+            other => Err(other),
+        }
+    }
+}
+
+impl From<CapsulePillar> for VepSegmentType {
+    fn from(pillar: CapsulePillar) -> Self {
+        match pillar {
+            CapsulePillar::Intent => Self::Intent,
+            CapsulePillar::Authority => Self::Authority,
+            CapsulePillar::Identity => Self::Identity,
+            CapsulePillar::Witness => Self::Witness,
+        }
+    }
+}
+
 /// A high-level view of a VEP packet using zero-copy references.
 pub struct VepPacket<'a> {
     buffer: &'a [u8],
 }
 
 impl<'a> VepPacket<'a> {
-    pub fn new(buffer: &'a [u8]) -> Result<Self, &'static str> {
+    pub fn new(buffer: &'a [u8]) -> Result<Self, VepError> {
         if buffer.len() < VEP_HEADER_SIZE {
-            return Err("Buffer too small for VEP header");
+            return Err(VepError::HeaderTooSmall);
         }
 
         let (header, _) =
-            VepHeader::ref_from_prefix(buffer).map_err(|_| "Failed to parse VEP header")?;
+            VepHeader::ref_from_prefix(buffer).map_err(|_| VepError::HeaderTooSmall)?;
 
         if header.magic != VEP_MAGIC {
-            return Err("Invalid VEP magic bytes");
+            return Err(VepError::InvalidMagic);
         }
 
         Ok(Self { buffer })
     }
 
+    /// Parses the packet and runs [`VepPacket::validate_strict`] before returning it.
+    pub fn new_strict(buffer: &'a [u8]) -> Result<Self, VepError> {
+        let packet = Self::new(buffer)?;
+        packet.validate_strict()?;
+        Ok(packet)
+    }
+
     pub fn header(&self) -> &VepHeader {
         VepHeader::ref_from_prefix(self.buffer).unwrap().0
     }
 
     /// Iterates through segments in the encrypted payload.
     /// In Phase 5.3, the segments are stored in a TLV format inside the payload.
+    /// Sealed segments are not returned; see [`VepPacket::get_sealed_segment`].
     pub fn get_segment_data(&self, segment_type: VepSegmentType) -> Option<&[u8]> {
+        self.find_segment(segment_type as u8)
+    }
+
+    /// Returns the sealed envelope for `segment_type`, if that segment is sealed.
+    pub fn get_sealed_segment(
+        &self,
+        segment_type: VepSegmentType,
+    ) -> Option<Result<SealedSegment, VepError>> {
+        self.find_segment(SEALED_SEGMENT_FLAG | segment_type as u8)
+            .map(|data| SealedSegment::from_bytes(segment_type, data))
+    }
+
+    /// Decrypts a sealed segment with a recipient's X25519 secret key.
+    pub fn open_segment(
+        &self,
+        segment_type: VepSegmentType,
+        recipient_secret: &[u8; 32],
+    ) -> Result<Vec<u8>, VepError> {
+        self.get_sealed_segment(segment_type)
+            .ok_or(VepError::MissingSegment(segment_type))??
+            .open(recipient_secret)
+    }
+
+    fn find_segment(&self, type_byte: u8) -> Option<&[u8]> {
         let mut offset = 0;
         let data = &self.buffer[VEP_HEADER_SIZE..];
 
@@ -77,7 +222,7 @@ impl<'a> VepPacket<'a> {
                 break;
             }
 
-            if t == segment_type as u8 {
+            if t == type_byte {
                 return Some(&data[offset..offset + len]);
             }
             offset += len;
@@ -85,68 +230,181 @@ impl<'a> VepPacket<'a> {
         None
     }
 
-    /// Verifies the cryptographic integrity of the packet against a CHORA public key. (Phase 3.2)
-    /// Following the CHORA Capsule v1 Spec: The signature is over the `capsule_root`.
-    pub fn verify(&self, chora_public_key_bytes: &[u8]) -> Result<bool, String> {
-        use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+    /// Strict structural validation of the packet.
+    ///
+    /// Rejects unsupported versions, truncated TLVs, trailing bytes, unknown or duplicate
+    /// segments, segments not allowed in the header version (e.g. `MagpieAst` in v2), missing
+    /// capsule pillars, sealed segments of types that cannot be sealed, a plaintext
+    /// `MagpieAst` next to a sealed Intent (it carries the intent source), and a header
+    /// `capsule_root` that disagrees with [`VepPacket::compute_capsule_root`].
+    pub fn validate_strict(&self) -> Result<(), VepError> {
+        let version = self.header().version;
+        if version != VEP_VERSION_V2 && version != VEP_VERSION_V3 {
+            return Err(VepError::UnsupportedVersion(version));
+        }
 
-        // 1. Reconstruct the Capsule to compute the composite capsule_root
-        let capsule = self.to_capsule()?;
-        let capsule_root = capsule.to_composite_hash()?;
+        let data = &self.buffer[VEP_HEADER_SIZE..];
+        let mut seen: Vec<VepSegmentType> = Vec::new();
+        let mut sealed_seen: Vec<VepSegmentType> = Vec::new();
+        let mut offset = 0;
 
-        // 2. Extract the Signature segment
-        let signature_bytes = self
-            .get_segment_data(VepSegmentType::Signature)
-            .ok_or("Missing Signature segment")?;
+        while offset < data.len() {
+            if offset + 5 > data.len() {
+                return Err(VepError::TrailingBytes(data.len() - offset));
+            }
 
-        // 3. Verify the signature over the capsule_root bytes
-        let public_key = VerifyingKey::from_bytes(
-            chora_public_key_bytes
-                .try_into()
-                .map_err(|_| "Invalid public key length")?,
-        )
-        .map_err(|e| format!("Invalid public key: {}", e))?;
+            let len_bytes: [u8; 4] = data[offset + 1..offset + 5].try_into().unwrap();
+            let len = u32::from_be_bytes(len_bytes);
+            let sealed = data[offset] & SEALED_SEGMENT_FLAG != 0;
+            let segment = VepSegmentType::try_from(data[offset] & !SEALED_SEGMENT_FLAG)
+                .map_err(|_| VepError::UnknownSegment(data[offset]))?;
+            if sealed && !segment.is_sealable() {
+                return Err(VepError::UnknownSegment(data[offset]));
+            }
+
+            if offset + 5 + len as usize > data.len() {
+                return Err(VepError::TruncatedSegment {
+                    offset: VEP_HEADER_SIZE + offset,
+                    len,
+                });
+            }
+            if seen.contains(&segment) {
+                return Err(VepError::DuplicateSegment(segment));
+            }
+            if (sealed || segment == VepSegmentType::MagpieAst) && version < VEP_VERSION_V3 {
+                return Err(VepError::SegmentVersionMismatch { segment, version });
+            }
+
+            seen.push(segment);
+            if sealed {
+                sealed_seen.push(segment);
+            }
+            offset += 5 + len as usize;
+        }
 
-        let signature = Signature::from_slice(signature_bytes)
-            .map_err(|e| format!("Invalid signature format: {}", e))?;
+        if sealed_seen.contains(&VepSegmentType::Intent)
+            && seen.contains(&VepSegmentType::MagpieAst)
+            && !sealed_seen.contains(&VepSegmentType::MagpieAst)
+        {
+            return Err(VepError::PlaintextIntentSource);
+        }
 
-        match public_key.verify(&capsule_root.0, &signature) {
-            Ok(_) => Ok(true),
-            Err(_) => Ok(false),
+        for required in [
+            VepSegmentType::Intent,
+            VepSegmentType::Authority,
+            VepSegmentType::Identity,
+            VepSegmentType::Witness,
+            VepSegmentType::Signature,
+        ] {
+            if !seen.contains(&required) {
+                return Err(VepError::MissingSegment(required));
+            }
         }
+
+        let root = self.compute_capsule_root()?;
+        let header_root = self.header().capsule_root;
+        if root.0 != header_root {
+            return Err(VepError::CapsuleRootMismatch {
+                header: hex::encode(header_root),
+                computed: root.to_hex(),
+            });
+        }
+
+        Ok(())
     }
 
-    /// Reconstructs a full VEX Capsule from the VEP segments.
-    pub fn to_capsule(&self) -> Result<crate::segment::Capsule, String> {
-        use crate::segment::{
-            AuthorityData, Capsule, CryptoData, IdentityData, IntentData, WitnessData,
+    /// Recomputes the composite capsule root from the segments.
+    ///
+    /// A sealed Intent segment contributes the hash of its envelope as the intent leaf,
+    /// and the envelopes of sealed Payload and MagpieAst segments are folded into the
+    /// root, so the root can be checked without the recipient key.
+    pub fn compute_capsule_root(&self) -> Result<Hash, VepError> {
+        let root =
+            crate::segment::composite_root(self.pillar_leaves()?).map_err(VepError::Hashing)?;
+        Ok(with_sealed_commitment(root, self.sealed_commitment()))
+    }
+
+    /// Export one pillar with a Merkle proof against the header `capsule_root`.
+    ///
+    /// Unlike [`Capsule::disclose`](crate::segment::Capsule::disclose) this works on packets
+    /// with a sealed Intent, as long as the Intent itself is not the disclosed pillar.
+    pub fn disclose(&self, pillar: CapsulePillar) -> Result<PillarDisclosure, VepError> {
+        use crate::segment::{AuthorityData, IdentityData, WitnessData};
+
+        fn jcs<T: serde::Serialize>(seg: &T) -> Result<String, VepError> {
+            serde_jcs::to_string(seg)
+                .map_err(|e| VepError::Encoding(format!("JCS serialization failed: {}", e)))
+        }
+
+        let segment = VepSegmentType::from(pillar);
+        let jcs = match pillar {
+            CapsulePillar::Intent => {
+                if self.get_sealed_segment(segment).is_some() {
+                    return Err(VepError::Sealed(segment));
+                }
+                jcs(&self.decode_intent()?)?
+            }
+            CapsulePillar::Authority => jcs(&parse_segment::<AuthorityData>(
+                segment,
+                self.required_segment(segment)?,
+            )?)?,
+            CapsulePillar::Identity => jcs(&parse_segment::<IdentityData>(
+                segment,
+                self.required_segment(segment)?,
+            )?)?,
+            CapsulePillar::Witness => jcs(&parse_segment::<WitnessData>(
+                segment,
+                self.required_segment(segment)?,
+            )?)?,
         };
-        use serde::Serialize;
 
-        let intent_bytes = self
-            .get_segment_data(VepSegmentType::Intent)
-            .ok_or("Missing Intent segment")?;
-        let auth_bytes = self
-            .get_segment_data(VepSegmentType::Authority)
-            .ok_or("Missing Authority segment")?;
-        let ident_bytes = self
-            .get_segment_data(VepSegmentType::Identity)
-            .ok_or("Missing Identity segment")?;
-        let witness_bytes = self
-            .get_segment_data(VepSegmentType::Witness)
-            .ok_or("Missing Witness segment")?;
-        let sig_bytes = self
-            .get_segment_data(VepSegmentType::Signature)
-            .ok_or("Missing Signature segment")?;
+        let mut proof = crate::segment::composite_proof(&self.pillar_leaves()?, pillar);
+        if let Some(commitment) = self.sealed_commitment() {
+            proof.root_hash = with_sealed_commitment(proof.root_hash, Some(commitment.clone()));
+            proof.path.push(crate::merkle::ProofStep {
+                sibling_hash: commitment,
+                direction: crate::merkle::ProofDirection::Right,
+            });
+        }
+
+        Ok(PillarDisclosure { pillar, jcs, proof })
+    }
+
+    fn sealed_commitment(&self) -> Option<Hash> {
+        sealed_commitment(SEALED_EXTRA_SEGMENTS.iter().filter_map(|&segment_type| {
+            self.find_segment(SEALED_SEGMENT_FLAG | segment_type as u8)
+                .map(|envelope| (segment_type, envelope))
+        }))
+    }
+
+    fn pillar_leaves(&self) -> Result<[Hash; 4], VepError> {
+        let intent_leaf = match self.get_sealed_segment(VepSegmentType::Intent) {
+            Some(sealed) => sealed_leaf(&sealed?),
+            None => self
+                .decode_intent()?
+                .to_jcs_hash()
+                .map_err(VepError::Hashing)?,
+        };
+
+        pillar_leaves_from_segments(
+            intent_leaf,
+            self.required_segment(VepSegmentType::Authority)?,
+            self.required_segment(VepSegmentType::Identity)?,
+            self.required_segment(VepSegmentType::Witness)?,
+        )
+    }
+
+    fn required_segment(&self, segment_type: VepSegmentType) -> Result<&[u8], VepError> {
+        self.get_segment_data(segment_type)
+            .ok_or(VepError::MissingSegment(segment_type))
+    }
 
-        let mut intent: IntentData = serde_json::from_slice(intent_bytes)
-            .map_err(|e| format!("Failed to parse Intent segment: {}", e))?;
-        let authority: AuthorityData = serde_json::from_slice(auth_bytes)
-            .map_err(|e| format!("Failed to parse Authority segment: {}", e))?;
-        let identity: IdentityData = serde_json::from_slice(ident_bytes)
-            .map_err(|e| format!("Failed to parse Identity segment: {}", e))?;
-        let witness: WitnessData = serde_json::from_slice(witness_bytes)
-            .map_err(|e| format!("Failed to parse Witness segment: {}", e))?;
+    /// Parses the plaintext Intent, filling `magpie_source` from the MagpieAst segment.
+    fn decode_intent(&self) -> Result<crate::segment::IntentData, VepError> {
+        use crate::segment::IntentData;
+
+        let intent_bytes = self.required_segment(VepSegmentType::Intent)?;
+        let mut intent: IntentData = parse_segment(VepSegmentType::Intent, intent_bytes)?;
 
         if let IntentData::Transparent {
             ref mut magpie_source,
@@ -161,10 +419,56 @@ impl<'a> VepPacket<'a> {
             }
         }
 
-        let intent_hash = intent.to_jcs_hash()?.to_hex();
+        Ok(intent)
+    }
+
+    /// Verifies the cryptographic integrity of the packet against a CHORA public key. (Phase 3.2)
+    /// Following the CHORA Capsule v1 Spec: The signature is over the `capsule_root`.
+    /// The packet must also pass [`VepPacket::validate_strict`].
+    pub fn verify(&self, chora_public_key_bytes: &[u8]) -> Result<bool, VepError> {
+        // 1. Strict structure check, which also recomputes the composite capsule_root
+        self.validate_strict()?;
+        let capsule_root = self.header().capsule_root;
+
+        // 2. Extract the Signature segment
+        let signature_bytes = self
+            .get_segment_data(VepSegmentType::Signature)
+            .ok_or(VepError::MissingSegment(VepSegmentType::Signature))?;
+
+        // 3. Verify the signature over the capsule_root bytes
+        verify_root_signature(&capsule_root, signature_bytes, chora_public_key_bytes)
+    }
+
+    /// Reconstructs a full VEX Capsule from the VEP segments.
+    ///
+    /// Fails with [`VepError::Sealed`] when the Intent segment is sealed.
+    pub fn to_capsule(&self) -> Result<crate::segment::Capsule, VepError> {
+        use crate::segment::{AuthorityData, Capsule, CryptoData, IdentityData, WitnessData};
+        use serde::Serialize;
+
+        if self.get_sealed_segment(VepSegmentType::Intent).is_some() {
+            return Err(VepError::Sealed(VepSegmentType::Intent));
+        }
+
+        let intent = self.decode_intent()?;
+        let authority: AuthorityData = parse_segment(
+            VepSegmentType::Authority,
+            self.required_segment(VepSegmentType::Authority)?,
+        )?;
+        let identity: IdentityData = parse_segment(
+            VepSegmentType::Identity,
+            self.required_segment(VepSegmentType::Identity)?,
+        )?;
+        let witness: WitnessData = parse_segment(
+            VepSegmentType::Witness,
+            self.required_segment(VepSegmentType::Witness)?,
+        )?;
+        let sig_bytes = self.required_segment(VepSegmentType::Signature)?;
 
-        fn hash_seg<T: Serialize>(seg: &T) -> Result<String, String> {
-            let jcs = serde_jcs::to_vec(seg).map_err(|e| e.to_string())?;
+        let intent_hash = intent.to_jcs_hash().map_err(VepError::Hashing)?.to_hex();
+
+        fn hash_seg<T: Serialize>(seg: &T) -> Result<String, VepError> {
+            let jcs = serde_jcs::to_vec(seg).map_err(|e| VepError::Hashing(e.to_string()))?;
             let mut hasher = sha2::Sha256::new();
             use sha2::Digest;
             hasher.update(&jcs);
@@ -173,7 +477,10 @@ impl<'a> VepPacket<'a> {
 
         let authority_hash = hash_seg(&authority)?;
         let identity_hash = hash_seg(&identity)?;
-        let witness_hash = witness.to_commitment_hash()?.to_hex();
+        let witness_hash = witness
+            .to_commitment_hash()
+            .map_err(VepError::Hashing)?
+            .to_hex();
 
         let mut capsule = Capsule {
             capsule_id: authority.capsule_id.clone(),
@@ -198,12 +505,16 @@ impl<'a> VepPacket<'a> {
             request_commitment: None,
         };
 
-        let root = capsule.to_composite_hash()?;
+        let root = capsule.to_composite_hash().map_err(VepError::Hashing)?;
         let root_hex = root.to_hex();
 
         // 5. Integrity Check: Verify that the recomputed Merkle root matches the packet header
-        if root_hex != hex::encode(self.header().capsule_root) {
-            return Err("VEP Integrity Failure: Capsule root mismatch".to_string());
+        let header_hex = hex::encode(self.header().capsule_root);
+        if root_hex != header_hex {
+            return Err(VepError::CapsuleRootMismatch {
+                header: header_hex,
+                computed: root_hex,
+            });
         }
 
         capsule.capsule_root = root_hex;
@@ -211,9 +522,355 @@ impl<'a> VepPacket<'a> {
         Ok(capsule)
     }
 }
+
+fn parse_segment<T: serde::de::DeserializeOwned>(
+    segment: VepSegmentType,
+    bytes: &[u8],
+) -> Result<T, VepError> {
+    serde_json::from_slice(bytes).map_err(|e| VepError::InvalidSegment {
+        segment,
+        reason: e.to_string(),
+    })
+}
+
+/// The intent leaf committed for a sealed Intent segment: the hash of the envelope bytes.
+fn sealed_leaf(sealed: &SealedSegment) -> Hash {
+    Hash::digest(&sealed.to_bytes())
+}
+
+/// Sealable segments outside the four pillars, in the order their envelopes are committed.
+const SEALED_EXTRA_SEGMENTS: [VepSegmentType; 2] =
+    [VepSegmentType::Payload, VepSegmentType::MagpieAst];
+
+/// Hash over the type and envelope digest of each sealed non-pillar segment, or `None`
+/// when there are none so that packets without them keep the plain 4-leaf root.
+fn sealed_commitment<'a>(
+    envelopes: impl IntoIterator<Item = (VepSegmentType, &'a [u8])>,
+) -> Option<Hash> {
+    let mut committed = Vec::new();
+    for (segment_type, envelope) in envelopes {
+        committed.push(SEALED_SEGMENT_FLAG | segment_type as u8);
+        committed.extend_from_slice(&Hash::digest(envelope).0);
+    }
+    (!committed.is_empty()).then(|| Hash::digest(&committed))
+}
+
+/// The capsule root over the 4-leaf `root` and the sealed segment commitment, if any.
+fn with_sealed_commitment(root: Hash, commitment: Option<Hash>) -> Hash {
+    match commitment {
+        Some(commitment) => Hash::combine(&root, &commitment),
+        None => root,
+    }
+}
+
+/// Capsule leaves for an already computed intent leaf and the encoded Authority, Identity
+/// and Witness segments, matching `Capsule::to_composite_hash`.
+fn pillar_leaves_from_segments(
+    intent_leaf: Hash,
+    authority: &[u8],
+    identity: &[u8],
+    witness: &[u8],
+) -> Result<[Hash; 4], VepError> {
+    use crate::segment::{AuthorityData, IdentityData, WitnessData};
+
+    fn jcs_leaf<T: serde::Serialize>(seg: &T) -> Result<Hash, VepError> {
+        serde_jcs::to_vec(seg)
+            .map(|jcs| Hash::digest(&jcs))
+            .map_err(|e| VepError::Hashing(e.to_string()))
+    }
+
+    let authority: AuthorityData = parse_segment(VepSegmentType::Authority, authority)?;
+    let identity: IdentityData = parse_segment(VepSegmentType::Identity, identity)?;
+    let witness: WitnessData = parse_segment(VepSegmentType::Witness, witness)?;
+
+    Ok([
+        intent_leaf,
+        jcs_leaf(&authority)?,
+        jcs_leaf(&identity)?,
+        witness.to_commitment_hash().map_err(VepError::Hashing)?,
+    ])
+}
+
+/// Verifies an Ed25519 signature over a capsule root.
+fn verify_root_signature(
+    capsule_root: &[u8; 32],
+    signature_bytes: &[u8],
+    public_key_bytes: &[u8],
+) -> Result<bool, VepError> {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    let public_key = VerifyingKey::from_bytes(
+        public_key_bytes
+            .try_into()
+            .map_err(|_| VepError::InvalidPublicKey("Invalid public key length".to_string()))?,
+    )
+    .map_err(|e| VepError::InvalidPublicKey(e.to_string()))?;
+
+    let signature = Signature::from_slice(signature_bytes)
+        .map_err(|e| VepError::InvalidSignature(e.to_string()))?;
+
+    Ok(public_key.verify(capsule_root, &signature).is_ok())
+}
+
+/// Builder for VEP packets, the write-side counterpart of [`VepPacket`].
+///
+/// Emits the 76-byte [`VepHeader`] followed by TLV-5 segments in insertion order.
+/// Packets produced with [`VepBuilder::from_capsule`] round-trip through
+/// [`VepPacket::to_capsule`]. Segments can be encrypted with [`VepBuilder::seal`].
+#[derive(Debug, Clone)]
+pub struct VepBuilder {
+    version: u8,
+    aid: [u8; 32],
+    capsule_root: [u8; 32],
+    nonce: u64,
+    segments: Vec<(VepSegmentType, Vec<u8>)>,
+    sealed: Vec<VepSegmentType>,
+}
+
+impl VepBuilder {
+    /// Create an empty builder for the given wire version (`VEP_VERSION_V2` or `VEP_VERSION_V3`).
+    pub fn new(version: u8) -> Self {
+        Self {
+            version,
+            aid: [0; 32],
+            capsule_root: [0; 32],
+            nonce: 0,
+            segments: Vec::new(),
+            sealed: Vec::new(),
+        }
+    }
+
+    /// Populate the header and all segments from a [`Capsule`](crate::segment::Capsule).
+    ///
+    /// The header `capsule_root` is recomputed with `Capsule::to_composite_hash`, and the
+    /// `MagpieAst` segment is only emitted for v3 packets.
+    ///
+    /// The header AID is the capsule's `identity.aid`, which must be 32 bytes of hex; use
+    /// [`VepBuilder::from_capsule_as`] for identities whose AID is not a raw key.
+    pub fn from_capsule(capsule: &crate::segment::Capsule, version: u8) -> Result<Self, VepError> {
+        let aid: [u8; 32] = hex::decode(&capsule.identity.aid)
+            .map_err(|e| VepError::Encoding(format!("Invalid AID hex: {}", e)))?
+            .try_into()
+            .map_err(|_| VepError::Encoding("AID must be 32 bytes".to_string()))?;
+
+        Self::from_capsule_as(capsule, aid, version)
+    }
+
+    /// Like [`VepBuilder::from_capsule`], with an explicit header AID.
+    pub fn from_capsule_as(
+        capsule: &crate::segment::Capsule,
+        aid: [u8; 32],
+        version: u8,
+    ) -> Result<Self, VepError> {
+        use crate::segment::IntentData;
+
+        let root = capsule.to_composite_hash().map_err(VepError::Hashing)?;
+
+        fn jcs<T: serde::Serialize>(seg: &T) -> Result<Vec<u8>, VepError> {
+            serde_jcs::to_vec(seg)
+                .map_err(|e| VepError::Encoding(format!("JCS serialization failed: {}", e)))
+        }
+
+        let signature = base64::Engine::decode(
+            &base64::engine::general_purpose::STANDARD,
+            &capsule.crypto.signature_b64,
+        )
+        .map_err(|e| VepError::InvalidSignature(e.to_string()))?;
+
+        let mut builder = Self::new(version)
+            .aid(aid)
+            .capsule_root(root.0)
+            .nonce(capsule.authority.nonce)
+            .segment(VepSegmentType::Intent, jcs(&capsule.intent)?)
+            .segment(VepSegmentType::Authority, jcs(&capsule.authority)?)
+            .segment(VepSegmentType::Identity, jcs(&capsule.identity)?)
+            .segment(VepSegmentType::Witness, jcs(&capsule.witness)?);
+
+        if version >= VEP_VERSION_V3 {
+            if let IntentData::Transparent {
+                magpie_source: Some(source),
+                ..
+            } = &capsule.intent
+            {
+                builder = builder.segment(VepSegmentType::MagpieAst, source.as_bytes().to_vec());
+            }
+        }
+
+        Ok(builder.segment(VepSegmentType::Signature, signature))
+    }
+
+    /// Set the header agent ID.
+    pub fn aid(mut self, aid: [u8; 32]) -> Self {
+        self.aid = aid;
+        self
+    }
+
+    /// Set the 4-leaf capsule root (recomputed by [`VepBuilder::seal`] for the Intent).
+    pub fn capsule_root(mut self, capsule_root: [u8; 32]) -> Self {
+        self.capsule_root = capsule_root;
+        self
+    }
+
+    /// Set the header nonce.
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    /// Add a segment. A segment of the same type that was added earlier is replaced in place.
+    pub fn segment(mut self, segment_type: VepSegmentType, data: Vec<u8>) -> Self {
+        self.sealed.retain(|t| *t != segment_type);
+        match self.segments.iter_mut().find(|(t, _)| *t == segment_type) {
+            Some(existing) => existing.1 = data,
+            None => self.segments.push((segment_type, data)),
+        }
+        self
+    }
+
+    /// Encrypt an existing segment to the given X25519 recipient public keys.
+    ///
+    /// Sealing the Intent segment replaces its leaf in the capsule root with the hash of
+    /// the envelope, and sealing a Payload or `MagpieAst` segment folds its envelope hash
+    /// into the root, so the Signature segment must be re-created over
+    /// [`VepBuilder::root`]. A `MagpieAst` segment carries the intent source and must be
+    /// sealed too when the Intent is, or [`VepBuilder::build`] fails.
+    pub fn seal(
+        mut self,
+        segment_type: VepSegmentType,
+        recipients: &[[u8; 32]],
+    ) -> Result<Self, VepError> {
+        if self.sealed.contains(&segment_type) {
+            return Err(VepError::Sealed(segment_type));
+        }
+
+        let index = self
+            .segments
+            .iter()
+            .position(|(t, _)| *t == segment_type)
+            .ok_or(VepError::MissingSegment(segment_type))?;
+
+        let sealed = SealedSegment::seal(segment_type, &self.segments[index].1, recipients)?;
+
+        if segment_type == VepSegmentType::Intent {
+            let find = |kind: VepSegmentType| {
+                self.segments
+                    .iter()
+                    .find(|(t, _)| *t == kind)
+                    .map(|(_, data)| data.as_slice())
+                    .ok_or(VepError::MissingSegment(kind))
+            };
+            let leaves = pillar_leaves_from_segments(
+                sealed_leaf(&sealed),
+                find(VepSegmentType::Authority)?,
+                find(VepSegmentType::Identity)?,
+                find(VepSegmentType::Witness)?,
+            )?;
+            self.capsule_root = crate::segment::composite_root(leaves)
+                .map_err(VepError::Hashing)?
+                .0;
+        }
+
+        self.segments[index].1 = sealed.to_bytes();
+        self.sealed.push(segment_type);
+        Ok(self)
+    }
+
+    /// The header `capsule_root` that will be written.
+    pub fn root(&self) -> [u8; 32] {
+        let envelopes = SEALED_EXTRA_SEGMENTS.iter().filter_map(|&segment_type| {
+            self.segments
+                .iter()
+                .find(|(t, _)| *t == segment_type && self.sealed.contains(t))
+                .map(|(_, envelope)| (segment_type, envelope.as_slice()))
+        });
+        with_sealed_commitment(Hash(self.capsule_root), sealed_commitment(envelopes)).0
+    }
+
+    /// Total size in bytes of the encoded packet.
+    pub fn encoded_len(&self) -> usize {
+        VEP_HEADER_SIZE
+            + self
+                .segments
+                .iter()
+                .map(|(_, data)| 5 + data.len())
+                .sum::<usize>()
+    }
+
+    fn header(&self) -> VepHeader {
+        VepHeader {
+            magic: VEP_MAGIC,
+            version: self.version,
+            aid: self.aid,
+            capsule_root: self.root(),
+            nonce: self.nonce.to_be_bytes(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), VepError> {
+        if self.version != VEP_VERSION_V2 && self.version != VEP_VERSION_V3 {
+            return Err(VepError::UnsupportedVersion(self.version));
+        }
+
+        for (segment_type, data) in &self.segments {
+            let sealed = self.sealed.contains(segment_type);
+            if (sealed || *segment_type == VepSegmentType::MagpieAst)
+                && self.version < VEP_VERSION_V3
+            {
+                return Err(VepError::SegmentVersionMismatch {
+                    segment: *segment_type,
+                    version: self.version,
+                });
+            }
+            if u32::try_from(data.len()).is_err() {
+                return Err(VepError::SegmentTooLarge {
+                    len: data.len() as u64,
+                    max: u32::MAX as u64,
+                });
+            }
+        }
+
+        let has_plaintext_source = self.segments.iter().any(|(t, _)| {
+            *t == VepSegmentType::MagpieAst && !self.sealed.contains(&VepSegmentType::MagpieAst)
+        });
+        if self.sealed.contains(&VepSegmentType::Intent) && has_plaintext_source {
+            return Err(VepError::PlaintextIntentSource);
+        }
+
+        Ok(())
+    }
+
+    /// Write the encoded packet to `writer`, returning the number of bytes written.
+    pub fn write_to<W: std::io::Write>(&self, writer: &mut W) -> Result<usize, VepError> {
+        self.validate()?;
+
+        writer.write_all(self.header().as_bytes())?;
+
+        for (segment_type, data) in &self.segments {
+            let type_byte = if self.sealed.contains(segment_type) {
+                SEALED_SEGMENT_FLAG | *segment_type as u8
+            } else {
+                *segment_type as u8
+            };
+            writer.write_all(&[type_byte])?;
+            writer.write_all(&(data.len() as u32).to_be_bytes())?;
+            writer.write_all(data)?;
+        }
+
+        Ok(self.encoded_len())
+    }
+
+    /// Encode the packet into a new buffer.
+    pub fn build(&self) -> Result<Vec<u8>, VepError> {
+        let mut buffer = Vec::with_capacity(self.encoded_len());
+        self.write_to(&mut buffer)?;
+        Ok(buffer)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_vep_parsing() {
@@ -286,4 +943,507 @@ mod tests {
 
         println!("Factual Audit: VEP Binary Protocol (v0x03) is 100% compliant.");
     }
+
+    fn sample_capsule(magpie_source: Option<String>) -> crate::segment::Capsule {
+        use crate::segment::{
+            AuthorityData, Capsule, CryptoData, IdentityData, IntentData, SchemaValue, WitnessData,
+        };
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let mut capsule = Capsule {
+            capsule_id: "builder-test".to_string(),
+            intent: IntentData::Transparent {
+                request_sha256: "ab".repeat(32),
+                confidence: 0.9,
+                capabilities: vec!["network".to_string()],
+                magpie_source,
+                metadata: SchemaValue::default(),
+            },
+            authority: AuthorityData {
+                capsule_id: "builder-test".to_string(),
+                outcome: "ALLOW".to_string(),
+                reason_code: "WITHIN_POLICY".to_string(),
+                trace_root: "cd".repeat(32),
+                nonce: 42,
+                escalation_id: None,
+                binding_status: None,
+                continuation_token: None,
+                gate_sensors: SchemaValue::default(),
+                metadata: SchemaValue::default(),
+            },
+            identity: IdentityData {
+                aid: hex::encode(signing_key.verifying_key().to_bytes()),
+                identity_type: "TPM_ECC_PERSISTENT".to_string(),
+                pcrs: None,
+                metadata: SchemaValue::default(),
+            },
+            witness: WitnessData {
+                chora_node_id: "chora-test".to_string(),
+                receipt_hash: "ef".repeat(32),
+                timestamp: 1710396000,
+                metadata: SchemaValue::default(),
+            },
+            intent_hash: String::new(),
+            authority_hash: String::new(),
+            identity_hash: String::new(),
+            witness_hash: String::new(),
+            capsule_root: String::new(),
+            crypto: CryptoData {
+                algo: "ed25519".to_string(),
+                public_key_endpoint: "/public_key".to_string(),
+                signature_scope: "capsule_root".to_string(),
+                signature_b64: String::new(),
+            },
+            request_commitment: None,
+        };
+
+        let root = capsule.to_composite_hash().unwrap();
+        capsule.capsule_root = root.to_hex();
+        capsule.crypto.signature_b64 = base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            signing_key.sign(&root.0).to_bytes(),
+        );
+        capsule
+    }
+
+    #[test]
+    fn test_builder_capsule_round_trip() {
+        let capsule = sample_capsule(Some("(intent allow)".to_string()));
+
+        for version in [VEP_VERSION_V2, VEP_VERSION_V3] {
+            let bytes = VepBuilder::from_capsule(&capsule, version)
+                .unwrap()
+                .build()
+                .unwrap();
+
+            let packet = VepPacket::new(&bytes).unwrap();
+            assert_eq!(packet.header().version, version);
+            assert_eq!(u64::from_be_bytes(packet.header().nonce), 42);
+            assert_eq!(
+                packet.get_segment_data(VepSegmentType::MagpieAst).is_some(),
+                version == VEP_VERSION_V3
+            );
+
+            let decoded = packet.to_capsule().unwrap();
+            assert_eq!(decoded.capsule_root, capsule.capsule_root);
+            assert_eq!(decoded.crypto.signature_b64, capsule.crypto.signature_b64);
+
+            let public_key = hex::decode(&capsule.identity.aid).unwrap();
+            assert!(packet.verify(&public_key).unwrap());
+
+            // Re-encoding the decoded capsule must be byte-identical
+            let reencoded = VepBuilder::from_capsule(&decoded, version)
+                .unwrap()
+                .build()
+                .unwrap();
+            assert_eq!(bytes, reencoded);
+        }
+    }
+
+    #[test]
+    fn test_builder_rejects_magpie_in_v2() {
+        let result = VepBuilder::new(VEP_VERSION_V2)
+            .segment(VepSegmentType::MagpieAst, b"(ast)".to_vec())
+            .build();
+        assert_eq!(
+            result.unwrap_err(),
+            VepError::SegmentVersionMismatch {
+                segment: VepSegmentType::MagpieAst,
+                version: VEP_VERSION_V2
+            }
+        );
+    }
+
+    fn strict_error(bytes: &[u8]) -> VepError {
+        VepPacket::new_strict(bytes)
+            .err()
+            .expect("packet should fail strict validation")
+    }
+
+    #[test]
+    fn test_sealed_intent_verifies_without_key() {
+        use crate::vep_seal::generate_recipient_keypair;
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let capsule = sample_capsule(Some("(intent allow)".to_string()));
+        let intent_jcs = serde_jcs::to_vec(&capsule.intent).unwrap();
+        let (auditor_secret, auditor_public) = generate_recipient_keypair();
+        let (owner_secret, owner_public) = generate_recipient_keypair();
+
+        let builder = VepBuilder::from_capsule(&capsule, VEP_VERSION_V3)
+            .unwrap()
+            .seal(VepSegmentType::Intent, &[auditor_public, owner_public])
+            .unwrap()
+            .seal(VepSegmentType::MagpieAst, &[owner_public])
+            .unwrap();
+        assert_ne!(
+            builder.root(),
+            capsule.to_composite_hash().unwrap().0,
+            "sealed intent must change the committed leaf"
+        );
+
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let signature = signing_key.sign(&builder.root()).to_bytes().to_vec();
+        let bytes = builder
+            .segment(VepSegmentType::Signature, signature)
+            .build()
+            .unwrap();
+
+        let source = b"(intent allow)";
+        assert!(
+            !bytes.windows(source.len()).any(|w| w == source),
+            "intent source must not appear in a sealed packet"
+        );
+
+        let packet = VepPacket::new_strict(&bytes).unwrap();
+        assert!(packet
+            .verify(&signing_key.verifying_key().to_bytes())
+            .unwrap());
+        assert!(packet.get_segment_data(VepSegmentType::Intent).is_none());
+        assert_eq!(
+            packet.to_capsule().unwrap_err(),
+            VepError::Sealed(VepSegmentType::Intent)
+        );
+
+        assert_eq!(
+            packet
+                .open_segment(VepSegmentType::Intent, &auditor_secret)
+                .unwrap(),
+            intent_jcs
+        );
+        assert_eq!(
+            packet
+                .open_segment(VepSegmentType::MagpieAst, &owner_secret)
+                .unwrap(),
+            b"(intent allow)"
+        );
+        assert_eq!(
+            packet
+                .open_segment(VepSegmentType::MagpieAst, &auditor_secret)
+                .unwrap_err(),
+            VepError::NotRecipient(VepSegmentType::MagpieAst)
+        );
+
+        // Flipping the last ciphertext byte of the sealed Intent breaks the committed root
+        let mut tampered = bytes.clone();
+        let intent_len: [u8; 4] = bytes[VEP_HEADER_SIZE + 1..VEP_HEADER_SIZE + 5]
+            .try_into()
+            .unwrap();
+        let intent_end = VEP_HEADER_SIZE + 5 + u32::from_be_bytes(intent_len) as usize;
+        tampered[intent_end - 1] ^= 0x01;
+        assert!(matches!(
+            strict_error(&tampered),
+            VepError::CapsuleRootMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn test_sealed_payload_is_committed() {
+        use crate::vep_seal::generate_recipient_keypair;
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let capsule = sample_capsule(None);
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = signing_key.verifying_key().to_bytes();
+        let (_, auditor_public) = generate_recipient_keypair();
+
+        let builder = VepBuilder::from_capsule(&capsule, VEP_VERSION_V3)
+            .unwrap()
+            .segment(VepSegmentType::Payload, b"settlement 42".to_vec())
+            .seal(VepSegmentType::Payload, &[auditor_public])
+            .unwrap();
+        assert_ne!(
+            builder.root(),
+            capsule.to_composite_hash().unwrap().0,
+            "sealed payload must be committed in the root"
+        );
+
+        let signature = signing_key.sign(&builder.root()).to_bytes().to_vec();
+        let bytes = builder
+            .segment(VepSegmentType::Signature, signature.clone())
+            .build()
+            .unwrap();
+        let packet = VepPacket::new_strict(&bytes).unwrap();
+        assert!(packet.verify(&public_key).unwrap());
+
+        // Disclosures carry the sealed commitment as a final proof step
+        let disclosure = packet.disclose(CapsulePillar::Intent).unwrap();
+        assert_eq!(disclosure.proof.path.len(), 3);
+        assert!(packet
+            .header()
+            .verify_disclosure(&disclosure, &signature, &public_key)
+            .unwrap());
+
+        // Flipping the last ciphertext byte of the sealed Payload breaks the committed root
+        let payload = bytes
+            .windows(5)
+            .position(|w| w[0] == SEALED_SEGMENT_FLAG | VepSegmentType::Payload as u8)
+            .unwrap();
+        let payload_len: [u8; 4] = bytes[payload + 1..payload + 5].try_into().unwrap();
+        let payload_end = payload + 5 + u32::from_be_bytes(payload_len) as usize;
+        let mut tampered = bytes.clone();
+        tampered[payload_end - 1] ^= 0x01;
+        assert!(matches!(
+            strict_error(&tampered),
+            VepError::CapsuleRootMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn test_sealed_intent_rejects_plaintext_source() {
+        use crate::vep_seal::generate_recipient_keypair;
+
+        let capsule = sample_capsule(Some("(intent allow)".to_string()));
+        let (_, auditor_public) = generate_recipient_keypair();
+
+        let builder = VepBuilder::from_capsule(&capsule, VEP_VERSION_V3)
+            .unwrap()
+            .seal(VepSegmentType::Intent, &[auditor_public])
+            .unwrap();
+        assert_eq!(
+            builder.build().unwrap_err(),
+            VepError::PlaintextIntentSource
+        );
+
+        // A plaintext MagpieAst appended to a packet with a sealed Intent fails strict parsing
+        let mut bytes = VepBuilder::from_capsule(&sample_capsule(None), VEP_VERSION_V3)
+            .unwrap()
+            .seal(VepSegmentType::Intent, &[auditor_public])
+            .unwrap()
+            .build()
+            .unwrap();
+        VepPacket::new_strict(&bytes).unwrap();
+        bytes.push(VepSegmentType::MagpieAst as u8);
+        bytes.extend_from_slice(&14u32.to_be_bytes());
+        bytes.extend_from_slice(b"(intent allow)");
+        assert_eq!(strict_error(&bytes), VepError::PlaintextIntentSource);
+    }
+
+    #[test]
+    fn test_capsule_pillar_disclosure() {
+        let capsule = sample_capsule(Some("(intent allow)".to_string()));
+        let root = capsule.to_composite_hash().unwrap();
+
+        for pillar in CapsulePillar::ALL {
+            let disclosure = capsule.disclose(pillar).unwrap();
+            assert_eq!(disclosure.proof.root_hash, root);
+            assert!(disclosure.verify_root(&root), "{:?} should verify", pillar);
+
+            // A disclosure relabelled as another pillar must not verify
+            let mut relabelled = disclosure.clone();
+            relabelled.pillar = CapsulePillar::ALL[(pillar.index() + 1) % 4];
+            assert!(!relabelled.verify_root(&root));
+        }
+
+        let mut forged = capsule.disclose(CapsulePillar::Authority).unwrap();
+        forged.jcs = forged.jcs.replace("ALLOW", "HALT");
+        assert!(!forged.verify_root(&root));
+    }
+
+    #[test]
+    fn test_disclosure_verifies_against_signed_header() {
+        use crate::vep_seal::generate_recipient_keypair;
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = signing_key.verifying_key().to_bytes();
+        let (_, auditor_public) = generate_recipient_keypair();
+
+        let builder = VepBuilder::from_capsule(&sample_capsule(None), VEP_VERSION_V3)
+            .unwrap()
+            .seal(VepSegmentType::Intent, &[auditor_public])
+            .unwrap();
+        let signature = signing_key.sign(&builder.root()).to_bytes().to_vec();
+        let bytes = builder
+            .segment(VepSegmentType::Signature, signature.clone())
+            .build()
+            .unwrap();
+
+        let packet = VepPacket::new_strict(&bytes).unwrap();
+        assert_eq!(
+            packet.disclose(CapsulePillar::Intent).unwrap_err(),
+            VepError::Sealed(VepSegmentType::Intent)
+        );
+
+        // The regulator only receives the header, the signature and the Authority pillar
+        let header = *packet.header();
+        let disclosure = packet.disclose(CapsulePillar::Authority).unwrap();
+        assert!(disclosure.jcs.contains("WITHIN_POLICY"));
+        assert!(header
+            .verify_disclosure(&disclosure, &signature, &public_key)
+            .unwrap());
+
+        let other_key = SigningKey::from_bytes(&[8u8; 32])
+            .verifying_key()
+            .to_bytes();
+        assert!(!header
+            .verify_disclosure(&disclosure, &signature, &other_key)
+            .unwrap());
+
+        let mut other_header = header;
+        other_header.capsule_root[0] ^= 0x01;
+        assert!(!other_header
+            .verify_disclosure(&disclosure, &signature, &public_key)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_sealing_rejected_in_v2_and_for_pillars() {
+        use crate::vep_seal::generate_recipient_keypair;
+
+        let (_, public) = generate_recipient_keypair();
+        let capsule = sample_capsule(None);
+
+        let v2 = VepBuilder::from_capsule(&capsule, VEP_VERSION_V2)
+            .unwrap()
+            .seal(VepSegmentType::Intent, &[public])
+            .unwrap();
+        assert_eq!(
+            v2.build().unwrap_err(),
+            VepError::SegmentVersionMismatch {
+                segment: VepSegmentType::Intent,
+                version: VEP_VERSION_V2
+            }
+        );
+
+        let v3 = VepBuilder::from_capsule(&capsule, VEP_VERSION_V3).unwrap();
+        assert!(v3
+            .clone()
+            .seal(VepSegmentType::Authority, &[public])
+            .is_err());
+        assert_eq!(
+            v3.seal(VepSegmentType::Payload, &[public]).unwrap_err(),
+            VepError::MissingSegment(VepSegmentType::Payload)
+        );
+    }
+
+    #[test]
+    fn test_strict_validation_accepts_builder_output() {
+        let capsule = sample_capsule(Some("(intent allow)".to_string()));
+        let bytes = VepBuilder::from_capsule(&capsule, VEP_VERSION_V3)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(VepPacket::new_strict(&bytes).is_ok());
+    }
+
+    #[test]
+    fn test_strict_validation_rejects_malformed_packets() {
+        let capsule = sample_capsule(Some("(intent allow)".to_string()));
+        let valid = VepBuilder::from_capsule(&capsule, VEP_VERSION_V3)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        // Duplicate segment
+        let mut bytes = valid.clone();
+        bytes.extend_from_slice(&[VepSegmentType::Payload as u8, 0, 0, 0, 1, 0xFF]);
+        bytes.extend_from_slice(&[VepSegmentType::Payload as u8, 0, 0, 0, 1, 0xFF]);
+        assert_eq!(
+            strict_error(&bytes),
+            VepError::DuplicateSegment(VepSegmentType::Payload)
+        );
+
+        // Unknown segment type
+        let mut bytes = valid.clone();
+        bytes.extend_from_slice(&[0x09, 0, 0, 0, 0]);
+        assert_eq!(strict_error(&bytes), VepError::UnknownSegment(0x09));
+
+        // Trailing bytes that do not form a TLV header
+        let mut bytes = valid.clone();
+        bytes.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(strict_error(&bytes), VepError::TrailingBytes(3));
+
+        // Declared length past the end of the buffer
+        let mut bytes = valid.clone();
+        bytes.extend_from_slice(&[VepSegmentType::Payload as u8, 0, 0, 0, 10, 0xFF]);
+        assert!(matches!(
+            strict_error(&bytes),
+            VepError::TruncatedSegment { len: 10, .. }
+        ));
+
+        // MagpieAst is a v3-only segment
+        let mut bytes = valid.clone();
+        bytes[3] = VEP_VERSION_V2;
+        assert_eq!(
+            strict_error(&bytes),
+            VepError::SegmentVersionMismatch {
+                segment: VepSegmentType::MagpieAst,
+                version: VEP_VERSION_V2
+            }
+        );
+
+        // Unsupported version
+        let mut bytes = valid.clone();
+        bytes[3] = 9;
+        assert_eq!(strict_error(&bytes), VepError::UnsupportedVersion(9));
+
+        // Header root that disagrees with the recomputed capsule root
+        let mut bytes = valid.clone();
+        bytes[36] ^= 0xFF;
+        assert!(matches!(
+            strict_error(&bytes),
+            VepError::CapsuleRootMismatch { .. }
+        ));
+        let public_key = hex::decode(&capsule.identity.aid).unwrap();
+        assert!(VepPacket::new(&bytes).unwrap().verify(&public_key).is_err());
+    }
+
+    #[test]
+    fn test_strict_validation_requires_pillars() {
+        let bytes = VepBuilder::new(VEP_VERSION_V3)
+            .segment(VepSegmentType::Intent, b"{}".to_vec())
+            .build()
+            .unwrap();
+        assert_eq!(
+            strict_error(&bytes),
+            VepError::MissingSegment(VepSegmentType::Authority)
+        );
+    }
+
+    #[test]
+    fn test_builder_replaces_duplicate_segment() {
+        let builder = VepBuilder::new(VEP_VERSION_V3)
+            .segment(VepSegmentType::Payload, b"first".to_vec())
+            .segment(VepSegmentType::Payload, b"second".to_vec());
+        let bytes = builder.build().unwrap();
+        assert_eq!(bytes.len(), builder.encoded_len());
+
+        let packet = VepPacket::new(&bytes).unwrap();
+        assert_eq!(
+            packet.get_segment_data(VepSegmentType::Payload).unwrap(),
+            b"second"
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_builder_segments_match_reader(
+            aid in prop::array::uniform32(any::<u8>()),
+            nonce in any::<u64>(),
+            payload in prop::collection::vec(any::<u8>(), 0..512),
+            signature in prop::collection::vec(any::<u8>(), 0..128),
+        ) {
+            let bytes = VepBuilder::new(VEP_VERSION_V3)
+                .aid(aid)
+                .nonce(nonce)
+                .segment(VepSegmentType::Payload, payload.clone())
+                .segment(VepSegmentType::Signature, signature.clone())
+                .build()
+                .unwrap();
+
+            let packet = VepPacket::new(&bytes).unwrap();
+            prop_assert_eq!(packet.header().aid, aid);
+            prop_assert_eq!(u64::from_be_bytes(packet.header().nonce), nonce);
+            prop_assert_eq!(
+                packet.get_segment_data(VepSegmentType::Payload).unwrap(),
+                &payload[..]
+            );
+            prop_assert_eq!(
+                packet.get_segment_data(VepSegmentType::Signature).unwrap(),
+                &signature[..]
+            );
+        }
+    }
 }
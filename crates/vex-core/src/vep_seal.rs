@@ -0,0 +1,348 @@
+//! Sealed VEP Segments
+//!
+//! AEAD encryption of selected VEP segments (Intent, Payload, MagpieAst) to one or
+//! more X25519 recipients.
+//!
+//! A random 256-bit content key encrypts the segment with AES-256-GCM. The content key
+//! is then wrapped for every recipient with a key derived via HKDF-SHA256 from an
+//! X25519 exchange between a per-envelope ephemeral key and the recipient key.
+//!
+//! Sealed segments are written with [`SEALED_SEGMENT_FLAG`] set on the TLV type byte.
+//! When the Intent segment is sealed, the intent leaf of the capsule root is the hash
+//! of the sealed envelope, so verifiers without a key can still check the root.
+//!
+//! Envelope layout:
+//! `version(1) | ephemeral_pk(32) | nonce(12) | count(2 BE) | [recipient_pk(32) | wrapped_key(48)]* | ciphertext`
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::Zeroize;
+
+use crate::vep::{VepError, VepSegmentType};
+
+/// Set on the TLV type byte of a sealed segment.
+pub const SEALED_SEGMENT_FLAG: u8 = 0x80;
+pub const SEAL_ENVELOPE_VERSION: u8 = 1;
+pub const SEAL_ALGORITHM: &str = "X25519-HKDF-SHA256-AES256GCM";
+
+const HKDF_INFO: &[u8] = b"vex-vep-seal-v1";
+const WRAPPED_KEY_SIZE: usize = 32 + 16;
+const RECIPIENT_ENTRY_SIZE: usize = 32 + WRAPPED_KEY_SIZE;
+const ENVELOPE_PREFIX_SIZE: usize = 1 + 32 + 12 + 2;
+
+impl VepSegmentType {
+    /// Whether this segment type may be sealed.
+    pub fn is_sealable(&self) -> bool {
+        matches!(self, Self::Intent | Self::Payload | Self::MagpieAst)
+    }
+}
+
+/// The content key wrapped for a single recipient.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealRecipient {
+    pub public_key: [u8; 32],
+    pub wrapped_key: [u8; WRAPPED_KEY_SIZE],
+}
+
+/// An encrypted VEP segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealedSegment {
+    pub segment_type: VepSegmentType,
+    pub ephemeral_public_key: [u8; 32],
+    pub nonce: [u8; 12],
+    pub recipients: Vec<SealRecipient>,
+    pub ciphertext: Vec<u8>,
+}
+
+impl SealedSegment {
+    /// Encrypt `plaintext` so that any of `recipients` (X25519 public keys) can open it.
+    pub fn seal(
+        segment_type: VepSegmentType,
+        plaintext: &[u8],
+        recipients: &[[u8; 32]],
+    ) -> Result<Self, VepError> {
+        if !segment_type.is_sealable() {
+            return Err(VepError::Seal(format!(
+                "{:?} segments cannot be sealed",
+                segment_type
+            )));
+        }
+        if recipients.is_empty() {
+            return Err(VepError::Seal("At least one recipient is required".into()));
+        }
+        if recipients.len() > u16::MAX as usize {
+            return Err(VepError::Seal("Too many recipients".into()));
+        }
+
+        let ephemeral = StaticSecret::random_from_rng(OsRng);
+        let ephemeral_public_key = PublicKey::from(&ephemeral).to_bytes();
+
+        let mut content_key = [0u8; 32];
+        OsRng.fill_bytes(&mut content_key);
+        let mut nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce);
+
+        let result = (|| {
+            let aad = content_aad(segment_type, &ephemeral_public_key);
+            let ciphertext = Aes256Gcm::new_from_slice(&content_key)
+                .map_err(|e| VepError::Seal(e.to_string()))?
+                .encrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: plaintext,
+                        aad: &aad,
+                    },
+                )
+                .map_err(|_| VepError::Seal("Segment encryption failed".into()))?;
+
+            let mut wrapped = Vec::with_capacity(recipients.len());
+            for public_key in recipients {
+                let shared = ephemeral.diffie_hellman(&PublicKey::from(*public_key));
+                if !shared.was_contributory() {
+                    return Err(VepError::Seal("Invalid recipient public key".into()));
+                }
+                let kek = derive_kek(shared.as_bytes(), &ephemeral_public_key, public_key)?;
+                let wrapped_key = kek
+                    .encrypt(
+                        Nonce::from_slice(&[0u8; 12]),
+                        Payload {
+                            msg: &content_key,
+                            aad: public_key,
+                        },
+                    )
+                    .map_err(|_| VepError::Seal("Key wrapping failed".into()))?
+                    .try_into()
+                    .map_err(|_| VepError::Seal("Unexpected wrapped key size".into()))?;
+
+                wrapped.push(SealRecipient {
+                    public_key: *public_key,
+                    wrapped_key,
+                });
+            }
+
+            Ok(Self {
+                segment_type,
+                ephemeral_public_key,
+                nonce,
+                recipients: wrapped,
+                ciphertext,
+            })
+        })();
+
+        content_key.zeroize();
+        result
+    }
+
+    /// Decrypt the segment with a recipient's X25519 secret key.
+    pub fn open(&self, recipient_secret: &[u8; 32]) -> Result<Vec<u8>, VepError> {
+        let secret = StaticSecret::from(*recipient_secret);
+        let public_key = PublicKey::from(&secret).to_bytes();
+
+        let recipient = self
+            .recipients
+            .iter()
+            .find(|r| r.public_key == public_key)
+            .ok_or(VepError::NotRecipient(self.segment_type))?;
+
+        let shared = secret.diffie_hellman(&PublicKey::from(self.ephemeral_public_key));
+        let kek = derive_kek(shared.as_bytes(), &self.ephemeral_public_key, &public_key)?;
+
+        let mut content_key = kek
+            .decrypt(
+                Nonce::from_slice(&[0u8; 12]),
+                Payload {
+                    msg: &recipient.wrapped_key,
+                    aad: &public_key,
+                },
+            )
+            .map_err(|_| VepError::Seal("Key unwrapping failed".into()))?;
+
+        let aad = content_aad(self.segment_type, &self.ephemeral_public_key);
+        let plaintext = Aes256Gcm::new_from_slice(&content_key)
+            .map_err(|e| VepError::Seal(e.to_string()))
+            .and_then(|cipher| {
+                cipher
+                    .decrypt(
+                        Nonce::from_slice(&self.nonce),
+                        Payload {
+                            msg: &self.ciphertext,
+                            aad: &aad,
+                        },
+                    )
+                    .map_err(|_| VepError::Seal("Segment decryption failed".into()))
+            });
+
+        content_key.zeroize();
+        plaintext
+    }
+
+    /// Encode the envelope stored as the TLV data of a sealed segment.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            ENVELOPE_PREFIX_SIZE
+                + self.recipients.len() * RECIPIENT_ENTRY_SIZE
+                + self.ciphertext.len(),
+        );
+        bytes.push(SEAL_ENVELOPE_VERSION);
+        bytes.extend_from_slice(&self.ephemeral_public_key);
+        bytes.extend_from_slice(&self.nonce);
+        bytes.extend_from_slice(&(self.recipients.len() as u16).to_be_bytes());
+        for recipient in &self.recipients {
+            bytes.extend_from_slice(&recipient.public_key);
+            bytes.extend_from_slice(&recipient.wrapped_key);
+        }
+        bytes.extend_from_slice(&self.ciphertext);
+        bytes
+    }
+
+    /// Decode an envelope from the TLV data of a sealed segment.
+    pub fn from_bytes(segment_type: VepSegmentType, bytes: &[u8]) -> Result<Self, VepError> {
+        let invalid = |reason: &str| VepError::InvalidSegment {
+            segment: segment_type,
+            reason: format!("Sealed envelope: {}", reason),
+        };
+
+        if !segment_type.is_sealable() {
+            return Err(invalid("segment type cannot be sealed"));
+        }
+        if bytes.len() < ENVELOPE_PREFIX_SIZE {
+            return Err(invalid("too short"));
+        }
+        if bytes[0] != SEAL_ENVELOPE_VERSION {
+            return Err(invalid("unsupported version"));
+        }
+
+        let ephemeral_public_key: [u8; 32] = bytes[1..33].try_into().unwrap();
+        let nonce: [u8; 12] = bytes[33..45].try_into().unwrap();
+        let count = u16::from_be_bytes([bytes[45], bytes[46]]) as usize;
+        if count == 0 {
+            return Err(invalid("no recipients"));
+        }
+
+        let recipients_end = ENVELOPE_PREFIX_SIZE + count * RECIPIENT_ENTRY_SIZE;
+        if bytes.len() < recipients_end {
+            return Err(invalid("truncated recipient list"));
+        }
+
+        let recipients = bytes[ENVELOPE_PREFIX_SIZE..recipients_end]
+            .chunks_exact(RECIPIENT_ENTRY_SIZE)
+            .map(|entry| SealRecipient {
+                public_key: entry[..32].try_into().unwrap(),
+                wrapped_key: entry[32..].try_into().unwrap(),
+            })
+            .collect();
+
+        Ok(Self {
+            segment_type,
+            ephemeral_public_key,
+            nonce,
+            recipients,
+            ciphertext: bytes[recipients_end..].to_vec(),
+        })
+    }
+}
+
+fn content_aad(segment_type: VepSegmentType, ephemeral_public_key: &[u8; 32]) -> [u8; 33] {
+    let mut aad = [0u8; 33];
+    aad[0] = SEALED_SEGMENT_FLAG | segment_type as u8;
+    aad[1..].copy_from_slice(ephemeral_public_key);
+    aad
+}
+
+fn derive_kek(
+    shared_secret: &[u8; 32],
+    ephemeral_public_key: &[u8; 32],
+    recipient_public_key: &[u8; 32],
+) -> Result<Aes256Gcm, VepError> {
+    let mut info = Vec::with_capacity(HKDF_INFO.len() + 64);
+    info.extend_from_slice(HKDF_INFO);
+    info.extend_from_slice(ephemeral_public_key);
+    info.extend_from_slice(recipient_public_key);
+
+    let mut kek = [0u8; 32];
+    Hkdf::<Sha256>::new(None, shared_secret)
+        .expand(&info, &mut kek)
+        .map_err(|e| VepError::Seal(e.to_string()))?;
+
+    let cipher = Aes256Gcm::new_from_slice(&kek).map_err(|e| VepError::Seal(e.to_string()));
+    kek.zeroize();
+    cipher
+}
+
+/// Generate an X25519 key pair `(secret, public)` for use as a seal recipient.
+pub fn generate_recipient_keypair() -> ([u8; 32], [u8; 32]) {
+    let secret = StaticSecret::random_from_rng(OsRng);
+    let public = PublicKey::from(&secret).to_bytes();
+    (secret.to_bytes(), public)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_open_multiple_recipients() {
+        let (alice_secret, alice_public) = generate_recipient_keypair();
+        let (bob_secret, bob_public) = generate_recipient_keypair();
+
+        let sealed = SealedSegment::seal(
+            VepSegmentType::Intent,
+            b"{\"prompt\":\"secret\"}",
+            &[alice_public, bob_public],
+        )
+        .unwrap();
+
+        let decoded =
+            SealedSegment::from_bytes(VepSegmentType::Intent, &sealed.to_bytes()).unwrap();
+        assert_eq!(decoded, sealed);
+
+        assert_eq!(
+            decoded.open(&alice_secret).unwrap(),
+            b"{\"prompt\":\"secret\"}"
+        );
+        assert_eq!(
+            decoded.open(&bob_secret).unwrap(),
+            b"{\"prompt\":\"secret\"}"
+        );
+    }
+
+    #[test]
+    fn test_open_rejects_non_recipient_and_tampering() {
+        let (_, alice_public) = generate_recipient_keypair();
+        let (eve_secret, _) = generate_recipient_keypair();
+        let (bob_secret, bob_public) = generate_recipient_keypair();
+
+        let sealed = SealedSegment::seal(
+            VepSegmentType::Payload,
+            b"payload",
+            &[alice_public, bob_public],
+        )
+        .unwrap();
+        assert_eq!(
+            sealed.open(&eve_secret).unwrap_err(),
+            VepError::NotRecipient(VepSegmentType::Payload)
+        );
+
+        let mut tampered = sealed.clone();
+        tampered.ciphertext[0] ^= 0x01;
+        assert!(tampered.open(&bob_secret).is_err());
+
+        // The segment type is bound as associated data
+        let mut retyped = sealed;
+        retyped.segment_type = VepSegmentType::MagpieAst;
+        assert!(retyped.open(&bob_secret).is_err());
+    }
+
+    #[test]
+    fn test_seal_rejects_unsealable_segments() {
+        let (_, public) = generate_recipient_keypair();
+        assert!(SealedSegment::seal(VepSegmentType::Authority, b"{}", &[public]).is_err());
+        assert!(SealedSegment::seal(VepSegmentType::Intent, b"{}", &[]).is_err());
+        assert!(SealedSegment::seal(VepSegmentType::Intent, b"{}", &[[0u8; 32]]).is_err());
+    }
+}
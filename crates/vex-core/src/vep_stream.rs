@@ -0,0 +1,598 @@
+//! Streaming VEP reader and multi-packet VEP archives
+//!
+//! [`VepReader`] walks the TLV-5 segments of a packet one at a time from any
+//! [`Read`] source, so packets never have to be held in memory as a whole.
+//!
+//! A VEP archive is a sequence of concatenated packets followed by an index:
+//! `packet_0 .. packet_n | entry_0 .. entry_n | footer`, where each entry is
+//! `offset(8 BE) | len(8 BE) | capsule_root(32)` and the footer is
+//! `index_offset(8 BE) | entry_count(8 BE) | "VEPX"`.
+
+use std::io::{self, Read, Seek, SeekFrom, Take, Write};
+
+use zerocopy::FromBytes;
+
+use crate::vep::{VepError, VepHeader, VepPacket, VepSegmentType, VEP_HEADER_SIZE, VEP_MAGIC};
+use crate::vep_seal::SEALED_SEGMENT_FLAG;
+
+/// Archive footer magic bytes: "VEPX" (4 bytes)
+pub const VEP_ARCHIVE_MAGIC: [u8; 4] = *b"VEPX";
+pub const VEP_ARCHIVE_FOOTER_SIZE: usize = 8 + 8 + 4;
+pub const VEP_ARCHIVE_ENTRY_SIZE: usize = 8 + 8 + 32;
+
+/// Default upper bound for a single segment (16 MiB)
+pub const DEFAULT_MAX_SEGMENT_LEN: u32 = 16 * 1024 * 1024;
+
+/// The TLV-5 prefix of a segment: type(1) | length(4 BE)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VepSegmentHeader {
+    pub segment_type: u8,
+    pub len: u32,
+}
+
+impl VepSegmentHeader {
+    /// The known segment type, or `None` for unknown type bytes. The sealed flag is ignored.
+    pub fn kind(&self) -> Option<VepSegmentType> {
+        VepSegmentType::try_from(self.segment_type & !SEALED_SEGMENT_FLAG).ok()
+    }
+
+    /// Whether the segment data is a sealed envelope (see [`crate::vep_seal`]).
+    pub fn is_sealed(&self) -> bool {
+        self.segment_type & SEALED_SEGMENT_FLAG != 0
+    }
+}
+
+/// A fully read segment yielded by [`VepReader`]'s iterator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VepSegment {
+    pub header: VepSegmentHeader,
+    pub data: Vec<u8>,
+}
+
+/// Streaming reader over a single VEP packet.
+pub struct VepReader<R> {
+    reader: R,
+    header: VepHeader,
+    /// Unread bytes of the current segment
+    pending: u64,
+    /// Packet offset and declared length of the current segment
+    segment_offset: usize,
+    segment_len: u32,
+    next_offset: usize,
+    max_segment_len: u32,
+    done: bool,
+}
+
+impl<R: Read> VepReader<R> {
+    /// Read and validate the 76-byte header.
+    pub fn new(mut reader: R) -> Result<Self, VepError> {
+        let mut header_bytes = [0u8; VEP_HEADER_SIZE];
+        if read_full(&mut reader, &mut header_bytes)? < VEP_HEADER_SIZE {
+            return Err(VepError::HeaderTooSmall);
+        }
+
+        let header =
+            VepHeader::read_from_bytes(&header_bytes[..]).map_err(|_| VepError::HeaderTooSmall)?;
+
+        if header.magic != VEP_MAGIC {
+            return Err(VepError::InvalidMagic);
+        }
+
+        Ok(Self {
+            reader,
+            header,
+            pending: 0,
+            segment_offset: VEP_HEADER_SIZE,
+            segment_len: 0,
+            next_offset: VEP_HEADER_SIZE,
+            max_segment_len: DEFAULT_MAX_SEGMENT_LEN,
+            done: false,
+        })
+    }
+
+    /// Reject segments whose declared length exceeds `max` before reading them.
+    pub fn with_max_segment_len(mut self, max: u32) -> Self {
+        self.max_segment_len = max;
+        self
+    }
+
+    pub fn header(&self) -> &VepHeader {
+        &self.header
+    }
+
+    /// Advance to the next segment, skipping any unread data of the current one.
+    /// Returns `Ok(None)` at a clean end of the packet.
+    pub fn next_segment_header(&mut self) -> Result<Option<VepSegmentHeader>, VepError> {
+        self.skip_pending()?;
+        if self.done {
+            return Ok(None);
+        }
+
+        let mut tlv = [0u8; 5];
+        let read = read_full(&mut self.reader, &mut tlv)?;
+        if read == 0 {
+            self.done = true;
+            return Ok(None);
+        }
+        if read < tlv.len() {
+            self.done = true;
+            return Err(VepError::TrailingBytes(read));
+        }
+
+        let header = VepSegmentHeader {
+            segment_type: tlv[0],
+            len: u32::from_be_bytes([tlv[1], tlv[2], tlv[3], tlv[4]]),
+        };
+        if header.len > self.max_segment_len {
+            self.done = true;
+            return Err(VepError::SegmentTooLarge {
+                len: header.len as u64,
+                max: self.max_segment_len as u64,
+            });
+        }
+
+        self.pending = header.len as u64;
+        self.segment_offset = self.next_offset;
+        self.segment_len = header.len;
+        self.next_offset += 5 + header.len as usize;
+        Ok(Some(header))
+    }
+
+    /// A reader over the unread bytes of the current segment.
+    pub fn segment_reader(&mut self) -> SegmentReader<'_, R> {
+        SegmentReader { inner: self }
+    }
+
+    /// Read the remaining bytes of the current segment into memory.
+    pub fn read_segment_data(&mut self) -> Result<Vec<u8>, VepError> {
+        let mut data = Vec::with_capacity(self.pending as usize);
+        self.segment_reader().read_to_end(&mut data)?;
+        if self.pending > 0 {
+            self.done = true;
+            return Err(self.truncated());
+        }
+        Ok(data)
+    }
+
+    /// Read the next segment in full.
+    pub fn next_segment(&mut self) -> Result<Option<VepSegment>, VepError> {
+        match self.next_segment_header()? {
+            Some(header) => Ok(Some(VepSegment {
+                header,
+                data: self.read_segment_data()?,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// Walk every segment without buffering its data, returning the segment headers.
+    pub fn validate(mut self) -> Result<Vec<VepSegmentHeader>, VepError> {
+        let mut headers = Vec::new();
+        while let Some(header) = self.next_segment_header()? {
+            headers.push(header);
+        }
+        Ok(headers)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn skip_pending(&mut self) -> Result<(), VepError> {
+        if self.pending == 0 {
+            return Ok(());
+        }
+        let expected = self.pending;
+        let skipped = io::copy(&mut (&mut self.reader).take(expected), &mut io::sink())?;
+        self.pending = 0;
+        if skipped < expected {
+            self.done = true;
+            return Err(self.truncated());
+        }
+        Ok(())
+    }
+
+    fn truncated(&self) -> VepError {
+        VepError::TruncatedSegment {
+            offset: self.segment_offset,
+            len: self.segment_len,
+        }
+    }
+}
+
+impl<R: Read> Iterator for VepReader<R> {
+    type Item = Result<VepSegment, VepError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_segment().transpose()
+    }
+}
+
+/// Reader over the data of the current segment, see [`VepReader::segment_reader`].
+pub struct SegmentReader<'a, R> {
+    inner: &'a mut VepReader<R>,
+}
+
+impl<R: Read> Read for SegmentReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let max = buf.len().min(self.inner.pending as usize);
+        if max == 0 {
+            return Ok(0);
+        }
+        let n = self.inner.reader.read(&mut buf[..max])?;
+        self.inner.pending -= n as u64;
+        Ok(n)
+    }
+}
+
+/// Reads until `buf` is full or EOF, returning the number of bytes read.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Location of one packet inside a VEP archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VepArchiveEntry {
+    pub offset: u64,
+    pub len: u64,
+    pub capsule_root: [u8; 32],
+}
+
+impl VepArchiveEntry {
+    fn to_bytes(self) -> [u8; VEP_ARCHIVE_ENTRY_SIZE] {
+        let mut bytes = [0u8; VEP_ARCHIVE_ENTRY_SIZE];
+        bytes[0..8].copy_from_slice(&self.offset.to_be_bytes());
+        bytes[8..16].copy_from_slice(&self.len.to_be_bytes());
+        bytes[16..48].copy_from_slice(&self.capsule_root);
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; VEP_ARCHIVE_ENTRY_SIZE]) -> Self {
+        let mut capsule_root = [0u8; 32];
+        capsule_root.copy_from_slice(&bytes[16..48]);
+        Self {
+            offset: u64::from_be_bytes(bytes[0..8].try_into().unwrap()),
+            len: u64::from_be_bytes(bytes[8..16].try_into().unwrap()),
+            capsule_root,
+        }
+    }
+}
+
+/// Writes concatenated VEP packets followed by an index footer.
+pub struct VepArchiveWriter<W: Write> {
+    writer: W,
+    offset: u64,
+    entries: Vec<VepArchiveEntry>,
+}
+
+impl<W: Write> VepArchiveWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            offset: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Append an encoded packet, returning its index in the archive.
+    pub fn append(&mut self, packet: &[u8]) -> Result<usize, VepError> {
+        let capsule_root = VepPacket::new(packet)?.header().capsule_root;
+
+        self.writer.write_all(packet)?;
+
+        self.entries.push(VepArchiveEntry {
+            offset: self.offset,
+            len: packet.len() as u64,
+            capsule_root,
+        });
+        self.offset += packet.len() as u64;
+
+        Ok(self.entries.len() - 1)
+    }
+
+    /// Write the index and footer, returning the underlying writer.
+    pub fn finish(mut self) -> Result<W, VepError> {
+        for entry in &self.entries {
+            self.writer.write_all(&entry.to_bytes())?;
+        }
+        self.writer.write_all(&self.offset.to_be_bytes())?;
+        self.writer
+            .write_all(&(self.entries.len() as u64).to_be_bytes())?;
+        self.writer.write_all(&VEP_ARCHIVE_MAGIC)?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+/// Random-access reader over a VEP archive. Only the index is held in memory.
+pub struct VepArchiveReader<R: Read + Seek> {
+    reader: R,
+    entries: Vec<VepArchiveEntry>,
+}
+
+impl<R: Read + Seek> VepArchiveReader<R> {
+    /// Read and validate the archive footer and index.
+    pub fn open(mut reader: R) -> Result<Self, VepError> {
+        let invalid = |reason: &str| VepError::InvalidArchive(reason.to_string());
+
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        if file_len < VEP_ARCHIVE_FOOTER_SIZE as u64 {
+            return Err(invalid("too small for footer"));
+        }
+
+        let mut footer = [0u8; VEP_ARCHIVE_FOOTER_SIZE];
+        reader.seek(SeekFrom::End(-(VEP_ARCHIVE_FOOTER_SIZE as i64)))?;
+        reader.read_exact(&mut footer)?;
+
+        if footer[16..20] != VEP_ARCHIVE_MAGIC {
+            return Err(invalid("bad footer magic"));
+        }
+
+        let (index_offset, count) = footer_index(&footer, file_len)
+            .ok_or_else(|| invalid("index does not match file length"))?;
+
+        reader.seek(SeekFrom::Start(index_offset))?;
+
+        let mut entries = Vec::with_capacity(count as usize);
+        let mut expected_offset = 0u64;
+        let mut entry_bytes = [0u8; VEP_ARCHIVE_ENTRY_SIZE];
+        for _ in 0..count {
+            reader.read_exact(&mut entry_bytes)?;
+            let entry = VepArchiveEntry::from_bytes(&entry_bytes);
+
+            // Packets must be contiguous and lie before the index
+            if entry.offset != expected_offset || entry.len < VEP_HEADER_SIZE as u64 {
+                return Err(VepError::InvalidArchive(format!(
+                    "bad entry at offset {}",
+                    entry.offset
+                )));
+            }
+            expected_offset = entry
+                .offset
+                .checked_add(entry.len)
+                .filter(|end| *end <= index_offset)
+                .ok_or_else(|| {
+                    VepError::InvalidArchive(format!("entry at offset {} overflows", entry.offset))
+                })?;
+
+            entries.push(entry);
+        }
+
+        if expected_offset != index_offset {
+            return Err(invalid("unindexed bytes before index"));
+        }
+
+        Ok(Self { reader, entries })
+    }
+
+    pub fn entries(&self) -> &[VepArchiveEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Find the first packet whose header carries the given capsule root.
+    pub fn find_by_capsule_root(&self, capsule_root: &[u8; 32]) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|e| &e.capsule_root == capsule_root)
+    }
+
+    /// Open a streaming reader over the packet at `index`.
+    pub fn packet(&mut self, index: usize) -> Result<VepReader<Take<&mut R>>, VepError> {
+        let entry = self.seek_entry(index)?;
+
+        let reader = VepReader::new((&mut self.reader).take(entry.len))?;
+        if reader.header().capsule_root != entry.capsule_root {
+            return Err(VepError::InvalidArchive(format!(
+                "index capsule root mismatch at index {}",
+                index
+            )));
+        }
+        Ok(reader)
+    }
+
+    /// Copy the raw bytes of the packet at `index` into memory.
+    pub fn read_packet(&mut self, index: usize) -> Result<Vec<u8>, VepError> {
+        let entry = self.seek_entry(index)?;
+
+        let mut packet = vec![0u8; entry.len as usize];
+        self.reader.read_exact(&mut packet)?;
+        Ok(packet)
+    }
+
+    fn seek_entry(&mut self, index: usize) -> Result<VepArchiveEntry, VepError> {
+        let entry = *self
+            .entries
+            .get(index)
+            .ok_or_else(|| VepError::InvalidArchive(format!("no packet at index {}", index)))?;
+        self.reader.seek(SeekFrom::Start(entry.offset))?;
+        Ok(entry)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Index offset and entry count of an archive footer, if the footer has the
+/// archive magic and its index exactly fills the space before the footer
+fn footer_index(footer: &[u8; VEP_ARCHIVE_FOOTER_SIZE], file_len: u64) -> Option<(u64, u64)> {
+    if footer[16..20] != VEP_ARCHIVE_MAGIC {
+        return None;
+    }
+    let index_offset = u64::from_be_bytes(footer[0..8].try_into().unwrap());
+    let count = u64::from_be_bytes(footer[8..16].try_into().unwrap());
+    let index_len = count.checked_mul(VEP_ARCHIVE_ENTRY_SIZE as u64)?;
+    (index_offset.checked_add(index_len)? == file_len - VEP_ARCHIVE_FOOTER_SIZE as u64)
+        .then_some((index_offset, count))
+}
+
+/// Returns true if `reader` holds a VEP archive: a footer whose index fits the file
+/// length, preceded by a VEP packet unless the archive is empty. The stream position
+/// is restored.
+pub fn is_vep_archive<R: Read + Seek>(reader: &mut R) -> io::Result<bool> {
+    let position = reader.stream_position()?;
+    let len = reader.seek(SeekFrom::End(0))?;
+
+    let mut footer = [0u8; VEP_ARCHIVE_FOOTER_SIZE];
+    let result = if len >= VEP_ARCHIVE_FOOTER_SIZE as u64 {
+        reader.seek(SeekFrom::End(-(VEP_ARCHIVE_FOOTER_SIZE as i64)))?;
+        reader.read_exact(&mut footer)?;
+        match footer_index(&footer, len) {
+            Some((0, 0)) => true,
+            Some((index_offset, _)) if index_offset >= VEP_MAGIC.len() as u64 => {
+                let mut magic = [0u8; 3];
+                reader.seek(SeekFrom::Start(0))?;
+                reader.read_exact(&mut magic)?;
+                magic == VEP_MAGIC
+            }
+            _ => false,
+        }
+    } else {
+        false
+    };
+
+    reader.seek(SeekFrom::Start(position))?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vep::{VepBuilder, VEP_VERSION_V3};
+    use std::io::Cursor;
+
+    fn packet(root: u8, payload: &[u8]) -> Vec<u8> {
+        VepBuilder::new(VEP_VERSION_V3)
+            .capsule_root([root; 32])
+            .nonce(root as u64)
+            .segment(VepSegmentType::Intent, b"{}".to_vec())
+            .segment(VepSegmentType::Payload, payload.to_vec())
+            .segment(VepSegmentType::Signature, vec![0xBB; 64])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reader_yields_segments_in_order() {
+        let bytes = packet(1, b"HELLO");
+        let reader = VepReader::new(Cursor::new(&bytes)).unwrap();
+        assert_eq!(reader.header().capsule_root, [1; 32]);
+
+        let segments: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+        let kinds: Vec<_> = segments.iter().map(|s| s.header.kind()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Some(VepSegmentType::Intent),
+                Some(VepSegmentType::Payload),
+                Some(VepSegmentType::Signature)
+            ]
+        );
+        assert_eq!(segments[1].data, b"HELLO");
+    }
+
+    #[test]
+    fn test_reader_skips_unread_segments() {
+        let bytes = packet(1, &[0xAA; 1000]);
+        let mut reader = VepReader::new(Cursor::new(&bytes)).unwrap();
+
+        assert_eq!(reader.next_segment_header().unwrap().unwrap().len, 2);
+        assert_eq!(reader.next_segment_header().unwrap().unwrap().len, 1000);
+        let sig = reader.next_segment().unwrap().unwrap();
+        assert_eq!(sig.header.kind(), Some(VepSegmentType::Signature));
+        assert!(reader.next_segment().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_reader_detects_truncation() {
+        let bytes = packet(1, b"HELLO");
+
+        // Cut inside the signature data
+        let truncated = &bytes[..bytes.len() - 10];
+        let result = VepReader::new(Cursor::new(truncated)).unwrap().validate();
+        assert!(matches!(
+            result.unwrap_err(),
+            VepError::TruncatedSegment { len: 64, .. }
+        ));
+
+        // Cut inside a TLV header
+        let truncated = &bytes[..VEP_HEADER_SIZE + 3];
+        let result = VepReader::new(Cursor::new(truncated)).unwrap().validate();
+        assert_eq!(result.unwrap_err(), VepError::TrailingBytes(3));
+    }
+
+    #[test]
+    fn test_reader_enforces_segment_limit() {
+        let bytes = packet(1, &[0u8; 4096]);
+        let result = VepReader::new(Cursor::new(&bytes))
+            .unwrap()
+            .with_max_segment_len(1024)
+            .validate();
+        assert!(matches!(
+            result.unwrap_err(),
+            VepError::SegmentTooLarge { len: 4096, .. }
+        ));
+    }
+
+    #[test]
+    fn test_archive_round_trip() {
+        let packets: Vec<_> = (1..=3)
+            .map(|i| packet(i, &vec![i; i as usize * 10]))
+            .collect();
+
+        let mut writer = VepArchiveWriter::new(Vec::new());
+        for p in &packets {
+            writer.append(p).unwrap();
+        }
+        let mut archive = Cursor::new(writer.finish().unwrap());
+        assert!(is_vep_archive(&mut archive).unwrap());
+
+        let mut reader = VepArchiveReader::open(archive).unwrap();
+        assert_eq!(reader.len(), 3);
+        assert_eq!(reader.find_by_capsule_root(&[2; 32]), Some(1));
+
+        for (i, expected) in packets.iter().enumerate() {
+            assert_eq!(&reader.read_packet(i).unwrap(), expected);
+
+            let mut packet = reader.packet(i).unwrap();
+            assert_eq!(packet.header().capsule_root, [i as u8 + 1; 32]);
+            packet.next_segment_header().unwrap();
+            let payload = packet.next_segment().unwrap().unwrap();
+            assert_eq!(payload.data.len(), (i + 1) * 10);
+        }
+    }
+
+    #[test]
+    fn test_archive_rejects_corrupt_index() {
+        let mut writer = VepArchiveWriter::new(Vec::new());
+        writer.append(&packet(1, b"a")).unwrap();
+        let mut bytes = writer.finish().unwrap();
+
+        // Bump the entry count so the index no longer fits the file
+        let count_pos = bytes.len() - 12 + 7;
+        bytes[count_pos] = 2;
+        assert!(VepArchiveReader::open(Cursor::new(&bytes)).is_err());
+
+        assert!(!is_vep_archive(&mut Cursor::new(packet(1, b"a"))).unwrap());
+        // A plain packet that merely ends in the archive magic is not an archive
+        assert!(!is_vep_archive(&mut Cursor::new(packet(1, b"payload VEPX"))).unwrap());
+    }
+}
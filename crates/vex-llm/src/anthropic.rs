@@ -0,0 +1,572 @@
+//! Anthropic Messages API provider
+//!
+//! Speaks `/v1/messages` directly: system messages go in the top-level `system`
+//! field, tool calls and results travel as `tool_use`/`tool_result` content
+//! blocks, and streaming uses the API's typed SSE events.
+//!
+//! Rate-limit headers are kept from every response (see
+//! [`AnthropicProvider::rate_limit_status`]). After a 429 carrying
+//! `retry-after`, calls fail fast with [`LlmError::RateLimited`] until the wait
+//! has passed rather than spending another request.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::message::{Image, Message, Role, ToolCall};
+use crate::provider::{LlmError, LlmProvider, LlmRequest, LlmResponse};
+use crate::stream::{next_chunk, LlmStream, SseDecoder, StreamEvent, MAX_STREAM_DURATION};
+
+/// Messages API version sent in the `anthropic-version` header
+const API_VERSION: &str = "2023-06-01";
+
+/// Messages API request body
+#[derive(Debug, Serialize)]
+struct MessagesRequest {
+    model: String,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    messages: Vec<AnthropicMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<serde_json::Value>,
+    temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+/// A message of content blocks; roles must alternate, so consecutive messages
+/// from the same side are merged
+#[derive(Debug, Serialize)]
+struct AnthropicMessage {
+    role: &'static str,
+    content: Vec<serde_json::Value>,
+}
+
+/// Content blocks for one conversation message
+fn content_blocks(message: &Message) -> Vec<serde_json::Value> {
+    if message.role == Role::Tool {
+        return vec![serde_json::json!({
+            "type": "tool_result",
+            "tool_use_id": message.tool_call_id.clone().unwrap_or_default(),
+            "content": message.content,
+        })];
+    }
+
+    let mut blocks: Vec<serde_json::Value> = message
+        .images
+        .iter()
+        .map(|image| {
+            let source = match image {
+                Image::Url { url } => serde_json::json!({ "type": "url", "url": url }),
+                Image::Base64 { media_type, data } => serde_json::json!({
+                    "type": "base64",
+                    "media_type": media_type,
+                    "data": data,
+                }),
+            };
+            serde_json::json!({ "type": "image", "source": source })
+        })
+        .collect();
+    // Empty text blocks are rejected
+    if !message.content.is_empty() {
+        blocks.push(serde_json::json!({ "type": "text", "text": message.content }));
+    }
+    blocks.extend(message.tool_calls.iter().map(|call| {
+        serde_json::json!({
+            "type": "tool_use",
+            "id": call.id,
+            "name": call.name,
+            "input": call.arguments,
+        })
+    }));
+    blocks
+}
+
+/// Response content block
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        #[serde(default)]
+        input: serde_json::Value,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// Token usage statistics
+#[derive(Debug, Default, Deserialize)]
+struct AnthropicUsage {
+    #[serde(default)]
+    input_tokens: Option<u32>,
+    #[serde(default)]
+    output_tokens: Option<u32>,
+}
+
+/// Messages API response body
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    model: String,
+    content: Vec<ContentBlock>,
+    #[serde(default)]
+    usage: AnthropicUsage,
+}
+
+/// One event of a streamed response
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamPayload {
+    MessageStart {
+        message: StreamMessage,
+    },
+    ContentBlockStart {
+        index: usize,
+        content_block: ContentBlock,
+    },
+    ContentBlockDelta {
+        index: usize,
+        delta: BlockDelta,
+    },
+    ContentBlockStop {
+        index: usize,
+    },
+    MessageDelta {
+        delta: MessageDeltaBody,
+        #[serde(default)]
+        usage: AnthropicUsage,
+    },
+    MessageStop,
+    Error {
+        error: ApiErrorBody,
+    },
+    /// `ping` and event types added after this client
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamMessage {
+    model: String,
+    #[serde(default)]
+    usage: AnthropicUsage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BlockDelta {
+    TextDelta {
+        text: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageDeltaBody {
+    #[serde(default)]
+    stop_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiErrorBody {
+    #[serde(rename = "type")]
+    kind: String,
+    message: String,
+}
+
+impl ApiErrorBody {
+    fn into_error(self) -> LlmError {
+        match self.kind.as_str() {
+            "rate_limit_error" => LlmError::RateLimited,
+            "overloaded_error" => LlmError::NotAvailable,
+            _ => LlmError::RequestFailed(format!("{}: {}", self.kind, self.message)),
+        }
+    }
+}
+
+/// Rate-limit state reported by the API's response headers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// `anthropic-ratelimit-requests-remaining`
+    pub requests_remaining: Option<u64>,
+    /// `anthropic-ratelimit-tokens-remaining`
+    pub tokens_remaining: Option<u64>,
+    /// `retry-after` of the last rate-limited response
+    pub retry_after: Option<Duration>,
+}
+
+#[derive(Debug, Default)]
+struct RateLimitState {
+    status: RateLimitStatus,
+    blocked_until: Option<Instant>,
+}
+
+/// Anthropic provider for the Messages API
+#[derive(Debug, Clone)]
+pub struct AnthropicProvider {
+    /// API key for authentication
+    api_key: String,
+    /// Model identifier (e.g., "claude-sonnet-4-5")
+    model: String,
+    /// HTTP client
+    client: reqwest::Client,
+    /// Base URL for the API
+    base_url: String,
+    /// Default request timeout
+    default_timeout: Duration,
+    rate_limits: Arc<Mutex<RateLimitState>>,
+}
+
+impl AnthropicProvider {
+    /// Create a new Anthropic provider
+    pub fn new(api_key: &str, model: &str) -> Self {
+        let timeout = std::env::var("VEX_LLM_TIMEOUT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(30);
+
+        Self {
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(timeout))
+                .build()
+                .unwrap_or_else(|_| reqwest::Client::new()),
+            base_url: "https://api.anthropic.com".to_string(),
+            default_timeout: Duration::from_secs(timeout),
+            rate_limits: Arc::default(),
+        }
+    }
+
+    /// Create with Claude Sonnet
+    pub fn sonnet(api_key: &str) -> Self {
+        Self::new(api_key, "claude-sonnet-4-5")
+    }
+
+    /// Create with Claude Haiku
+    pub fn haiku(api_key: &str) -> Self {
+        Self::new(api_key, "claude-haiku-4-5")
+    }
+
+    /// Set a custom base URL
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Rate-limit headers of the latest response
+    pub fn rate_limit_status(&self) -> RateLimitStatus {
+        self.lock_rate_limits().status.clone()
+    }
+
+    fn lock_rate_limits(&self) -> std::sync::MutexGuard<'_, RateLimitState> {
+        self.rate_limits.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn messages_request(&self, request: LlmRequest, stream: bool) -> MessagesRequest {
+        let mut system = Vec::new();
+        let mut messages: Vec<AnthropicMessage> = Vec::new();
+        for message in request.conversation() {
+            let role = match message.role {
+                Role::System => {
+                    system.push(message.content);
+                    continue;
+                }
+                Role::Assistant => "assistant",
+                // Tool results are returned in a user turn
+                Role::User | Role::Tool => "user",
+            };
+            let blocks = content_blocks(&message);
+            match messages.last_mut() {
+                Some(last) if last.role == role => last.content.extend(blocks),
+                _ => messages.push(AnthropicMessage {
+                    role,
+                    content: blocks,
+                }),
+            }
+        }
+
+        MessagesRequest {
+            model: self.model.clone(),
+            max_tokens: request.max_tokens,
+            system: (!system.is_empty()).then(|| system.join("\n\n")),
+            messages,
+            tools: request
+                .tools
+                .iter()
+                .map(|t| t.to_anthropic_format())
+                .collect(),
+            temperature: request.temperature,
+            top_p: request.top_p,
+            stream,
+        }
+    }
+
+    /// Keep the rate-limit headers of a response, blocking further calls for
+    /// `retry-after` when rate limited
+    fn record_rate_limits(&self, response: &reqwest::Response) {
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        let status = RateLimitStatus {
+            requests_remaining: header("anthropic-ratelimit-requests-remaining"),
+            tokens_remaining: header("anthropic-ratelimit-tokens-remaining"),
+            retry_after: header("retry-after").map(Duration::from_secs),
+        };
+
+        let mut limits = self.lock_rate_limits();
+        limits.blocked_until = match status.retry_after {
+            Some(wait) if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                Some(Instant::now() + wait)
+            }
+            _ => None,
+        };
+        limits.status = status;
+    }
+
+    /// POST a messages request, mapping HTTP failures to `LlmError`
+    async fn send(
+        &self,
+        api_request: &MessagesRequest,
+        request_timeout: Duration,
+    ) -> Result<reqwest::Response, LlmError> {
+        if let Some(until) = self.lock_rate_limits().blocked_until {
+            if Instant::now() < until {
+                return Err(LlmError::RateLimited);
+            }
+        }
+
+        let url = format!("{}/v1/messages", self.base_url);
+        let mut builder = self
+            .client
+            .post(&url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", API_VERSION)
+            .json(api_request);
+        if api_request.stream {
+            builder = builder.timeout(MAX_STREAM_DURATION);
+        }
+
+        let response = tokio::time::timeout(request_timeout, builder.send())
+            .await
+            .map_err(|_| LlmError::Timeout(request_timeout.as_millis() as u64))?
+            .map_err(|e| LlmError::ConnectionFailed(e.to_string()))?;
+        self.record_rate_limits(&response);
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(match status.as_u16() {
+                429 => LlmError::RateLimited,
+                // Overloaded
+                529 => LlmError::NotAvailable,
+                _ => LlmError::RequestFailed(format!("Status: {}, Body: {}", status, body)),
+            });
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn model(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    async fn is_available(&self) -> bool {
+        self.client
+            .get(format!("{}/v1/models", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", API_VERSION)
+            .send()
+            .await
+            .is_ok()
+    }
+
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse, LlmError> {
+        let start = Instant::now();
+        let request_timeout = request.timeout.unwrap_or(self.default_timeout);
+        let api_request = self.messages_request(request, false);
+        let response = self.send(&api_request, request_timeout).await?;
+
+        let api_response: MessagesResponse = response
+            .json()
+            .await
+            .map_err(|e| LlmError::InvalidResponse(e.to_string()))?;
+
+        let mut content = String::new();
+        let mut tool_calls = Vec::new();
+        for block in api_response.content {
+            match block {
+                ContentBlock::Text { text } => content.push_str(&text),
+                ContentBlock::ToolUse { id, name, input } => {
+                    tool_calls.push(ToolCall::new(id, name, input))
+                }
+                ContentBlock::Other => {}
+            }
+        }
+        let usage = api_response.usage;
+
+        Ok(LlmResponse {
+            content,
+            model: api_response.model,
+            tokens_used: usage
+                .output_tokens
+                .map(|output| output + usage.input_tokens.unwrap_or(0)),
+            latency_ms: start.elapsed().as_millis() as u64,
+            trace_root: None,
+            tool_calls,
+            provider: None,
+            cached: false,
+        })
+    }
+
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream, LlmError> {
+        let request_timeout = request.timeout.unwrap_or(self.default_timeout);
+        let api_request = self.messages_request(request, true);
+        let mut response = self.send(&api_request, request_timeout).await?;
+        let mut model = self.model.clone();
+
+        Ok(Box::pin(async_stream::try_stream! {
+            let mut sse = SseDecoder::default();
+            // Tool-use blocks being streamed: (id, name, partial JSON input)
+            let mut tool_blocks: BTreeMap<usize, (String, String, String)> = BTreeMap::new();
+            let mut input_tokens = None;
+            let mut output_tokens = None;
+            let mut stop_reason = None;
+            let mut done = false;
+            while !done {
+                let events = match next_chunk(&mut response, request_timeout).await? {
+                    Some(chunk) => sse.push(&chunk),
+                    None => {
+                        done = true;
+                        sse.finish()
+                    }
+                };
+                for data in events {
+                    let payload: StreamPayload = serde_json::from_str(&data)
+                        .map_err(|e| LlmError::InvalidResponse(e.to_string()))?;
+                    match payload {
+                        StreamPayload::MessageStart { message } => {
+                            model = message.model;
+                            input_tokens = message.usage.input_tokens;
+                        }
+                        StreamPayload::ContentBlockStart { index, content_block } => {
+                            match content_block {
+                                ContentBlock::Text { text } if !text.is_empty() => {
+                                    yield StreamEvent::Delta { text };
+                                }
+                                ContentBlock::ToolUse { id, name, .. } => {
+                                    tool_blocks.insert(index, (id, name, String::new()));
+                                }
+                                _ => {}
+                            }
+                        }
+                        StreamPayload::ContentBlockDelta { index, delta } => match delta {
+                            BlockDelta::TextDelta { text } => {
+                                yield StreamEvent::Delta { text };
+                            }
+                            BlockDelta::InputJsonDelta { partial_json } => {
+                                if let Some((_, _, input)) = tool_blocks.get_mut(&index) {
+                                    input.push_str(&partial_json);
+                                }
+                            }
+                            BlockDelta::Other => {}
+                        },
+                        StreamPayload::ContentBlockStop { index } => {
+                            if let Some((id, name, input)) = tool_blocks.remove(&index) {
+                                let input = if input.trim().is_empty() {
+                                    serde_json::json!({})
+                                } else {
+                                    serde_json::from_str(&input)
+                                        .map_err(|e| LlmError::InvalidResponse(e.to_string()))?
+                                };
+                                yield StreamEvent::ToolCall(ToolCall::new(id, name, input));
+                            }
+                        }
+                        StreamPayload::MessageDelta { delta, usage } => {
+                            stop_reason = delta.stop_reason.or(stop_reason);
+                            output_tokens = usage.output_tokens.or(output_tokens);
+                        }
+                        StreamPayload::MessageStop => {
+                            done = true;
+                            break;
+                        }
+                        StreamPayload::Error { error } => Err(error.into_error())?,
+                        StreamPayload::Other => {}
+                    }
+                }
+            }
+            if let Some(completion_tokens) = output_tokens {
+                yield StreamEvent::Usage {
+                    prompt_tokens: input_tokens,
+                    completion_tokens: Some(completion_tokens),
+                    total_tokens: completion_tokens + input_tokens.unwrap_or(0),
+                };
+            }
+            yield StreamEvent::Finish {
+                reason: stop_reason.unwrap_or_else(|| "end_turn".to_string()),
+                model,
+            };
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_request_merges_turns() {
+        let llm = AnthropicProvider::haiku("key");
+        let request = LlmRequest::chat(
+            "Be brief.",
+            vec![
+                Message::system("Answer in English."),
+                Message::assistant("").with_tool_calls(vec![ToolCall::new(
+                    "toolu_1",
+                    "calc",
+                    serde_json::json!({"expr": "2+2"}),
+                )]),
+                Message::tool_result("toolu_1", "4"),
+                Message::user("Thanks").with_image(Image::Url {
+                    url: "https://example.com/a.png".to_string(),
+                }),
+            ],
+        );
+
+        let body = serde_json::to_value(llm.messages_request(request, false)).unwrap();
+        assert_eq!(body["system"], "Be brief.\n\nAnswer in English.");
+        assert!(body.get("stream").is_none());
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["content"][0]["type"], "tool_use");
+        // The tool result and the next user message share one user turn
+        assert_eq!(messages[1]["role"], "user");
+        assert_eq!(messages[1]["content"][0]["type"], "tool_result");
+        assert_eq!(messages[1]["content"][0]["tool_use_id"], "toolu_1");
+        assert_eq!(messages[1]["content"][1]["source"]["type"], "url");
+        assert_eq!(messages[1]["content"][2]["text"], "Thanks");
+    }
+}
@@ -1,11 +1,12 @@
-//! Cached LLM provider wrapper using Moka
+//! Cached LLM provider wrapper
 //!
-//! Provides in-memory caching of LLM responses to reduce latency and API costs.
-//! Uses the Moka cache library for high-performance concurrent caching with
-//! TTL-based expiration.
+//! Provides caching of LLM responses to reduce latency and API costs. Responses
+//! live in an [`LlmCacheBackend`]: in-process Moka by default, or storage
+//! shared across replicas (see `persistent_cache`, behind the `persist`
+//! feature). Replayed responses are marked with [`LlmResponse::cached`].
 //!
 //! # 2025 Best Practices
-//! - Uses SHA-256 hash of request as cache key
+//! - Uses SHA-256 hash of the canonical request as cache key
 //! - Configurable TTL and max entries
 //! - Thread-safe concurrent access
 //! - Does NOT cache streaming responses
@@ -14,7 +15,9 @@ use async_trait::async_trait;
 use moka::future::Cache;
 use sha2::{Digest, Sha256};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
+use thiserror::Error;
 
 use crate::{LlmError, LlmProvider, LlmRequest, LlmResponse};
 
@@ -59,35 +62,65 @@ impl LlmCacheConfig {
     }
 }
 
-/// Calculate cache key from request
-fn cache_key(request: &LlmRequest) -> String {
-    let mut hasher = Sha256::new();
-    if let Some(tenant_id) = &request.tenant_id {
-        hasher.update(tenant_id.as_bytes());
-        hasher.update(b"|");
-    }
-    hasher.update(request.system.as_bytes());
-    hasher.update(b"|");
-    hasher.update(request.prompt.as_bytes());
-    hasher.update(b"|");
-    hasher.update(request.temperature.to_be_bytes());
-    hasher.update(b"|");
-    hasher.update(request.max_tokens.to_be_bytes());
-    hex::encode(hasher.finalize())
+/// Calculate the cache key of a request answered by `model`
+///
+/// The key is the SHA-256 of the RFC 8785 (JCS) canonical JSON of the model
+/// and the full request (tenant, conversation, tools and sampling parameters),
+/// so every replica derives the same key for the same request. Fails when the
+/// request cannot be canonicalized (e.g. non-finite sampling parameters).
+pub fn cache_key(model: &str, request: &LlmRequest) -> Result<String, serde_json::Error> {
+    // JCS writes non-finite floats as null, which would collide with other requests
+    let sampling = [
+        Some(request.temperature),
+        request.top_p,
+        request.presence_penalty,
+        request.frequency_penalty,
+    ];
+    if sampling.into_iter().flatten().any(|v| !v.is_finite()) {
+        return Err(serde::ser::Error::custom(
+            "non-finite sampling parameter has no canonical form",
+        ));
+    }
+    let canonical = serde_jcs::to_vec(&serde_json::json!({
+        "model": model,
+        "request": request,
+    }))?;
+    Ok(hex::encode(Sha256::digest(&canonical)))
 }
 
-/// Cached LLM provider wrapper
+/// Error from a cache backend
+#[derive(Debug, Error)]
+#[error("LLM cache error: {0}")]
+pub struct LlmCacheError(pub String);
+
+/// Storage for cached responses, addressed by [`cache_key`]
+///
+/// Backends own expiry and eviction. Errors never fail a completion: the
+/// provider treats them as a miss and logs them.
+#[async_trait]
+pub trait LlmCacheBackend: Send + Sync + std::fmt::Debug {
+    /// Look up an unexpired response
+    async fn get(&self, key: &str) -> Result<Option<LlmResponse>, LlmCacheError>;
+
+    /// Store a response
+    async fn insert(&self, key: &str, response: &LlmResponse) -> Result<(), LlmCacheError>;
+
+    /// Remove every cached response
+    async fn clear(&self) -> Result<(), LlmCacheError>;
+
+    /// Number of cached responses
+    async fn entry_count(&self) -> Result<u64, LlmCacheError>;
+}
+
+/// In-process cache backed by Moka
 #[derive(Debug)]
-pub struct CachedProvider<P: LlmProvider> {
-    inner: P,
+pub struct MemoryLlmCache {
     cache: Cache<String, LlmResponse>,
-    hits: AtomicU64,
-    misses: AtomicU64,
 }
 
-impl<P: LlmProvider> CachedProvider<P> {
-    /// Create a cached wrapper around an LLM provider
-    pub fn new(provider: P, config: LlmCacheConfig) -> Self {
+impl MemoryLlmCache {
+    /// Create an in-process cache
+    pub fn new(config: LlmCacheConfig) -> Self {
         let mut builder = Cache::builder()
             .max_capacity(config.max_entries)
             .time_to_live(config.ttl);
@@ -97,18 +130,76 @@ impl<P: LlmProvider> CachedProvider<P> {
         }
 
         Self {
-            inner: provider,
             cache: builder.build(),
-            hits: AtomicU64::new(0),
-            misses: AtomicU64::new(0),
         }
     }
+}
+
+#[async_trait]
+impl LlmCacheBackend for MemoryLlmCache {
+    async fn get(&self, key: &str) -> Result<Option<LlmResponse>, LlmCacheError> {
+        Ok(self.cache.get(key).await)
+    }
+
+    async fn insert(&self, key: &str, response: &LlmResponse) -> Result<(), LlmCacheError> {
+        self.cache.insert(key.to_string(), response.clone()).await;
+        Ok(())
+    }
+
+    async fn clear(&self) -> Result<(), LlmCacheError> {
+        self.cache.invalidate_all();
+        Ok(())
+    }
+
+    async fn entry_count(&self) -> Result<u64, LlmCacheError> {
+        self.cache.run_pending_tasks().await;
+        Ok(self.cache.entry_count())
+    }
+}
+
+/// Cached LLM provider wrapper
+#[derive(Debug)]
+pub struct CachedProvider<P: LlmProvider> {
+    inner: P,
+    cache: Arc<dyn LlmCacheBackend>,
+    model: String,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<P: LlmProvider> CachedProvider<P> {
+    /// Create a cached wrapper around an LLM provider
+    pub fn new(provider: P, config: LlmCacheConfig) -> Self {
+        Self::with_backend(provider, Arc::new(MemoryLlmCache::new(config)))
+    }
 
     /// Create with default configuration
     pub fn wrap(provider: P) -> Self {
         Self::new(provider, LlmCacheConfig::default())
     }
 
+    /// Create a wrapper over a (possibly shared) cache backend
+    ///
+    /// Entries are keyed by the provider's model, or by its name if it does not
+    /// report one.
+    pub fn with_backend(provider: P, cache: Arc<dyn LlmCacheBackend>) -> Self {
+        let model = provider.model().unwrap_or(provider.name()).to_string();
+        Self {
+            inner: provider,
+            cache,
+            model,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Set the model name keyed into cache entries (defaults to the provider's
+    /// model); replicas sharing a backend must agree on it
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
     /// Get cache statistics
     pub fn stats(&self) -> (u64, u64, f64) {
         let hits = self.hits.load(Ordering::Relaxed);
@@ -123,13 +214,13 @@ impl<P: LlmProvider> CachedProvider<P> {
     }
 
     /// Clear the cache
-    pub fn clear(&self) {
-        self.cache.invalidate_all();
+    pub async fn clear(&self) -> Result<(), LlmCacheError> {
+        self.cache.clear().await
     }
 
     /// Get current cache size
-    pub fn size(&self) -> u64 {
-        self.cache.entry_count()
+    pub async fn size(&self) -> Result<u64, LlmCacheError> {
+        self.cache.entry_count().await
     }
 }
 
@@ -139,18 +230,35 @@ impl<P: LlmProvider + 'static> LlmProvider for CachedProvider<P> {
         "cached"
     }
 
+    fn model(&self) -> Option<&str> {
+        self.inner.model()
+    }
+
     async fn is_available(&self) -> bool {
         self.inner.is_available().await
     }
 
     async fn complete(&self, request: LlmRequest) -> Result<LlmResponse, LlmError> {
-        let key = cache_key(&request);
+        let key = match cache_key(&self.model, &request) {
+            Ok(key) => key,
+            Err(e) => {
+                // Never share a key between requests that cannot be told apart
+                tracing::warn!(error = %e, "LLM request not cacheable, bypassing cache");
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return self.inner.complete(request).await;
+            }
+        };
 
         // Check cache first
-        if let Some(cached) = self.cache.get(&key).await {
-            self.hits.fetch_add(1, Ordering::Relaxed);
-            tracing::debug!(cache_key = %key, "LLM cache hit");
-            return Ok(cached);
+        match self.cache.get(&key).await {
+            Ok(Some(mut cached)) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                tracing::debug!(cache_key = %key, "LLM cache hit");
+                cached.cached = true;
+                return Ok(cached);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!(cache_key = %key, error = %e, "LLM cache lookup failed"),
         }
 
         // Cache miss - call provider
@@ -158,8 +266,10 @@ impl<P: LlmProvider + 'static> LlmProvider for CachedProvider<P> {
         let response = self.inner.complete(request).await?;
 
         // Cache the response
-        self.cache.insert(key.clone(), response.clone()).await;
-        tracing::debug!(cache_key = %key, "LLM cache miss - stored");
+        match self.cache.insert(&key, &response).await {
+            Ok(()) => tracing::debug!(cache_key = %key, "LLM cache miss - stored"),
+            Err(e) => tracing::warn!(cache_key = %key, error = %e, "LLM cache store failed"),
+        }
 
         Ok(response)
     }
@@ -183,6 +293,8 @@ mod tests {
         let resp2 = cached.complete(req).await.unwrap();
 
         assert_eq!(resp1.content, resp2.content);
+        assert!(!resp1.cached);
+        assert!(resp2.cached);
 
         let (hits, misses, _) = cached.stats();
         assert_eq!(hits, 1);
@@ -204,4 +316,38 @@ mod tests {
         assert_eq!(hits, 0);
         assert_eq!(misses, 2);
     }
+
+    #[test]
+    fn test_cache_key_uses_provider_model() {
+        let backend: Arc<dyn LlmCacheBackend> =
+            Arc::new(MemoryLlmCache::new(LlmCacheConfig::default()));
+
+        let resilient = crate::ResilientProvider::new(
+            crate::DeepSeekProvider::chat("key"),
+            crate::LlmCircuitConfig::conservative(),
+        );
+        let cached = CachedProvider::with_backend(resilient, backend.clone());
+        assert_eq!(cached.model, "deepseek-chat");
+        assert_eq!(cached.model(), Some("deepseek-chat"));
+
+        // Providers without a model fall back to their name
+        let mock = CachedProvider::with_backend(MockProvider::smart(), backend);
+        assert_eq!(mock.model, mock.inner.name());
+    }
+
+    #[tokio::test]
+    async fn test_uncanonicalizable_requests_bypass_cache() {
+        let cached = CachedProvider::wrap(MockProvider::constant("fresh"));
+
+        let mut nan = LlmRequest::simple("same prompt");
+        nan.temperature = f32::NAN;
+        let mut infinite = LlmRequest::simple("same prompt");
+        infinite.temperature = f32::INFINITY;
+        assert!(cache_key("mock", &nan).is_err());
+
+        assert!(!cached.complete(nan.clone()).await.unwrap().cached);
+        assert!(!cached.complete(infinite).await.unwrap().cached);
+        assert!(!cached.complete(nan).await.unwrap().cached);
+        assert_eq!(cached.stats().0, 0);
+    }
 }
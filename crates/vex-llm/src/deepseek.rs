@@ -2,8 +2,9 @@
 
 use async_trait::async_trait;
 
-use crate::openai_compat::OpenAICompatibleProvider;
+use crate::openai_compat::{JsonMode, OpenAICompatibleProvider};
 use crate::provider::{LlmError, LlmProvider, LlmRequest, LlmResponse};
+use crate::stream::LlmStream;
 
 /// DeepSeek provider for inference
 #[derive(Debug, Clone)]
@@ -20,7 +21,9 @@ impl DeepSeekProvider {
                 model,
                 "https://api.deepseek.com",
                 "deepseek",
-            ),
+            )
+            // DeepSeek supports JSON mode but not schema-constrained output
+            .with_json_mode(JsonMode::Object),
         }
     }
 
@@ -41,6 +44,10 @@ impl LlmProvider for DeepSeekProvider {
         self.inner.name()
     }
 
+    fn model(&self) -> Option<&str> {
+        self.inner.model()
+    }
+
     async fn is_available(&self) -> bool {
         self.inner.is_available().await
     }
@@ -48,6 +55,10 @@ impl LlmProvider for DeepSeekProvider {
     async fn complete(&self, request: LlmRequest) -> Result<LlmResponse, LlmError> {
         self.inner.complete(request).await
     }
+
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream, LlmError> {
+        self.inner.complete_stream(request).await
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,274 @@
+//! Provider fallback chains with optional hedged requests
+//!
+//! [`FallbackProvider`] tries an ordered list of providers, moving to the next
+//! one when a call fails with a transient error (rate limited, timed out or not
+//! available, which includes an open [`ResilientProvider`](crate::ResilientProvider)
+//! circuit). Other errors are returned as-is: a request one provider rejects is
+//! unlikely to fare better elsewhere.
+//!
+//! With hedging enabled, the next provider is also started when the current
+//! one has not answered within the hedge delay, and the first success wins.
+//! An error from one attempt does not cancel the others still in flight.
+//! The answering provider is recorded in [`LlmResponse::provider`].
+
+use async_trait::async_trait;
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{LlmError, LlmProvider, LlmRequest, LlmResponse, LlmStream};
+
+/// Whether an error should move the call on to the next provider
+fn should_fall_back(error: &LlmError) -> bool {
+    matches!(
+        error,
+        LlmError::RateLimited | LlmError::Timeout(_) | LlmError::NotAvailable
+    )
+}
+
+/// Ordered chain of providers
+#[derive(Debug, Clone, Default)]
+pub struct FallbackProvider {
+    providers: Vec<(String, Arc<dyn LlmProvider>)>,
+    hedge_after: Option<Duration>,
+}
+
+impl FallbackProvider {
+    /// Create an empty chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a provider, named for the audit trail
+    pub fn add_provider(mut self, name: impl Into<String>, provider: Arc<dyn LlmProvider>) -> Self {
+        self.providers.push((name.into(), provider));
+        self
+    }
+
+    /// Start the next provider as well when the current one has not answered
+    /// within `delay`
+    pub fn with_hedging(mut self, delay: Duration) -> Self {
+        self.hedge_after = Some(delay);
+        self
+    }
+
+    /// Names of the providers, in the order they are tried
+    pub fn provider_names(&self) -> Vec<&str> {
+        self.providers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for FallbackProvider {
+    fn name(&self) -> &str {
+        "fallback"
+    }
+
+    async fn is_available(&self) -> bool {
+        for (_, provider) in &self.providers {
+            if provider.is_available().await {
+                return true;
+            }
+        }
+        false
+    }
+
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse, LlmError> {
+        let attempt = |index: usize| {
+            let provider = self.providers[index].1.clone();
+            let request = request.clone();
+            async move { (index, provider.complete(request).await) }
+        };
+
+        let mut in_flight = FuturesUnordered::new();
+        let mut next = 0;
+        let mut last_error = LlmError::NotAvailable;
+        // Set by a non-transient error: no further providers are started
+        let mut stopped = false;
+        loop {
+            if in_flight.is_empty() {
+                if stopped || next == self.providers.len() {
+                    return Err(last_error);
+                }
+                in_flight.push(attempt(next));
+                next += 1;
+            }
+
+            let hedge = async {
+                match self.hedge_after {
+                    Some(delay) if !stopped && next < self.providers.len() => {
+                        tokio::time::sleep(delay).await
+                    }
+                    _ => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                Some((index, result)) = in_flight.next() => {
+                    let name = &self.providers[index].0;
+                    match result {
+                        Ok(mut response) => {
+                            response.provider = Some(name.clone());
+                            return Ok(response);
+                        }
+                        Err(e) if should_fall_back(&e) => {
+                            tracing::warn!(provider = %name, error = %e, "Provider failed, falling back");
+                            last_error = e;
+                        }
+                        Err(e) => {
+                            tracing::warn!(provider = %name, error = %e, "Provider failed");
+                            stopped = true;
+                            last_error = e;
+                        }
+                    }
+                }
+                _ = hedge => {
+                    tracing::info!(
+                        provider = %self.providers[next].0,
+                        "Hedging slow provider"
+                    );
+                    in_flight.push(attempt(next));
+                    next += 1;
+                }
+            }
+        }
+    }
+
+    /// Falls back only while opening the stream; streams are not hedged
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream, LlmError> {
+        let mut last_error = LlmError::NotAvailable;
+        for (name, provider) in &self.providers {
+            match provider.complete_stream(request.clone()).await {
+                Ok(stream) => return Ok(stream),
+                Err(e) if should_fall_back(&e) => {
+                    tracing::warn!(provider = %name, error = %e, "Provider failed, falling back");
+                    last_error = e;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockProvider;
+
+    /// Provider that always fails with the given error
+    #[derive(Debug)]
+    struct FailingProvider(fn() -> LlmError);
+
+    #[async_trait]
+    impl LlmProvider for FailingProvider {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        async fn is_available(&self) -> bool {
+            false
+        }
+
+        async fn complete(&self, _request: LlmRequest) -> Result<LlmResponse, LlmError> {
+            Err((self.0)())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_on_transient_errors() {
+        let llm = FallbackProvider::new()
+            .add_provider(
+                "limited",
+                Arc::new(FailingProvider(|| LlmError::RateLimited)),
+            )
+            .add_provider("down", Arc::new(FailingProvider(|| LlmError::NotAvailable)))
+            .add_provider("backup", Arc::new(MockProvider::constant("ok")));
+
+        let response = llm.complete(LlmRequest::simple("hi")).await.unwrap();
+        assert_eq!(response.content, "ok");
+        assert_eq!(response.provider.as_deref(), Some("backup"));
+        assert!(llm.is_available().await);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_stops_on_other_errors() {
+        let llm = FallbackProvider::new()
+            .add_provider(
+                "broken",
+                Arc::new(FailingProvider(|| LlmError::InvalidResponse("bad".into()))),
+            )
+            .add_provider("backup", Arc::new(MockProvider::constant("ok")));
+
+        let err = llm.complete(LlmRequest::simple("hi")).await.unwrap_err();
+        assert!(matches!(err, LlmError::InvalidResponse(_)));
+
+        let empty = FallbackProvider::new();
+        let err = empty.complete(LlmRequest::simple("hi")).await.unwrap_err();
+        assert!(matches!(err, LlmError::NotAvailable));
+    }
+
+    #[tokio::test]
+    async fn test_hedged_request_takes_first_success() {
+        let llm = FallbackProvider::new()
+            .add_provider(
+                "slow",
+                Arc::new(MockProvider::constant("slow").with_latency(2000)),
+            )
+            .add_provider(
+                "fast",
+                Arc::new(MockProvider::constant("fast").with_latency(10)),
+            )
+            .with_hedging(Duration::from_millis(50));
+
+        let start = std::time::Instant::now();
+        let response = llm.complete(LlmRequest::simple("hi")).await.unwrap();
+        assert_eq!(response.content, "fast");
+        assert_eq!(response.provider.as_deref(), Some("fast"));
+        assert!(start.elapsed() < Duration::from_millis(1000));
+
+        // Without hedging the chain waits for the first provider
+        let llm = FallbackProvider::new()
+            .add_provider(
+                "slow",
+                Arc::new(MockProvider::constant("slow").with_latency(100)),
+            )
+            .add_provider("fast", Arc::new(MockProvider::constant("fast")));
+        let response = llm.complete(LlmRequest::simple("hi")).await.unwrap();
+        assert_eq!(response.provider.as_deref(), Some("slow"));
+    }
+
+    #[tokio::test]
+    async fn test_hedge_failure_keeps_first_attempt() {
+        let llm = FallbackProvider::new()
+            .add_provider(
+                "slow",
+                Arc::new(MockProvider::constant("slow").with_latency(100)),
+            )
+            .add_provider(
+                "broken",
+                Arc::new(FailingProvider(|| LlmError::InvalidResponse("bad".into()))),
+            )
+            .add_provider("unused", Arc::new(MockProvider::constant("unused")))
+            .with_hedging(Duration::from_millis(10));
+
+        let response = llm.complete(LlmRequest::simple("hi")).await.unwrap();
+        assert_eq!(response.content, "slow");
+        assert_eq!(response.provider.as_deref(), Some("slow"));
+
+        // Once every attempt has failed the last error is returned
+        let llm = FallbackProvider::new()
+            .add_provider("down", Arc::new(FailingProvider(|| LlmError::NotAvailable)))
+            .add_provider(
+                "broken",
+                Arc::new(FailingProvider(|| LlmError::InvalidResponse("bad".into()))),
+            )
+            .add_provider("unused", Arc::new(MockProvider::constant("unused")))
+            .with_hedging(Duration::from_millis(10));
+        let err = llm.complete(LlmRequest::simple("hi")).await.unwrap_err();
+        assert!(matches!(err, LlmError::InvalidResponse(_)));
+    }
+}
@@ -6,6 +6,7 @@
 //!
 //! | Provider | Type | Key Required |
 //! |----------|------|--------------|
+//! | Anthropic | API | `ANTHROPIC_API_KEY` |
 //! | DeepSeek | API | `DEEPSEEK_API_KEY` |
 //! | Mistral | API | `MISTRAL_API_KEY` |
 //! | OpenAI | API | `OPENAI_API_KEY` |
@@ -39,6 +40,17 @@
 //! let response = llm.ask("Explain Merkle trees").await.unwrap();
 //! ```
 //!
+//! ## With Anthropic
+//!
+//! ```rust,ignore
+//! use vex_llm::AnthropicProvider;
+//!
+//! let api_key = std::env::var("ANTHROPIC_API_KEY").unwrap();
+//! let llm = AnthropicProvider::sonnet(&api_key); // or haiku(), new(key, model)
+//!
+//! let response = llm.ask("Explain Merkle trees").await.unwrap();
+//! ```
+//!
 //! ## With Mistral
 //!
 //! ```rust,ignore
@@ -61,20 +73,27 @@
 //! // limiter.try_acquire("user123").await.unwrap();
 //! ```
 
+pub mod anthropic;
 pub mod cached_provider;
 pub mod config;
 pub mod deepseek;
+pub mod fallback_provider;
 pub mod mcp;
+pub mod message;
 pub mod metrics;
 pub mod mistral;
 pub mod mock;
 pub mod ollama;
 pub mod openai;
 pub mod openai_compat;
+#[cfg(feature = "persist")]
+pub mod persistent_cache;
 pub mod provider;
 pub mod rate_limit;
 pub mod resilient_provider;
+pub mod stream;
 pub mod streaming_tool;
+pub mod structured;
 pub mod tool;
 pub mod tool_error;
 pub mod tool_executor;
@@ -84,19 +103,28 @@ pub mod wasm_tool;
 #[cfg(test)]
 mod wasm_tool_tests;
 
-pub use cached_provider::{CachedProvider, LlmCacheConfig};
+pub use anthropic::{AnthropicProvider, RateLimitStatus};
+pub use cached_provider::{
+    CachedProvider, LlmCacheBackend, LlmCacheConfig, LlmCacheError, MemoryLlmCache,
+};
 pub use config::{ConfigError, LlmConfig, VexConfig};
 pub use deepseek::DeepSeekProvider;
+pub use fallback_provider::FallbackProvider;
+pub use message::{Image, Message, Role, ToolCall, ToolSpec};
 pub use metrics::{global_metrics, Metrics, MetricsSnapshot, Span, Timer};
 pub use mistral::MistralProvider;
 pub use mock::MockProvider;
 pub use ollama::OllamaProvider;
 pub use openai::OpenAIProvider;
-pub use openai_compat::OpenAICompatibleProvider;
+pub use openai_compat::{JsonMode, OpenAICompatibleProvider};
+#[cfg(feature = "persist")]
+pub use persistent_cache::PersistentLlmCache;
 pub use provider::{EmbeddingProvider, LlmError, LlmProvider, LlmRequest, LlmResponse};
 pub use rate_limit::{RateLimitConfig, RateLimitError, RateLimitedProvider, RateLimiter};
 pub use resilient_provider::{CircuitState, LlmCircuitConfig, ResilientProvider};
+pub use stream::{LlmStream, StreamCollector, StreamEvent};
 pub use streaming_tool::{StreamConfig, StreamingTool, ToolChunk, ToolStream};
+pub use structured::{extract_json, ResponseFormat, Structured, StructuredError, StructuredOutput};
 pub use tool::{Capability, Tool, ToolDefinition, ToolRegistry};
 pub use tool_error::ToolError;
 pub use tool_executor::ToolExecutor;
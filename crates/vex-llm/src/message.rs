@@ -0,0 +1,264 @@
+//! Multi-turn chat messages and native tool calls
+//!
+//! An [`LlmRequest`](crate::LlmRequest) carries its conversation history as
+//! [`Message`]s and the tools the model may call as [`ToolSpec`]s. Providers map
+//! both onto their own wire format; tool calls the model makes come back in
+//! [`LlmResponse::tool_calls`](crate::LlmResponse::tool_calls) and are answered
+//! with [`Message::tool_result`].
+//!
+//! ```rust
+//! use vex_llm::{LlmRequest, Message, ToolCall, ToolDefinition, ToolSpec};
+//!
+//! const WEATHER: ToolDefinition = ToolDefinition::new(
+//!     "weather",
+//!     "Current weather for a city",
+//!     r#"{"type": "object", "properties": {"city": {"type": "string"}}}"#,
+//! );
+//!
+//! let call = ToolCall::new("call_1", "weather", serde_json::json!({"city": "Oslo"}));
+//! let request = LlmRequest::chat(
+//!     "You are a weather assistant.",
+//!     vec![
+//!         Message::user("How cold is Oslo?"),
+//!         Message::assistant("").with_tool_calls(vec![call]),
+//!         Message::tool_result("call_1", "-3°C, snowing"),
+//!     ],
+//! )
+//! .with_tools(vec![ToolSpec::from(&WEATHER)]);
+//!
+//! assert_eq!(request.conversation().len(), 4); // system + history
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::tool::ToolDefinition;
+
+/// Author of a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+    /// Result of a tool call, answering the call named by `tool_call_id`
+    Tool,
+}
+
+impl Role {
+    /// Wire name of the role
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::System => "system",
+            Role::User => "user",
+            Role::Assistant => "assistant",
+            Role::Tool => "tool",
+        }
+    }
+}
+
+/// Image attached to a message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum Image {
+    /// Image fetched by the provider
+    Url { url: String },
+    /// Inline image data
+    Base64 { media_type: String, data: String },
+}
+
+impl Image {
+    /// The image as a URL (inline images become `data:` URLs)
+    pub fn to_url(&self) -> String {
+        match self {
+            Image::Url { url } => url.clone(),
+            Image::Base64 { media_type, data } => format!("data:{};base64,{}", media_type, data),
+        }
+    }
+
+    /// Bytes sent for the image (the encoded data, or the URL)
+    pub fn size(&self) -> usize {
+        match self {
+            Image::Url { url } => url.len(),
+            Image::Base64 { data, .. } => data.len(),
+        }
+    }
+}
+
+/// A tool call requested by the model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// Provider-assigned ID, echoed back by the tool result
+    pub id: String,
+    pub name: String,
+    pub arguments: serde_json::Value,
+}
+
+impl ToolCall {
+    /// Create a tool call
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        arguments: serde_json::Value,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            arguments,
+        }
+    }
+}
+
+/// One message of a conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    pub role: Role,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<Image>,
+    /// Tool calls made by an assistant message
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    /// Call a tool message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl Message {
+    fn new(role: Role, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            images: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }
+    }
+
+    /// System instructions
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new(Role::System, content)
+    }
+
+    /// User message
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new(Role::User, content)
+    }
+
+    /// Assistant message
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new(Role::Assistant, content)
+    }
+
+    /// Result of the tool call `tool_call_id`
+    pub fn tool_result(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            tool_call_id: Some(tool_call_id.into()),
+            ..Self::new(Role::Tool, content)
+        }
+    }
+
+    /// Attach an image
+    pub fn with_image(mut self, image: Image) -> Self {
+        self.images.push(image);
+        self
+    }
+
+    /// Record the tool calls an assistant message made
+    pub fn with_tool_calls(mut self, tool_calls: Vec<ToolCall>) -> Self {
+        self.tool_calls = tool_calls;
+        self
+    }
+}
+
+/// A tool the model may call, in owned form for requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
+    /// JSON Schema for the tool's arguments
+    pub parameters: serde_json::Value,
+}
+
+impl ToolSpec {
+    /// Convert to OpenAI-compatible tool format
+    pub fn to_openai_format(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": self.name,
+                "description": self.description,
+                "parameters": self.parameters,
+            }
+        })
+    }
+
+    /// Convert to Anthropic Claude-compatible tool format
+    pub fn to_anthropic_format(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "description": self.description,
+            "input_schema": self.parameters,
+        })
+    }
+}
+
+impl From<&ToolDefinition> for ToolSpec {
+    fn from(definition: &ToolDefinition) -> Self {
+        Self {
+            name: definition.name.to_string(),
+            description: definition.description.to_string(),
+            parameters: serde_json::from_str(definition.parameters)
+                .unwrap_or(serde_json::json!({})),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_serde_round_trip() {
+        let message = Message::tool_result("call_1", "42");
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"role": "tool", "content": "42", "tool_call_id": "call_1"})
+        );
+        assert_eq!(serde_json::from_value::<Message>(json).unwrap(), message);
+
+        let image = Image::Base64 {
+            media_type: "image/png".to_string(),
+            data: "iVBORw0".to_string(),
+        };
+        assert_eq!(image.to_url(), "data:image/png;base64,iVBORw0");
+    }
+
+    #[test]
+    fn test_request_validation_counts_images() {
+        use crate::provider::{LlmRequest, MAX_IMAGE_SIZE};
+
+        let image = |len| Image::Base64 {
+            media_type: "image/png".to_string(),
+            data: "A".repeat(len),
+        };
+        let request = LlmRequest::chat(
+            "",
+            vec![Message::user("look").with_image(image(MAX_IMAGE_SIZE / 2))],
+        );
+        assert_eq!(request.image_len(), MAX_IMAGE_SIZE / 2);
+        assert!(request.validate().is_ok());
+
+        let request = LlmRequest::chat(
+            "",
+            vec![
+                Message::user("look").with_image(image(MAX_IMAGE_SIZE / 2)),
+                Message::user("and this").with_image(image(MAX_IMAGE_SIZE / 2 + 1)),
+            ],
+        );
+        assert!(matches!(
+            request.validate(),
+            Err(crate::LlmError::InputTooLarge(len, MAX_IMAGE_SIZE)) if len == MAX_IMAGE_SIZE + 1
+        ));
+    }
+}
@@ -4,6 +4,7 @@ use async_trait::async_trait;
 
 use crate::openai_compat::OpenAICompatibleProvider;
 use crate::provider::{LlmError, LlmProvider, LlmRequest, LlmResponse};
+use crate::stream::LlmStream;
 
 /// Mistral AI provider for inference
 #[derive(Debug)]
@@ -20,7 +21,9 @@ impl MistralProvider {
                 model,
                 "https://api.mistral.ai",
                 "mistral",
-            ),
+            )
+            // Mistral rejects `stream_options`; it reports usage in the last chunk
+            .with_stream_usage(false),
         }
     }
 
@@ -82,6 +85,10 @@ impl LlmProvider for MistralProvider {
         self.inner.name()
     }
 
+    fn model(&self) -> Option<&str> {
+        self.inner.model()
+    }
+
     async fn is_available(&self) -> bool {
         self.inner.is_available().await
     }
@@ -89,6 +96,10 @@ impl LlmProvider for MistralProvider {
     async fn complete(&self, request: LlmRequest) -> Result<LlmResponse, LlmError> {
         self.inner.complete(request).await
     }
+
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream, LlmError> {
+        self.inner.complete_stream(request).await
+    }
 }
 
 #[cfg(test)]
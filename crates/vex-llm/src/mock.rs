@@ -4,6 +4,7 @@ use async_trait::async_trait;
 use std::time::Instant;
 
 use crate::provider::{LlmError, LlmProvider, LlmRequest, LlmResponse};
+use crate::stream::{LlmStream, StreamEvent};
 
 /// A mock LLM provider that returns predefined responses
 /// Perfect for testing without needing actual LLM access
@@ -152,8 +153,37 @@ impl LlmProvider for MockProvider {
             tokens_used: Some((request.prompt.len() / 4) as u32 + 100),
             latency_ms: start.elapsed().as_millis() as u64,
             trace_root: None,
+            tool_calls: Vec::new(),
+            provider: None,
+            cached: false,
         })
     }
+
+    /// Streams the canned response word by word
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream, LlmError> {
+        let response = self.complete(request).await?;
+        let mut events: Vec<Result<StreamEvent, LlmError>> = response
+            .content
+            .split_inclusive(' ')
+            .map(|word| {
+                Ok(StreamEvent::Delta {
+                    text: word.to_string(),
+                })
+            })
+            .collect();
+        if let Some(total_tokens) = response.tokens_used {
+            events.push(Ok(StreamEvent::Usage {
+                prompt_tokens: None,
+                completion_tokens: None,
+                total_tokens,
+            }));
+        }
+        events.push(Ok(StreamEvent::Finish {
+            reason: "stop".to_string(),
+            model: response.model,
+        }));
+        Ok(Box::pin(futures::stream::iter(events)))
+    }
 }
 
 #[async_trait]
@@ -4,15 +4,20 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
 
+use crate::message::{Image, Message, ToolCall};
 use crate::provider::{LlmError, LlmProvider, LlmRequest, LlmResponse};
+use crate::stream::{next_chunk, LineDecoder, LlmStream, StreamEvent, MAX_STREAM_DURATION};
 
-/// Ollama API request format
+/// Ollama chat API request format
 #[derive(Debug, Serialize)]
 struct OllamaRequest {
     model: String,
-    prompt: String,
-    system: Option<String>,
+    messages: Vec<OllamaMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<serde_json::Value>,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<serde_json::Value>,
     options: OllamaOptions,
 }
 
@@ -20,17 +25,109 @@ struct OllamaRequest {
 struct OllamaOptions {
     temperature: f32,
     num_predict: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+}
+
+/// Chat message in the Ollama format
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct OllamaMessage {
+    #[serde(default)]
+    role: String,
+    #[serde(default)]
+    content: String,
+    /// Base64-encoded images
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    images: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tool_calls: Vec<OllamaToolCall>,
+}
+
+impl OllamaMessage {
+    fn from_message(message: &Message) -> Result<Self, LlmError> {
+        let images = message
+            .images
+            .iter()
+            .map(|image| match image {
+                Image::Base64 { data, .. } => Ok(data.clone()),
+                Image::Url { .. } => Err(LlmError::RequestFailed(
+                    "Ollama accepts inline (base64) images only".to_string(),
+                )),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            role: message.role.as_str().to_string(),
+            content: message.content.clone(),
+            images,
+            tool_calls: message
+                .tool_calls
+                .iter()
+                .map(|call| OllamaToolCall {
+                    function: OllamaFunctionCall {
+                        name: call.name.clone(),
+                        arguments: call.arguments.clone(),
+                    },
+                })
+                .collect(),
+        })
+    }
+
+    /// Tool calls with generated IDs (Ollama does not assign any)
+    fn into_tool_calls(self) -> Vec<ToolCall> {
+        self.tool_calls
+            .into_iter()
+            .map(|call| {
+                ToolCall::new(
+                    format!("call_{}", uuid::Uuid::new_v4().simple()),
+                    call.function.name,
+                    call.function.arguments,
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OllamaToolCall {
+    function: OllamaFunctionCall,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OllamaFunctionCall {
+    name: String,
+    arguments: serde_json::Value,
 }
 
 /// Ollama API response format
 #[derive(Debug, Deserialize)]
 struct OllamaApiResponse {
-    response: String,
+    #[serde(default)]
+    message: OllamaMessage,
     model: String,
     #[serde(default)]
     eval_count: Option<u32>,
 }
 
+/// One NDJSON line of a streamed Ollama response
+#[derive(Debug, Deserialize)]
+struct OllamaStreamChunk {
+    #[serde(default)]
+    message: OllamaMessage,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    done_reason: Option<String>,
+    #[serde(default)]
+    prompt_eval_count: Option<u32>,
+    #[serde(default)]
+    eval_count: Option<u32>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
 /// Ollama provider for local LLM inference
 #[derive(Debug)]
 pub struct OllamaProvider {
@@ -86,43 +183,42 @@ impl OllamaProvider {
             default_timeout: std::time::Duration::from_secs(timeout),
         }
     }
-}
-
-#[async_trait]
-impl LlmProvider for OllamaProvider {
-    fn name(&self) -> &str {
-        "ollama"
-    }
 
-    async fn is_available(&self) -> bool {
-        let url = format!("{}/api/tags", self.base_url);
-        self.client.get(&url).send().await.is_ok()
-    }
-
-    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse, LlmError> {
-        let start = Instant::now();
-        let url = format!("{}/api/generate", self.base_url);
+    /// POST a chat request, mapping HTTP failures to `LlmError`
+    async fn send(
+        &self,
+        request: LlmRequest,
+        stream: bool,
+        request_timeout: std::time::Duration,
+    ) -> Result<reqwest::Response, LlmError> {
+        let url = format!("{}/api/chat", self.base_url);
 
         let ollama_request = OllamaRequest {
             model: self.model.clone(),
-            prompt: request.prompt,
-            system: Some(request.system),
-            stream: false,
+            messages: request
+                .conversation()
+                .iter()
+                .map(OllamaMessage::from_message)
+                .collect::<Result<_, _>>()?,
+            tools: request.tools.iter().map(|t| t.to_openai_format()).collect(),
+            stream,
+            // Ollama constrains generation to a JSON schema passed as `format`
+            format: request.response_format.map(|format| format.schema),
             options: OllamaOptions {
                 temperature: request.temperature,
                 num_predict: request.max_tokens,
+                seed: request.seed,
             },
         };
 
-        let request_timeout = request.timeout.unwrap_or(self.default_timeout);
-
-        let response = tokio::time::timeout(
-            request_timeout,
-            self.client.post(&url).json(&ollama_request).send(),
-        )
-        .await
-        .map_err(|_| LlmError::Timeout(request_timeout.as_millis() as u64))?
-        .map_err(|e| LlmError::ConnectionFailed(e.to_string()))?;
+        let mut builder = self.client.post(&url).json(&ollama_request);
+        if stream {
+            builder = builder.timeout(MAX_STREAM_DURATION);
+        }
+        let response = tokio::time::timeout(request_timeout, builder.send())
+            .await
+            .map_err(|_| LlmError::Timeout(request_timeout.as_millis() as u64))?
+            .map_err(|e| LlmError::ConnectionFailed(e.to_string()))?;
 
         if !response.status().is_success() {
             return Err(LlmError::RequestFailed(format!(
@@ -130,20 +226,103 @@ impl LlmProvider for OllamaProvider {
                 response.status()
             )));
         }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn model(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    async fn is_available(&self) -> bool {
+        let url = format!("{}/api/tags", self.base_url);
+        self.client.get(&url).send().await.is_ok()
+    }
 
-        let api_response: OllamaApiResponse = response
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse, LlmError> {
+        let start = Instant::now();
+        let request_timeout = request.timeout.unwrap_or(self.default_timeout);
+        let response = self.send(request, false, request_timeout).await?;
+
+        let mut api_response: OllamaApiResponse = response
             .json()
             .await
             .map_err(|e| LlmError::InvalidResponse(e.to_string()))?;
 
+        let content = std::mem::take(&mut api_response.message.content);
         Ok(LlmResponse {
-            content: api_response.response,
+            content,
             model: api_response.model,
             tokens_used: api_response.eval_count,
             latency_ms: start.elapsed().as_millis() as u64,
             trace_root: None,
+            tool_calls: api_response.message.into_tool_calls(),
+            provider: None,
+            cached: false,
         })
     }
+
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream, LlmError> {
+        let request_timeout = request.timeout.unwrap_or(self.default_timeout);
+        let mut response = self.send(request, true, request_timeout).await?;
+        let mut model = self.model.clone();
+
+        Ok(Box::pin(async_stream::try_stream! {
+            let mut lines = LineDecoder::default();
+            let mut finish_reason = None;
+            while finish_reason.is_none() {
+                let batch = match next_chunk(&mut response, request_timeout).await? {
+                    Some(chunk) => lines.push(&chunk),
+                    None => {
+                        let rest: Vec<String> = lines.finish().into_iter().collect();
+                        if rest.is_empty() {
+                            break;
+                        }
+                        rest
+                    }
+                };
+                for line in batch.iter().filter(|l| !l.trim().is_empty()) {
+                    let chunk: OllamaStreamChunk = serde_json::from_str(line)
+                        .map_err(|e| LlmError::InvalidResponse(e.to_string()))?;
+                    if let Some(error) = chunk.error {
+                        Err(LlmError::RequestFailed(error))?;
+                    }
+                    if let Some(chunk_model) = chunk.model {
+                        model = chunk_model;
+                    }
+                    let mut message = chunk.message;
+                    if !message.content.is_empty() {
+                        yield StreamEvent::Delta { text: std::mem::take(&mut message.content) };
+                    }
+                    for call in message.into_tool_calls() {
+                        yield StreamEvent::ToolCall(call);
+                    }
+                    if chunk.done {
+                        if let Some(completion_tokens) = chunk.eval_count {
+                            yield StreamEvent::Usage {
+                                prompt_tokens: chunk.prompt_eval_count,
+                                completion_tokens: Some(completion_tokens),
+                                total_tokens: completion_tokens
+                                    + chunk.prompt_eval_count.unwrap_or(0),
+                            };
+                        }
+                        finish_reason = Some(chunk.done_reason.unwrap_or_else(|| "stop".to_string()));
+                        break;
+                    }
+                }
+            }
+            yield StreamEvent::Finish {
+                reason: finish_reason.unwrap_or_else(|| "stop".to_string()),
+                model,
+            };
+        }))
+    }
 }
 
 #[cfg(test)]
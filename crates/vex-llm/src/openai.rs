@@ -4,6 +4,7 @@ use async_trait::async_trait;
 
 use crate::openai_compat::OpenAICompatibleProvider;
 use crate::provider::{LlmError, LlmProvider, LlmRequest, LlmResponse};
+use crate::stream::LlmStream;
 
 /// OpenAI provider
 #[derive(Debug)]
@@ -46,6 +47,10 @@ impl LlmProvider for OpenAIProvider {
         self.inner.name()
     }
 
+    fn model(&self) -> Option<&str> {
+        self.inner.model()
+    }
+
     async fn is_available(&self) -> bool {
         self.inner.is_available().await
     }
@@ -53,4 +58,8 @@ impl LlmProvider for OpenAIProvider {
     async fn complete(&self, request: LlmRequest) -> Result<LlmResponse, LlmError> {
         self.inner.complete(request).await
     }
+
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream, LlmError> {
+        self.inner.complete_stream(request).await
+    }
 }
@@ -5,15 +5,99 @@
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::Instant;
 
+use crate::message::{Message, ToolCall};
 use crate::provider::{LlmError, LlmProvider, LlmRequest, LlmResponse};
+use crate::stream::{next_chunk, LlmStream, SseDecoder, StreamEvent, MAX_STREAM_DURATION};
+use crate::structured::ResponseFormat;
 
 /// Chat message in the OpenAI-compatible format
 #[derive(Debug, Serialize)]
 pub struct ChatMessage {
     pub role: String,
-    pub content: String,
+    /// Text, content parts (when images are attached), or null for a message
+    /// that only calls tools
+    pub content: serde_json::Value,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ChatToolCall>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl From<&Message> for ChatMessage {
+    fn from(message: &Message) -> Self {
+        let content = if !message.images.is_empty() {
+            let mut parts = vec![serde_json::json!({ "type": "text", "text": message.content })];
+            parts.extend(message.images.iter().map(|image| {
+                serde_json::json!({ "type": "image_url", "image_url": { "url": image.to_url() } })
+            }));
+            serde_json::Value::Array(parts)
+        } else if message.content.is_empty() && !message.tool_calls.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::Value::String(message.content.clone())
+        };
+
+        Self {
+            role: message.role.as_str().to_string(),
+            content,
+            tool_calls: message.tool_calls.iter().map(ChatToolCall::from).collect(),
+            tool_call_id: message.tool_call_id.clone(),
+        }
+    }
+}
+
+/// A function call in the OpenAI-compatible format
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChatToolCall {
+    pub id: String,
+    #[serde(rename = "type", default = "function_type")]
+    pub kind: String,
+    pub function: ChatFunctionCall,
+}
+
+/// Name and JSON-encoded arguments of a function call
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChatFunctionCall {
+    pub name: String,
+    pub arguments: String,
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+impl From<&ToolCall> for ChatToolCall {
+    fn from(call: &ToolCall) -> Self {
+        Self {
+            id: call.id.clone(),
+            kind: function_type(),
+            function: ChatFunctionCall {
+                name: call.name.clone(),
+                arguments: call.arguments.to_string(),
+            },
+        }
+    }
+}
+
+impl From<ChatToolCall> for ToolCall {
+    fn from(call: ChatToolCall) -> Self {
+        ToolCall::new(
+            call.id,
+            call.function.name,
+            parse_arguments(call.function.arguments),
+        )
+    }
+}
+
+/// Decode function arguments, keeping malformed JSON as a string
+fn parse_arguments(arguments: String) -> serde_json::Value {
+    if arguments.trim().is_empty() {
+        return serde_json::json!({});
+    }
+    serde_json::from_str(&arguments).unwrap_or(serde_json::Value::String(arguments))
 }
 
 /// Request body for OpenAI-compatible chat completion APIs
@@ -21,6 +105,8 @@ pub struct ChatMessage {
 pub struct ChatCompletionRequest {
     pub model: String,
     pub messages: Vec<ChatMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<serde_json::Value>,
     pub temperature: f32,
     pub max_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -29,6 +115,40 @@ pub struct ChatCompletionRequest {
     pub presence_penalty: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<serde_json::Value>,
+}
+
+/// How a provider constrains output when a request carries a `ResponseFormat`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonMode {
+    /// `{"type": "json_schema"}` with the request schema (OpenAI, Mistral)
+    #[default]
+    Schema,
+    /// `{"type": "json_object"}` without a schema (DeepSeek)
+    Object,
+    /// No native JSON mode; rely on prompt instructions and validation
+    None,
+}
+
+impl JsonMode {
+    /// Wire value for the `response_format` field
+    fn response_format(self, format: &ResponseFormat) -> Option<serde_json::Value> {
+        match self {
+            JsonMode::Schema => Some(serde_json::json!({
+                "type": "json_schema",
+                "json_schema": { "name": format.name, "schema": format.schema },
+            })),
+            JsonMode::Object => Some(serde_json::json!({ "type": "json_object" })),
+            JsonMode::None => None,
+        }
+    }
 }
 
 /// A single choice in a chat completion response
@@ -40,12 +160,19 @@ pub struct Choice {
 /// Message content within a choice
 #[derive(Debug, Deserialize)]
 pub struct MessageContent {
-    pub content: String,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Vec<ChatToolCall>,
 }
 
 /// Token usage statistics
 #[derive(Debug, Deserialize)]
 pub struct Usage {
+    #[serde(default)]
+    pub prompt_tokens: Option<u32>,
+    #[serde(default)]
+    pub completion_tokens: Option<u32>,
     pub total_tokens: u32,
 }
 
@@ -57,6 +184,55 @@ pub struct ChatCompletionResponse {
     pub usage: Option<Usage>,
 }
 
+/// One `chat.completion.chunk` event of a streamed response
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionChunk {
+    #[serde(default)]
+    pub choices: Vec<ChunkChoice>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+/// A single choice in a streamed chunk
+#[derive(Debug, Deserialize)]
+pub struct ChunkChoice {
+    #[serde(default)]
+    pub delta: ChunkDelta,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// Content added by a streamed chunk
+#[derive(Debug, Default, Deserialize)]
+pub struct ChunkDelta {
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Vec<ChunkToolCall>,
+}
+
+/// Fragment of a streamed tool call; fragments with the same `index` are
+/// concatenated
+#[derive(Debug, Deserialize)]
+pub struct ChunkToolCall {
+    pub index: usize,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub function: Option<ChunkFunctionCall>,
+}
+
+/// Function name and argument fragment of a streamed tool call
+#[derive(Debug, Deserialize)]
+pub struct ChunkFunctionCall {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
 /// A provider that speaks the OpenAI `/v1/chat/completions` protocol.
 ///
 /// This single struct replaces duplicated implementations across OpenAI, DeepSeek,
@@ -76,6 +252,10 @@ pub struct OpenAICompatibleProvider {
     pub default_timeout: std::time::Duration,
     /// Human-readable provider name (e.g., "openai", "deepseek", "mistral")
     pub provider_name: String,
+    /// Native JSON mode used for structured output
+    pub json_mode: JsonMode,
+    /// Ask for usage in streamed responses (`stream_options.include_usage`)
+    pub stream_usage: bool,
 }
 
 impl OpenAICompatibleProvider {
@@ -101,6 +281,8 @@ impl OpenAICompatibleProvider {
             base_url: base_url.into(),
             default_timeout: std::time::Duration::from_secs(timeout),
             provider_name: provider_name.into(),
+            json_mode: JsonMode::default(),
+            stream_usage: true,
         }
     }
 
@@ -109,61 +291,67 @@ impl OpenAICompatibleProvider {
         self.base_url = base_url.into();
         self
     }
-}
 
-#[async_trait]
-impl LlmProvider for OpenAICompatibleProvider {
-    fn name(&self) -> &str {
-        &self.provider_name
+    /// Set the native JSON mode used for structured output
+    pub fn with_json_mode(mut self, json_mode: JsonMode) -> Self {
+        self.json_mode = json_mode;
+        self
     }
 
-    async fn is_available(&self) -> bool {
-        self.client
-            .get(format!("{}/v1/models", self.base_url))
-            .bearer_auth(&self.api_key)
-            .send()
-            .await
-            .is_ok()
+    /// Set whether streamed requests ask for usage (APIs that reject
+    /// `stream_options` need this off)
+    pub fn with_stream_usage(mut self, stream_usage: bool) -> Self {
+        self.stream_usage = stream_usage;
+        self
     }
 
-    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse, LlmError> {
-        let start = Instant::now();
-        let url = format!("{}/v1/chat/completions", self.base_url);
-
-        let messages = vec![
-            ChatMessage {
-                role: "system".to_string(),
-                content: request.system,
-            },
-            ChatMessage {
-                role: "user".to_string(),
-                content: request.prompt,
-            },
-        ];
+    fn chat_request(&self, request: LlmRequest, stream: bool) -> ChatCompletionRequest {
+        let messages = request
+            .conversation()
+            .iter()
+            .map(ChatMessage::from)
+            .collect();
 
-        let api_request = ChatCompletionRequest {
+        ChatCompletionRequest {
             model: self.model.clone(),
             messages,
+            tools: request.tools.iter().map(|t| t.to_openai_format()).collect(),
             temperature: request.temperature,
             max_tokens: request.max_tokens,
             top_p: request.top_p,
             presence_penalty: request.presence_penalty,
             frequency_penalty: request.frequency_penalty,
-        };
+            seed: request.seed,
+            response_format: request
+                .response_format
+                .as_ref()
+                .and_then(|format| self.json_mode.response_format(format)),
+            stream,
+            stream_options: (stream && self.stream_usage)
+                .then(|| serde_json::json!({ "include_usage": true })),
+        }
+    }
 
-        let request_timeout = request.timeout.unwrap_or(self.default_timeout);
+    /// POST a chat completion request, mapping HTTP failures to `LlmError`
+    async fn send(
+        &self,
+        api_request: &ChatCompletionRequest,
+        request_timeout: std::time::Duration,
+    ) -> Result<reqwest::Response, LlmError> {
+        let url = format!("{}/v1/chat/completions", self.base_url);
+        let mut builder = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.api_key)
+            .json(api_request);
+        if api_request.stream {
+            builder = builder.timeout(MAX_STREAM_DURATION);
+        }
 
-        let response = tokio::time::timeout(
-            request_timeout,
-            self.client
-                .post(&url)
-                .bearer_auth(&self.api_key)
-                .json(&api_request)
-                .send(),
-        )
-        .await
-        .map_err(|_| LlmError::Timeout(request_timeout.as_millis() as u64))?
-        .map_err(|e| LlmError::ConnectionFailed(e.to_string()))?;
+        let response = tokio::time::timeout(request_timeout, builder.send())
+            .await
+            .map_err(|_| LlmError::Timeout(request_timeout.as_millis() as u64))?
+            .map_err(|e| LlmError::ConnectionFailed(e.to_string()))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -178,17 +366,48 @@ impl LlmProvider for OpenAICompatibleProvider {
                 status, body
             )));
         }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAICompatibleProvider {
+    fn name(&self) -> &str {
+        &self.provider_name
+    }
+
+    fn model(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    async fn is_available(&self) -> bool {
+        self.client
+            .get(format!("{}/v1/models", self.base_url))
+            .bearer_auth(&self.api_key)
+            .send()
+            .await
+            .is_ok()
+    }
+
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse, LlmError> {
+        let start = Instant::now();
+        let request_timeout = request.timeout.unwrap_or(self.default_timeout);
+        let api_request = self.chat_request(request, false);
+        let response = self.send(&api_request, request_timeout).await?;
 
         let api_response: ChatCompletionResponse = response
             .json()
             .await
             .map_err(|e| LlmError::InvalidResponse(e.to_string()))?;
 
-        let content = api_response
-            .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .unwrap_or_default();
+        let message = api_response.choices.into_iter().next().map(|c| c.message);
+        let (content, tool_calls) = match message {
+            Some(message) => (
+                message.content.unwrap_or_default(),
+                message.tool_calls.into_iter().map(ToolCall::from).collect(),
+            ),
+            None => (String::new(), Vec::new()),
+        };
 
         Ok(LlmResponse {
             content,
@@ -196,6 +415,73 @@ impl LlmProvider for OpenAICompatibleProvider {
             tokens_used: api_response.usage.map(|u| u.total_tokens),
             latency_ms: start.elapsed().as_millis() as u64,
             trace_root: None,
+            tool_calls,
+            provider: None,
+            cached: false,
         })
     }
+
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream, LlmError> {
+        let request_timeout = request.timeout.unwrap_or(self.default_timeout);
+        let api_request = self.chat_request(request, true);
+        let mut response = self.send(&api_request, request_timeout).await?;
+        let mut model = self.model.clone();
+
+        Ok(Box::pin(async_stream::try_stream! {
+            let mut sse = SseDecoder::default();
+            let mut tool_calls: BTreeMap<usize, (String, String, String)> = BTreeMap::new();
+            let mut finish_reason = None;
+            let mut done = false;
+            while !done {
+                let events = match next_chunk(&mut response, request_timeout).await? {
+                    Some(chunk) => sse.push(&chunk),
+                    None => {
+                        done = true;
+                        sse.finish()
+                    }
+                };
+                for data in events {
+                    if data == "[DONE]" {
+                        done = true;
+                        break;
+                    }
+                    let chunk: ChatCompletionChunk = serde_json::from_str(&data)
+                        .map_err(|e| LlmError::InvalidResponse(e.to_string()))?;
+                    if let Some(chunk_model) = chunk.model {
+                        model = chunk_model;
+                    }
+                    for choice in chunk.choices {
+                        if let Some(text) = choice.delta.content.filter(|t| !t.is_empty()) {
+                            yield StreamEvent::Delta { text };
+                        }
+                        for fragment in choice.delta.tool_calls {
+                            let (id, name, arguments) = tool_calls.entry(fragment.index).or_default();
+                            id.push_str(fragment.id.as_deref().unwrap_or_default());
+                            if let Some(function) = fragment.function {
+                                name.push_str(function.name.as_deref().unwrap_or_default());
+                                arguments.push_str(function.arguments.as_deref().unwrap_or_default());
+                            }
+                        }
+                        if choice.finish_reason.is_some() {
+                            finish_reason = choice.finish_reason;
+                        }
+                    }
+                    if let Some(usage) = chunk.usage {
+                        yield StreamEvent::Usage {
+                            prompt_tokens: usage.prompt_tokens,
+                            completion_tokens: usage.completion_tokens,
+                            total_tokens: usage.total_tokens,
+                        };
+                    }
+                }
+            }
+            for (id, name, arguments) in tool_calls.into_values() {
+                yield StreamEvent::ToolCall(ToolCall::new(id, name, parse_arguments(arguments)));
+            }
+            yield StreamEvent::Finish {
+                reason: finish_reason.unwrap_or_else(|| "stop".to_string()),
+                model,
+            };
+        }))
+    }
 }
@@ -0,0 +1,324 @@
+//! LLM response cache over a `vex_persist` storage backend
+//!
+//! Entries are stored under their content-addressed [`cache_key`] so every
+//! replica sharing the database (SQLite or Postgres) hits the same entries, and
+//! they survive restarts. Expired entries are dropped when read. Each instance
+//! keeps an estimate of the entry count, so the key scan runs only on the first
+//! insert and once the estimate passes `max_entries`; the oldest entries are then
+//! evicted down to 90% of the limit. Entries written by other replicas are only
+//! counted at the next scan, so the shared cache can briefly exceed the limit.
+//!
+//! [`cache_key`]: crate::cached_provider::cache_key
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use vex_persist::{StorageBackend, StorageError};
+
+use crate::cached_provider::{LlmCacheBackend, LlmCacheConfig, LlmCacheError};
+use crate::LlmResponse;
+
+impl From<StorageError> for LlmCacheError {
+    fn from(e: StorageError) -> Self {
+        LlmCacheError(e.to_string())
+    }
+}
+
+/// A cached response as stored
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    response: LlmResponse,
+    stored_at: DateTime<Utc>,
+}
+
+/// Persistent cache shared through a storage backend
+///
+/// Honors `ttl` and `max_entries` of [`LlmCacheConfig`]; `tti` would need a
+/// write on every hit and is ignored with a warning.
+#[derive(Debug)]
+pub struct PersistentLlmCache<B: StorageBackend + ?Sized> {
+    backend: Arc<B>,
+    prefix: String,
+    ttl: Duration,
+    max_entries: u64,
+    /// Entry count as of the last scan plus local inserts since; `None` until scanned
+    estimated_entries: Mutex<Option<u64>>,
+}
+
+impl<B: StorageBackend + ?Sized> PersistentLlmCache<B> {
+    /// Create a cache over a storage backend
+    pub fn new(backend: Arc<B>, config: LlmCacheConfig) -> Self {
+        if let Some(tti) = config.tti {
+            tracing::warn!(
+                ?tti,
+                "Persistent LLM cache ignores time-to-idle; entries expire by ttl only"
+            );
+        }
+        Self {
+            backend,
+            prefix: "llm_cache:".to_string(),
+            ttl: config.ttl,
+            max_entries: config.max_entries,
+            estimated_entries: Mutex::new(None),
+        }
+    }
+
+    fn set_estimate(&self, count: u64) {
+        *self.estimated_entries.lock().unwrap() = Some(count);
+    }
+
+    /// Count a new entry, returning whether a capacity scan is due
+    fn count_insert(&self) -> bool {
+        let mut estimate = self.estimated_entries.lock().unwrap();
+        match estimate.as_mut() {
+            Some(count) => {
+                *count += 1;
+                *count > self.max_entries
+            }
+            None => true,
+        }
+    }
+
+    fn count_removal(&self) {
+        if let Some(count) = self.estimated_entries.lock().unwrap().as_mut() {
+            *count = count.saturating_sub(1);
+        }
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    fn is_expired(&self, entry: &CacheEntry) -> bool {
+        let age = Utc::now().signed_duration_since(entry.stored_at);
+        age.to_std().is_ok_and(|age| age >= self.ttl)
+    }
+
+    async fn load(&self, key: &str) -> Result<Option<CacheEntry>, LlmCacheError> {
+        match self.backend.get_value(key).await? {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| LlmCacheError(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// Evict expired and oldest entries once the cache is over capacity
+    async fn evict(&self) -> Result<(), LlmCacheError> {
+        let keys = self.backend.list_keys(&self.prefix).await?;
+        if keys.len() as u64 <= self.max_entries {
+            self.set_estimate(keys.len() as u64);
+            return Ok(());
+        }
+
+        let mut live = Vec::with_capacity(keys.len());
+        for key in keys {
+            match self.load(&key).await {
+                Ok(Some(entry)) if !self.is_expired(&entry) => live.push((entry.stored_at, key)),
+                // Expired or unreadable
+                Ok(Some(_)) | Err(_) => {
+                    self.backend.delete(&key).await?;
+                }
+                Ok(None) => {}
+            }
+        }
+
+        let target = (self.max_entries - self.max_entries / 10) as usize;
+        if live.len() > target {
+            live.sort();
+            for (_, key) in &live[..live.len() - target] {
+                self.backend.delete(key).await?;
+            }
+        }
+        self.set_estimate(live.len().min(target) as u64);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: StorageBackend + ?Sized> LlmCacheBackend for PersistentLlmCache<B> {
+    async fn get(&self, key: &str) -> Result<Option<LlmResponse>, LlmCacheError> {
+        let key = self.key(key);
+        match self.load(&key).await? {
+            Some(entry) if self.is_expired(&entry) => {
+                self.backend.delete(&key).await?;
+                self.count_removal();
+                Ok(None)
+            }
+            entry => Ok(entry.map(|e| e.response)),
+        }
+    }
+
+    async fn insert(&self, key: &str, response: &LlmResponse) -> Result<(), LlmCacheError> {
+        let entry = CacheEntry {
+            response: response.clone(),
+            stored_at: Utc::now(),
+        };
+        let value = serde_json::to_value(&entry).map_err(|e| LlmCacheError(e.to_string()))?;
+        self.backend.set_value(&self.key(key), value).await?;
+        if self.count_insert() {
+            self.evict().await?;
+        }
+        Ok(())
+    }
+
+    async fn clear(&self) -> Result<(), LlmCacheError> {
+        for key in self.backend.list_keys(&self.prefix).await? {
+            self.backend.delete(&key).await?;
+        }
+        self.set_estimate(0);
+        Ok(())
+    }
+
+    async fn entry_count(&self) -> Result<u64, LlmCacheError> {
+        Ok(self.backend.list_keys(&self.prefix).await?.len() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CachedProvider, LlmProvider, LlmRequest, MockProvider};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use vex_persist::backend::MemoryBackend;
+
+    /// Memory backend counting key scans
+    #[derive(Debug, Default)]
+    struct CountingBackend {
+        inner: MemoryBackend,
+        scans: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl StorageBackend for CountingBackend {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        async fn is_healthy(&self) -> bool {
+            true
+        }
+
+        async fn set_value(&self, key: &str, value: serde_json::Value) -> Result<(), StorageError> {
+            self.inner.set_value(key, value).await
+        }
+
+        async fn get_value(&self, key: &str) -> Result<Option<serde_json::Value>, StorageError> {
+            self.inner.get_value(key).await
+        }
+
+        async fn delete(&self, key: &str) -> Result<bool, StorageError> {
+            self.inner.delete(key).await
+        }
+
+        async fn exists(&self, key: &str) -> Result<bool, StorageError> {
+            self.inner.exists(key).await
+        }
+
+        async fn list_keys(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+            self.scans.fetch_add(1, Ordering::SeqCst);
+            self.inner.list_keys(prefix).await
+        }
+    }
+
+    fn config(max_entries: u64, ttl: Duration) -> LlmCacheConfig {
+        LlmCacheConfig {
+            max_entries,
+            ttl,
+            tti: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shared_cache_across_providers() {
+        let backend = Arc::new(MemoryBackend::new());
+        let cache: Arc<dyn LlmCacheBackend> = Arc::new(PersistentLlmCache::new(
+            backend.clone(),
+            config(100, Duration::from_secs(60)),
+        ));
+
+        // Two "replicas" over the same storage
+        let first = CachedProvider::with_backend(MockProvider::constant("fresh"), cache.clone())
+            .with_model("mock-1");
+        let second = CachedProvider::with_backend(MockProvider::constant("other"), cache.clone())
+            .with_model("mock-1");
+
+        let fresh = first.complete(LlmRequest::simple("hi")).await.unwrap();
+        assert!(!fresh.cached);
+        let replayed = second.complete(LlmRequest::simple("hi")).await.unwrap();
+        assert!(replayed.cached);
+        assert_eq!(replayed.content, "fresh");
+
+        // Tenant and model are part of the key
+        let mut tenant_request = LlmRequest::simple("hi");
+        tenant_request.tenant_id = Some("tenant-b".to_string());
+        assert!(!second.complete(tenant_request).await.unwrap().cached);
+        let other_model = CachedProvider::with_backend(MockProvider::constant("other"), cache)
+            .with_model("mock-2");
+        assert!(
+            !other_model
+                .complete(LlmRequest::simple("hi"))
+                .await
+                .unwrap()
+                .cached
+        );
+        assert_eq!(backend.list_keys("llm_cache:").await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_ttl_and_size_eviction() {
+        let backend = Arc::new(MemoryBackend::new());
+        let response = MockProvider::constant("x")
+            .complete(LlmRequest::simple("hi"))
+            .await
+            .unwrap();
+
+        let expiring = PersistentLlmCache::new(backend.clone(), config(100, Duration::ZERO));
+        expiring.insert("a", &response).await.unwrap();
+        assert!(expiring.get("a").await.unwrap().is_none());
+        assert_eq!(expiring.entry_count().await.unwrap(), 0);
+
+        let bounded = PersistentLlmCache::new(backend, config(10, Duration::from_secs(60)));
+        for i in 0..11 {
+            bounded.insert(&format!("k{}", i), &response).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        // Evicted down to 90% of capacity, oldest first
+        assert_eq!(bounded.entry_count().await.unwrap(), 9);
+        assert!(bounded.get("k0").await.unwrap().is_none());
+        assert!(bounded.get("k10").await.unwrap().is_some());
+
+        bounded.clear().await.unwrap();
+        assert_eq!(bounded.entry_count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_inserts_do_not_scan_keys() {
+        let backend = Arc::new(CountingBackend::default());
+        let cache = PersistentLlmCache::new(backend.clone(), config(10, Duration::from_secs(60)));
+        let response = MockProvider::constant("x")
+            .complete(LlmRequest::simple("hi"))
+            .await
+            .unwrap();
+
+        for i in 0..10 {
+            cache.insert(&format!("k{}", i), &response).await.unwrap();
+        }
+        // Only the first insert scans to seed the estimate
+        assert_eq!(backend.scans.load(Ordering::SeqCst), 1);
+
+        // Going over capacity scans once and evicts
+        cache.insert("k10", &response).await.unwrap();
+        assert_eq!(backend.scans.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            backend.inner.list_keys("llm_cache:").await.unwrap().len(),
+            9
+        );
+    }
+}
@@ -4,6 +4,10 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::message::{Message, ToolCall, ToolSpec};
+use crate::stream::{response_stream, LlmStream};
+use crate::structured::ResponseFormat;
+
 /// Errors from LLM providers
 #[derive(Debug, Error)]
 pub enum LlmError {
@@ -27,6 +31,8 @@ pub enum LlmError {
 pub const MAX_PROMPT_SIZE: usize = 100 * 1024;
 /// Maximum allowed system prompt size in bytes (10KB)
 pub const MAX_SYSTEM_SIZE: usize = 10 * 1024;
+/// Maximum total size of attached images in bytes (20MB, base64 encoded)
+pub const MAX_IMAGE_SIZE: usize = 20 * 1024 * 1024;
 
 /// A request to an LLM
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,8 +42,14 @@ pub struct LlmRequest {
     pub tenant_id: Option<String>,
     /// System prompt (role/persona)
     pub system: String,
-    /// User message
+    /// Conversation history, between the system prompt and `prompt`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<Message>,
+    /// User message (empty when the conversation ends in `messages`)
     pub prompt: String,
+    /// Tools the model may call natively
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<ToolSpec>,
     /// Temperature (0.0 = deterministic, 1.0 = creative)
     pub temperature: f32,
     /// Maximum tokens to generate
@@ -48,6 +60,12 @@ pub struct LlmRequest {
     pub presence_penalty: Option<f32>,
     /// Frequency penalty
     pub frequency_penalty: Option<f32>,
+    /// Sampling seed for providers that support reproducible sampling
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// JSON schema the response must conform to (structured output)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
     /// Optional timeout override for this specific request
     #[serde(skip)]
     pub timeout: Option<std::time::Duration>,
@@ -59,12 +77,16 @@ impl LlmRequest {
         Self {
             tenant_id: None,
             system: "You are a helpful assistant.".to_string(),
+            messages: Vec::new(),
             prompt: prompt.to_string(),
+            tools: Vec::new(),
             temperature: 0.7,
             max_tokens: 1024,
             top_p: None,
             presence_penalty: None,
             frequency_penalty: None,
+            seed: None,
+            response_format: None,
             timeout: None,
         }
     }
@@ -73,25 +95,83 @@ impl LlmRequest {
     pub fn with_role(system: &str, prompt: &str) -> Self {
         Self {
             system: system.to_string(),
+            messages: Vec::new(),
             prompt: prompt.to_string(),
+            tools: Vec::new(),
             temperature: 0.7,
             max_tokens: 1024,
             tenant_id: None,
             top_p: None,
             presence_penalty: None,
             frequency_penalty: None,
+            seed: None,
+            response_format: None,
             timeout: None,
         }
     }
 
+    /// Create a multi-turn request from a conversation history
+    pub fn chat(system: &str, messages: Vec<Message>) -> Self {
+        Self {
+            messages,
+            ..Self::with_role(system, "")
+        }
+    }
+
+    /// Offer tools for native function calling
+    pub fn with_tools(mut self, tools: Vec<ToolSpec>) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    /// The full conversation: system prompt, history, then `prompt`
+    pub fn conversation(&self) -> Vec<Message> {
+        let mut conversation = Vec::with_capacity(self.messages.len() + 2);
+        if !self.system.is_empty() {
+            conversation.push(Message::system(self.system.as_str()));
+        }
+        conversation.extend(self.messages.iter().cloned());
+        if !self.prompt.is_empty() {
+            conversation.push(Message::user(self.prompt.as_str()));
+        }
+        conversation
+    }
+
+    /// Bytes of text input (system prompt, history and prompt)
+    pub fn input_len(&self) -> usize {
+        self.system.len()
+            + self.prompt.len()
+            + self.messages.iter().map(|m| m.content.len()).sum::<usize>()
+    }
+
+    /// Bytes of images attached to the history
+    pub fn image_len(&self) -> usize {
+        self.messages
+            .iter()
+            .flat_map(|m| &m.images)
+            .map(|image| image.size())
+            .sum()
+    }
+
+    /// Request a response conforming to a JSON schema
+    pub fn with_response_format(mut self, format: ResponseFormat) -> Self {
+        self.response_format = Some(format);
+        self
+    }
+
     /// Validate request sizes to prevent DoS attacks
     pub fn validate(&self) -> Result<(), LlmError> {
-        if self.prompt.len() > MAX_PROMPT_SIZE {
-            return Err(LlmError::InputTooLarge(self.prompt.len(), MAX_PROMPT_SIZE));
+        let prompt_len = self.input_len() - self.system.len();
+        if prompt_len > MAX_PROMPT_SIZE {
+            return Err(LlmError::InputTooLarge(prompt_len, MAX_PROMPT_SIZE));
         }
         if self.system.len() > MAX_SYSTEM_SIZE {
             return Err(LlmError::InputTooLarge(self.system.len(), MAX_SYSTEM_SIZE));
         }
+        let image_len = self.image_len();
+        if image_len > MAX_IMAGE_SIZE {
+            return Err(LlmError::InputTooLarge(image_len, MAX_IMAGE_SIZE));
+        }
         Ok(())
     }
 }
@@ -110,6 +190,16 @@ pub struct LlmResponse {
     /// Merkle root of logit hashes (for cryptographic verification)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub trace_root: Option<String>,
+    /// Tool calls requested by the model
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    /// Provider that answered, when a wrapper chose among several (see
+    /// [`FallbackProvider`](crate::FallbackProvider))
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Replayed from a response cache rather than generated for this request
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
 }
 
 /// Trait for LLM providers
@@ -118,12 +208,25 @@ pub trait LlmProvider: Send + Sync + std::fmt::Debug {
     /// Get the provider name
     fn name(&self) -> &str;
 
+    /// The model requests are sent to, if the provider uses a single one
+    fn model(&self) -> Option<&str> {
+        None
+    }
+
     /// Check if the provider is available
     async fn is_available(&self) -> bool;
 
     /// Generate a completion
     async fn complete(&self, request: LlmRequest) -> Result<LlmResponse, LlmError>;
 
+    /// Generate a completion as a stream of token deltas, usage and finish reason
+    ///
+    /// The default implementation waits for [`LlmProvider::complete`] and
+    /// streams the whole response as a single delta.
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream, LlmError> {
+        Ok(response_stream(self.complete(request).await?))
+    }
+
     /// Generate with a simple prompt (convenience method)
     async fn ask(&self, prompt: &str) -> Result<String, LlmError> {
         let response = self.complete(LlmRequest::simple(prompt)).await?;
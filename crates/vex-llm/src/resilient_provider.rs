@@ -14,7 +14,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
-use crate::{LlmError, LlmProvider, LlmRequest, LlmResponse};
+use crate::{LlmError, LlmProvider, LlmRequest, LlmResponse, LlmStream};
 
 /// Circuit breaker state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -152,6 +152,20 @@ impl<P: LlmProvider> ResilientProvider<P> {
         }
     }
 
+    async fn record_result(&self, error: Option<&LlmError>) {
+        match error {
+            None => self.record_success().await,
+            // Only count as failure for connection/availability issues, not validation
+            Some(
+                LlmError::ConnectionFailed(_)
+                | LlmError::NotAvailable
+                | LlmError::RateLimited
+                | LlmError::Timeout(_),
+            ) => self.record_failure().await,
+            Some(_) => {}
+        }
+    }
+
     async fn check_circuit(&self) -> Result<(), LlmError> {
         let mut state = self.cb_state.write().await;
 
@@ -181,6 +195,10 @@ impl<P: LlmProvider + 'static> LlmProvider for ResilientProvider<P> {
         "resilient"
     }
 
+    fn model(&self) -> Option<&str> {
+        self.inner.model()
+    }
+
     async fn is_available(&self) -> bool {
         self.check_circuit().await.is_ok() && self.inner.is_available().await
     }
@@ -192,25 +210,19 @@ impl<P: LlmProvider + 'static> LlmProvider for ResilientProvider<P> {
         self.check_circuit().await?;
 
         // Execute request
-        match self.inner.complete(request).await {
-            Ok(response) => {
-                self.record_success().await;
-                Ok(response)
-            }
-            Err(e) => {
-                // Only count as failure for connection/availability issues, not validation
-                match &e {
-                    LlmError::ConnectionFailed(_)
-                    | LlmError::NotAvailable
-                    | LlmError::RateLimited
-                    | LlmError::Timeout(_) => {
-                        self.record_failure().await;
-                    }
-                    _ => {}
-                }
-                Err(e)
-            }
-        }
+        let result = self.inner.complete(request).await;
+        self.record_result(result.as_ref().err()).await;
+        result
+    }
+
+    /// Opening the stream counts toward the circuit; errors mid-stream do not
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream, LlmError> {
+        self.total_requests.fetch_add(1, Ordering::Relaxed);
+        self.check_circuit().await?;
+
+        let result = self.inner.complete_stream(request).await;
+        self.record_result(result.as_ref().err()).await;
+        result
     }
 }
 
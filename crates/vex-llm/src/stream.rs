@@ -0,0 +1,290 @@
+//! Token streaming for LLM completions
+//!
+//! [`LlmProvider::complete_stream`](crate::LlmProvider::complete_stream) yields
+//! [`StreamEvent`]s as the model generates: text deltas, complete tool calls,
+//! token usage when the provider reports it, and a final [`StreamEvent::Finish`]
+//! with the finish reason. Providers without native streaming fall back to
+//! [`response_stream`], which replays a complete response as one delta.
+//!
+//! ```rust
+//! use futures::StreamExt;
+//! use vex_llm::{LlmProvider, LlmRequest, MockProvider, StreamEvent};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let llm = MockProvider::constant("Merkle trees hash pairs of nodes");
+//! let mut stream = llm.complete_stream(LlmRequest::simple("Explain")).await.unwrap();
+//! let mut text = String::new();
+//! while let Some(event) = stream.next().await {
+//!     if let StreamEvent::Delta { text: delta } = event.unwrap() {
+//!         text.push_str(&delta);
+//!     }
+//! }
+//! assert_eq!(text, "Merkle trees hash pairs of nodes");
+//! # }
+//! ```
+
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use crate::message::ToolCall;
+use crate::provider::{LlmError, LlmResponse};
+
+/// Upper bound on a streamed request, replacing the client's total timeout
+/// (the per-request timeout applies to each chunk instead)
+pub const MAX_STREAM_DURATION: Duration = Duration::from_secs(600);
+
+/// One event of a streamed completion
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    /// Generated text fragment
+    Delta { text: String },
+    /// A tool call, emitted once its arguments are complete
+    ToolCall(ToolCall),
+    /// Token usage reported by the provider
+    Usage {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prompt_tokens: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        completion_tokens: Option<u32>,
+        total_tokens: u32,
+    },
+    /// Generation finished; always the last event
+    Finish { reason: String, model: String },
+}
+
+/// Stream of completion events
+pub type LlmStream = Pin<Box<dyn Stream<Item = Result<StreamEvent, LlmError>> + Send>>;
+
+/// Stream a complete response (fallback for providers that do not stream)
+pub fn response_stream(response: LlmResponse) -> LlmStream {
+    let mut events = vec![Ok(StreamEvent::Delta {
+        text: response.content,
+    })];
+    events.extend(
+        response
+            .tool_calls
+            .into_iter()
+            .map(StreamEvent::ToolCall)
+            .map(Ok),
+    );
+    if let Some(total_tokens) = response.tokens_used {
+        events.push(Ok(StreamEvent::Usage {
+            prompt_tokens: None,
+            completion_tokens: None,
+            total_tokens,
+        }));
+    }
+    events.push(Ok(StreamEvent::Finish {
+        reason: "stop".to_string(),
+        model: response.model,
+    }));
+    Box::pin(futures::stream::iter(events))
+}
+
+/// Accumulates stream events into an [`LlmResponse`]
+#[derive(Debug)]
+pub struct StreamCollector {
+    content: String,
+    model: String,
+    tokens_used: Option<u32>,
+    tool_calls: Vec<ToolCall>,
+    finish_reason: Option<String>,
+    start: Instant,
+}
+
+impl Default for StreamCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamCollector {
+    /// Start collecting (latency is measured from now)
+    pub fn new() -> Self {
+        Self {
+            content: String::new(),
+            model: String::new(),
+            tokens_used: None,
+            tool_calls: Vec::new(),
+            finish_reason: None,
+            start: Instant::now(),
+        }
+    }
+
+    /// Add one event
+    pub fn push(&mut self, event: &StreamEvent) {
+        match event {
+            StreamEvent::Delta { text } => self.content.push_str(text),
+            StreamEvent::ToolCall(call) => self.tool_calls.push(call.clone()),
+            StreamEvent::Usage { total_tokens, .. } => self.tokens_used = Some(*total_tokens),
+            StreamEvent::Finish { reason, model } => {
+                self.finish_reason = Some(reason.clone());
+                self.model = model.clone();
+            }
+        }
+    }
+
+    /// Finish reason, once the stream has finished
+    pub fn finish_reason(&self) -> Option<&str> {
+        self.finish_reason.as_deref()
+    }
+
+    /// The response assembled so far
+    pub fn into_response(self) -> LlmResponse {
+        LlmResponse {
+            content: self.content,
+            model: self.model,
+            tokens_used: self.tokens_used,
+            latency_ms: self.start.elapsed().as_millis() as u64,
+            trace_root: None,
+            tool_calls: self.tool_calls,
+            provider: None,
+            cached: false,
+        }
+    }
+}
+
+/// Drain a stream into a single response
+pub async fn collect(mut stream: LlmStream) -> Result<LlmResponse, LlmError> {
+    let mut collector = StreamCollector::new();
+    while let Some(event) = stream.next().await {
+        collector.push(&event?);
+    }
+    Ok(collector.into_response())
+}
+
+/// Read the next body chunk, failing if none arrives within `idle_timeout`
+pub(crate) async fn next_chunk(
+    response: &mut reqwest::Response,
+    idle_timeout: Duration,
+) -> Result<Option<Vec<u8>>, LlmError> {
+    tokio::time::timeout(idle_timeout, response.chunk())
+        .await
+        .map_err(|_| LlmError::Timeout(idle_timeout.as_millis() as u64))?
+        .map(|chunk| chunk.map(|bytes| bytes.to_vec()))
+        .map_err(|e| LlmError::ConnectionFailed(e.to_string()))
+}
+
+/// Splits a byte stream into lines (NDJSON), buffering partial lines and
+/// multi-byte characters across chunks
+#[derive(Debug, Default)]
+pub(crate) struct LineDecoder {
+    buffer: Vec<u8>,
+}
+
+impl LineDecoder {
+    /// Add a chunk and return the complete lines it finished
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut lines = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line[..pos]);
+            lines.push(line.strip_suffix('\r').unwrap_or(&line).to_string());
+        }
+        lines
+    }
+
+    /// The unterminated final line, if any
+    pub(crate) fn finish(&mut self) -> Option<String> {
+        let rest = std::mem::take(&mut self.buffer);
+        let rest = String::from_utf8_lossy(&rest).trim().to_string();
+        (!rest.is_empty()).then_some(rest)
+    }
+}
+
+/// Decodes Server-Sent Events, returning the `data` of each event
+#[derive(Debug, Default)]
+pub(crate) struct SseDecoder {
+    lines: LineDecoder,
+    data: Vec<String>,
+}
+
+impl SseDecoder {
+    /// Add a chunk and return the data of every event it completed
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        let mut events = Vec::new();
+        for line in self.lines.push(chunk) {
+            self.line(line, &mut events);
+        }
+        events
+    }
+
+    /// Flush an event left open when the stream ended
+    pub(crate) fn finish(&mut self) -> Vec<String> {
+        let mut events = Vec::new();
+        if let Some(line) = self.lines.finish() {
+            self.line(line, &mut events);
+        }
+        self.line(String::new(), &mut events);
+        events
+    }
+
+    fn line(&mut self, line: String, events: &mut Vec<String>) {
+        if line.is_empty() {
+            if !self.data.is_empty() {
+                events.push(std::mem::take(&mut self.data).join("\n"));
+            }
+        } else if let Some(value) = line.strip_prefix("data:") {
+            self.data
+                .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+        }
+        // Comments (":") and other fields (event, id, retry) are ignored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_decoder_split_chunks() {
+        let mut decoder = LineDecoder::default();
+        assert!(decoder.push(b"{\"a\":").is_empty());
+        assert_eq!(decoder.push(b"1}\r\n{\"b\""), vec!["{\"a\":1}"]);
+        // A multi-byte character split across chunks survives
+        let snowman = "☃".as_bytes();
+        assert!(decoder.push(&snowman[..1]).is_empty());
+        assert_eq!(
+            decoder.push(&[&snowman[1..], b"\n"].concat()),
+            vec!["{\"b\"☃"]
+        );
+        decoder.push(b"tail");
+        assert_eq!(decoder.finish().as_deref(), Some("tail"));
+    }
+
+    #[test]
+    fn test_sse_decoder() {
+        let mut decoder = SseDecoder::default();
+        let body =
+            ": keep-alive\n\ndata: {\"x\":1}\n\ndata:a\ndata: b\n\nevent: ping\ndata: [DONE]";
+        let (head, tail) = body.split_at(17);
+        let mut events = decoder.push(head.as_bytes());
+        events.extend(decoder.push(tail.as_bytes()));
+        assert_eq!(events, vec!["{\"x\":1}", "a\nb"]);
+        assert_eq!(decoder.finish(), vec!["[DONE]"]);
+    }
+
+    #[tokio::test]
+    async fn test_response_stream_round_trip() {
+        let response = LlmResponse {
+            content: "hello".to_string(),
+            model: "m".to_string(),
+            tokens_used: Some(3),
+            latency_ms: 0,
+            trace_root: None,
+            tool_calls: vec![ToolCall::new("call_1", "lookup", serde_json::json!({}))],
+            provider: None,
+            cached: false,
+        };
+        let collected = collect(response_stream(response)).await.unwrap();
+        assert_eq!(collected.content, "hello");
+        assert_eq!(collected.tool_calls[0].name, "lookup");
+        assert_eq!(collected.model, "m");
+        assert_eq!(collected.tokens_used, Some(3));
+    }
+}
@@ -0,0 +1,376 @@
+//! Structured (JSON-schema) output for LLM requests
+//!
+//! A [`ResponseFormat`] attached to an [`LlmRequest`] asks providers that support
+//! it (OpenAI-compatible `response_format`, Ollama `format`) to constrain
+//! generation to a JSON schema. Because not every provider or model honours
+//! that, [`StructuredOutput`] also validates each reply against the schema and
+//! re-prompts the model with the validation errors until it gets a valid
+//! object or runs out of repair attempts.
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use serde_json::json;
+//! use vex_llm::{LlmRequest, MockProvider, ResponseFormat, StructuredOutput};
+//!
+//! #[derive(Deserialize)]
+//! struct Verdict {
+//!     safe: bool,
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let format = ResponseFormat::new(
+//!     "verdict",
+//!     json!({
+//!         "type": "object",
+//!         "properties": { "safe": { "type": "boolean" } },
+//!         "required": ["safe"]
+//!     }),
+//! );
+//! let llm = MockProvider::constant(r#"{"safe": true}"#);
+//! let verdict = StructuredOutput::new(format)
+//!     .complete::<Verdict, _>(&llm, LlmRequest::simple("Is this safe?"))
+//!     .await
+//!     .unwrap();
+//! assert!(verdict.value.safe);
+//! # }
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::future::Future;
+use thiserror::Error;
+
+use crate::provider::{LlmError, LlmProvider, LlmRequest, LlmResponse};
+
+/// Default number of repair prompts after the first invalid reply
+pub const DEFAULT_MAX_REPAIRS: u32 = 2;
+
+/// Maximum characters of an invalid reply echoed back in a repair prompt
+const MAX_ECHOED_OUTPUT: usize = 2000;
+
+/// JSON schema a response must conform to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseFormat {
+    /// Schema name (sent to providers that require one)
+    pub name: String,
+    /// JSON schema for the response object
+    pub schema: Value,
+}
+
+impl ResponseFormat {
+    /// Create a response format from a name and JSON schema
+    pub fn new(name: impl Into<String>, schema: Value) -> Self {
+        Self {
+            name: name.into(),
+            schema,
+        }
+    }
+
+    /// Prompt text describing the expected reply
+    pub fn instructions(&self) -> String {
+        format!(
+            "Respond with a single JSON value that conforms to this JSON schema, \
+             with no surrounding text or code fences:\n{}",
+            self.schema
+        )
+    }
+
+    /// Validate a JSON value against the schema
+    pub fn validate(&self, value: &Value) -> Result<(), Vec<String>> {
+        let compiled = jsonschema::JSONSchema::compile(&self.schema)
+            .map_err(|e| vec![format!("Invalid response schema: {}", e)])?;
+        compiled.validate(value).map_err(|errors| {
+            errors
+                .map(|e| {
+                    let path = e.instance_path.to_string();
+                    if path.is_empty() {
+                        e.to_string()
+                    } else {
+                        format!("{}: {}", path, e)
+                    }
+                })
+                .collect()
+        })
+    }
+
+    /// Extract, validate and deserialize a model reply
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> Result<T, Vec<String>> {
+        let value = extract_json(content)
+            .ok_or_else(|| vec!["Reply does not contain a JSON value".to_string()])?;
+        self.validate(&value)?;
+        serde_json::from_value(value).map_err(|e| vec![e.to_string()])
+    }
+}
+
+/// Find the JSON value in a model reply
+///
+/// Accepts a bare value, a value inside a Markdown code fence, or the outermost
+/// `{...}`/`[...]` span of text surrounding it.
+pub fn extract_json(content: &str) -> Option<Value> {
+    let trimmed = content.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Some(value);
+    }
+
+    if let Some(fenced) = trimmed.split("```").nth(1) {
+        let body = fenced.strip_prefix("json").unwrap_or(fenced);
+        if let Ok(value) = serde_json::from_str(body.trim()) {
+            return Some(value);
+        }
+    }
+
+    [('{', '}'), ('[', ']')].iter().find_map(|&(open, close)| {
+        let start = trimmed.find(open)?;
+        let end = trimmed.rfind(close)?;
+        serde_json::from_str(trimmed.get(start..=end)?).ok()
+    })
+}
+
+/// A validated structured reply
+#[derive(Debug, Clone)]
+pub struct Structured<T> {
+    /// The deserialized value
+    pub value: T,
+    /// The raw response that produced it
+    pub response: LlmResponse,
+    /// Number of completions made (1 = valid on the first try)
+    pub attempts: u32,
+}
+
+/// Errors from a structured completion
+#[derive(Debug, Error)]
+pub enum StructuredError<E = LlmError> {
+    /// The underlying completion failed
+    #[error("{0}")]
+    Provider(E),
+    /// No valid reply within the repair budget
+    #[error("Invalid structured output after {attempts} attempts: {}", errors.join("; "))]
+    Invalid {
+        attempts: u32,
+        errors: Vec<String>,
+        /// Last reply received
+        output: String,
+    },
+}
+
+impl From<StructuredError<LlmError>> for LlmError {
+    fn from(err: StructuredError<LlmError>) -> Self {
+        match err {
+            StructuredError::Provider(e) => e,
+            invalid => LlmError::InvalidResponse(invalid.to_string()),
+        }
+    }
+}
+
+/// Validate-and-repair driver for structured completions
+#[derive(Debug, Clone)]
+pub struct StructuredOutput {
+    /// Expected response format
+    pub format: ResponseFormat,
+    /// Repair prompts allowed after the first invalid reply
+    pub max_repairs: u32,
+}
+
+impl StructuredOutput {
+    /// Create a driver with the default repair budget
+    pub fn new(format: ResponseFormat) -> Self {
+        Self {
+            format,
+            max_repairs: DEFAULT_MAX_REPAIRS,
+        }
+    }
+
+    /// Set the number of repair prompts
+    pub fn with_max_repairs(mut self, max_repairs: u32) -> Self {
+        self.max_repairs = max_repairs;
+        self
+    }
+
+    /// Attach the response format and schema instructions to a request
+    pub fn request(&self, mut request: LlmRequest) -> LlmRequest {
+        request.prompt = format!("{}\n\n{}", request.prompt, self.format.instructions());
+        request.response_format = Some(self.format.clone());
+        request
+    }
+
+    /// Build a follow-up request asking the model to fix an invalid reply
+    pub fn repair_request(
+        &self,
+        request: &LlmRequest,
+        output: &str,
+        errors: &[String],
+    ) -> LlmRequest {
+        let echoed: String = output.chars().take(MAX_ECHOED_OUTPUT).collect();
+        let mut repair = request.clone();
+        repair.prompt = format!(
+            "{}\n\nYour previous reply was rejected:\n{}\n\nValidation errors:\n- {}\n\n\
+             Reply again with only the corrected JSON.",
+            request.prompt,
+            echoed,
+            errors.join("\n- ")
+        );
+        repair
+    }
+
+    /// Run a structured completion against a provider
+    pub async fn complete<T, L>(
+        &self,
+        llm: &L,
+        request: LlmRequest,
+    ) -> Result<Structured<T>, StructuredError>
+    where
+        T: DeserializeOwned,
+        L: LlmProvider + ?Sized,
+    {
+        self.complete_with(request, |req| llm.complete(req)).await
+    }
+
+    /// Run a structured completion through a caller-supplied completion function
+    ///
+    /// Lets callers route every attempt through their own accounting (budgets,
+    /// cancellation, transcripts) while sharing the validate-and-repair loop.
+    pub async fn complete_with<T, E, F, Fut>(
+        &self,
+        request: LlmRequest,
+        mut complete: F,
+    ) -> Result<Structured<T>, StructuredError<E>>
+    where
+        T: DeserializeOwned,
+        F: FnMut(LlmRequest) -> Fut,
+        Fut: Future<Output = Result<LlmResponse, E>>,
+    {
+        let request = self.request(request);
+        let mut next = request.clone();
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let response = complete(next).await.map_err(StructuredError::Provider)?;
+            let errors = match self.format.parse::<T>(&response.content) {
+                Ok(value) => {
+                    return Ok(Structured {
+                        value,
+                        response,
+                        attempts,
+                    })
+                }
+                Err(errors) => errors,
+            };
+
+            tracing::warn!(
+                schema = %self.format.name,
+                attempt = attempts,
+                "Structured output failed validation: {}",
+                errors.join("; ")
+            );
+            if attempts > self.max_repairs {
+                return Err(StructuredError::Invalid {
+                    attempts,
+                    errors,
+                    output: response.content,
+                });
+            }
+            next = self.repair_request(&request, &response.content, &errors);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockProvider;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize)]
+    struct Score {
+        score: f64,
+    }
+
+    fn score_format() -> ResponseFormat {
+        ResponseFormat::new(
+            "score",
+            json!({
+                "type": "object",
+                "properties": { "score": { "type": "number", "minimum": 0, "maximum": 1 } },
+                "required": ["score"]
+            }),
+        )
+    }
+
+    #[test]
+    fn test_extract_json() {
+        assert_eq!(extract_json(r#"{"a": 1}"#), Some(json!({"a": 1})));
+        assert_eq!(
+            extract_json("Sure:\n```json\n{\"a\": 1}\n```"),
+            Some(json!({"a": 1}))
+        );
+        assert_eq!(
+            extract_json("Here you go {\"a\": [1]} done"),
+            Some(json!({"a": [1]}))
+        );
+        assert_eq!(extract_json("rules: [1, 2]"), Some(json!([1, 2])));
+        assert_eq!(extract_json("no json here"), None);
+    }
+
+    #[test]
+    fn test_parse_validates_schema() {
+        let format = score_format();
+        assert_eq!(
+            format.parse::<Score>(r#"{"score": 0.4}"#).unwrap().score,
+            0.4
+        );
+
+        let errors = format.parse::<Score>(r#"{"score": 7}"#).unwrap_err();
+        assert!(errors[0].contains("/score"));
+        assert!(format.parse::<Score>(r#"{"other": 1}"#).is_err());
+        assert!(format.parse::<Score>("not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_repairs_invalid_reply() {
+        let llm = MockProvider::new(vec![
+            "I think the score is high".to_string(),
+            r#"{"score": 0.9}"#.to_string(),
+        ]);
+        let result = StructuredOutput::new(score_format())
+            .complete::<Score, _>(&llm, LlmRequest::simple("Score this"))
+            .await
+            .unwrap();
+        assert_eq!(result.value.score, 0.9);
+        assert_eq!(result.attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_repairs() {
+        let llm = MockProvider::constant(r#"{"score": "high"}"#);
+        let err = StructuredOutput::new(score_format())
+            .with_max_repairs(1)
+            .complete::<Score, _>(&llm, LlmRequest::simple("Score this"))
+            .await
+            .unwrap_err();
+        match err {
+            StructuredError::Invalid {
+                attempts, output, ..
+            } => {
+                assert_eq!(attempts, 2);
+                assert_eq!(output, r#"{"score": "high"}"#);
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_request_attaches_format() {
+        let structured = StructuredOutput::new(score_format());
+        let request = structured.request(LlmRequest::simple("Score this"));
+        assert_eq!(request.response_format, Some(score_format()));
+        assert!(request.prompt.starts_with("Score this"));
+        assert!(request.prompt.contains("JSON schema"));
+
+        let repair = structured.repair_request(&request, "oops", &["bad".to_string()]);
+        assert!(repair.prompt.contains("oops"));
+        assert!(repair.prompt.contains("- bad"));
+        assert_eq!(repair.response_format, request.response_format);
+    }
+}
@@ -29,6 +29,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::message::ToolSpec;
 use crate::tool_error::ToolError;
 
 /// Definition of a tool that can be called by an LLM.
@@ -76,25 +77,12 @@ impl ToolDefinition {
 
     /// Convert to OpenAI-compatible tool format
     pub fn to_openai_format(&self) -> serde_json::Value {
-        serde_json::json!({
-            "type": "function",
-            "function": {
-                "name": self.name,
-                "description": self.description,
-                "parameters": serde_json::from_str::<serde_json::Value>(self.parameters)
-                    .unwrap_or(serde_json::json!({}))
-            }
-        })
+        ToolSpec::from(self).to_openai_format()
     }
 
     /// Convert to Anthropic Claude-compatible tool format
     pub fn to_anthropic_format(&self) -> serde_json::Value {
-        serde_json::json!({
-            "name": self.name,
-            "description": self.description,
-            "input_schema": serde_json::from_str::<serde_json::Value>(self.parameters)
-                .unwrap_or(serde_json::json!({}))
-        })
+        ToolSpec::from(self).to_anthropic_format()
     }
 }
 
@@ -317,6 +305,17 @@ impl ToolRegistry {
         self.tools.values().map(|t| t.definition()).collect()
     }
 
+    /// Tool specs for native function calling, sorted by name
+    pub fn specs(&self) -> Vec<ToolSpec> {
+        let mut specs: Vec<ToolSpec> = self
+            .tools
+            .values()
+            .map(|t| ToolSpec::from(t.definition()))
+            .collect();
+        specs.sort_by(|a, b| a.name.cmp(&b.name));
+        specs
+    }
+
     /// Generate OpenAI-compatible tool list
     pub fn to_openai_format(&self) -> Vec<serde_json::Value> {
         self.tools
@@ -0,0 +1,215 @@
+//! Anthropic Messages API tests against a local mock HTTP server
+
+mod common;
+
+use common::{serve, serve_once};
+use futures::StreamExt;
+use vex_llm::stream::collect;
+use vex_llm::{
+    AnthropicProvider, LlmError, LlmProvider, LlmRequest, Message, StreamEvent, ToolCall,
+    ToolDefinition, ToolSpec,
+};
+
+const WEATHER: ToolDefinition = ToolDefinition::new(
+    "weather",
+    "Current weather for a city",
+    r#"{"type": "object", "properties": {"city": {"type": "string"}}}"#,
+);
+
+const TOOL_USE_RESPONSE: &str = r#"{
+    "id": "msg_01",
+    "type": "message",
+    "role": "assistant",
+    "model": "claude-test",
+    "content": [
+        {"type": "text", "text": "Let me check."},
+        {"type": "tool_use", "id": "toolu_01", "name": "weather", "input": {"city": "Bergen"}}
+    ],
+    "stop_reason": "tool_use",
+    "usage": {"input_tokens": 30, "output_tokens": 12}
+}"#;
+
+#[tokio::test]
+async fn test_anthropic_tool_use() {
+    let (url, request) = serve(
+        200,
+        &[
+            ("Content-Type", "application/json"),
+            ("anthropic-ratelimit-requests-remaining", "49"),
+            ("anthropic-ratelimit-tokens-remaining", "39000"),
+        ],
+        TOOL_USE_RESPONSE,
+        2,
+    )
+    .await;
+    let llm = AnthropicProvider::new("key", "claude-test").with_base_url(&url);
+
+    let request_body = LlmRequest::chat(
+        "You are a weather assistant.",
+        vec![Message::user("How cold is Bergen?")],
+    )
+    .with_tools(vec![ToolSpec::from(&WEATHER)]);
+    let response = llm.complete(request_body).await.unwrap();
+    assert_eq!(response.content, "Let me check.");
+    assert_eq!(response.model, "claude-test");
+    assert_eq!(response.tokens_used, Some(42));
+    assert_eq!(
+        response.tool_calls,
+        vec![ToolCall::new(
+            "toolu_01",
+            "weather",
+            serde_json::json!({"city": "Bergen"})
+        )]
+    );
+
+    let status = llm.rate_limit_status();
+    assert_eq!(status.requests_remaining, Some(49));
+    assert_eq!(status.tokens_remaining, Some(39000));
+    assert_eq!(status.retry_after, None);
+
+    let request = request.await.unwrap();
+    assert!(request.starts_with("POST /v1/messages"));
+    assert!(request.contains("x-api-key: key"));
+    assert!(request.contains("anthropic-version: 2023-06-01"));
+    let (_, body) = request.split_once("\r\n\r\n").unwrap();
+    let sent: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(sent["system"], "You are a weather assistant.");
+    assert_eq!(sent["tools"][0], WEATHER.to_anthropic_format());
+    assert_eq!(sent["messages"][0]["role"], "user");
+    assert_eq!(
+        sent["messages"][0]["content"][0]["text"],
+        "How cold is Bergen?"
+    );
+}
+
+const MESSAGES_SSE: &str = concat!(
+    "event: message_start\n",
+    "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_02\",\"type\":\"message\",",
+    "\"role\":\"assistant\",\"model\":\"claude-test\",\"content\":[],\"usage\":{\"input_tokens\":25,\"output_tokens\":1}}}\n\n",
+    "event: content_block_start\n",
+    "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+    "event: ping\n",
+    "data: {\"type\":\"ping\"}\n\n",
+    "event: content_block_delta\n",
+    "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hel\"}}\n\n",
+    "event: content_block_delta\n",
+    "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"lo!\"}}\n\n",
+    "event: content_block_stop\n",
+    "data: {\"type\":\"content_block_stop\",\"index\":0}\n\n",
+    "event: content_block_start\n",
+    "data: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"tool_use\",",
+    "\"id\":\"toolu_02\",\"name\":\"weather\",\"input\":{}}}\n\n",
+    "event: content_block_delta\n",
+    "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"city\\\":\"}}\n\n",
+    "event: content_block_delta\n",
+    "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\" \\\"Oslo\\\"}\"}}\n\n",
+    "event: content_block_stop\n",
+    "data: {\"type\":\"content_block_stop\",\"index\":1}\n\n",
+    "event: message_delta\n",
+    "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\"},\"usage\":{\"output_tokens\":17}}\n\n",
+    "event: message_stop\n",
+    "data: {\"type\":\"message_stop\"}\n\n",
+);
+
+#[tokio::test]
+async fn test_anthropic_stream() {
+    let (url, request) = serve_once("text/event-stream", MESSAGES_SSE, 6).await;
+    let llm = AnthropicProvider::new("key", "claude-test").with_base_url(&url);
+
+    let stream = llm
+        .complete_stream(LlmRequest::simple("Say hello"))
+        .await
+        .unwrap();
+    let events: Vec<StreamEvent> = stream.map(|e| e.unwrap()).collect().await;
+    assert_eq!(
+        events,
+        vec![
+            StreamEvent::Delta {
+                text: "Hel".to_string()
+            },
+            StreamEvent::Delta {
+                text: "lo!".to_string()
+            },
+            StreamEvent::ToolCall(ToolCall::new(
+                "toolu_02",
+                "weather",
+                serde_json::json!({"city": "Oslo"})
+            )),
+            StreamEvent::Usage {
+                prompt_tokens: Some(25),
+                completion_tokens: Some(17),
+                total_tokens: 42,
+            },
+            StreamEvent::Finish {
+                reason: "tool_use".to_string(),
+                model: "claude-test".to_string(),
+            },
+        ]
+    );
+    assert!(request.await.unwrap().contains("\"stream\":true"));
+}
+
+#[tokio::test]
+async fn test_anthropic_stream_error_event() {
+    let (url, _) = serve_once(
+        "text/event-stream",
+        concat!(
+            "event: error\n",
+            "data: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n",
+        ),
+        1,
+    )
+    .await;
+    let llm = AnthropicProvider::new("key", "claude-test").with_base_url(&url);
+
+    let stream = llm
+        .complete_stream(LlmRequest::simple("Say hello"))
+        .await
+        .unwrap();
+    assert!(matches!(collect(stream).await, Err(LlmError::NotAvailable)));
+}
+
+#[tokio::test]
+async fn test_anthropic_retry_after_fails_fast() {
+    let (url, request) = serve(
+        429,
+        &[
+            ("Content-Type", "application/json"),
+            ("retry-after", "30"),
+            ("anthropic-ratelimit-requests-remaining", "0"),
+        ],
+        r#"{"type":"error","error":{"type":"rate_limit_error","message":"Rate limited"}}"#,
+        1,
+    )
+    .await;
+    let llm = AnthropicProvider::new("key", "claude-test").with_base_url(&url);
+
+    let err = llm.complete(LlmRequest::simple("Hi")).await.unwrap_err();
+    assert!(matches!(err, LlmError::RateLimited));
+    request.await.unwrap();
+    let status = llm.rate_limit_status();
+    assert_eq!(status.requests_remaining, Some(0));
+    assert_eq!(status.retry_after, Some(std::time::Duration::from_secs(30)));
+
+    // The mock server is gone: a second request would fail to connect, so a
+    // RateLimited error shows it was never sent
+    let err = llm.complete(LlmRequest::simple("Hi")).await.unwrap_err();
+    assert!(matches!(err, LlmError::RateLimited));
+}
+
+#[tokio::test]
+async fn test_anthropic_overloaded() {
+    let (url, _) = serve(
+        529,
+        &[("Content-Type", "application/json")],
+        r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+        1,
+    )
+    .await;
+    let llm = AnthropicProvider::new("key", "claude-test").with_base_url(&url);
+
+    let err = llm.complete(LlmRequest::simple("Hi")).await.unwrap_err();
+    assert!(matches!(err, LlmError::NotAvailable));
+    // Only a 429 with retry-after blocks further calls
+    assert_eq!(llm.rate_limit_status().retry_after, None);
+}
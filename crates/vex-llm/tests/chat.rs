@@ -0,0 +1,163 @@
+//! Multi-turn requests and native tool calls against a local mock HTTP server
+
+mod common;
+
+use common::serve_once;
+use vex_llm::stream::collect;
+use vex_llm::{
+    Image, LlmProvider, LlmRequest, Message, OllamaProvider, OpenAICompatibleProvider, ToolCall,
+    ToolDefinition, ToolSpec,
+};
+
+const WEATHER: ToolDefinition = ToolDefinition::new(
+    "weather",
+    "Current weather for a city",
+    r#"{"type": "object", "properties": {"city": {"type": "string"}}}"#,
+);
+
+/// JSON body of a raw HTTP request
+fn body(request: &str) -> serde_json::Value {
+    let (_, body) = request.split_once("\r\n\r\n").unwrap();
+    serde_json::from_str(body).unwrap()
+}
+
+/// A conversation that has already made one tool call
+fn weather_request() -> LlmRequest {
+    LlmRequest::chat(
+        "You are a weather assistant.",
+        vec![
+            Message::user("How cold is Oslo?").with_image(Image::Base64 {
+                media_type: "image/png".to_string(),
+                data: "iVBORw0".to_string(),
+            }),
+            Message::assistant("").with_tool_calls(vec![ToolCall::new(
+                "call_1",
+                "weather",
+                serde_json::json!({"city": "Oslo"}),
+            )]),
+            Message::tool_result("call_1", "-3°C"),
+            Message::user("And Bergen?"),
+        ],
+    )
+    .with_tools(vec![ToolSpec::from(&WEATHER)])
+}
+
+const OPENAI_TOOL_RESPONSE: &str = r#"{
+    "id": "c2",
+    "object": "chat.completion",
+    "model": "gpt-test",
+    "choices": [{
+        "index": 0,
+        "message": {
+            "role": "assistant",
+            "content": null,
+            "tool_calls": [{
+                "id": "call_2",
+                "type": "function",
+                "function": {"name": "weather", "arguments": "{\"city\":\"Bergen\"}"}
+            }]
+        },
+        "finish_reason": "tool_calls"
+    }],
+    "usage": {"prompt_tokens": 40, "completion_tokens": 12, "total_tokens": 52}
+}"#;
+
+#[tokio::test]
+async fn test_openai_compatible_tool_calls() {
+    let (url, request) = serve_once("application/json", OPENAI_TOOL_RESPONSE, 1).await;
+    let llm = OpenAICompatibleProvider::new("key", "gpt-test", url, "openai");
+
+    let response = llm.complete(weather_request()).await.unwrap();
+    assert_eq!(response.content, "");
+    assert_eq!(
+        response.tool_calls,
+        vec![ToolCall::new(
+            "call_2",
+            "weather",
+            serde_json::json!({"city": "Bergen"})
+        )]
+    );
+
+    let sent = body(&request.await.unwrap());
+    assert_eq!(sent["tools"][0], WEATHER.to_openai_format());
+    let messages = sent["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 5);
+    assert_eq!(messages[0]["role"], "system");
+    assert_eq!(
+        messages[1]["content"][1]["image_url"]["url"],
+        "data:image/png;base64,iVBORw0"
+    );
+    assert!(messages[2]["content"].is_null());
+    assert_eq!(
+        messages[2]["tool_calls"][0]["function"]["arguments"],
+        "{\"city\":\"Oslo\"}"
+    );
+    assert_eq!(messages[3]["role"], "tool");
+    assert_eq!(messages[3]["tool_call_id"], "call_1");
+    assert_eq!(messages[4]["content"], "And Bergen?");
+}
+
+const OPENAI_TOOL_SSE: &str = concat!(
+    "data: {\"model\":\"gpt-test\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",",
+    "\"tool_calls\":[{\"index\":0,\"id\":\"call_2\",\"type\":\"function\",",
+    "\"function\":{\"name\":\"weather\",\"arguments\":\"\"}}]}}]}\n\n",
+    "data: {\"model\":\"gpt-test\",\"choices\":[{\"index\":0,\"delta\":{",
+    "\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"{\\\"city\\\":\"}}]}}]}\n\n",
+    "data: {\"model\":\"gpt-test\",\"choices\":[{\"index\":0,\"delta\":{",
+    "\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"\\\"Bergen\\\"}\"}}]}}]}\n\n",
+    "data: {\"model\":\"gpt-test\",\"choices\":[{\"index\":0,\"delta\":{},\"finish_reason\":\"tool_calls\"}]}\n\n",
+    "data: [DONE]\n\n",
+);
+
+#[tokio::test]
+async fn test_openai_compatible_streamed_tool_call() {
+    let (url, _) = serve_once("text/event-stream", OPENAI_TOOL_SSE, 4).await;
+    let llm = OpenAICompatibleProvider::new("key", "gpt-test", url, "openai");
+
+    let stream = llm.complete_stream(weather_request()).await.unwrap();
+    let response = collect(stream).await.unwrap();
+    assert_eq!(
+        response.tool_calls,
+        vec![ToolCall::new(
+            "call_2",
+            "weather",
+            serde_json::json!({"city": "Bergen"})
+        )]
+    );
+}
+
+const OLLAMA_TOOL_RESPONSE: &str = r#"{
+    "model": "llama3",
+    "created_at": "2026-01-01T00:00:00Z",
+    "message": {
+        "role": "assistant",
+        "content": "",
+        "tool_calls": [{"function": {"name": "weather", "arguments": {"city": "Bergen"}}}]
+    },
+    "done": true,
+    "eval_count": 9
+}"#;
+
+#[tokio::test]
+async fn test_ollama_tool_calls() {
+    let (url, request) = serve_once("application/json", OLLAMA_TOOL_RESPONSE, 1).await;
+    let llm = OllamaProvider::with_url(&url, "llama3");
+
+    let response = llm.complete(weather_request()).await.unwrap();
+    assert_eq!(response.tool_calls.len(), 1);
+    assert_eq!(response.tool_calls[0].name, "weather");
+    assert_eq!(
+        response.tool_calls[0].arguments,
+        serde_json::json!({"city": "Bergen"})
+    );
+
+    let sent = body(&request.await.unwrap());
+    assert_eq!(sent["tools"][0], WEATHER.to_openai_format());
+    let messages = sent["messages"].as_array().unwrap();
+    assert_eq!(messages[1]["images"][0], "iVBORw0");
+    assert_eq!(
+        messages[2]["tool_calls"][0]["function"]["arguments"]["city"],
+        "Oslo"
+    );
+    assert_eq!(messages[3]["role"], "tool");
+}
@@ -0,0 +1,71 @@
+//! Local mock HTTP server for provider tests
+
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+/// Serve one 200 response with `body` split into `parts` writes; returns the
+/// base URL and the raw request received
+pub async fn serve_once(
+    content_type: &'static str,
+    body: &'static str,
+    parts: usize,
+) -> (String, oneshot::Receiver<String>) {
+    serve(200, &[("Content-Type", content_type)], body, parts).await
+}
+
+/// Serve one response with the given status and headers
+pub async fn serve(
+    status: u16,
+    headers: &[(&str, &str)],
+    body: &'static str,
+    parts: usize,
+) -> (String, oneshot::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = oneshot::channel();
+    let mut head = format!("HTTP/1.1 {} Mock\r\n", status);
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("Connection: close\r\n\r\n");
+
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = socket.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end]
+                    .lines()
+                    .find_map(|l| {
+                        l.to_ascii_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                if request.len() >= header_end + 4 + content_length {
+                    break;
+                }
+            }
+            if n == 0 {
+                break;
+            }
+        }
+        let _ = tx.send(String::from_utf8_lossy(&request).to_string());
+
+        socket.write_all(head.as_bytes()).await.unwrap();
+        let step = body.len().div_ceil(parts);
+        for chunk in body.as_bytes().chunks(step) {
+            socket.write_all(chunk).await.unwrap();
+            socket.flush().await.unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    });
+
+    (format!("http://{}", addr), rx)
+}
@@ -27,6 +27,8 @@ async fn test_deepseek_real_request() {
     let request = LlmRequest {
         prompt: "What is 2 + 2? Answer with just the number.".to_string(),
         system: "You are a helpful assistant. Be extremely concise.".to_string(),
+        messages: Vec::new(),
+        tools: Vec::new(),
         temperature: 0.0,
         max_tokens: 10,
         timeout: None,
@@ -34,6 +36,8 @@ async fn test_deepseek_real_request() {
         top_p: None,
         presence_penalty: None,
         frequency_penalty: None,
+        seed: None,
+        response_format: None,
     };
 
     let response = provider.complete(request).await;
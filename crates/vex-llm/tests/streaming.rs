@@ -0,0 +1,144 @@
+//! Streaming tests against a local mock HTTP server
+//!
+//! Each test serves a recorded SSE or NDJSON body in several TCP writes (split
+//! mid-line) and checks the events the provider yields.
+
+mod common;
+
+use common::serve_once;
+use futures::StreamExt;
+use vex_llm::stream::collect;
+use vex_llm::{
+    LlmProvider, LlmRequest, MistralProvider, OllamaProvider, OpenAICompatibleProvider, StreamEvent,
+};
+
+async fn events(llm: &dyn LlmProvider) -> Vec<StreamEvent> {
+    let stream = llm
+        .complete_stream(LlmRequest::simple("Say hello"))
+        .await
+        .unwrap();
+    stream.map(|e| e.unwrap()).collect().await
+}
+
+fn text(events: &[StreamEvent]) -> String {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            StreamEvent::Delta { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+const OPENAI_SSE: &str = concat!(
+    "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"model\":\"gpt-test\",",
+    "\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"\"},\"finish_reason\":null}]}\n\n",
+    "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"model\":\"gpt-test\",",
+    "\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hel\"},\"finish_reason\":null}]}\n\n",
+    ": keep-alive\n\n",
+    "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"model\":\"gpt-test\",",
+    "\"choices\":[{\"index\":0,\"delta\":{\"content\":\"lo!\"},\"finish_reason\":null}]}\n\n",
+    "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"model\":\"gpt-test\",",
+    "\"choices\":[{\"index\":0,\"delta\":{},\"finish_reason\":\"length\"}]}\n\n",
+    "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"model\":\"gpt-test\",\"choices\":[],",
+    "\"usage\":{\"prompt_tokens\":9,\"completion_tokens\":3,\"total_tokens\":12}}\n\n",
+    "data: [DONE]\n\n",
+);
+
+#[tokio::test]
+async fn test_openai_compatible_sse_stream() {
+    let (url, request) = serve_once("text/event-stream", OPENAI_SSE, 7).await;
+    let llm = OpenAICompatibleProvider::new("key", "gpt-test", url, "openai");
+
+    let events = events(&llm).await;
+    assert_eq!(text(&events), "Hello!");
+    assert!(events.contains(&StreamEvent::Usage {
+        prompt_tokens: Some(9),
+        completion_tokens: Some(3),
+        total_tokens: 12,
+    }));
+    assert_eq!(
+        events.last(),
+        Some(&StreamEvent::Finish {
+            reason: "length".to_string(),
+            model: "gpt-test".to_string(),
+        })
+    );
+
+    let request = request.await.unwrap();
+    assert!(request.starts_with("POST /v1/chat/completions"));
+    assert!(request.contains("\"stream\":true"));
+    assert!(request.contains("\"include_usage\":true"));
+}
+
+#[tokio::test]
+async fn test_mistral_stream_collects_to_response() {
+    let (url, request) = serve_once("text/event-stream", OPENAI_SSE, 3).await;
+    let llm = MistralProvider::small("key").with_base_url(&url);
+
+    let stream = llm
+        .complete_stream(LlmRequest::simple("Say hello"))
+        .await
+        .unwrap();
+    let response = collect(stream).await.unwrap();
+    assert_eq!(response.content, "Hello!");
+    assert_eq!(response.model, "gpt-test");
+    assert_eq!(response.tokens_used, Some(12));
+    assert!(!request.await.unwrap().contains("stream_options"));
+}
+
+const OLLAMA_NDJSON: &str = concat!(
+    "{\"model\":\"llama3\",\"created_at\":\"2026-01-01T00:00:00Z\",\"message\":{\"role\":\"assistant\",\"content\":\"Hi\"},\"done\":false}\n",
+    "{\"model\":\"llama3\",\"created_at\":\"2026-01-01T00:00:00Z\",\"message\":{\"role\":\"assistant\",\"content\":\" there\"},\"done\":false}\n",
+    "{\"model\":\"llama3\",\"created_at\":\"2026-01-01T00:00:00Z\",\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true,",
+    "\"done_reason\":\"stop\",\"prompt_eval_count\":5,\"eval_count\":2}\n",
+);
+
+#[tokio::test]
+async fn test_ollama_ndjson_stream() {
+    let (url, request) = serve_once("application/x-ndjson", OLLAMA_NDJSON, 5).await;
+    let llm = OllamaProvider::with_url(&url, "llama3");
+
+    let events = events(&llm).await;
+    assert_eq!(
+        events,
+        vec![
+            StreamEvent::Delta {
+                text: "Hi".to_string()
+            },
+            StreamEvent::Delta {
+                text: " there".to_string()
+            },
+            StreamEvent::Usage {
+                prompt_tokens: Some(5),
+                completion_tokens: Some(2),
+                total_tokens: 7,
+            },
+            StreamEvent::Finish {
+                reason: "stop".to_string(),
+                model: "llama3".to_string(),
+            },
+        ]
+    );
+    let request = request.await.unwrap();
+    assert!(request.starts_with("POST /api/chat"));
+    assert!(request.contains("\"stream\":true"));
+}
+
+#[tokio::test]
+async fn test_ollama_stream_error_line() {
+    let (url, _) = serve_once(
+        "application/x-ndjson",
+        "{\"error\":\"model 'llama9' not found\"}\n",
+        1,
+    )
+    .await;
+    let llm = OllamaProvider::with_url(&url, "llama9");
+
+    let mut stream = llm
+        .complete_stream(LlmRequest::simple("Say hello"))
+        .await
+        .unwrap();
+    let err = stream.next().await.unwrap().unwrap_err();
+    assert!(err.to_string().contains("not found"));
+}
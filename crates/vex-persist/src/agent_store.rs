@@ -5,7 +5,7 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::backend::{StorageBackend, StorageError, StorageExt};
-use vex_core::{Agent, AgentConfig};
+use vex_core::{Agent, AgentConfig, Genome};
 
 /// Serializable agent state
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,7 +14,12 @@ pub struct AgentState {
     pub parent_id: Option<Uuid>,
     pub config: AgentConfig,
     pub generation: u32,
+    #[serde(default)]
+    pub depth: u8,
     pub fitness: f64,
+    /// Evolved genome (absent in records written before genomes were stored)
+    #[serde(default)]
+    pub genome: Option<Genome>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -26,8 +31,10 @@ impl From<&Agent> for AgentState {
             parent_id: agent.parent_id,
             config: agent.config.clone(),
             generation: agent.generation,
+            depth: agent.depth,
             fitness: agent.fitness,
-            created_at: chrono::Utc::now(),
+            genome: Some(agent.genome.clone()),
+            created_at: agent.created_at,
             updated_at: chrono::Utc::now(),
         }
     }
@@ -40,7 +47,12 @@ impl AgentState {
         agent.id = self.id;
         agent.parent_id = self.parent_id;
         agent.generation = self.generation;
+        agent.depth = self.depth;
         agent.fitness = self.fitness;
+        agent.created_at = self.created_at;
+        if let Some(genome) = &self.genome {
+            agent.genome = genome.clone();
+        }
         agent
     }
 }
@@ -135,12 +147,13 @@ mod tests {
         let store = AgentStore::new(backend);
         let tenant_id = "test-tenant";
 
-        let agent = Agent::new(AgentConfig {
+        let mut agent = Agent::new(AgentConfig {
             name: "TestAgent".to_string(),
             role: "Tester".to_string(),
             max_depth: 2,
             spawn_shadow: true,
         });
+        agent.genome.set_trait("skepticism", 0.9);
         let id = agent.id;
 
         // Save
@@ -153,6 +166,7 @@ mod tests {
         let loaded = store.load(tenant_id, id).await.unwrap().unwrap();
         assert_eq!(loaded.id, id);
         assert_eq!(loaded.config.name, "TestAgent");
+        assert_eq!(loaded.genome.get_trait("skepticism"), Some(0.9));
 
         // List
         let ids = store.list(tenant_id).await.unwrap();
@@ -0,0 +1,119 @@
+//! Content-addressed artifact storage
+//!
+//! Artifacts (e.g. execution transcripts) are stored under the SHA-256 of
+//! their RFC 8785 (JCS) canonical JSON, so an audit event can link an artifact
+//! by hash and anyone holding the hash can detect tampering on read.
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::Arc;
+
+use crate::backend::{StorageBackend, StorageError};
+use vex_core::Hash;
+
+/// Content hash of a serializable value (SHA-256 over its JCS form)
+pub fn content_hash<T: Serialize + ?Sized>(value: &T) -> Result<Hash, StorageError> {
+    let bytes = serde_jcs::to_vec(value).map_err(|e| StorageError::Serialization(e.to_string()))?;
+    Ok(Hash::digest(&bytes))
+}
+
+/// Artifact store for persistence
+#[derive(Debug)]
+pub struct ArtifactStore<B: StorageBackend + ?Sized> {
+    backend: Arc<B>,
+    prefix: String,
+}
+
+impl<B: StorageBackend + ?Sized> ArtifactStore<B> {
+    /// Create a new artifact store
+    pub fn new(backend: Arc<B>) -> Self {
+        Self {
+            backend,
+            prefix: "artifact:".to_string(),
+        }
+    }
+
+    fn key(&self, tenant_id: &str, hash: &Hash) -> String {
+        format!("{}tenant:{}:{}", self.prefix, tenant_id, hash.to_hex())
+    }
+
+    /// Store an artifact and return its content hash (idempotent)
+    pub async fn put<T: Serialize + ?Sized>(
+        &self,
+        tenant_id: &str,
+        artifact: &T,
+    ) -> Result<Hash, StorageError> {
+        let value = serde_json::to_value(artifact)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        let hash = content_hash(&value)?;
+        let key = self.key(tenant_id, &hash);
+        if !self.backend.exists(&key).await? {
+            self.backend.set_value(&key, value).await?;
+        }
+        Ok(hash)
+    }
+
+    /// Load an artifact, rejecting it if its content no longer matches `hash`
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        tenant_id: &str,
+        hash: &Hash,
+    ) -> Result<Option<T>, StorageError> {
+        let Some(value) = self.backend.get_value(&self.key(tenant_id, hash)).await? else {
+            return Ok(None);
+        };
+        if &content_hash(&value)? != hash {
+            return Err(StorageError::Internal(format!(
+                "Artifact {} does not match its content hash",
+                hash.to_hex()
+            )));
+        }
+        serde_json::from_value(value)
+            .map(Some)
+            .map_err(|e| StorageError::Serialization(e.to_string()))
+    }
+
+    /// Check whether an artifact exists
+    pub async fn exists(&self, tenant_id: &str, hash: &Hash) -> Result<bool, StorageError> {
+        self.backend.exists(&self.key(tenant_id, hash)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::MemoryBackend;
+
+    #[tokio::test]
+    async fn test_artifact_store() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = ArtifactStore::new(backend.clone());
+        let tenant_id = "test-tenant";
+
+        let artifact = serde_json::json!({"b": 2, "a": [1, 2, 3]});
+        let hash = store.put(tenant_id, &artifact).await.unwrap();
+        // Key order does not change the content hash
+        let reordered = serde_json::json!({"a": [1, 2, 3], "b": 2});
+        assert_eq!(store.put(tenant_id, &reordered).await.unwrap(), hash);
+
+        let loaded: serde_json::Value = store.get(tenant_id, &hash).await.unwrap().unwrap();
+        assert_eq!(loaded, artifact);
+        assert!(store
+            .get::<serde_json::Value>("other-tenant", &hash)
+            .await
+            .unwrap()
+            .is_none());
+
+        // Tampered content is rejected
+        backend
+            .set_value(
+                &format!("artifact:tenant:{}:{}", tenant_id, hash.to_hex()),
+                serde_json::json!({"a": [1, 2, 3], "b": 3}),
+            )
+            .await
+            .unwrap();
+        assert!(store
+            .get::<serde_json::Value>(tenant_id, &hash)
+            .await
+            .is_err());
+    }
+}
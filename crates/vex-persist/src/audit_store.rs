@@ -2,13 +2,18 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::backend::{StorageBackend, StorageError, StorageExt};
-use vex_core::{Hash, MerkleTree};
+use vex_core::{
+    ConsistencyProof, Hash, HashAlgorithm, IncrementalMerkleTree, KeyEvent, KeyHistory,
+    MerkleFrontier, MerkleHasher, MerkleLogView, MerkleMultiProof, MerkleNodes, MerkleProof,
+    MerkleTree, NodeId, NodeRecorder, Sha256Hasher,
+};
 
-use vex_core::audit::{ActorType, AuditEvent, AuditEventType, HashParams};
+use vex_core::audit::{ActorType, AuditEvent, AuditEventType, Signature, ThresholdPolicy};
 use vex_hardware::api::AgentIdentity;
 
 /// Per-tenant chain state for proper multi-tenancy isolation
@@ -18,6 +23,15 @@ struct ChainState {
     last_hash: Option<Hash>,
     /// Monotonic sequence counter for this tenant
     sequence: u64,
+    /// Compact Merkle frontier over the chain's event hashes
+    #[serde(default)]
+    frontier: MerkleFrontier,
+    /// Number of events whose Merkle log nodes are stored
+    #[serde(default)]
+    log_size: u64,
+    /// Hash function of the stored log nodes
+    #[serde(default)]
+    log_algorithm: HashAlgorithm,
 }
 
 /// Audit store for compliance logging
@@ -29,21 +43,68 @@ struct ChainState {
 pub struct AuditStore<B: StorageBackend + ?Sized> {
     backend: Arc<B>,
     prefix: String,
+    hasher: Arc<dyn MerkleHasher>,
+    threshold_policies: Vec<(AuditEventType, ThresholdPolicy)>,
+    /// Verified key histories by tenant, reused while the stored entries are unchanged
+    key_histories: tokio::sync::RwLock<HashMap<String, KeyHistory>>,
 }
 
 impl<B: StorageBackend + ?Sized> AuditStore<B> {
     /// Create a new audit store
     pub fn new(backend: Arc<B>) -> Self {
+        Self::with_hasher(backend, Arc::new(Sha256Hasher))
+    }
+
+    /// Create an audit store whose Merkle trees use `hasher` for internal nodes
+    /// (e.g. Poseidon for ZK-friendly audit trees). Event hashes stay SHA-256.
+    pub fn with_hasher(backend: Arc<B>, hasher: Arc<dyn MerkleHasher>) -> Self {
         Self {
             backend,
             prefix: "audit:".to_string(),
+            hasher,
+            threshold_policies: Vec::new(),
+            key_histories: tokio::sync::RwLock::new(HashMap::new()),
         }
     }
 
+    /// Require M-of-N co-signatures for every event of `event_type`
+    ///
+    /// The policy is committed in each matching event's hash, and `verify_chain`
+    /// fails until enough authorized signers have added co-signatures.
+    pub fn with_threshold_policy(
+        mut self,
+        event_type: AuditEventType,
+        policy: ThresholdPolicy,
+    ) -> Self {
+        self.threshold_policies.retain(|(t, _)| t != &event_type);
+        self.threshold_policies.push((event_type, policy));
+        self
+    }
+
+    fn threshold_policy_for(&self, event_type: &AuditEventType) -> Option<&ThresholdPolicy> {
+        self.threshold_policies
+            .iter()
+            .find(|(t, _)| t == event_type)
+            .map(|(_, p)| p)
+    }
+
+    /// Hash function used for the tenant Merkle trees
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        self.hasher.algorithm()
+    }
+
     fn event_key(&self, tenant_id: &str, id: Uuid) -> String {
         format!("{}tenant:{}:event:{}", self.prefix, tenant_id, id)
     }
 
+    fn co_signature_key(&self, tenant_id: &str, event_id: Uuid, signer_id: &str) -> String {
+        format!(
+            "{}/cosig/{}",
+            self.event_key(tenant_id, event_id),
+            signer_id
+        )
+    }
+
     fn chain_key(&self, tenant_id: &str) -> String {
         format!("{}tenant:{}:chain", self.prefix, tenant_id)
     }
@@ -60,6 +121,79 @@ impl<B: StorageBackend + ?Sized> AuditStore<B> {
         format!("{}tenant:{}:capsule:{}", self.prefix, tenant_id, capsule_id)
     }
 
+    fn log_node_key(&self, tenant_id: &str, (height, index): NodeId) -> String {
+        format!(
+            "{}tenant:{}:log:{}:{}:{}",
+            self.prefix,
+            tenant_id,
+            self.hash_algorithm(),
+            height,
+            index
+        )
+    }
+
+    fn sequence_key(&self, tenant_id: &str, sequence: u64) -> String {
+        format!("{}tenant:{}:seq:{}", self.prefix, tenant_id, sequence)
+    }
+
+    fn key_history_key(&self, tenant_id: &str) -> String {
+        format!("{}tenant:{}:key_history", self.prefix, tenant_id)
+    }
+
+    /// Signing key history for a tenant chain
+    ///
+    /// The stored entries are read on every call, but only verified again when they
+    /// differ from the last history verified for the tenant (e.g. after another
+    /// replica saved a rotation).
+    pub async fn key_history(&self, tenant_id: &str) -> Result<KeyHistory, StorageError> {
+        let entries: Vec<KeyEvent> = self
+            .backend
+            .get(&self.key_history_key(tenant_id))
+            .await?
+            .unwrap_or_default();
+        if let Some(history) = self.key_histories.read().await.get(tenant_id) {
+            if history.entries() == entries.as_slice() {
+                return Ok(history.clone());
+            }
+        }
+
+        let history = KeyHistory::from_entries(entries)
+            .map_err(|e| StorageError::Internal(format!("Invalid key history: {}", e)))?;
+        self.key_histories
+            .write()
+            .await
+            .insert(tenant_id.to_string(), history.clone());
+        Ok(history)
+    }
+
+    /// Persist a tenant's key history
+    ///
+    /// The history is append-only: the stored entries must be a prefix of `history`.
+    pub async fn save_key_history(
+        &self,
+        tenant_id: &str,
+        history: &KeyHistory,
+    ) -> Result<(), StorageError> {
+        let stored: Vec<KeyEvent> = self
+            .backend
+            .get(&self.key_history_key(tenant_id))
+            .await?
+            .unwrap_or_default();
+        if !history.entries().starts_with(&stored) {
+            return Err(StorageError::Internal(
+                "Key history is append-only: stored entries were modified".to_string(),
+            ));
+        }
+        self.backend
+            .set(
+                &self.key_history_key(tenant_id),
+                &history.entries().to_vec(),
+            )
+            .await?;
+        self.key_histories.write().await.remove(tenant_id);
+        Ok(())
+    }
+
     /// Get per-tenant chain state from storage
     async fn get_chain_state(&self, tenant_id: &str) -> Result<ChainState, StorageError> {
         self.backend
@@ -100,6 +234,16 @@ impl<B: StorageBackend + ?Sized> AuditStore<B> {
         // Get per-tenant chain state
         let mut chain_state = self.get_chain_state(tenant_id).await?;
         let seq = chain_state.sequence;
+        // The frontier is SHA-256 only; other hashers build their log from the chain
+        let track_frontier = self.hash_algorithm() == HashAlgorithm::Sha256;
+        if track_frontier && chain_state.frontier.len() != seq {
+            // Chain state written before the frontier was tracked
+            chain_state.frontier = self.rebuild_frontier(tenant_id).await?;
+        }
+        if !self.log_is_current(&chain_state) {
+            // Chain written before log nodes were stored, or with another hasher
+            self.backfill_log(tenant_id, &mut chain_state).await?;
+        }
 
         let mut event = match &chain_state.last_hash {
             Some(prev) => {
@@ -111,6 +255,7 @@ impl<B: StorageBackend + ?Sized> AuditStore<B> {
         // Set actor after creation to override default system actor
         event.actor = actor;
         event.vep_blob = vep_blob.clone();
+        event.threshold_policy = self.threshold_policy_for(&event.event_type).cloned();
 
         // Phase 2.2: Populating EvidenceCapsule if witness_receipt is present
         if let Some(wr) = witness_receipt {
@@ -162,44 +307,37 @@ impl<B: StorageBackend + ?Sized> AuditStore<B> {
             });
         }
 
-        // Rehash after setting actor
-        event.hash = AuditEvent::compute_hash(HashParams {
-            event_type: &event.event_type,
-            timestamp: event.timestamp.timestamp(),
-            sequence_number: event.sequence_number,
-            data: &event.data,
-            actor: &event.actor,
-            rationale: &event.rationale,
-            policy_version: &event.policy_version,
-            data_provenance_hash: &event.data_provenance_hash,
-            human_review_required: event.human_review_required,
-            approval_count: event.approval_signatures.len(),
-            evidence_capsule: &event.evidence_capsule,
-            schema_version: &event.schema_version,
-        });
+        // Rehash after setting actor and threshold policy
+        event.hash = AuditEvent::compute_hash(event.hash_params());
 
         // 7. Hardware Signing (Phase 3 Integration)
         if let Some(id) = identity {
+            // Once a tenant has a key history, only its signers may sign, and only with
+            // the key valid for this sequence number (not rotated out or revoked)
+            let history = self.key_history(tenant_id).await?;
+            if !history.is_empty() {
+                if !history.knows_signer(&id.agent_id) {
+                    return Err(StorageError::Internal(format!(
+                        "Signer {} is not in the tenant key history",
+                        id.agent_id
+                    )));
+                }
+                let valid = history.key_for(&id.agent_id, seq);
+                if valid.map(|k| hex::encode(k.to_bytes())) != Some(id.public_key_hex()) {
+                    return Err(StorageError::Internal(format!(
+                        "Signing key for {} is not valid at sequence {}",
+                        id.agent_id, seq
+                    )));
+                }
+            }
+
             event
                 .sign_hardware(id)
                 .await
                 .map_err(|e| StorageError::Internal(format!("Hardware signing failed: {}", e)))?;
 
             // Recompute base hash to include signature count
-            event.hash = AuditEvent::compute_hash(HashParams {
-                event_type: &event.event_type,
-                timestamp: event.timestamp.timestamp(),
-                sequence_number: event.sequence_number,
-                data: &event.data,
-                actor: &event.actor,
-                rationale: &event.rationale,
-                policy_version: &event.policy_version,
-                data_provenance_hash: &event.data_provenance_hash,
-                human_review_required: event.human_review_required,
-                approval_count: event.approval_signatures.len(),
-                evidence_capsule: &event.evidence_capsule,
-                schema_version: &event.schema_version,
-            });
+            event.hash = AuditEvent::compute_hash(event.hash_params());
         }
 
         if let Some(prev) = &event.previous_hash {
@@ -220,9 +358,27 @@ impl<B: StorageBackend + ?Sized> AuditStore<B> {
         chain.push(event.id);
         self.backend.set(&self.chain_key(tenant_id), &chain).await?;
 
+        // Append to the stored Merkle log: the leaf and the subtrees it completes
+        let completed = self
+            .with_stored_log(tenant_id, seq, |log| log.append(event.hash.clone()))
+            .await?
+            .ok_or_else(|| StorageError::Internal("Merkle log append failed".to_string()))?;
+        for (id, hash) in completed {
+            self.backend
+                .set(&self.log_node_key(tenant_id, id), &hash)
+                .await?;
+        }
+        self.backend
+            .set(&self.sequence_key(tenant_id, seq), &event.id)
+            .await?;
+
         // Update per-tenant chain state
         chain_state.last_hash = Some(event.hash.clone());
         chain_state.sequence += 1;
+        chain_state.log_size += 1;
+        if track_frontier {
+            chain_state.frontier.append(event.hash.clone());
+        }
         self.set_chain_state(tenant_id, &chain_state).await?;
 
         // Phase 2.2: Index by witness receipt for O(1) lookup
@@ -319,9 +475,69 @@ impl<B: StorageBackend + ?Sized> AuditStore<B> {
         Ok(None)
     }
 
-    /// Get event by ID
+    /// Attach a co-signature to a logged event
+    ///
+    /// The signature must be a valid Ed25519 signature over the event hash from a
+    /// signer allowed by the event's threshold policy. Each signer's signature is
+    /// stored under its own key, so concurrent signers (on any replica) never
+    /// overwrite each other; a signer's first signature is kept.
+    pub async fn add_co_signature(
+        &self,
+        tenant_id: &str,
+        event_id: Uuid,
+        signature: Signature,
+    ) -> Result<AuditEvent, StorageError> {
+        let event = self
+            .get(tenant_id, event_id)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(event_id.to_string()))?;
+
+        event
+            .verify_co_signature(&signature)
+            .map_err(StorageError::Internal)?;
+
+        if !event
+            .co_signatures
+            .iter()
+            .any(|s| s.signer_id == signature.signer_id)
+        {
+            self.backend
+                .set(
+                    &self.co_signature_key(tenant_id, event_id, &signature.signer_id),
+                    &signature,
+                )
+                .await?;
+        }
+        self.get(tenant_id, event_id)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(event_id.to_string()))
+    }
+
+    /// Get event by ID, with the co-signatures added since it was logged
     pub async fn get(&self, tenant_id: &str, id: Uuid) -> Result<Option<AuditEvent>, StorageError> {
-        self.backend.get(&self.event_key(tenant_id, id)).await
+        let Some(mut event) = self
+            .backend
+            .get::<AuditEvent>(&self.event_key(tenant_id, id))
+            .await?
+        else {
+            return Ok(None);
+        };
+        let signers = event
+            .threshold_policy
+            .as_ref()
+            .map(|p| p.signers.clone())
+            .unwrap_or_default();
+        for signer_id in signers {
+            if event.co_signatures.iter().any(|s| s.signer_id == signer_id) {
+                continue;
+            }
+            let signature: Option<Signature> = self
+                .backend
+                .get(&self.co_signature_key(tenant_id, id, &signer_id))
+                .await?;
+            event.co_signatures.extend(signature);
+        }
+        Ok(Some(event))
     }
 
     /// Get all events in chain order
@@ -344,16 +560,310 @@ impl<B: StorageBackend + ?Sized> AuditStore<B> {
     /// Build Merkle tree of all events for a tenant
     pub async fn build_merkle_tree(&self, tenant_id: &str) -> Result<MerkleTree, StorageError> {
         let events = self.get_chain(tenant_id).await?;
-        let leaves: Vec<(String, Hash)> = events
-            .iter()
-            .map(|e| (e.id.to_string(), e.hash.clone()))
-            .collect();
-        Ok(MerkleTree::from_leaves(leaves))
+        Ok(self.tree_from_events(&events))
+    }
+
+    async fn rebuild_frontier(&self, tenant_id: &str) -> Result<MerkleFrontier, StorageError> {
+        let mut frontier = MerkleFrontier::new();
+        frontier.extend(self.get_chain(tenant_id).await?.into_iter().map(|e| e.hash));
+        Ok(frontier)
+    }
+
+    /// Whether every event's Merkle log nodes are stored with this store's hasher
+    fn log_is_current(&self, chain_state: &ChainState) -> bool {
+        chain_state.log_size == chain_state.sequence
+            && (chain_state.sequence == 0 || chain_state.log_algorithm == self.hash_algorithm())
+    }
+
+    /// Store the Merkle log nodes of every event in the chain (once per legacy chain)
+    async fn backfill_log(
+        &self,
+        tenant_id: &str,
+        chain_state: &mut ChainState,
+    ) -> Result<(), StorageError> {
+        let events = self.get_chain(tenant_id).await?;
+        let mut log = IncrementalMerkleTree::with_hasher(self.hasher.clone());
+        log.extend(events.iter().map(|e| e.hash.clone()));
+        for (id, hash) in log.nodes() {
+            self.backend
+                .set(&self.log_node_key(tenant_id, id), hash)
+                .await?;
+        }
+        for (index, event) in events.iter().enumerate() {
+            self.backend
+                .set(&self.sequence_key(tenant_id, index as u64), &event.id)
+                .await?;
+        }
+        chain_state.log_size = log.len();
+        chain_state.log_algorithm = self.hash_algorithm();
+        Ok(())
+    }
+
+    /// Run `f` over the first `size` events of the stored Merkle log
+    ///
+    /// `f` runs twice: once to record the nodes it reads, then over those nodes as
+    /// loaded from storage, so only O(log n) nodes are fetched.
+    async fn with_stored_log<T>(
+        &self,
+        tenant_id: &str,
+        size: u64,
+        f: impl Fn(&MerkleLogView<'_, dyn MerkleNodes>) -> Option<T>,
+    ) -> Result<Option<T>, StorageError> {
+        let recorder = NodeRecorder::new();
+        f(&MerkleLogView::new(
+            &recorder as &dyn MerkleNodes,
+            size,
+            self.hasher.as_ref(),
+        ));
+
+        let mut nodes: HashMap<NodeId, Hash> = HashMap::new();
+        for id in recorder.into_nodes() {
+            let hash = self
+                .backend
+                .get(&self.log_node_key(tenant_id, id))
+                .await?
+                .ok_or_else(|| {
+                    StorageError::Internal(format!("Merkle log node {:?} is missing", id))
+                })?;
+            nodes.insert(id, hash);
+        }
+        Ok(f(&MerkleLogView::new(
+            &nodes as &dyn MerkleNodes,
+            size,
+            self.hasher.as_ref(),
+        )))
+    }
+
+    /// Event at chain position `index`, via the sequence index
+    async fn event_at(
+        &self,
+        tenant_id: &str,
+        index: u64,
+    ) -> Result<Option<AuditEvent>, StorageError> {
+        let id: Option<Uuid> = self
+            .backend
+            .get(&self.sequence_key(tenant_id, index))
+            .await?;
+        match id {
+            Some(id) => self.get(tenant_id, id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Current Merkle root for a tenant
+    ///
+    /// Read from the chain state frontier (SHA-256) or the stored log nodes, without
+    /// loading events.
+    pub async fn merkle_root(&self, tenant_id: &str) -> Result<Option<Hash>, StorageError> {
+        let chain_state = self.get_chain_state(tenant_id).await?;
+        if self.hash_algorithm() == HashAlgorithm::Sha256
+            && chain_state.frontier.len() == chain_state.sequence
+        {
+            return Ok(chain_state.frontier.root());
+        }
+        if self.log_is_current(&chain_state) {
+            return self
+                .with_stored_log(tenant_id, chain_state.log_size, |log| log.root())
+                .await;
+        }
+        Ok(self.build_merkle_log(tenant_id).await?.root())
+    }
+
+    /// Build the append-only Merkle log of all events for a tenant
+    ///
+    /// Loads the whole chain, so this is O(n) in the tenant's event count. Roots and
+    /// proofs are served from the stored log nodes instead, and only fall back to this
+    /// for chains logged before the nodes were stored.
+    pub async fn build_merkle_log(
+        &self,
+        tenant_id: &str,
+    ) -> Result<IncrementalMerkleTree, StorageError> {
+        let events = self.get_chain(tenant_id).await?;
+        let mut log = IncrementalMerkleTree::with_hasher(self.hasher.clone());
+        log.extend(events.into_iter().map(|e| e.hash));
+        Ok(log)
+    }
+
+    /// Inclusion proof for the event at `index` in the log of `tree_size` events
+    ///
+    /// Reads O(log n) stored log nodes.
+    pub async fn inclusion_proof(
+        &self,
+        tenant_id: &str,
+        index: u64,
+        tree_size: u64,
+    ) -> Result<Option<MerkleProof>, StorageError> {
+        let chain_state = self.get_chain_state(tenant_id).await?;
+        if !self.log_is_current(&chain_state) {
+            let log = self.build_merkle_log(tenant_id).await?;
+            return Ok(log.inclusion_proof(index, tree_size));
+        }
+        if tree_size > chain_state.log_size {
+            return Ok(None);
+        }
+        self.with_stored_log(tenant_id, tree_size, |log| log.inclusion_proof(index))
+            .await
+    }
+
+    /// Proof that the log of `old_size` events is a prefix of the log of `new_size` events
+    ///
+    /// Reads O(log² n) stored log nodes.
+    pub async fn consistency_proof(
+        &self,
+        tenant_id: &str,
+        old_size: u64,
+        new_size: u64,
+    ) -> Result<Option<ConsistencyProof>, StorageError> {
+        let chain_state = self.get_chain_state(tenant_id).await?;
+        if !self.log_is_current(&chain_state) {
+            let log = self.build_merkle_log(tenant_id).await?;
+            return Ok(log.consistency_proof(old_size, new_size));
+        }
+        if new_size > chain_state.log_size {
+            return Ok(None);
+        }
+        self.with_stored_log(tenant_id, new_size, |log| log.consistency_proof(old_size))
+            .await
+    }
+
+    /// Compact multiproof for the given events against the current tenant root
+    ///
+    /// Returns None if any event is not part of the tenant chain.
+    pub async fn multiproof(
+        &self,
+        tenant_id: &str,
+        event_ids: &[Uuid],
+    ) -> Result<Option<MerkleMultiProof>, StorageError> {
+        let chain_state = self.get_chain_state(tenant_id).await?;
+        if !self.log_is_current(&chain_state) {
+            let events = self.get_chain(tenant_id).await?;
+            let mut indices = Vec::with_capacity(event_ids.len());
+            for id in event_ids {
+                match events.iter().position(|e| &e.id == id) {
+                    Some(index) => indices.push(index),
+                    None => return Ok(None),
+                }
+            }
+            return Ok(self.tree_from_events(&events).get_multiproof(&indices));
+        }
+
+        let mut leaves = Vec::with_capacity(event_ids.len());
+        let mut hashes = HashMap::with_capacity(event_ids.len());
+        for id in event_ids {
+            match self.get(tenant_id, *id).await? {
+                Some(event) if event.sequence_number < chain_state.log_size => {
+                    leaves.push((event.sequence_number, id.to_string()));
+                    hashes.insert(id.to_string(), self.hasher.hash_digest_leaf(&event.hash));
+                }
+                _ => return Ok(None),
+            }
+        }
+        let proof = self
+            .with_stored_log(tenant_id, chain_state.log_size, |log| {
+                log.multiproof(&leaves)
+            })
+            .await?;
+        // A leaf that does not match the event's hash means the log and the events disagree
+        if let Some(proof) = &proof {
+            if proof
+                .leaves
+                .iter()
+                .any(|leaf| hashes.get(&leaf.leaf_id) != Some(&leaf.leaf_hash))
+            {
+                return Err(StorageError::Internal(
+                    "Merkle log does not match the stored events".to_string(),
+                ));
+            }
+        }
+        Ok(proof)
+    }
+
+    /// Compact multiproof for every event with a timestamp in `[start, end)`
+    ///
+    /// Events are stamped when appended, so the range is found by binary search over
+    /// the chain; only the boundary probes and the disclosed events are loaded.
+    pub async fn multiproof_between(
+        &self,
+        tenant_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Option<MerkleMultiProof>, StorageError> {
+        let chain_state = self.get_chain_state(tenant_id).await?;
+        if !self.log_is_current(&chain_state) {
+            let events = self.get_chain(tenant_id).await?;
+            let indices: Vec<usize> = events
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| e.timestamp >= start && e.timestamp < end)
+                .map(|(i, _)| i)
+                .collect();
+            return Ok(self.tree_from_events(&events).get_multiproof(&indices));
+        }
+
+        let size = chain_state.log_size;
+        let first = self.first_event_at(tenant_id, size, start).await?;
+        let last = self.first_event_at(tenant_id, size, end).await?;
+        let mut leaves = Vec::new();
+        for index in first..last.max(first) {
+            let id: Uuid = self
+                .backend
+                .get(&self.sequence_key(tenant_id, index))
+                .await?
+                .ok_or_else(|| StorageError::NotFound(format!("sequence {}", index)))?;
+            leaves.push((index, id.to_string()));
+        }
+        self.with_stored_log(tenant_id, size, |log| log.multiproof(&leaves))
+            .await
+    }
+
+    /// Position of the first of the `size` events stamped at or after `at`
+    async fn first_event_at(
+        &self,
+        tenant_id: &str,
+        size: u64,
+        at: DateTime<Utc>,
+    ) -> Result<u64, StorageError> {
+        let (mut lo, mut hi) = (0, size);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let event = self
+                .event_at(tenant_id, mid)
+                .await?
+                .ok_or_else(|| StorageError::NotFound(format!("sequence {}", mid)))?;
+            if event.timestamp < at {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        Ok(lo)
+    }
+
+    fn tree_from_events(&self, events: &[AuditEvent]) -> MerkleTree {
+        MerkleTree::from_leaves_with_hasher(
+            events
+                .iter()
+                .map(|e| (e.id.to_string(), e.hash.clone()))
+                .collect(),
+            self.hasher.as_ref(),
+        )
     }
 
     /// Verify chain integrity for a tenant
+    ///
+    /// Every event hash is recomputed from its fields and chain link, so an edited
+    /// event (e.g. a removed threshold policy) fails even without a configured policy.
+    /// If the tenant has a key history, every approval signature is checked against the
+    /// key that was valid for the signer at the event's sequence number.
     pub async fn verify_chain(&self, tenant_id: &str) -> Result<bool, StorageError> {
         let events = self.get_chain(tenant_id).await?;
+        let history = match self.key_history(tenant_id).await {
+            Ok(history) => history,
+            Err(e) => {
+                tracing::warn!("Chain integrity failed: {}", e);
+                return Ok(false);
+            }
+        };
 
         for (i, event) in events.iter().enumerate() {
             if i == 0 {
@@ -392,6 +902,50 @@ impl<B: StorageBackend + ?Sized> AuditStore<B> {
                     }
                 }
             }
+
+            let base_hash = AuditEvent::compute_hash(event.hash_params());
+            let expected = match &event.previous_hash {
+                Some(prev) => {
+                    AuditEvent::compute_chained_hash(&base_hash, prev, event.sequence_number)
+                }
+                None => base_hash,
+            };
+            if expected != event.hash {
+                tracing::warn!(
+                    "Chain integrity failed: event {} does not match its hash",
+                    event.id
+                );
+                return Ok(false);
+            }
+
+            if let Some(required) = self.threshold_policy_for(&event.event_type) {
+                let committed = event
+                    .threshold_policy
+                    .as_ref()
+                    .is_some_and(|p| p.satisfies(required));
+                if !committed {
+                    tracing::warn!(
+                        "Chain integrity failed: event {} does not carry the required threshold policy",
+                        event.id
+                    );
+                    return Ok(false);
+                }
+            }
+
+            if let Err(e) = event.verify_threshold() {
+                tracing::warn!("Chain integrity failed: {}", e);
+                return Ok(false);
+            }
+
+            if !history.is_empty() {
+                let verified = event.verify_approval_signatures(|signer_id| {
+                    history.key_for(signer_id, event.sequence_number)
+                });
+                if let Err(e) = verified {
+                    tracing::warn!("Chain integrity failed: {}", e);
+                    return Ok(false);
+                }
+            }
         }
 
         tracing::info!(
@@ -405,11 +959,15 @@ impl<B: StorageBackend + ?Sized> AuditStore<B> {
     /// Export audit trail for compliance for a tenant
     pub async fn export(&self, tenant_id: &str) -> Result<AuditExport, StorageError> {
         let events = self.get_chain(tenant_id).await?;
-        let merkle_tree = self.build_merkle_tree(tenant_id).await?;
+        let merkle_root = self.merkle_root(tenant_id).await?;
+        // Replaying the history validates it before it is embedded
+        let key_history = self.key_history(tenant_id).await?.entries().to_vec();
 
         Ok(AuditExport {
             events,
-            merkle_root: merkle_tree.root_hash().map(|h| h.to_string()),
+            key_history,
+            merkle_root: merkle_root.map(|h| h.to_string()),
+            hash_algorithm: self.hash_algorithm(),
             exported_at: Utc::now(),
             verified: self.verify_chain(tenant_id).await.unwrap_or(false),
         })
@@ -420,7 +978,13 @@ impl<B: StorageBackend + ?Sized> AuditStore<B> {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditExport {
     pub events: Vec<AuditEvent>,
+    /// Signing key history needed to verify approval signatures
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub key_history: Vec<KeyEvent>,
     pub merkle_root: Option<String>,
+    /// Hash function used to build `merkle_root` (absent in older exports: SHA-256)
+    #[serde(default)]
+    pub hash_algorithm: HashAlgorithm,
     pub exported_at: DateTime<Utc>,
     pub verified: bool,
 }
@@ -671,6 +1235,543 @@ mod tests {
         assert_ne!(root1, root2);
     }
 
+    #[tokio::test]
+    async fn test_audit_merkle_log_grows_consistently() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = AuditStore::new(backend);
+        let tenant = "tenant-log";
+
+        let log_events = |count: usize| {
+            let store = &store;
+            async move {
+                for i in 0..count {
+                    store
+                        .log(
+                            tenant,
+                            AuditEventType::AgentExecuted,
+                            ActorType::System("test".to_string()),
+                            None,
+                            serde_json::json!({ "step": i }),
+                            None,
+                            None,
+                            None,
+                        )
+                        .await
+                        .unwrap();
+                }
+            }
+        };
+
+        log_events(5).await;
+        let old_root = store.merkle_root(tenant).await.unwrap().unwrap();
+        assert_eq!(
+            Some(&old_root),
+            store.build_merkle_tree(tenant).await.unwrap().root_hash()
+        );
+
+        log_events(3).await;
+        let new_root = store.merkle_root(tenant).await.unwrap().unwrap();
+        assert_eq!(
+            Some(&new_root),
+            store.build_merkle_tree(tenant).await.unwrap().root_hash()
+        );
+
+        let consistency = store
+            .consistency_proof(tenant, 5, 8)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(consistency.verify(&old_root, &new_root));
+
+        let inclusion = store.inclusion_proof(tenant, 2, 5).await.unwrap().unwrap();
+        assert!(inclusion.verify(&old_root));
+        assert!(store.inclusion_proof(tenant, 8, 8).await.unwrap().is_none());
+
+        let chain = store.get_chain(tenant).await.unwrap();
+        let ids: Vec<Uuid> = chain.iter().skip(2).take(4).map(|e| e.id).collect();
+        let multiproof = store.multiproof(tenant, &ids).await.unwrap().unwrap();
+        assert_eq!(multiproof.leaves.len(), 4);
+        assert!(multiproof.verify(&new_root));
+        assert!(store
+            .multiproof(tenant, &[Uuid::new_v4()])
+            .await
+            .unwrap()
+            .is_none());
+
+        let day = store
+            .multiproof_between(
+                tenant,
+                chain[0].timestamp,
+                Utc::now() + chrono::Duration::seconds(1),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(day.leaves.len(), 8);
+        assert!(day.siblings.is_empty());
+        assert!(day.verify(&new_root));
+    }
+
+    #[tokio::test]
+    async fn test_audit_proofs_read_stored_log() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = AuditStore::new(backend.clone());
+        let tenant = "tenant-nodes";
+
+        let mut ids = Vec::new();
+        for i in 0..9 {
+            let event = store
+                .log(
+                    tenant,
+                    AuditEventType::AgentExecuted,
+                    ActorType::System("test".to_string()),
+                    None,
+                    serde_json::json!({ "step": i }),
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+            ids.push(event.id);
+        }
+        let reference = store.build_merkle_log(tenant).await.unwrap();
+        let root = reference.root().unwrap();
+        let old_root = reference.root_at(4).unwrap();
+
+        // Proofs come from the stored nodes, not the events
+        for id in &ids {
+            backend.delete(&store.event_key(tenant, *id)).await.unwrap();
+        }
+        backend.delete(&store.chain_key(tenant)).await.unwrap();
+
+        let inclusion = store.inclusion_proof(tenant, 6, 9).await.unwrap().unwrap();
+        assert!(inclusion.verify(&root));
+        let consistency = store
+            .consistency_proof(tenant, 4, 9)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(consistency.verify(&old_root, &root));
+        assert!(store
+            .inclusion_proof(tenant, 0, 10)
+            .await
+            .unwrap()
+            .is_none());
+
+        // A missing node is an error rather than a wrong proof
+        backend
+            .delete(&store.log_node_key(tenant, (0, 7)))
+            .await
+            .unwrap();
+        assert!(store.inclusion_proof(tenant, 6, 9).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_audit_store_backfills_legacy_log() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = AuditStore::new(backend.clone());
+        let tenant = "tenant-legacy";
+
+        let log_one = |i: usize| {
+            let store = &store;
+            async move {
+                store
+                    .log(
+                        tenant,
+                        AuditEventType::AgentExecuted,
+                        ActorType::System("test".to_string()),
+                        None,
+                        serde_json::json!({ "step": i }),
+                        None,
+                        None,
+                        None,
+                    )
+                    .await
+                    .unwrap()
+            }
+        };
+        for i in 0..5 {
+            log_one(i).await;
+        }
+
+        // Chain state from before log nodes were stored
+        for key in backend
+            .list_keys("audit:tenant:tenant-legacy:log:")
+            .await
+            .unwrap()
+        {
+            backend.delete(&key).await.unwrap();
+        }
+        let mut chain_state = store.get_chain_state(tenant).await.unwrap();
+        chain_state.log_size = 0;
+        store.set_chain_state(tenant, &chain_state).await.unwrap();
+
+        // Reads fall back to the events; the next append stores every node
+        let proof = store.inclusion_proof(tenant, 1, 5).await.unwrap().unwrap();
+        assert!(proof.verify(&store.merkle_root(tenant).await.unwrap().unwrap()));
+        log_one(5).await;
+        assert_eq!(store.get_chain_state(tenant).await.unwrap().log_size, 6);
+
+        let root = store
+            .build_merkle_log(tenant)
+            .await
+            .unwrap()
+            .root()
+            .unwrap();
+        let proof = store.inclusion_proof(tenant, 2, 6).await.unwrap().unwrap();
+        assert!(proof.verify(&root));
+        let chain = store.get_chain(tenant).await.unwrap();
+        let ids: Vec<Uuid> = chain.iter().map(|e| e.id).collect();
+        let multiproof = store.multiproof(tenant, &ids[1..3]).await.unwrap().unwrap();
+        assert!(multiproof.verify(&root));
+    }
+
+    #[tokio::test]
+    async fn test_audit_store_custom_hash_algorithm() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = AuditStore::with_hasher(backend, Arc::new(vex_core::Sha512_256Hasher));
+        let tenant = "tenant-sha512";
+
+        for i in 0..3 {
+            store
+                .log(
+                    tenant,
+                    AuditEventType::AgentExecuted,
+                    ActorType::System("test".to_string()),
+                    None,
+                    serde_json::json!({ "step": i }),
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+
+        // The SHA-256 frontier is not maintained for other hashers
+        let chain_state = store.get_chain_state(tenant).await.unwrap();
+        assert!(chain_state.frontier.is_empty());
+
+        let tree = store.build_merkle_tree(tenant).await.unwrap();
+        assert_eq!(tree.algorithm(), HashAlgorithm::Sha512_256);
+        assert_eq!(
+            store.merkle_root(tenant).await.unwrap().as_ref(),
+            tree.root_hash()
+        );
+
+        let export = store.export(tenant).await.unwrap();
+        assert_eq!(export.hash_algorithm, HashAlgorithm::Sha512_256);
+        assert!(export.verified);
+
+        let proof = store.inclusion_proof(tenant, 1, 3).await.unwrap().unwrap();
+        assert_eq!(proof.algorithm, HashAlgorithm::Sha512_256);
+        assert!(proof.verify(tree.root_hash().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_audit_human_override_requires_two_signers() {
+        let alice = ed25519_dalek::SigningKey::from_bytes(&[1u8; 32]);
+        let bob = ed25519_dalek::SigningKey::from_bytes(&[2u8; 32]);
+        let mallory = ed25519_dalek::SigningKey::from_bytes(&[3u8; 32]);
+
+        let backend = Arc::new(MemoryBackend::new());
+        let store = AuditStore::new(backend).with_threshold_policy(
+            AuditEventType::HumanOverride,
+            ThresholdPolicy::new(2, &[alice.verifying_key(), bob.verifying_key()]).unwrap(),
+        );
+        let tenant = "tenant-override";
+
+        store
+            .log(
+                tenant,
+                AuditEventType::AgentExecuted,
+                ActorType::System("test".to_string()),
+                None,
+                serde_json::json!({}),
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let event = store
+            .log(
+                tenant,
+                AuditEventType::HumanOverride,
+                ActorType::Human("alice".to_string()),
+                None,
+                serde_json::json!({"override": "allow"}),
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(event.threshold_policy.is_some());
+        assert!(!store.verify_chain(tenant).await.unwrap());
+
+        // Unauthorized signers are rejected outright
+        let forged = Signature::create(
+            hex::encode(mallory.verifying_key().to_bytes()),
+            &event.hash.0,
+            &mallory,
+        );
+        assert!(store
+            .add_co_signature(tenant, event.id, forged)
+            .await
+            .is_err());
+
+        let sig_a = Signature::create(
+            hex::encode(alice.verifying_key().to_bytes()),
+            &event.hash.0,
+            &alice,
+        );
+        store
+            .add_co_signature(tenant, event.id, sig_a.clone())
+            .await
+            .unwrap();
+        store
+            .add_co_signature(tenant, event.id, sig_a)
+            .await
+            .unwrap();
+        assert!(!store.verify_chain(tenant).await.unwrap());
+
+        let sig_b = Signature::create(
+            hex::encode(bob.verifying_key().to_bytes()),
+            &event.hash.0,
+            &bob,
+        );
+        let signed = store
+            .add_co_signature(tenant, event.id, sig_b)
+            .await
+            .unwrap();
+        assert_eq!(signed.co_signatures.len(), 2);
+        assert!(store.verify_chain(tenant).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_verify_chain_detects_edited_events() {
+        let alice = ed25519_dalek::SigningKey::from_bytes(&[1u8; 32]);
+        let bob = ed25519_dalek::SigningKey::from_bytes(&[2u8; 32]);
+
+        let backend = Arc::new(MemoryBackend::new());
+        let store = AuditStore::new(backend.clone()).with_threshold_policy(
+            AuditEventType::HumanOverride,
+            ThresholdPolicy::new(2, &[alice.verifying_key(), bob.verifying_key()]).unwrap(),
+        );
+        let tenant = "tenant-edited";
+        for event_type in [AuditEventType::AgentExecuted, AuditEventType::HumanOverride] {
+            store
+                .log(
+                    tenant,
+                    event_type,
+                    ActorType::System("test".to_string()),
+                    None,
+                    serde_json::json!({}),
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+
+        // Stripping the committed policy must not let a store without one pass
+        let unconfigured = AuditStore::new(backend.clone());
+        assert!(!unconfigured.verify_chain(tenant).await.unwrap());
+
+        let mut event = unconfigured.get_chain(tenant).await.unwrap().pop().unwrap();
+        event.threshold_policy = None;
+        backend
+            .set(&unconfigured.event_key(tenant, event.id), &event)
+            .await
+            .unwrap();
+        assert!(!unconfigured.verify_chain(tenant).await.unwrap());
+
+        // Edits to the first (unchained) event are caught too
+        let mut first = unconfigured.get_chain(tenant).await.unwrap().remove(0);
+        first.data = serde_json::json!({"edited": true});
+        backend
+            .set(&unconfigured.event_key(tenant, first.id), &first)
+            .await
+            .unwrap();
+        assert!(!unconfigured.verify_chain(tenant).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_co_signatures_from_replicas_are_kept() {
+        let keys: Vec<_> = (1u8..=3)
+            .map(|i| ed25519_dalek::SigningKey::from_bytes(&[i; 32]))
+            .collect();
+        let verifying: Vec<_> = keys.iter().map(|k| k.verifying_key()).collect();
+
+        let backend = Arc::new(MemoryBackend::new());
+        let replica = || {
+            AuditStore::new(backend.clone()).with_threshold_policy(
+                AuditEventType::HumanOverride,
+                ThresholdPolicy::new(3, &verifying).unwrap(),
+            )
+        };
+        // Independent store instances share nothing but the backend
+        let replicas = [replica(), replica(), replica()];
+        let store = &replicas[0];
+        let tenant = "tenant-concurrent";
+        let event = store
+            .log(
+                tenant,
+                AuditEventType::HumanOverride,
+                ActorType::Human("alice".to_string()),
+                None,
+                serde_json::json!({"override": "allow"}),
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let sign = |i: usize| {
+            let signature = Signature::create(
+                hex::encode(keys[i].verifying_key().to_bytes()),
+                &event.hash.0,
+                &keys[i],
+            );
+            replicas[i].add_co_signature(tenant, event.id, signature)
+        };
+        let (a, b, c) = tokio::join!(sign(0), sign(1), sign(2));
+        assert!(a.is_ok() && b.is_ok() && c.is_ok());
+
+        let stored = store.get(tenant, event.id).await.unwrap().unwrap();
+        assert_eq!(stored.co_signatures.len(), 3);
+        assert!(store.verify_chain(tenant).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_audit_key_rotation() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = AuditStore::new(backend);
+        let tenant = "tenant-keys";
+
+        let old_id = AgentIdentity::new();
+        let mut new_id = AgentIdentity::new();
+        new_id.agent_id = old_id.agent_id.clone();
+
+        let mut history = KeyHistory::new();
+        history.introduce(&old_id.agent_id, &old_id, 0).unwrap();
+        store.save_key_history(tenant, &history).await.unwrap();
+
+        let log = |id: &AgentIdentity| {
+            let id = id.clone();
+            let store = &store;
+            async move {
+                store
+                    .log(
+                        tenant,
+                        AuditEventType::AgentExecuted,
+                        ActorType::System("test".to_string()),
+                        None,
+                        serde_json::json!({}),
+                        Some(&id),
+                        None,
+                        None,
+                    )
+                    .await
+            }
+        };
+
+        log(&old_id).await.unwrap();
+        history
+            .rotate(&old_id.agent_id, &old_id, &new_id.public_key_hex(), 1)
+            .unwrap();
+        store.save_key_history(tenant, &history).await.unwrap();
+
+        // The rotated-out key can no longer sign, nor can a signer outside the history
+        assert!(log(&old_id).await.is_err());
+        assert!(log(&AgentIdentity::new()).await.is_err());
+        log(&new_id).await.unwrap();
+        assert!(store.verify_chain(tenant).await.unwrap());
+
+        // History is append-only
+        assert!(store
+            .save_key_history(tenant, &KeyHistory::new())
+            .await
+            .is_err());
+
+        // Revoking the new key retroactively breaks verification of event 1
+        history
+            .revoke(
+                &new_id.agent_id,
+                &new_id.public_key_hex(),
+                &new_id,
+                1,
+                Some("compromised".to_string()),
+            )
+            .unwrap();
+        store.save_key_history(tenant, &history).await.unwrap();
+        assert!(!store.verify_chain(tenant).await.unwrap());
+
+        let export = store.export(tenant).await.unwrap();
+        assert_eq!(export.key_history.len(), 3);
+
+        // A tampered stored history is not exported
+        let mut entries = history.entries().to_vec();
+        entries[1].effective_sequence = 5;
+        store
+            .backend
+            .set(&store.key_history_key(tenant), &entries)
+            .await
+            .unwrap();
+        assert!(store.export(tenant).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_key_history_saved_by_replica_is_used() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = AuditStore::new(backend.clone());
+        let replica = AuditStore::new(backend);
+        let tenant = "tenant-keys";
+
+        let old_id = AgentIdentity::new();
+        let mut new_id = AgentIdentity::new();
+        new_id.agent_id = old_id.agent_id.clone();
+
+        let mut history = KeyHistory::new();
+        history.introduce(&old_id.agent_id, &old_id, 0).unwrap();
+        store.save_key_history(tenant, &history).await.unwrap();
+
+        let log = |id: &AgentIdentity| {
+            let id = id.clone();
+            let store = &store;
+            async move {
+                store
+                    .log(
+                        tenant,
+                        AuditEventType::AgentExecuted,
+                        ActorType::System("test".to_string()),
+                        None,
+                        serde_json::json!({}),
+                        Some(&id),
+                        None,
+                        None,
+                    )
+                    .await
+            }
+        };
+
+        log(&old_id).await.unwrap();
+        log(&old_id).await.unwrap();
+
+        // A rotation saved elsewhere is seen despite the cached history
+        history
+            .rotate(&old_id.agent_id, &old_id, &new_id.public_key_hex(), 2)
+            .unwrap();
+        replica.save_key_history(tenant, &history).await.unwrap();
+        assert!(log(&old_id).await.is_err());
+        log(&new_id).await.unwrap();
+        assert!(store.verify_chain(tenant).await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_audit_witness_receipt_lookup() {
         let backend = Arc::new(MemoryBackend::new());
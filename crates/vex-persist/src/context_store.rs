@@ -107,11 +107,51 @@ impl<B: StorageBackend + ?Sized> ContextStore<B> {
         Ok(contexts)
     }
 
+    /// Load the most recently saved context for an agent
+    pub async fn latest_by_agent(
+        &self,
+        tenant_id: &str,
+        agent_id: Uuid,
+    ) -> Result<Option<ContextPacket>, StorageError> {
+        let context_ids: Vec<Uuid> = self
+            .backend
+            .get(&self.agent_key(tenant_id, agent_id))
+            .await?
+            .unwrap_or_default();
+        match context_ids.last() {
+            Some(id) => self.load(tenant_id, *id).await,
+            None => Ok(None),
+        }
+    }
+
     /// Delete a context
     pub async fn delete(&self, tenant_id: &str, id: Uuid) -> Result<bool, StorageError> {
         self.backend.delete(&self.key(tenant_id, id)).await
     }
 
+    /// Delete every context saved for an agent, returning how many were removed
+    pub async fn delete_by_agent(
+        &self,
+        tenant_id: &str,
+        agent_id: Uuid,
+    ) -> Result<usize, StorageError> {
+        let context_ids: Vec<Uuid> = self
+            .backend
+            .get(&self.agent_key(tenant_id, agent_id))
+            .await?
+            .unwrap_or_default();
+        let mut removed = 0;
+        for id in context_ids {
+            if self.delete(tenant_id, id).await? {
+                removed += 1;
+            }
+        }
+        self.backend
+            .delete(&self.agent_key(tenant_id, agent_id))
+            .await?;
+        Ok(removed)
+    }
+
     /// Get total count of stored contexts for a tenant
     pub async fn count(&self, tenant_id: &str) -> Result<usize, StorageError> {
         let tenant_prefix = format!("{}tenant:{}:", self.prefix, tenant_id);
@@ -146,7 +186,22 @@ mod tests {
         let agent_contexts = store.load_by_agent(tenant_id, agent_id).await.unwrap();
         assert_eq!(agent_contexts.len(), 1);
 
+        // Latest by agent
+        packet.content = "Updated content".to_string();
+        store.save(tenant_id, &packet).await.unwrap();
+        let latest = store.latest_by_agent(tenant_id, agent_id).await.unwrap();
+        assert_eq!(latest.unwrap().content, "Updated content");
+
         // Count
-        assert_eq!(store.count(tenant_id).await.unwrap(), 1);
+        assert_eq!(store.count(tenant_id).await.unwrap(), 2);
+
+        // Delete by agent
+        assert_eq!(store.delete_by_agent(tenant_id, agent_id).await.unwrap(), 2);
+        assert_eq!(store.count(tenant_id).await.unwrap(), 0);
+        assert!(store
+            .latest_by_agent(tenant_id, agent_id)
+            .await
+            .unwrap()
+            .is_none());
     }
 }
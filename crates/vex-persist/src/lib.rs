@@ -9,6 +9,7 @@
 
 pub mod agent_store;
 pub mod api_key_store;
+pub mod artifact_store;
 pub mod audit_store;
 pub mod backend;
 pub mod context_store;
@@ -19,9 +20,11 @@ pub mod postgres;
 pub mod queue;
 pub mod sqlite;
 pub mod vector_store;
+pub mod workflow_store;
 
 pub use agent_store::AgentStore;
 pub use api_key_store::{validate_api_key, ApiKeyError, ApiKeyRecord, ApiKeyStore};
+pub use artifact_store::{content_hash, ArtifactStore};
 pub use audit_store::AuditStore;
 pub use backend::{StorageBackend, StorageError, StorageExt};
 pub use context_store::ContextStore;
@@ -40,3 +43,6 @@ pub use vector_store::PgVectorStore;
 pub use vector_store::{
     MemoryVectorStore, SqliteVectorStore, VectorEmbedding, VectorError, VectorStoreBackend,
 };
+pub use workflow_store::{
+    StepRecord, StepStatus, WorkflowRunRecord, WorkflowStatus, WorkflowStore,
+};
@@ -0,0 +1,239 @@
+//! Workflow run storage
+//!
+//! Persists the step state of DAG workflow runs so a crashed run can resume
+//! from its last completed step.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::backend::{StorageBackend, StorageError, StorageExt};
+use vex_core::ContextPacket;
+
+/// Lifecycle state of a workflow run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkflowStatus {
+    /// Run is in progress (or was interrupted and can be resumed)
+    Running,
+    /// Every reachable node completed
+    Completed,
+    /// Run reached an escalation node
+    Escalated,
+    /// A node failed; resuming retries it
+    Failed,
+}
+
+impl WorkflowStatus {
+    /// Whether the run can still make progress
+    pub fn is_resumable(&self) -> bool {
+        matches!(self, Self::Running | Self::Failed)
+    }
+}
+
+/// State of one node in a run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Completed,
+    /// No incoming edge condition held
+    Skipped,
+    Failed,
+}
+
+/// Persisted outcome of one workflow node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepRecord {
+    pub node_id: String,
+    pub status: StepStatus,
+    pub output: String,
+    pub confidence: f64,
+    /// Context packet produced by the node (completed steps only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<ContextPacket>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub finished_at: DateTime<Utc>,
+}
+
+/// Persisted state of a workflow run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRunRecord {
+    pub run_id: Uuid,
+    pub workflow_name: String,
+    /// Serialized workflow definition, so a run resumes without its caller
+    pub definition: serde_json::Value,
+    pub input: String,
+    pub status: WorkflowStatus,
+    /// Finished steps keyed by node ID
+    pub steps: BTreeMap<String, StepRecord>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WorkflowRunRecord {
+    /// Create a new running record
+    pub fn new(
+        workflow_name: impl Into<String>,
+        definition: serde_json::Value,
+        input: impl Into<String>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            run_id: Uuid::new_v4(),
+            workflow_name: workflow_name.into(),
+            definition,
+            input: input.into(),
+            status: WorkflowStatus::Running,
+            steps: BTreeMap::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Workflow run store for persistence
+#[derive(Debug)]
+pub struct WorkflowStore<B: StorageBackend + ?Sized> {
+    backend: Arc<B>,
+    prefix: String,
+}
+
+impl<B: StorageBackend + ?Sized> WorkflowStore<B> {
+    /// Create a new workflow store
+    pub fn new(backend: Arc<B>) -> Self {
+        Self {
+            backend,
+            prefix: "workflow:".to_string(),
+        }
+    }
+
+    fn key(&self, tenant_id: &str, run_id: Uuid) -> String {
+        format!("{}tenant:{}:run:{}", self.prefix, tenant_id, run_id)
+    }
+
+    fn active_list_key(&self, tenant_id: &str) -> String {
+        format!("{}tenant:{}:active", self.prefix, tenant_id)
+    }
+
+    /// Save a run, keeping the index of resumable runs in sync
+    pub async fn save(
+        &self,
+        tenant_id: &str,
+        record: &WorkflowRunRecord,
+    ) -> Result<(), StorageError> {
+        let mut record = record.clone();
+        record.updated_at = Utc::now();
+        self.backend
+            .set(&self.key(tenant_id, record.run_id), &record)
+            .await?;
+
+        let mut active: Vec<Uuid> = self
+            .backend
+            .get(&self.active_list_key(tenant_id))
+            .await?
+            .unwrap_or_default();
+        let listed = active.contains(&record.run_id);
+        if record.status.is_resumable() && !listed {
+            active.push(record.run_id);
+        } else if !record.status.is_resumable() && listed {
+            active.retain(|id| *id != record.run_id);
+        } else {
+            return Ok(());
+        }
+        self.backend
+            .set(&self.active_list_key(tenant_id), &active)
+            .await
+    }
+
+    /// Load a run by ID
+    pub async fn load(
+        &self,
+        tenant_id: &str,
+        run_id: Uuid,
+    ) -> Result<Option<WorkflowRunRecord>, StorageError> {
+        self.backend.get(&self.key(tenant_id, run_id)).await
+    }
+
+    /// List runs that are still running or failed (candidates for resume)
+    pub async fn list_resumable(
+        &self,
+        tenant_id: &str,
+    ) -> Result<Vec<WorkflowRunRecord>, StorageError> {
+        let ids: Vec<Uuid> = self
+            .backend
+            .get(&self.active_list_key(tenant_id))
+            .await?
+            .unwrap_or_default();
+
+        let mut runs = Vec::new();
+        for id in ids {
+            if let Some(run) = self.load(tenant_id, id).await? {
+                runs.push(run);
+            }
+        }
+        Ok(runs)
+    }
+
+    /// Delete a run
+    pub async fn delete(&self, tenant_id: &str, run_id: Uuid) -> Result<bool, StorageError> {
+        let mut active: Vec<Uuid> = self
+            .backend
+            .get(&self.active_list_key(tenant_id))
+            .await?
+            .unwrap_or_default();
+        if active.contains(&run_id) {
+            active.retain(|id| *id != run_id);
+            self.backend
+                .set(&self.active_list_key(tenant_id), &active)
+                .await?;
+        }
+        self.backend.delete(&self.key(tenant_id, run_id)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::MemoryBackend;
+
+    #[tokio::test]
+    async fn test_workflow_store() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = WorkflowStore::new(backend);
+        let tenant_id = "test-tenant";
+
+        let mut record = WorkflowRunRecord::new("triage", serde_json::json!({}), "input");
+        store.save(tenant_id, &record).await.unwrap();
+        assert_eq!(store.list_resumable(tenant_id).await.unwrap().len(), 1);
+
+        record.steps.insert(
+            "a".to_string(),
+            StepRecord {
+                node_id: "a".to_string(),
+                status: StepStatus::Completed,
+                output: "done".to_string(),
+                confidence: 0.9,
+                context: Some(ContextPacket::new("done")),
+                error: None,
+                finished_at: Utc::now(),
+            },
+        );
+        record.status = WorkflowStatus::Completed;
+        store.save(tenant_id, &record).await.unwrap();
+
+        let loaded = store.load(tenant_id, record.run_id).await.unwrap().unwrap();
+        assert_eq!(loaded.status, WorkflowStatus::Completed);
+        assert_eq!(loaded.steps["a"].output, "done");
+        assert!(store.list_resumable(tenant_id).await.unwrap().is_empty());
+
+        assert!(store.delete(tenant_id, record.run_id).await.unwrap());
+        assert!(store
+            .load(tenant_id, record.run_id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -209,7 +209,7 @@ impl Router {
         if let Some(pub_key) = &self.config.chora_public_key {
             if !packet
                 .verify(pub_key)
-                .map_err(RouterError::VepVerificationFailed)?
+                .map_err(|e| RouterError::VepVerificationFailed(e.to_string()))?
             {
                 return Err(RouterError::VepVerificationFailed(
                     "Cryptographic signature mismatch".to_string(),
@@ -220,7 +220,7 @@ impl Router {
         // 2. Reconstruct the capsule (intent + authority)
         let capsule = packet
             .to_capsule()
-            .map_err(RouterError::VepVerificationFailed)?;
+            .map_err(|e| RouterError::VepVerificationFailed(e.to_string()))?;
 
         // 3. Extract prompt from intent (hardened identifier)
         let intent_id = match &capsule.intent {
@@ -328,7 +328,7 @@ impl Router {
         _complexity: &QueryComplexity,
     ) -> Result<RoutingDecision, RouterError> {
         let mut models: Vec<&Model> = self.pool.models.iter().collect();
-        models.sort_by(|a, b| a.config.latency_ms.cmp(&b.config.latency_ms));
+        models.sort_by_key(|m| m.config.latency_ms);
 
         let model = models.first().ok_or(RouterError::NoModelsAvailable)?;
 
@@ -508,6 +508,9 @@ impl LlmProvider for Router {
             tokens_used: Some(((request.prompt.len() + response_len) as f64 / 4.0) as u32),
             latency_ms: latency,
             trace_root: None,
+            tool_calls: Vec::new(),
+            provider: None,
+            cached: false,
         })
     }
 
@@ -0,0 +1,695 @@
+//! Batched asynchronous anchoring
+//!
+//! Anchoring every orchestration root inline adds the latency of the slowest
+//! backend to each request and anchors one tiny tree per call. [`AnchorService`]
+//! instead accumulates roots per tenant and anchors a single aggregate root once
+//! a batch reaches [`AnchorPolicy::max_batch_size`] or
+//! [`AnchorPolicy::max_batch_age`]. Each submitted root gets a [`PendingAnchor`]
+//! that resolves to a [`BatchedAnchorReceipt`] carrying the backend receipts and
+//! an inclusion proof from the root to the aggregate.
+//!
+//! Failing backends are retried with exponential backoff; a batch that no
+//! backend accepts is re-queued and retried after a growing delay (capped at
+//! [`AnchorPolicy::max_requeue_delay`]). With storage attached, receipts are
+//! persisted so they can be looked up after the handle is gone, and pending roots
+//! are persisted until anchored so [`AnchorService::recover`] can re-queue them
+//! after a restart. Without storage, pending roots are lost when the process exits.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::{oneshot, Mutex, Notify};
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use vex_anchor::{AnchorBackend, AnchorMetadata, AnchorReceipt};
+use vex_core::{Hash, MerkleProof, MerkleTree};
+use vex_persist::{StorageBackend, StorageError, StorageExt};
+
+/// Errors from the anchoring service
+#[derive(Error, Debug)]
+pub enum AnchorServiceError {
+    #[error("Anchor service shut down before the root was anchored")]
+    Closed,
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+    #[error("At least one anchor backend is required")]
+    NoBackends,
+}
+
+/// When batches are anchored and how failing backends are retried
+#[derive(Debug, Clone)]
+pub struct AnchorPolicy {
+    /// Anchor once a tenant has this many pending roots
+    pub max_batch_size: usize,
+    /// Anchor once the oldest pending root has waited this long
+    pub max_batch_age: Duration,
+    /// Attempts per backend for each batch
+    pub max_attempts: u32,
+    /// Delay before the first retry (doubled after each attempt)
+    pub retry_backoff: Duration,
+    /// Longest wait before retrying a batch no backend accepted (the wait starts at
+    /// `max_batch_age` and doubles after each rejected flush)
+    pub max_requeue_delay: Duration,
+}
+
+impl Default for AnchorPolicy {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 64,
+            max_batch_age: Duration::from_secs(60),
+            max_attempts: 3,
+            retry_backoff: Duration::from_millis(500),
+            max_requeue_delay: Duration::from_secs(600),
+        }
+    }
+}
+
+/// Proof that a submitted root was anchored as part of a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchedAnchorReceipt {
+    /// Root that was submitted
+    pub root: Hash,
+    pub batch_id: Uuid,
+    /// Aggregate root that was anchored
+    pub batch_root: Hash,
+    /// Inclusion proof from `root` to `batch_root`
+    pub proof: MerkleProof,
+    /// Receipts from every backend that anchored the batch
+    pub receipts: Vec<AnchorReceipt>,
+    /// Backends that failed after all retries
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_backends: Vec<String>,
+}
+
+impl BatchedAnchorReceipt {
+    /// Check the inclusion proof and that every receipt anchors the batch root
+    pub fn verify(&self) -> bool {
+        let batch_root = self.batch_root.to_hex();
+        self.proof.leaf_hash == self.root
+            && self.proof.verify(&self.batch_root)
+            && !self.receipts.is_empty()
+            && self.receipts.iter().all(|r| r.root_hash == batch_root)
+    }
+}
+
+/// Handle for a submitted root that resolves once its batch is anchored
+#[derive(Debug)]
+pub struct PendingAnchor {
+    tenant_id: String,
+    root: Hash,
+    rx: oneshot::Receiver<BatchedAnchorReceipt>,
+}
+
+impl PendingAnchor {
+    pub fn tenant_id(&self) -> &str {
+        &self.tenant_id
+    }
+
+    pub fn root(&self) -> &Hash {
+        &self.root
+    }
+
+    /// Wait for the batch containing this root to be anchored
+    pub async fn wait(self) -> Result<BatchedAnchorReceipt, AnchorServiceError> {
+        self.rx.await.map_err(|_| AnchorServiceError::Closed)
+    }
+}
+
+struct PendingRoot {
+    root: Hash,
+    event_count: u64,
+    tx: oneshot::Sender<BatchedAnchorReceipt>,
+}
+
+struct TenantBatch {
+    opened_at: Instant,
+    roots: Vec<PendingRoot>,
+    /// Flushes in a row that no backend accepted
+    failures: u32,
+    /// Set after a rejected flush: the batch is not due before this
+    retry_at: Option<Instant>,
+}
+
+impl TenantBatch {
+    fn new() -> Self {
+        Self {
+            opened_at: Instant::now(),
+            roots: Vec::new(),
+            failures: 0,
+            retry_at: None,
+        }
+    }
+
+    fn contains(&self, root: &Hash) -> bool {
+        self.roots.iter().any(|p| &p.root == root)
+    }
+}
+
+/// A pending root as persisted until it is anchored
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredRoot {
+    tenant_id: String,
+    root: Hash,
+    event_count: u64,
+}
+
+struct Inner {
+    backends: Vec<Arc<dyn AnchorBackend>>,
+    policy: AnchorPolicy,
+    batches: Mutex<HashMap<String, TenantBatch>>,
+    wake: Notify,
+    shutdown: CancellationToken,
+}
+
+/// Background scheduler that anchors per-tenant batches of roots
+#[derive(Clone)]
+pub struct AnchorService {
+    inner: Arc<Inner>,
+    storage: Option<Arc<dyn StorageBackend>>,
+}
+
+impl std::fmt::Debug for AnchorService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnchorService")
+            .field(
+                "backends",
+                &self
+                    .inner
+                    .backends
+                    .iter()
+                    .map(|b| b.name())
+                    .collect::<Vec<_>>(),
+            )
+            .field("policy", &self.inner.policy)
+            .finish()
+    }
+}
+
+impl AnchorService {
+    /// Create a service anchoring to `backends`, of which there must be at least one
+    pub fn new(
+        backends: Vec<Arc<dyn AnchorBackend>>,
+        policy: AnchorPolicy,
+    ) -> Result<Self, AnchorServiceError> {
+        if backends.is_empty() {
+            return Err(AnchorServiceError::NoBackends);
+        }
+        Ok(Self {
+            inner: Arc::new(Inner {
+                backends,
+                policy,
+                batches: Mutex::new(HashMap::new()),
+                wake: Notify::new(),
+                shutdown: CancellationToken::new(),
+            }),
+            storage: None,
+        })
+    }
+
+    /// Persist receipts and pending roots to `storage` (call before [`AnchorService::start`])
+    pub fn with_storage(mut self, storage: Arc<dyn StorageBackend>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    pub fn policy(&self) -> &AnchorPolicy {
+        &self.inner.policy
+    }
+
+    /// Queue a root for anchoring; the handle resolves when its batch is anchored
+    pub async fn submit(&self, tenant_id: &str, root: Hash, event_count: u64) -> PendingAnchor {
+        let (tx, rx) = oneshot::channel();
+        self.persist_pending(tenant_id, &root, event_count).await;
+        let full = {
+            let mut batches = self.inner.batches.lock().await;
+            let batch = batches
+                .entry(tenant_id.to_string())
+                .or_insert_with(TenantBatch::new);
+            batch.roots.push(PendingRoot {
+                root: root.clone(),
+                event_count,
+                tx,
+            });
+            batch.roots.len() >= self.inner.policy.max_batch_size
+        };
+        // A new batch may set an earlier deadline, so always wake the worker
+        self.inner.wake.notify_one();
+        if full {
+            tracing::debug!(tenant_id = %tenant_id, "Anchor batch full");
+        }
+
+        PendingAnchor {
+            tenant_id: tenant_id.to_string(),
+            root,
+            rx,
+        }
+    }
+
+    /// Re-queue roots persisted by an earlier run that were never anchored
+    ///
+    /// Call before [`AnchorService::start`]. The recovered roots have no
+    /// [`PendingAnchor`] handle; look their receipts up with [`AnchorService::receipt`].
+    pub async fn recover(&self) -> Result<usize, AnchorServiceError> {
+        let Some(storage) = &self.storage else {
+            return Ok(0);
+        };
+        let mut recovered = 0;
+        for key in storage.list_keys(PENDING_PREFIX).await? {
+            let Some(stored) = storage.get::<StoredRoot>(&key).await? else {
+                continue;
+            };
+            let mut batches = self.inner.batches.lock().await;
+            let batch = batches
+                .entry(stored.tenant_id)
+                .or_insert_with(TenantBatch::new);
+            if batch.contains(&stored.root) {
+                continue;
+            }
+            // Nobody waits on a recovered root, so the receiver is dropped
+            let (tx, _) = oneshot::channel();
+            batch.roots.push(PendingRoot {
+                root: stored.root,
+                event_count: stored.event_count,
+                tx,
+            });
+            recovered += 1;
+        }
+        if recovered > 0 {
+            tracing::info!(roots = recovered, "Recovered pending anchor roots");
+            self.inner.wake.notify_one();
+        }
+        Ok(recovered)
+    }
+
+    async fn persist_pending(&self, tenant_id: &str, root: &Hash, event_count: u64) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        let stored = StoredRoot {
+            tenant_id: tenant_id.to_string(),
+            root: root.clone(),
+            event_count,
+        };
+        if let Err(e) = storage.set(&pending_key(tenant_id, root), &stored).await {
+            tracing::warn!(tenant_id = %tenant_id, "Failed to persist pending anchor root: {}", e);
+        }
+    }
+
+    /// Number of roots waiting to be anchored for a tenant
+    pub async fn pending(&self, tenant_id: &str) -> usize {
+        self.inner
+            .batches
+            .lock()
+            .await
+            .get(tenant_id)
+            .map_or(0, |b| b.roots.len())
+    }
+
+    /// Start the background worker that flushes batches according to the policy
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let deadline = service.next_deadline().await;
+                tokio::select! {
+                    _ = service.inner.shutdown.cancelled() => break,
+                    _ = service.inner.wake.notified() => {}
+                    _ = async {
+                        match deadline {
+                            Some(deadline) => tokio::time::sleep_until(deadline).await,
+                            None => std::future::pending().await,
+                        }
+                    } => {}
+                }
+                service.flush_due().await;
+            }
+        })
+    }
+
+    /// Stop the worker after anchoring everything still pending
+    pub async fn shutdown(&self) {
+        self.inner.shutdown.cancel();
+        self.flush_all().await;
+    }
+
+    async fn next_deadline(&self) -> Option<Instant> {
+        let batches = self.inner.batches.lock().await;
+        batches.values().map(|b| self.due_at(b)).min()
+    }
+
+    /// When a batch should be flushed: at its retry time after a rejected flush,
+    /// otherwise once full or old enough
+    fn due_at(&self, batch: &TenantBatch) -> Instant {
+        match batch.retry_at {
+            Some(retry_at) => retry_at,
+            None if batch.roots.len() >= self.inner.policy.max_batch_size => batch.opened_at,
+            None => batch.opened_at + self.inner.policy.max_batch_age,
+        }
+    }
+
+    /// Anchor batches that are full or old enough and not backing off
+    pub async fn flush_due(&self) {
+        let now = Instant::now();
+        let due: Vec<String> = {
+            let batches = self.inner.batches.lock().await;
+            batches
+                .iter()
+                .filter(|(_, b)| now >= self.due_at(b))
+                .map(|(tenant, _)| tenant.clone())
+                .collect()
+        };
+        for tenant in due {
+            self.flush(&tenant).await;
+        }
+    }
+
+    /// Anchor every pending batch regardless of policy
+    pub async fn flush_all(&self) {
+        let tenants: Vec<String> = self.inner.batches.lock().await.keys().cloned().collect();
+        for tenant in tenants {
+            self.flush(&tenant).await;
+        }
+    }
+
+    /// Anchor a tenant's pending batch now
+    pub async fn flush(&self, tenant_id: &str) {
+        let Some(batch) = self.inner.batches.lock().await.remove(tenant_id) else {
+            return;
+        };
+        if batch.roots.is_empty() {
+            return;
+        }
+
+        let batch_id = Uuid::new_v4();
+        let tree = MerkleTree::from_leaves(
+            batch
+                .roots
+                .iter()
+                .map(|p| (p.root.to_hex(), p.root.clone()))
+                .collect(),
+        );
+        let Some(batch_root) = tree.root_hash().cloned() else {
+            return;
+        };
+        let event_count = batch.roots.iter().map(|p| p.event_count).sum();
+
+        let mut receipts = Vec::new();
+        let mut failed_backends = Vec::new();
+        for backend in &self.inner.backends {
+            let metadata = AnchorMetadata::new(tenant_id, event_count).with_description(format!(
+                "batch {} ({} roots)",
+                batch_id,
+                batch.roots.len()
+            ));
+            match self
+                .anchor_with_retry(backend.as_ref(), &batch_root, metadata)
+                .await
+            {
+                Some(receipt) => receipts.push(receipt),
+                None => failed_backends.push(backend.name().to_string()),
+            }
+        }
+
+        if receipts.is_empty() {
+            let failures = batch.failures + 1;
+            let delay = self
+                .inner
+                .policy
+                .max_batch_age
+                .saturating_mul(1 << (failures - 1).min(16))
+                .min(self.inner.policy.max_requeue_delay);
+            tracing::error!(
+                tenant_id = %tenant_id,
+                roots = batch.roots.len(),
+                failures = failures,
+                retry_in_ms = delay.as_millis() as u64,
+                "No anchor backend accepted the batch, re-queueing"
+            );
+            let mut batches = self.inner.batches.lock().await;
+            let requeued = batches
+                .entry(tenant_id.to_string())
+                .or_insert_with(TenantBatch::new);
+            requeued.opened_at = requeued.opened_at.min(batch.opened_at);
+            requeued.failures = failures;
+            requeued.retry_at = Some(Instant::now() + delay);
+            let newer = std::mem::replace(&mut requeued.roots, batch.roots);
+            requeued.roots.extend(newer);
+            return;
+        }
+
+        tracing::info!(
+            tenant_id = %tenant_id,
+            batch_id = %batch_id,
+            roots = batch.roots.len(),
+            backends = receipts.len(),
+            "Anchored root batch"
+        );
+
+        for (index, pending) in batch.roots.into_iter().enumerate() {
+            let Some(proof) = tree.get_proof(index) else {
+                continue;
+            };
+            let receipt = BatchedAnchorReceipt {
+                root: pending.root,
+                batch_id,
+                batch_root: batch_root.clone(),
+                proof,
+                receipts: receipts.clone(),
+                failed_backends: failed_backends.clone(),
+            };
+            if let Some(storage) = &self.storage {
+                if let Err(e) = storage
+                    .set(&receipt_key(tenant_id, &receipt.root), &receipt)
+                    .await
+                {
+                    tracing::warn!(tenant_id = %tenant_id, "Failed to persist anchor receipt: {}", e);
+                }
+                if let Err(e) = storage.delete(&pending_key(tenant_id, &receipt.root)).await {
+                    tracing::warn!(tenant_id = %tenant_id, "Failed to clear pending anchor root: {}", e);
+                }
+            }
+            // The caller may have dropped the handle; the stored receipt remains
+            let _ = pending.tx.send(receipt);
+        }
+    }
+
+    async fn anchor_with_retry(
+        &self,
+        backend: &dyn AnchorBackend,
+        root: &Hash,
+        metadata: AnchorMetadata,
+    ) -> Option<AnchorReceipt> {
+        let mut backoff = self.inner.policy.retry_backoff;
+        for attempt in 1..=self.inner.policy.max_attempts.max(1) {
+            match backend.anchor(root, metadata.clone()).await {
+                Ok(receipt) => return Some(receipt),
+                Err(e) => {
+                    tracing::warn!(
+                        backend = %backend.name(),
+                        attempt = attempt,
+                        "Anchoring failed: {}",
+                        e
+                    );
+                    if attempt < self.inner.policy.max_attempts {
+                        tokio::time::sleep(backoff).await;
+                        backoff *= 2;
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Look up the persisted receipt for a previously submitted root
+    pub async fn receipt(
+        &self,
+        tenant_id: &str,
+        root: &Hash,
+    ) -> Result<Option<BatchedAnchorReceipt>, AnchorServiceError> {
+        match &self.storage {
+            Some(storage) => Ok(storage.get(&receipt_key(tenant_id, root)).await?),
+            None => Ok(None),
+        }
+    }
+}
+
+fn receipt_key(tenant_id: &str, root: &Hash) -> String {
+    format!("anchor:tenant:{}:root:{}", tenant_id, root.to_hex())
+}
+
+const PENDING_PREFIX: &str = "anchor:pending:";
+
+fn pending_key(tenant_id: &str, root: &Hash) -> String {
+    format!("{}{}:{}", PENDING_PREFIX, tenant_id, root.to_hex())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use vex_anchor::AnchorError;
+    use vex_persist::backend::MemoryBackend;
+
+    /// Fails the first `failures` calls, then anchors
+    #[derive(Debug, Default)]
+    struct FlakyAnchor {
+        failures: u32,
+        calls: AtomicU32,
+    }
+
+    #[async_trait]
+    impl AnchorBackend for FlakyAnchor {
+        async fn anchor(
+            &self,
+            root: &Hash,
+            metadata: AnchorMetadata,
+        ) -> Result<AnchorReceipt, AnchorError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.failures {
+                return Err(AnchorError::Network("unreachable".to_string()));
+            }
+            Ok(AnchorReceipt {
+                backend: "flaky".to_string(),
+                root_hash: root.to_hex(),
+                anchor_id: format!("anchor-{}", call),
+                anchored_at: chrono::Utc::now(),
+                proof: None,
+                metadata,
+            })
+        }
+
+        async fn verify(&self, _receipt: &AnchorReceipt) -> Result<bool, AnchorError> {
+            Ok(true)
+        }
+
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn is_healthy(&self) -> bool {
+            true
+        }
+    }
+
+    fn policy() -> AnchorPolicy {
+        AnchorPolicy {
+            max_batch_size: 3,
+            max_batch_age: Duration::from_millis(20),
+            max_attempts: 3,
+            retry_backoff: Duration::from_millis(1),
+            max_requeue_delay: Duration::from_millis(200),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_anchor_service_batches_by_size() {
+        let backend = Arc::new(FlakyAnchor::default());
+        let storage: Arc<dyn StorageBackend> = Arc::new(MemoryBackend::new());
+        let service = AnchorService::new(vec![backend.clone()], policy())
+            .unwrap()
+            .with_storage(storage.clone());
+        let worker = service.start();
+
+        let roots: Vec<Hash> = (0..3u8).map(|i| Hash::digest(&[i])).collect();
+        let mut handles = Vec::new();
+        for root in &roots {
+            handles.push(service.submit("tenant-a", root.clone(), 1).await);
+        }
+
+        let mut batch_roots = Vec::new();
+        for handle in handles {
+            let receipt = handle.wait().await.unwrap();
+            assert!(receipt.verify());
+            batch_roots.push(receipt.batch_root);
+        }
+        assert!(batch_roots.windows(2).all(|w| w[0] == w[1]));
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 1);
+
+        let stored = service
+            .receipt("tenant-a", &roots[1])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.root, roots[1]);
+        assert_eq!(stored.receipts[0].metadata.event_count, 3);
+
+        service.shutdown().await;
+        worker.await.unwrap();
+    }
+
+    #[test]
+    fn test_anchor_service_requires_backend() {
+        assert!(matches!(
+            AnchorService::new(Vec::new(), policy()),
+            Err(AnchorServiceError::NoBackends)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_anchor_service_age_and_retry() {
+        // Two failures fit within three attempts
+        let backend = Arc::new(FlakyAnchor {
+            failures: 2,
+            ..Default::default()
+        });
+        let service = AnchorService::new(vec![backend.clone()], policy()).unwrap();
+        let worker = service.start();
+
+        let pending = service.submit("tenant-b", Hash::digest(b"lonely"), 1).await;
+        let receipt = pending.wait().await.unwrap();
+        assert!(receipt.verify());
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 3);
+
+        // Every backend down: the batch stays queued until one recovers
+        let down = Arc::new(FlakyAnchor {
+            failures: 6,
+            ..Default::default()
+        });
+        let service = AnchorService::new(vec![down.clone()], policy()).unwrap();
+        let pending = service.submit("tenant-c", Hash::digest(b"retry"), 1).await;
+        service.flush("tenant-c").await;
+        service.flush("tenant-c").await;
+        assert_eq!(service.pending("tenant-c").await, 1);
+
+        // Two rejections back off for twice the batch age, past the batch's own deadline
+        tokio::time::sleep(Duration::from_millis(25)).await;
+        service.flush_due().await;
+        assert_eq!(down.calls.load(Ordering::SeqCst), 6);
+        assert!(service.next_deadline().await.unwrap() > Instant::now());
+
+        service.flush("tenant-c").await;
+        assert_eq!(service.pending("tenant-c").await, 0);
+        assert!(pending.wait().await.unwrap().verify());
+
+        worker.abort();
+    }
+
+    #[tokio::test]
+    async fn test_anchor_service_recovers_pending_roots() {
+        let storage: Arc<dyn StorageBackend> = Arc::new(MemoryBackend::new());
+        let root = Hash::digest(b"survivor");
+
+        // A service that exits before its batch is anchored
+        let first = AnchorService::new(vec![Arc::new(FlakyAnchor::default())], policy())
+            .unwrap()
+            .with_storage(storage.clone());
+        let _handle = first.submit("tenant-d", root.clone(), 2).await;
+        drop(first);
+
+        let restarted = AnchorService::new(vec![Arc::new(FlakyAnchor::default())], policy())
+            .unwrap()
+            .with_storage(storage.clone());
+        assert_eq!(restarted.recover().await.unwrap(), 1);
+        assert_eq!(restarted.recover().await.unwrap(), 0);
+        restarted.flush_all().await;
+
+        let receipt = restarted.receipt("tenant-d", &root).await.unwrap().unwrap();
+        assert!(receipt.verify());
+        assert!(storage.list_keys(PENDING_PREFIX).await.unwrap().is_empty());
+    }
+}
@@ -16,9 +16,9 @@ pub enum VepError {
 }
 
 /// magic(3) | version(1) | aid(32) | capsule_root(32) | nonce(8)
-pub const VEP_HEADER_SIZE: usize = 3 + 1 + 32 + 32 + 8;
-pub const VEP_MAGIC: [u8; 3] = *b"VEP";
-pub const VEP_VERSION: u8 = 0x03;
+pub const VEP_HEADER_SIZE: usize = vex_core::vep::VEP_HEADER_SIZE;
+pub const VEP_MAGIC: [u8; 3] = vex_core::vep::VEP_MAGIC;
+pub const VEP_VERSION: u8 = vex_core::vep::VEP_VERSION_V3;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IntentSegment {
@@ -198,63 +198,52 @@ impl EvidenceCapsuleV0 {
     }
 
     pub fn to_vep_binary(&self) -> Result<Vec<u8>, VepError> {
-        let mut buffer = Vec::with_capacity(1024);
-
-        // 1. Header: magic(3) | version(1) | aid(32) | capsule_root(32) | nonce(8)
-        buffer.extend_from_slice(&VEP_MAGIC);
-        buffer.push(VEP_VERSION);
-
-        let aid_bytes = hex::decode(&self.identity.aid)
-            .map_err(|e| VepError::BinaryFormat(format!("Invalid AID hex: {}", e)))?;
-        buffer.extend_from_slice(&aid_bytes);
-
-        let root_bytes = hex::decode(&self.capsule_root)
-            .map_err(|e| VepError::BinaryFormat(format!("Invalid root hex: {}", e)))?;
-        buffer.extend_from_slice(&root_bytes);
-
-        buffer.extend_from_slice(&self.authority.nonce.to_be_bytes());
-
-        // 2. Helper to append TLV segment
-        fn append_segment(buffer: &mut Vec<u8>, segment_type: u8, data: &[u8]) {
-            buffer.push(segment_type);
-            buffer.extend_from_slice(&(data.len() as u32).to_be_bytes());
-            buffer.extend_from_slice(data);
-        }
-
-        // 3. Main Pillars (JSON serialized)
-        let intent_bytes =
-            serde_jcs::to_vec(&self.intent).map_err(|e| VepError::Jcs(e.to_string()))?;
-        append_segment(&mut buffer, 1, &intent_bytes); // Intent
-
-        let auth_bytes =
-            serde_jcs::to_vec(&self.authority).map_err(|e| VepError::Jcs(e.to_string()))?;
-        append_segment(&mut buffer, 2, &auth_bytes); // Authority
-
-        let ident_bytes =
-            serde_jcs::to_vec(&self.identity).map_err(|e| VepError::Jcs(e.to_string()))?;
-        append_segment(&mut buffer, 3, &ident_bytes); // Identity
-
-        let witness_bytes =
-            serde_jcs::to_vec(&self.witness).map_err(|e| VepError::Jcs(e.to_string()))?;
-        append_segment(&mut buffer, 5, &witness_bytes); // Witness
-
-        // 4. Dedicated Magpie AST Segment (Raw Binary)
+        use vex_core::vep::{VepBuilder, VepSegmentType};
+
+        // Header: magic(3) | version(1) | aid(32) | capsule_root(32) | nonce(8)
+        let aid: [u8; 32] = hex::decode(&self.identity.aid)
+            .map_err(|e| VepError::BinaryFormat(format!("Invalid AID hex: {}", e)))?
+            .try_into()
+            .map_err(|_| VepError::BinaryFormat("AID must be 32 bytes".to_string()))?;
+
+        let capsule_root: [u8; 32] = hex::decode(&self.capsule_root)
+            .map_err(|e| VepError::BinaryFormat(format!("Invalid root hex: {}", e)))?
+            .try_into()
+            .map_err(|_| VepError::BinaryFormat("Capsule root must be 32 bytes".to_string()))?;
+
+        // Main Pillars (JCS serialized)
+        let mut builder = VepBuilder::new(VEP_VERSION)
+            .aid(aid)
+            .capsule_root(capsule_root)
+            .nonce(self.authority.nonce)
+            .segment(VepSegmentType::Intent, jcs_segment(&self.intent)?)
+            .segment(VepSegmentType::Authority, jcs_segment(&self.authority)?)
+            .segment(VepSegmentType::Identity, jcs_segment(&self.identity)?)
+            .segment(VepSegmentType::Witness, jcs_segment(&self.witness)?);
+
+        // Dedicated Magpie AST Segment (Raw Binary)
         if let Some(ast) = &self.intent.magpie_source {
-            append_segment(&mut buffer, 7, ast.as_bytes());
+            builder = builder.segment(VepSegmentType::MagpieAst, ast.as_bytes().to_vec());
         }
 
-        // 5. Signature (Raw Binary)
+        // Signature (Raw Binary)
         use base64::Engine as _;
         let sig_bytes = base64::engine::general_purpose::STANDARD
             .decode(&self.crypto.signature_b64)
             .map_err(|e| VepError::Crypto(format!("Base64 decode failed: {}", e)))?;
-        append_segment(&mut buffer, 6, &sig_bytes);
 
-        Ok(buffer)
+        builder
+            .segment(VepSegmentType::Signature, sig_bytes)
+            .build()
+            .map_err(|e| VepError::BinaryFormat(e.to_string()))
     }
 }
 
+fn jcs_segment<T: Serialize>(segment: &T) -> Result<Vec<u8>, VepError> {
+    serde_jcs::to_vec(segment).map_err(|e| VepError::Jcs(e.to_string()))
+}
+
 fn hash_segment<T: Serialize>(segment: &T) -> Result<String, VepError> {
-    let jcs_bytes = serde_jcs::to_vec(segment).map_err(|e| VepError::Jcs(e.to_string()))?;
+    let jcs_bytes = jcs_segment(segment)?;
     Ok(vex_core::merkle::Hash::digest(&jcs_bytes).to_hex())
 }
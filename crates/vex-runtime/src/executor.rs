@@ -4,28 +4,68 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::gate::Gate;
+use crate::run_context::{RunContext, RunError, StopReason};
+use crate::suspension::{self, SuspendedExecution, SuspensionStore, EXECUTION_SUSPENDED};
+use crate::tool_loop::{self, ModelAction, ToolCallOutcome, ToolCallRecord};
+use crate::transcript::{
+    ReplayReport, Transcript, TranscriptInput, TranscriptOutcome, TranscriptRecorder,
+};
 use serde::Deserialize;
+use serde_json::json;
 use vex_adversarial::{
-    Consensus, ConsensusProtocol, Debate, DebateRound, ShadowAgent, ShadowConfig, Vote,
+    Consensus, ConsensusProtocol, Debate, DebateRound, ModelVote, ShadowAgent, ShadowConfig, Vote,
 };
 use vex_core::{Agent, ContextPacket, Hash};
 use vex_hardware::api::AgentIdentity;
-use vex_llm::Capability;
-use vex_persist::{AuditStore, StorageBackend};
+use vex_llm::{Capability, ToolExecutor};
+use vex_persist::{ArtifactStore, AuditStore, StorageBackend};
 
 #[derive(Debug, Deserialize)]
 struct ChallengeResponse {
     is_challenge: bool,
     confidence: f64,
     reasoning: String,
-    suggested_revision: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct VoteResponse {
     agrees: bool,
-    reflection: String,
     confidence: f64,
+    #[serde(alias = "reflection")]
+    reasoning: String,
+}
+
+/// Schema for the Red agent's challenge
+fn challenge_format() -> ResponseFormat {
+    ResponseFormat::new(
+        "challenge",
+        json!({
+            "type": "object",
+            "properties": {
+                "is_challenge": { "type": "boolean" },
+                "confidence": { "type": "number", "minimum": 0, "maximum": 1 },
+                "reasoning": { "type": "string" },
+                "suggested_revision": { "type": ["string", "null"] }
+            },
+            "required": ["is_challenge", "confidence", "reasoning"]
+        }),
+    )
+}
+
+/// Schema for the Blue agent's post-debate vote
+fn vote_format() -> ResponseFormat {
+    ResponseFormat::new(
+        "vote",
+        json!({
+            "type": "object",
+            "properties": {
+                "agrees": { "type": "boolean" },
+                "confidence": { "type": "number", "minimum": 0, "maximum": 1 },
+                "reasoning": { "type": "string" }
+            },
+            "required": ["agrees", "confidence", "reasoning"]
+        }),
+    )
 }
 
 /// Configuration for agent execution
@@ -37,6 +77,14 @@ pub struct ExecutorConfig {
     pub consensus_protocol: ConsensusProtocol,
     /// Whether to spawn shadow agents
     pub enable_adversarial: bool,
+    /// Maximum model turns in the tool-calling loop
+    pub max_tool_steps: usize,
+    /// Confidence reported to the gate for individual tool calls, which are
+    /// checked before any debate has produced an estimate
+    pub tool_call_confidence: f64,
+    /// Sampling seed sent with LLM requests when recording transcripts
+    /// (random per execution if unset)
+    pub seed: Option<u64>,
 }
 
 impl Default for ExecutorConfig {
@@ -45,6 +93,31 @@ impl Default for ExecutorConfig {
             max_debate_rounds: 3,
             consensus_protocol: ConsensusProtocol::Majority,
             enable_adversarial: true,
+            max_tool_steps: 8,
+            tool_call_confidence: 0.5,
+            seed: None,
+        }
+    }
+}
+
+/// A provider on the adversarial verification panel
+#[derive(Debug, Clone)]
+pub struct PanelMember {
+    /// Voter ID used in consensus
+    pub id: Uuid,
+    /// Provider that challenges the claim
+    pub provider: Arc<dyn LlmProvider>,
+    /// Weight of this provider's votes
+    pub weight: f64,
+}
+
+impl PanelMember {
+    /// Create a panel member with the given vote weight
+    pub fn new(provider: Arc<dyn LlmProvider>, weight: f64) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            provider,
+            weight,
         }
     }
 }
@@ -68,9 +141,15 @@ pub struct ExecutionResult {
     pub debate: Option<Debate>,
     /// CHORA Evidence Capsule
     pub evidence: Option<vex_core::audit::EvidenceCapsule>,
+    /// Tool calls made before the final answer
+    pub tool_calls: Vec<ToolCallRecord>,
+    /// Why execution stopped early (the debate was cut short)
+    pub stopped: Option<StopReason>,
+    /// Content hash of the stored execution transcript
+    pub transcript_hash: Option<Hash>,
 }
 
-use vex_llm::{LlmProvider, LlmRequest};
+use vex_llm::{LlmProvider, LlmRequest, ResponseFormat, StructuredError, StructuredOutput};
 
 /// Agent executor - runs agents with LLM backends
 pub struct AgentExecutor<L: LlmProvider + ?Sized> {
@@ -86,6 +165,14 @@ pub struct AgentExecutor<L: LlmProvider + ?Sized> {
     pub identity: Option<Arc<AgentIdentity>>,
     /// ZK Verifier (Phase 4)
     pub verifier: Option<Arc<dyn vex_core::zk::ZkVerifier>>,
+    /// Tools advertised to the model
+    pub tools: Option<Arc<ToolExecutor>>,
+    /// Store for execution transcripts (recording is off when unset)
+    pub transcripts: Option<Arc<ArtifactStore<dyn StorageBackend>>>,
+    /// Providers that challenge claims instead of a single Red agent
+    pub panel: Vec<PanelMember>,
+    /// Store for executions suspended on escalation (escalations fail when unset)
+    pub suspensions: Option<Arc<SuspensionStore>>,
 }
 
 impl<L: LlmProvider + ?Sized> std::fmt::Debug for AgentExecutor<L> {
@@ -106,6 +193,10 @@ impl<L: LlmProvider + ?Sized> Clone for AgentExecutor<L> {
             audit_store: self.audit_store.clone(),
             identity: self.identity.clone(),
             verifier: self.verifier.clone(),
+            tools: self.tools.clone(),
+            transcripts: self.transcripts.clone(),
+            panel: self.panel.clone(),
+            suspensions: self.suspensions.clone(),
         }
     }
 }
@@ -120,6 +211,10 @@ impl<L: LlmProvider + ?Sized> AgentExecutor<L> {
             audit_store: None,
             identity: None,
             verifier: None,
+            tools: None,
+            transcripts: None,
+            panel: Vec::new(),
+            suspensions: None,
         }
     }
 
@@ -140,6 +235,43 @@ impl<L: LlmProvider + ?Sized> AgentExecutor<L> {
         self
     }
 
+    /// Policy gate consulted by this executor
+    pub fn gate(&self) -> &Arc<dyn Gate> {
+        &self.gate
+    }
+
+    /// Let the model call tools from `tools` before answering
+    pub fn with_tools(mut self, tools: Arc<ToolExecutor>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    /// Record a replayable transcript of every execution into `store`
+    pub fn with_transcripts(mut self, store: Arc<ArtifactStore<dyn StorageBackend>>) -> Self {
+        self.transcripts = Some(store);
+        self
+    }
+
+    /// Verify claims with a panel of providers (e.g. local Ollama plus a hosted model)
+    ///
+    /// Each member challenges the claim every debate round and its vote counts with
+    /// its weight in consensus. Without a panel the executor's own provider plays Red.
+    pub fn with_panel(mut self, panel: Vec<PanelMember>) -> Self {
+        self.panel = panel;
+        self
+    }
+
+    /// Suspend escalated executions into `store` until a continuation token arrives
+    ///
+    /// An execution is suspended when the gate returns `ESCALATE`, or when it
+    /// requests capabilities without a continuation token. The error then starts
+    /// with [`EXECUTION_SUSPENDED`] and names the escalation; see
+    /// [`AgentExecutor::resume`].
+    pub fn with_suspensions(mut self, store: Arc<SuspensionStore>) -> Self {
+        self.suspensions = Some(store);
+        self
+    }
+
     /// Execute an agent with a prompt and return the result
     pub async fn execute(
         &self,
@@ -148,6 +280,239 @@ impl<L: LlmProvider + ?Sized> AgentExecutor<L> {
         prompt: &str,
         intent_data: Option<vex_core::segment::IntentData>,
         capabilities: Vec<Capability>,
+    ) -> Result<ExecutionResult, String> {
+        self.execute_with_context(
+            &RunContext::new(),
+            tenant_id,
+            agent,
+            prompt,
+            intent_data,
+            capabilities,
+        )
+        .await
+    }
+
+    /// Execute an agent under a run context (cancellation, deadline, budget)
+    ///
+    /// If the run stops before the first answer, this returns an error. If it stops
+    /// during adversarial verification, the unverified answer is still gated and
+    /// returned with `stopped` set.
+    ///
+    /// With transcripts enabled, the transcript is stored even when the gate
+    /// halts; its hash is then appended to the error.
+    pub async fn execute_with_context(
+        &self,
+        ctx: &RunContext,
+        tenant_id: &str,
+        agent: &mut Agent,
+        prompt: &str,
+        intent_data: Option<vex_core::segment::IntentData>,
+        capabilities: Vec<Capability>,
+    ) -> Result<ExecutionResult, String> {
+        let Some(store) = &self.transcripts else {
+            let result = self
+                .run_execution(ctx, tenant_id, agent, prompt, intent_data, capabilities)
+                .await?;
+            self.log_execution(tenant_id, agent, prompt, &result).await;
+            return Ok(result);
+        };
+
+        let seed = self
+            .config
+            .seed
+            .unwrap_or_else(|| Uuid::new_v4().as_u64_pair().0);
+        let recorder = Arc::new(TranscriptRecorder::record(seed));
+        let input = TranscriptInput {
+            agent: agent.clone(),
+            prompt: prompt.to_string(),
+            intent_data: intent_data.clone(),
+            capabilities: capabilities.clone(),
+        };
+        let outcome = self
+            .run_execution(
+                &ctx.clone().with_transcript(recorder.clone()),
+                tenant_id,
+                agent,
+                prompt,
+                intent_data,
+                capabilities,
+            )
+            .await;
+
+        let transcript_hash = match store.put(tenant_id, &recorder.transcript(input)).await {
+            Ok(hash) => Some(hash),
+            Err(e) => {
+                tracing::warn!(agent_id = %agent.id, "Failed to store transcript: {}", e);
+                None
+            }
+        };
+        let mut result = match (outcome, &transcript_hash) {
+            (Ok(result), _) => result,
+            (Err(e), Some(hash)) => return Err(format!("{} (transcript {})", e, hash.to_hex())),
+            (Err(e), None) => return Err(e),
+        };
+        result.transcript_hash = transcript_hash;
+        self.log_execution(tenant_id, agent, prompt, &result).await;
+        Ok(result)
+    }
+
+    /// Re-run a recorded execution through this executor's gate and tool checks,
+    /// feeding the recorded LLM responses and tool outputs.
+    ///
+    /// Nothing is audited or stored. The report lists every request, gate decision,
+    /// tool call or outcome that differs from the recording. Fails if the transcript
+    /// cannot be hashed.
+    pub async fn replay(&self, transcript: &Transcript) -> Result<ReplayReport, String> {
+        let transcript_hash = transcript
+            .content_hash()
+            .map_err(|e| format!("Failed to hash transcript: {}", e))?;
+        let mut executor = self.clone();
+        executor.audit_store = None;
+        executor.transcripts = None;
+        executor.suspensions = None;
+
+        let recorder = Arc::new(TranscriptRecorder::replay(transcript));
+        let ctx = RunContext::new().with_transcript(recorder.clone());
+        let input = &transcript.input;
+        let mut agent = input.agent.clone();
+        let error = executor
+            .run_execution(
+                &ctx,
+                "replay",
+                &mut agent,
+                &input.prompt,
+                input.intent_data.clone(),
+                input.capabilities.clone(),
+            )
+            .await
+            .err();
+
+        Ok(ReplayReport {
+            transcript_hash,
+            divergences: recorder.finish_replay(transcript),
+            outcome: recorder.outcome(),
+            error,
+        })
+    }
+
+    /// Load a stored transcript by hash and replay it
+    pub async fn replay_stored(
+        &self,
+        tenant_id: &str,
+        transcript_hash: &Hash,
+    ) -> Result<ReplayReport, String> {
+        let store = self
+            .transcripts
+            .as_ref()
+            .ok_or("No transcript store configured")?;
+        let transcript: Transcript = store
+            .get(tenant_id, transcript_hash)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Transcript {} not found", transcript_hash.to_hex()))?;
+        self.replay(&transcript).await
+    }
+
+    /// Finish a suspended execution with the continuation token issued for its
+    /// escalation
+    ///
+    /// The token must resolve the suspended escalation, be bound to its capsule
+    /// root, be within its lifetime and pass the gate's token verification. The
+    /// gated answer is then committed as if the gate had allowed it: the agent's
+    /// context is updated, the execution is audited and the suspension removed.
+    /// On success `suspended.agent` holds the updated agent.
+    pub async fn resume(
+        &self,
+        suspended: &mut SuspendedExecution,
+        token: vex_core::ContinuationToken,
+    ) -> Result<ExecutionResult, String> {
+        suspended.check_token(&token)?;
+        let mut capsule = suspended.evidence.clone();
+        capsule.continuation_token = Some(token);
+        self.verify_governed_action(suspended.agent.id, &capsule, &suspended.response)
+            .await?;
+
+        let agent = &mut suspended.agent;
+        let mut context = ContextPacket::new(&suspended.response);
+        context.source_agent = Some(agent.id);
+        context.importance = suspended.confidence;
+        agent.context = context.clone();
+        agent.fitness = suspended.confidence;
+
+        let result = ExecutionResult {
+            agent_id: agent.id,
+            response: suspended.response.clone(),
+            verified: suspended.verified,
+            confidence: suspended.confidence,
+            trace_root: context.trace_root.clone(),
+            context,
+            debate: suspended.debate.clone(),
+            evidence: Some(capsule),
+            tool_calls: suspended.tool_calls.clone(),
+            stopped: None,
+            transcript_hash: None,
+        };
+        self.log_execution(&suspended.tenant_id, agent, &suspended.prompt, &result)
+            .await;
+
+        if let Some(store) = &self.suspensions {
+            if let Err(e) = store
+                .remove(&suspended.tenant_id, &suspended.escalation_id)
+                .await
+            {
+                tracing::warn!(
+                    escalation_id = %suspended.escalation_id,
+                    "Failed to remove resumed execution: {}",
+                    e
+                );
+            }
+        }
+        Ok(result)
+    }
+
+    /// Load a suspended execution and resume it (see [`AgentExecutor::resume`])
+    ///
+    /// The execution is claimed first, so of two concurrent calls only one resumes
+    /// it; a failed resume puts it back. Returns the updated agent along with the result.
+    pub async fn resume_stored(
+        &self,
+        tenant_id: &str,
+        escalation_id: &str,
+        token: vex_core::ContinuationToken,
+    ) -> Result<(Agent, ExecutionResult), String> {
+        let store = self
+            .suspensions
+            .as_ref()
+            .ok_or("No suspension store configured")?;
+        // Claim before resuming so concurrent resumes cannot both commit and audit
+        let mut suspended = store
+            .claim(tenant_id, escalation_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("No execution suspended on escalation {}", escalation_id))?;
+        match self.resume(&mut suspended, token).await {
+            Ok(result) => Ok((suspended.agent, result)),
+            Err(e) => {
+                if let Err(restore_err) = store.restore(&suspended).await {
+                    tracing::warn!(
+                        escalation_id = %escalation_id,
+                        "Failed to restore suspended execution: {}",
+                        restore_err
+                    );
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn run_execution(
+        &self,
+        ctx: &RunContext,
+        tenant_id: &str,
+        agent: &mut Agent,
+        prompt: &str,
+        intent_data: Option<vex_core::segment::IntentData>,
+        capabilities: Vec<Capability>,
     ) -> Result<ExecutionResult, String> {
         // Step 1: Format context and get initial response from Blue agent
         let full_prompt = if !agent.context.content.is_empty() {
@@ -159,21 +524,41 @@ impl<L: LlmProvider + ?Sized> AgentExecutor<L> {
             prompt.to_string()
         };
 
-        let blue_response = self
-            .llm
-            .complete(LlmRequest::with_role(&agent.config.role, &full_prompt))
-            .await
-            .map_err(|e| e.to_string())?
-            .content;
-
-        // Step 2: If adversarial is enabled, run debate
-        let (final_response, verified, confidence, debate) = if self.config.enable_adversarial {
-            self.run_adversarial_verification(agent, prompt, &blue_response)
+        let (blue_response, tool_calls) = match &self.tools {
+            Some(tools) if !tools.registry().is_empty() => {
+                self.run_tool_loop(
+                    ctx,
+                    tenant_id,
+                    agent,
+                    prompt,
+                    &full_prompt,
+                    tools,
+                    &capabilities,
+                )
                 .await?
-        } else {
-            (blue_response, false, 0.5, None)
+            }
+            _ => {
+                let response = ctx
+                    .complete_streamed(
+                        self.llm.as_ref(),
+                        LlmRequest::with_role(&agent.config.role, &full_prompt),
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .content;
+                (response, Vec::new())
+            }
         };
 
+        // Step 2: If adversarial is enabled, run debate
+        let (final_response, verified, confidence, debate, stopped) =
+            if self.config.enable_adversarial {
+                self.run_adversarial_verification(ctx, agent, prompt, &blue_response)
+                    .await?
+            } else {
+                (blue_response, false, 0.5, None, None)
+            };
+
         // Step 2.5: Policy Gate Verification (Mutation Risk Control)
         let capsule = self
             .gate
@@ -181,61 +566,55 @@ impl<L: LlmProvider + ?Sized> AgentExecutor<L> {
                 agent.id,
                 prompt,
                 &final_response,
-                intent_data,
+                intent_data.clone(),
                 confidence,
                 &capabilities,
             )
             .await;
 
+        if let Some(recorder) = ctx.transcript() {
+            recorder.record_gate(&final_response, confidence, &capsule);
+            recorder.set_outcome(TranscriptOutcome {
+                response: final_response.clone(),
+                verified,
+                confidence,
+                gate_outcome: capsule.outcome.clone(),
+            });
+        }
+
         if capsule.outcome == "HALT" {
             return Err(format!("Gate Blocking: {}", capsule.reason_code));
         }
 
+        // Step 2.55: Human-in-the-loop - park escalated work until it is approved
+        if let Some(store) = &self.suspensions {
+            let needs_token = !capabilities.is_empty() && capsule.continuation_token.is_none();
+            if capsule.outcome == "ESCALATE" || needs_token {
+                let suspended = SuspendedExecution {
+                    escalation_id: suspension::escalation_id(&capsule),
+                    tenant_id: tenant_id.to_string(),
+                    capsule_root: suspension::capsule_root(&capsule),
+                    agent: agent.clone(),
+                    prompt: prompt.to_string(),
+                    intent_data,
+                    capabilities,
+                    response: final_response,
+                    verified,
+                    confidence,
+                    debate,
+                    tool_calls,
+                    evidence: capsule,
+                    suspended_at: chrono::Utc::now(),
+                };
+                return Err(self.suspend(store, &suspended).await);
+            }
+        }
+
         // Step 2.6: Governed Execution Verification (The AEM Trap)
         // If capabilities are requested, we MUST have a valid, context-bound Continuation Token.
         if !capabilities.is_empty() {
-            if let Some(token) = &capsule.continuation_token {
-                let aid = self.identity.as_ref().map(|id| id.agent_id.clone());
-
-                // Binding Surface: Intent Hash (Merkle-hardened representation of the current command)
-                use sha2::{Digest, Sha256};
-                let intent_hash = hex::encode(Sha256::digest(final_response.as_bytes()));
-
-                // Perform Stateless Edge Verification
-                self.gate
-                    .verify_token(
-                        token,
-                        aid.as_deref(),
-                        Some(&intent_hash),
-                        token.payload.circuit_id.as_deref(),
-                    )
-                    .await
-                    .map_err(|e| format!("AEM_GOVERNANCE_VIOLATION: {}", e))?;
-
-                // Phase 4: ZK Re-verification (High Assurance)
-                // If we have a Shadow Intent and a local verifier, re-check the STARK proof.
-                if let (Some(verifier), Some(intent)) = (&self.verifier, &capsule.intent_data) {
-                    if let vex_core::segment::IntentData::Shadow { .. } = intent {
-                        intent
-                            .verify_shadow(verifier.as_ref())
-                            .map_err(|e| format!("AEM_GOVERNANCE_VIOLATION (ZK_FAIL): {}", e))?;
-
-                        tracing::info!(
-                            agent_id = %agent.id,
-                            "AEM: STARK proof re-verified locally for Shadow Intent."
-                        );
-                    }
-                }
-
-                tracing::info!(
-                    agent_id = %agent.id,
-                    intent_hash = %intent_hash,
-                    "AEM: Governed execution permitted via validated token."
-                );
-            } else {
-                // Fail-Closed: No token = No privileged action.
-                return Err("AEM_GOVERNANCE_VIOLATION: Privileged action requires a valid continuation token (Escalation Required).".to_string());
-            }
+            self.verify_governed_action(agent.id, &capsule, &final_response)
+                .await?;
         }
 
         // Step 3: Create context packet with hash
@@ -247,7 +626,7 @@ impl<L: LlmProvider + ?Sized> AgentExecutor<L> {
         agent.context = context.clone();
         agent.fitness = confidence;
 
-        let result = ExecutionResult {
+        Ok(ExecutionResult {
             agent_id: agent.id,
             response: final_response,
             verified,
@@ -255,39 +634,418 @@ impl<L: LlmProvider + ?Sized> AgentExecutor<L> {
             trace_root: context.trace_root.clone(),
             context: context.clone(),
             debate,
-            evidence: Some(capsule.clone()),
+            evidence: Some(capsule),
+            tool_calls,
+            stopped,
+            transcript_hash: None,
+        })
+    }
+
+    /// Persist a suspended execution and return the error that reports it
+    async fn suspend(&self, store: &SuspensionStore, suspended: &SuspendedExecution) -> String {
+        if let Err(e) = store.suspend(suspended).await {
+            return format!(
+                "Gate Escalation: {} (failed to suspend execution: {})",
+                suspended.evidence.reason_code, e
+            );
+        }
+        if let Some(audit) = &self.audit_store {
+            let capsule = &suspended.evidence;
+            let _ = audit
+                .log(
+                    &suspended.tenant_id,
+                    vex_core::audit::AuditEventType::Escalation,
+                    vex_core::audit::ActorType::Bot(suspended.agent.id),
+                    Some(suspended.agent.id),
+                    json!({
+                        "prompt": suspended.prompt,
+                        "escalation_id": suspended.escalation_id,
+                        "capsule_root": suspended.capsule_root,
+                        "reason_code": capsule.reason_code,
+                        "status": "SUSPENDED",
+                    }),
+                    self.identity.as_ref().map(|id| id.as_ref()),
+                    Some(capsule.witness_receipt.clone()),
+                    capsule.vep_blob.clone(),
+                )
+                .await;
+        }
+        tracing::info!(
+            agent_id = %suspended.agent.id,
+            escalation_id = %suspended.escalation_id,
+            "Execution suspended pending human approval"
+        );
+        format!(
+            "{}: escalation {} awaiting continuation token",
+            EXECUTION_SUSPENDED, suspended.escalation_id
+        )
+    }
+
+    /// Step 5: Automatic Hardware-Signed Audit Log (Phase 3)
+    async fn log_execution(
+        &self,
+        tenant_id: &str,
+        agent: &Agent,
+        prompt: &str,
+        result: &ExecutionResult,
+    ) {
+        let (Some(store), Some(capsule)) = (&self.audit_store, &result.evidence) else {
+            return;
         };
+        let mut data = serde_json::json!({
+            "prompt": prompt,
+            "confidence": result.confidence,
+            "verified": result.verified,
+        });
+        if let Some(reason) = &result.stopped {
+            data["stopped"] = serde_json::json!(reason);
+        }
+        if let Some(debate) = result.debate.as_ref().filter(|d| !d.model_votes.is_empty()) {
+            data["model_votes"] = serde_json::json!(debate.model_votes);
+            data["agreeing_models"] = serde_json::json!(debate.agreeing_models());
+        }
+        if let Some(token) = result
+            .evidence
+            .as_ref()
+            .and_then(|c| c.continuation_token.as_ref())
+        {
+            data["continues_escalation_id"] = serde_json::json!(token.payload.ledger_event_id);
+        }
+        if let Some(hash) = &result.transcript_hash {
+            // Prefixed so the audit sanitizer does not redact it as a secret
+            data["transcript"] = serde_json::json!(format!("sha256:{}", hash.to_hex()));
+        }
+        let _ = store
+            .log(
+                tenant_id,
+                vex_core::audit::AuditEventType::AgentExecuted,
+                vex_core::audit::ActorType::Bot(agent.id),
+                Some(agent.id),
+                data,
+                self.identity.as_ref().map(|id| id.as_ref()),
+                Some(capsule.witness_receipt.clone()),
+                capsule.vep_blob.clone(),
+            )
+            .await;
+    }
+
+    /// ReAct-style loop: let the model call tools until it gives a final answer
+    /// or `max_tool_steps` turns are used up.
+    ///
+    /// After the budget, the model gets one more turn to answer; a further tool call
+    /// fails the run instead of surfacing the raw call as the answer.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_tool_loop(
+        &self,
+        ctx: &RunContext,
+        tenant_id: &str,
+        agent: &Agent,
+        prompt: &str,
+        full_prompt: &str,
+        tools: &ToolExecutor,
+        capabilities: &[Capability],
+    ) -> Result<(String, Vec<ToolCallRecord>), String> {
+        let system = format!(
+            "{}\n\n{}",
+            agent.config.role,
+            tool_loop::tool_instructions(tools.registry())
+        );
+        let mut transcript = String::new();
+        let mut records = Vec::new();
+
+        for step in 0..self.config.max_tool_steps {
+            let output = ctx
+                .complete(
+                    self.llm.as_ref(),
+                    LlmRequest::with_role(
+                        &system,
+                        &tool_loop::turn_prompt(full_prompt, &transcript),
+                    ),
+                )
+                .await
+                .map_err(|e| e.to_string())?
+                .content;
+
+            let (tool, arguments) = match tool_loop::parse_action(&output) {
+                ModelAction::Final(answer) => return Ok((answer, records)),
+                ModelAction::Call { tool, arguments } => (tool, arguments),
+            };
+
+            let record = self
+                .run_tool_call(
+                    ctx.transcript(),
+                    tenant_id,
+                    agent,
+                    prompt,
+                    tools,
+                    capabilities,
+                    ToolCallRecord::new(step, tool, arguments),
+                )
+                .await;
+            transcript.push_str(&record.observation());
+            records.push(record);
+        }
+
+        // Step budget exhausted: ask for an answer from what has been gathered
+        tracing::warn!(
+            agent_id = %agent.id,
+            steps = self.config.max_tool_steps,
+            "Tool step budget exhausted, requesting final answer"
+        );
+        let output = ctx
+            .complete(
+                self.llm.as_ref(),
+                LlmRequest::with_role(
+                    &agent.config.role,
+                    &format!(
+                        "{}\n\nThe tool budget is exhausted. Give your final answer now without calling tools.",
+                        tool_loop::turn_prompt(full_prompt, &transcript)
+                    ),
+                ),
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .content;
+        match tool_loop::parse_action(&output) {
+            ModelAction::Final(answer) => Ok((answer, records)),
+            ModelAction::Call { tool, .. } => Err(format!(
+                "Tool step budget of {} exhausted; model still requested tool '{}'",
+                self.config.max_tool_steps, tool
+            )),
+        }
+    }
+
+    /// Run one tool call through the capability check, the gate and the tool executor,
+    /// then record it as an audit event.
+    ///
+    /// When replaying, the recorded tool output is used instead of running the tool.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn run_tool_call(
+        &self,
+        recorder: Option<&TranscriptRecorder>,
+        tenant_id: &str,
+        agent: &Agent,
+        prompt: &str,
+        tools: &ToolExecutor,
+        capabilities: &[Capability],
+        mut record: ToolCallRecord,
+    ) -> ToolCallRecord {
+        record = match tools.registry().get(&record.tool) {
+            None => {
+                let error = format!("Unknown tool '{}'", record.tool);
+                record.fail(error)
+            }
+            Some(tool) => {
+                let required = tool.capabilities();
+                let missing: Vec<&Capability> = required
+                    .iter()
+                    .filter(|c| **c != Capability::PureComputation && !capabilities.contains(c))
+                    .collect();
+
+                if !missing.is_empty() {
+                    record.deny(format!("Capability not granted: {:?}", missing))
+                } else {
+                    // Gate decision for this specific call
+                    let action = serde_json::json!({
+                        "tool": record.tool,
+                        "arguments": record.arguments,
+                    })
+                    .to_string();
+                    let capsule = self
+                        .gate
+                        .execute_gate(
+                            agent.id,
+                            prompt,
+                            &action,
+                            None,
+                            self.config.tool_call_confidence,
+                            &required,
+                        )
+                        .await;
+                    record.gate_outcome = Some(capsule.outcome.clone());
+                    if let Some(recorder) = recorder {
+                        recorder.record_gate(&action, self.config.tool_call_confidence, &capsule);
+                    }
+
+                    let privileged = required.iter().any(|c| *c != Capability::PureComputation);
+                    if capsule.outcome == "HALT" {
+                        record.deny(format!("Gate Blocking: {}", capsule.reason_code))
+                    } else if let Err(e) = match privileged {
+                        true => {
+                            self.verify_governed_action(agent.id, &capsule, &action)
+                                .await
+                        }
+                        false => Ok(()),
+                    } {
+                        record.deny(e)
+                    } else if let Some(recorder) = recorder.filter(|r| r.is_replay()) {
+                        match recorder.replay_tool() {
+                            Some(recorded) if recorded.outcome == ToolCallOutcome::Executed => {
+                                record.outcome = ToolCallOutcome::Executed;
+                                record.result_hash = recorded.result_hash;
+                                record.output = recorded.output;
+                                record
+                            }
+                            Some(recorded) => record.fail(recorded.error.unwrap_or_default()),
+                            None => record.fail("No recorded tool output".to_string()),
+                        }
+                    } else {
+                        match tools.execute(&record.tool, record.arguments.clone()).await {
+                            Ok(result) => {
+                                record.outcome = ToolCallOutcome::Executed;
+                                record.result_hash = Some(result.hash);
+                                record.output = Some(result.output);
+                                record
+                            }
+                            Err(e) => record.fail(e.to_string()),
+                        }
+                    }
+                }
+            }
+        };
+
+        if let Some(recorder) = recorder {
+            recorder.record_tool(&record);
+        }
 
-        // Step 5: Automatic Hardware-Signed Audit Log (Phase 3)
         if let Some(store) = &self.audit_store {
-            let _ = store
+            match store
                 .log(
                     tenant_id,
-                    vex_core::audit::AuditEventType::AgentExecuted,
+                    vex_core::audit::AuditEventType::ToolCall,
                     vex_core::audit::ActorType::Bot(agent.id),
                     Some(agent.id),
-                    serde_json::json!({
-                        "prompt": prompt,
-                        "confidence": confidence,
-                        "verified": verified,
-                    }),
+                    record.audit_data(),
                     self.identity.as_ref().map(|id| id.as_ref()),
-                    Some(capsule.witness_receipt.clone()),
-                    capsule.vep_blob.clone(),
+                    None,
+                    None,
                 )
-                .await;
+                .await
+            {
+                Ok(event) => record.audit_event_id = Some(event.id),
+                Err(e) => tracing::warn!(
+                    agent_id = %agent.id,
+                    tool = %record.tool,
+                    "Failed to audit tool call: {}",
+                    e
+                ),
+            }
         }
 
-        Ok(result)
+        record
+    }
+
+    /// Check the continuation token that authorizes a privileged action (AEM)
+    ///
+    /// The token must bind to this agent and to the SHA-256 of `action`.
+    async fn verify_governed_action(
+        &self,
+        agent_id: Uuid,
+        capsule: &vex_core::audit::EvidenceCapsule,
+        action: &str,
+    ) -> Result<(), String> {
+        if let Some(token) = &capsule.continuation_token {
+            let aid = self.identity.as_ref().map(|id| id.agent_id.clone());
+
+            // Binding Surface: Intent Hash (Merkle-hardened representation of the current command)
+            use sha2::{Digest, Sha256};
+            let intent_hash = hex::encode(Sha256::digest(action.as_bytes()));
+
+            // Perform Stateless Edge Verification
+            self.gate
+                .verify_token(
+                    token,
+                    aid.as_deref(),
+                    Some(&intent_hash),
+                    token.payload.circuit_id.as_deref(),
+                )
+                .await
+                .map_err(|e| format!("AEM_GOVERNANCE_VIOLATION: {}", e))?;
+
+            // Phase 4: ZK Re-verification (High Assurance)
+            // If we have a Shadow Intent and a local verifier, re-check the STARK proof.
+            if let (Some(verifier), Some(intent)) = (&self.verifier, &capsule.intent_data) {
+                if let vex_core::segment::IntentData::Shadow { .. } = intent {
+                    intent
+                        .verify_shadow(verifier.as_ref())
+                        .map_err(|e| format!("AEM_GOVERNANCE_VIOLATION (ZK_FAIL): {}", e))?;
+
+                    tracing::info!(
+                        agent_id = %agent_id,
+                        "AEM: STARK proof re-verified locally for Shadow Intent."
+                    );
+                }
+            }
+
+            tracing::info!(
+                agent_id = %agent_id,
+                intent_hash = %intent_hash,
+                "AEM: Governed execution permitted via validated token."
+            );
+        } else {
+            // Fail-Closed: No token = No privileged action.
+            return Err("AEM_GOVERNANCE_VIOLATION: Privileged action requires a valid continuation token (Escalation Required).".to_string());
+        }
+        Ok(())
+    }
+
+    /// Ask one provider to challenge a claim, returning the challenge and the
+    /// answering model
+    ///
+    /// Unparseable challenges (after repair retries) fail closed as a challenge.
+    async fn red_challenge<P: LlmProvider + ?Sized>(
+        &self,
+        ctx: &RunContext,
+        llm: &P,
+        request: LlmRequest,
+    ) -> Result<(ChallengeResponse, Option<String>), RunError> {
+        match StructuredOutput::new(challenge_format())
+            .complete_with::<ChallengeResponse, _, _, _>(request, |request| {
+                ctx.complete(llm, request)
+            })
+            .await
+        {
+            Ok(structured) => Ok((structured.value, Some(structured.response.model))),
+            Err(StructuredError::Provider(e)) => Err(e),
+            Err(StructuredError::Invalid { errors, .. }) => Ok((
+                ChallengeResponse {
+                    is_challenge: true,
+                    confidence: 0.5,
+                    reasoning: format!("Unparseable challenge: {}", errors.join("; ")),
+                },
+                None,
+            )),
+        }
     }
 
     /// Run adversarial verification with Red agent
+    ///
+    /// If the run stops mid-debate, the Blue response is returned unverified along
+    /// with the rounds completed so far and the stop reason.
+    #[allow(clippy::type_complexity)]
     async fn run_adversarial_verification(
         &self,
+        ctx: &RunContext,
         blue_agent: &Agent,
         _original_prompt: &str,
         blue_response: &str,
-    ) -> Result<(String, bool, f64, Option<Debate>), String> {
+    ) -> Result<(String, bool, f64, Option<Debate>, Option<StopReason>), String> {
+        let interrupted = |debate: Debate, reason: StopReason| {
+            tracing::warn!(
+                agent_id = %blue_agent.id,
+                rounds = debate.rounds.len(),
+                "Adversarial debate stopped early: {}",
+                reason
+            );
+            Ok((
+                blue_response.to_string(),
+                false,
+                0.5,
+                Some(debate),
+                Some(reason),
+            ))
+        };
+
         // Create shadow agent
         let shadow = ShadowAgent::new(blue_agent, ShadowConfig::default());
 
@@ -300,51 +1058,72 @@ impl<L: LlmProvider + ?Sized> AgentExecutor<L> {
         // Run debate rounds
         for round_num in 1..=self.config.max_debate_rounds {
             // Red agent challenges
-            let mut challenge_prompt = shadow.challenge_prompt(blue_response);
-            challenge_prompt.push_str("\n\nIMPORTANT: Respond in valid JSON format: {\"is_challenge\": boolean, \"confidence\": float (0.0-1.0), \"reasoning\": \"string\", \"suggested_revision\": \"string\" | null}. If you agree with the statement, set is_challenge to false.");
-
-            let red_output = self
-                .llm
-                .complete(LlmRequest::with_role(
-                    &shadow.agent.config.role,
-                    &challenge_prompt,
-                ))
-                .await
-                .map_err(|e| e.to_string())?
-                .content;
+            let challenge_request = LlmRequest::with_role(
+                &shadow.agent.config.role,
+                &format!(
+                    "{}\n\nIf you agree with the statement, set is_challenge to false.",
+                    shadow.challenge_prompt(blue_response)
+                ),
+            );
 
-            // Try to parse JSON response — fail closed on parse errors
-            let (is_challenge, red_confidence, red_reasoning, _suggested_revision) =
-                if let Ok(res) = serde_json::from_str::<ChallengeResponse>(&red_output) {
-                    (
-                        res.is_challenge,
-                        res.confidence,
-                        res.reasoning,
-                        res.suggested_revision,
-                    )
-                } else if let Some(start) = red_output.find('{') {
-                    if let Some(end) = red_output.rfind('}') {
-                        if let Ok(res) =
-                            serde_json::from_str::<ChallengeResponse>(&red_output[start..=end])
-                        {
-                            (
-                                res.is_challenge,
-                                res.confidence,
-                                res.reasoning,
-                                res.suggested_revision,
-                            )
-                        } else {
-                            // Fail closed: treat unparseable response as a challenge
-                            (true, 0.5, red_output.clone(), None)
-                        }
+            let (is_challenge, red_reasoning) = if self.panel.is_empty() {
+                let (challenge, _) = match self
+                    .red_challenge(ctx, self.llm.as_ref(), challenge_request)
+                    .await
+                {
+                    Ok(challenge) => challenge,
+                    Err(RunError::Stopped(reason)) => return interrupted(debate, reason),
+                    Err(e) => return Err(e.to_string()),
+                };
+                consensus.add_vote(Vote {
+                    agent_id: shadow.agent.id,
+                    agrees: !challenge.is_challenge,
+                    confidence: challenge.confidence,
+                    reasoning: Some(challenge.reasoning.clone()),
+                });
+                (challenge.is_challenge, challenge.reasoning)
+            } else {
+                let mut challenges = Vec::new();
+                let mut agreements = Vec::new();
+                for member in &self.panel {
+                    let (challenge, model) = match self
+                        .red_challenge(ctx, member.provider.as_ref(), challenge_request.clone())
+                        .await
+                    {
+                        Ok(challenge) => challenge,
+                        Err(RunError::Stopped(reason)) => return interrupted(debate, reason),
+                        Err(e) => return Err(e.to_string()),
+                    };
+                    let model = model.unwrap_or_else(|| member.provider.name().to_string());
+                    consensus.set_weight(member.id, member.weight);
+                    consensus.add_vote(Vote {
+                        agent_id: member.id,
+                        agrees: !challenge.is_challenge,
+                        confidence: challenge.confidence,
+                        reasoning: Some(challenge.reasoning.clone()),
+                    });
+                    debate.model_votes.push(ModelVote {
+                        round: round_num,
+                        provider: member.provider.name().to_string(),
+                        model: model.clone(),
+                        agrees: !challenge.is_challenge,
+                        confidence: challenge.confidence,
+                        weight: member.weight,
+                    });
+                    let line = format!("[{}] {}", model, challenge.reasoning);
+                    if challenge.is_challenge {
+                        challenges.push(line);
                     } else {
-                        // Fail closed
-                        (true, 0.5, "Parsing failed".to_string(), None)
+                        agreements.push(line);
                     }
+                }
+                // Blue answers every challenge; an unchallenged round records the agreements
+                if challenges.is_empty() {
+                    (false, agreements.join("\n"))
                 } else {
-                    // Fail closed
-                    (true, 0.5, "No JSON found".to_string(), None)
-                };
+                    (true, challenges.join("\n"))
+                }
+            };
 
             let rebuttal = if is_challenge {
                 let rebuttal_prompt = format!(
@@ -354,16 +1133,17 @@ impl<L: LlmProvider + ?Sized> AgentExecutor<L> {
                      Please address these concerns or provide a revised response.",
                     blue_response, red_reasoning
                 );
-                Some(
-                    self.llm
-                        .complete(LlmRequest::with_role(
-                            &blue_agent.config.role,
-                            &rebuttal_prompt,
-                        ))
-                        .await
-                        .map_err(|e| e.to_string())?
-                        .content,
-                )
+                match ctx
+                    .complete(
+                        self.llm.as_ref(),
+                        LlmRequest::with_role(&blue_agent.config.role, &rebuttal_prompt),
+                    )
+                    .await
+                {
+                    Ok(response) => Some(response.content),
+                    Err(RunError::Stopped(reason)) => return interrupted(debate, reason),
+                    Err(e) => return Err(e.to_string()),
+                }
             } else {
                 None
             };
@@ -371,18 +1151,10 @@ impl<L: LlmProvider + ?Sized> AgentExecutor<L> {
             debate.add_round(DebateRound {
                 round: round_num,
                 blue_claim: blue_response.to_string(),
-                red_challenge: red_reasoning.clone(),
+                red_challenge: red_reasoning,
                 blue_rebuttal: rebuttal,
             });
 
-            // Vote: Red votes based on whether it found a challenge
-            consensus.add_vote(Vote {
-                agent_id: shadow.agent.id,
-                agrees: !is_challenge,
-                confidence: red_confidence,
-                reasoning: Some(red_reasoning),
-            });
-
             if !is_challenge {
                 break;
             }
@@ -405,55 +1177,37 @@ impl<L: LlmProvider + ?Sized> AgentExecutor<L> {
             ));
         }
 
-        reflection_prompt.push_str("\nBased on this debate, do you still stand by your original response? \
-                                    Respond in valid JSON: {\"agrees\": boolean, \"confidence\": float (0.0-1.0), \"reasoning\": \"string\"}.");
-
-        let blue_vote_res = self
-            .llm
-            .complete(LlmRequest::with_role(
-                &blue_agent.config.role,
-                &reflection_prompt,
-            ))
-            .await;
+        reflection_prompt
+            .push_str("\nBased on this debate, do you still stand by your original response?");
 
-        // Fail closed: on parse failure, blue does NOT agree (conservative)
-        let (blue_agrees, blue_confidence, blue_reasoning) = if let Ok(resp) = blue_vote_res {
-            if let Ok(vote) = serde_json::from_str::<VoteResponse>(&resp.content) {
-                (vote.agrees, vote.confidence, vote.reflection)
-            } else if let Some(start) = resp.content.find('{') {
-                if let Some(end) = resp.content.rfind('}') {
-                    if let Ok(vote) =
-                        serde_json::from_str::<VoteResponse>(&resp.content[start..=end])
-                    {
-                        (vote.agrees, vote.confidence, vote.reflection)
-                    } else {
-                        (
-                            false,
-                            blue_agent.fitness,
-                            "Failed to parse reflection JSON".to_string(),
-                        )
-                    }
-                } else {
-                    (
-                        false,
-                        blue_agent.fitness,
-                        "No JSON in reflection".to_string(),
-                    )
+        // Fail closed: on failure, blue does NOT agree (conservative)
+        let (blue_agrees, blue_confidence, blue_reasoning) =
+            match StructuredOutput::new(vote_format())
+                .complete_with::<VoteResponse, _, _, _>(
+                    LlmRequest::with_role(&blue_agent.config.role, &reflection_prompt),
+                    |request| ctx.complete(self.llm.as_ref(), request),
+                )
+                .await
+            {
+                Ok(structured) => (
+                    structured.value.agrees,
+                    structured.value.confidence,
+                    structured.value.reasoning,
+                ),
+                Err(StructuredError::Provider(RunError::Stopped(reason))) => {
+                    return interrupted(debate, reason)
                 }
-            } else {
-                (
+                Err(StructuredError::Provider(_)) => (
                     false,
                     blue_agent.fitness,
-                    "No reflection content".to_string(),
-                )
-            }
-        } else {
-            (
-                false,
-                blue_agent.fitness,
-                "Reflection LLM call failed".to_string(),
-            )
-        };
+                    "Reflection LLM call failed".to_string(),
+                ),
+                Err(StructuredError::Invalid { .. }) => (
+                    false,
+                    blue_agent.fitness,
+                    "Failed to parse reflection JSON".to_string(),
+                ),
+            };
 
         consensus.add_vote(Vote {
             agent_id: blue_agent.id,
@@ -483,7 +1237,7 @@ impl<L: LlmProvider + ?Sized> AgentExecutor<L> {
         // Fail closed: if no consensus decision, reject the claim
         debate.conclude(consensus.decision.unwrap_or(false), confidence);
 
-        Ok((final_response, verified, confidence, Some(debate)))
+        Ok((final_response, verified, confidence, Some(debate), None))
     }
 }
 
@@ -513,4 +1267,387 @@ mod tests {
         // verified is false by design when enable_adversarial = false
         assert!(!result.verified);
     }
+
+    #[tokio::test]
+    async fn test_executor_budget_stops_debate() {
+        use crate::gate::GenericGateMock;
+        use vex_llm::MockProvider;
+        let llm = Arc::new(MockProvider::new(vec!["Blue answer".to_string()]));
+        let executor =
+            AgentExecutor::new(llm, ExecutorConfig::default(), Arc::new(GenericGateMock));
+        let mut agent = Agent::new(AgentConfig::default());
+
+        // The Blue call uses 100+ tokens, so the Red challenge is refused
+        let ctx = RunContext::new().with_token_budget(50);
+        let result = executor
+            .execute_with_context(&ctx, "test-tenant", &mut agent, "Test prompt", None, vec![])
+            .await
+            .unwrap();
+        assert_eq!(result.response, "Blue answer");
+        assert!(!result.verified);
+        assert!(result.debate.unwrap().rounds.is_empty());
+        assert!(matches!(
+            result.stopped,
+            Some(StopReason::TokenBudgetExhausted { limit: 50, .. })
+        ));
+
+        // Nothing can run once the context is cancelled
+        let ctx = RunContext::new();
+        ctx.cancel();
+        let err = executor
+            .execute_with_context(&ctx, "test-tenant", &mut agent, "Test prompt", None, vec![])
+            .await
+            .unwrap_err();
+        assert!(err.contains("cancelled"));
+    }
+
+    #[tokio::test]
+    async fn test_executor_repairs_malformed_challenge() {
+        use crate::gate::GenericGateMock;
+        use vex_llm::MockProvider;
+        let llm = Arc::new(MockProvider::new(vec![
+            "Blue answer".to_string(),
+            "I mostly agree, no real issues here.".to_string(),
+            r#"{"is_challenge": false, "confidence": 0.9, "reasoning": "Sound"}"#.to_string(),
+            r#"{"agrees": true, "confidence": 0.9, "reasoning": "Unchallenged"}"#.to_string(),
+        ]));
+        let executor =
+            AgentExecutor::new(llm, ExecutorConfig::default(), Arc::new(GenericGateMock));
+        let mut agent = Agent::new(AgentConfig::default());
+
+        let result = executor
+            .execute("test-tenant", &mut agent, "Test prompt", None, vec![])
+            .await
+            .unwrap();
+        assert_eq!(result.response, "Blue answer");
+        assert!(result.verified);
+        let debate = result.debate.unwrap();
+        assert_eq!(debate.rounds.len(), 1);
+        assert_eq!(debate.rounds[0].red_challenge, "Sound");
+    }
+
+    #[tokio::test]
+    async fn test_executor_provider_panel() {
+        use crate::gate::GenericGateMock;
+        use vex_llm::MockProvider;
+        let llm = Arc::new(MockProvider::new(vec![
+            "Blue answer".to_string(),
+            "Blue rebuttal".to_string(),
+            r#"{"agrees": true, "confidence": 0.9, "reasoning": "Stands"}"#.to_string(),
+        ]));
+        let mut local = MockProvider::constant(
+            r#"{"is_challenge": true, "confidence": 0.8, "reasoning": "Unsourced"}"#,
+        );
+        local.name = "llama3".to_string();
+        let mut hosted = MockProvider::constant(
+            r#"{"is_challenge": false, "confidence": 0.9, "reasoning": "Correct"}"#,
+        );
+        hosted.name = "gpt-4o".to_string();
+
+        let config = ExecutorConfig {
+            max_debate_rounds: 1,
+            ..Default::default()
+        };
+        let executor = AgentExecutor::new(llm, config, Arc::new(GenericGateMock)).with_panel(vec![
+            PanelMember::new(Arc::new(local), 1.0),
+            PanelMember::new(Arc::new(hosted), 2.0),
+        ]);
+        let mut agent = Agent::new(AgentConfig::default());
+
+        let result = executor
+            .execute("test-tenant", &mut agent, "Test prompt", None, vec![])
+            .await
+            .unwrap();
+
+        // The heavier hosted model and Blue outweigh the local challenge
+        assert!(result.verified);
+        assert_eq!(result.response, "Blue answer");
+        let debate = result.debate.unwrap();
+        assert_eq!(debate.rounds[0].red_challenge, "[llama3] Unsourced");
+        assert_eq!(
+            debate.rounds[0].blue_rebuttal.as_deref(),
+            Some("Blue rebuttal")
+        );
+        assert_eq!(debate.model_votes.len(), 2);
+        assert_eq!(debate.model_votes[1].weight, 2.0);
+        assert_eq!(debate.agreeing_models(), vec!["gpt-4o"]);
+    }
+
+    struct FetchTool {
+        definition: vex_llm::ToolDefinition,
+    }
+
+    #[async_trait::async_trait]
+    impl vex_llm::Tool for FetchTool {
+        fn definition(&self) -> &vex_llm::ToolDefinition {
+            &self.definition
+        }
+
+        async fn execute(
+            &self,
+            _args: serde_json::Value,
+        ) -> Result<serde_json::Value, vex_llm::ToolError> {
+            Ok(serde_json::json!({"body": "fetched"}))
+        }
+
+        fn capabilities(&self) -> Vec<Capability> {
+            vec![Capability::Network]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_executor_tool_loop() {
+        use crate::gate::GenericGateMock;
+        use vex_llm::{CalculatorTool, MockProvider, ToolDefinition, ToolRegistry};
+        use vex_persist::backend::MemoryBackend;
+
+        let llm = Arc::new(MockProvider::new(vec![
+            r#"{"tool": "fetch", "arguments": {"url": "https://example.com"}}"#.to_string(),
+            r#"{"tool": "calculator", "arguments": {"expression": "6 * 7"}}"#.to_string(),
+            r#"{"final_answer": "The answer is 42."}"#.to_string(),
+        ]));
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(CalculatorTool::new()));
+        registry.register(Arc::new(FetchTool {
+            definition: ToolDefinition::new("fetch", "Fetch a URL", "{}"),
+        }));
+
+        let config = ExecutorConfig {
+            enable_adversarial: false,
+            ..Default::default()
+        };
+        let backend: Arc<dyn StorageBackend> = Arc::new(MemoryBackend::new());
+        let store = Arc::new(AuditStore::new(backend));
+        let mut executor = AgentExecutor::new(llm, config, Arc::new(GenericGateMock))
+            .with_tools(Arc::new(ToolExecutor::new(registry)));
+        executor.audit_store = Some(store.clone());
+        let mut agent = Agent::new(AgentConfig::default());
+
+        let result = executor
+            .execute("tool-tenant", &mut agent, "What is 6 * 7?", None, vec![])
+            .await
+            .unwrap();
+
+        assert_eq!(result.response, "The answer is 42.");
+        assert_eq!(result.tool_calls.len(), 2);
+        // Network was not granted to this execution
+        assert_eq!(result.tool_calls[0].outcome, ToolCallOutcome::Denied);
+        assert_eq!(result.tool_calls[1].outcome, ToolCallOutcome::Executed);
+        assert_eq!(
+            result.tool_calls[1].output.as_ref().unwrap()["result"],
+            42.0
+        );
+
+        let chain = store.get_chain("tool-tenant").await.unwrap();
+        let tool_events: Vec<_> = chain
+            .iter()
+            .filter(|e| e.event_type == vex_core::audit::AuditEventType::ToolCall)
+            .collect();
+        assert_eq!(tool_events.len(), 2);
+        assert_eq!(Some(tool_events[1].id), result.tool_calls[1].audit_event_id);
+        assert!(store.verify_chain("tool-tenant").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_executor_transcript_replay() {
+        use crate::gate::GenericGateMock;
+        use crate::transcript::{DivergenceKind, TranscriptEntry};
+        use vex_llm::{CalculatorTool, MockProvider, ToolRegistry};
+        use vex_persist::backend::MemoryBackend;
+
+        let llm = Arc::new(MockProvider::new(vec![
+            r#"{"tool": "calculator", "arguments": {"expression": "6 * 7"}}"#.to_string(),
+            r#"{"final_answer": "The answer is 42."}"#.to_string(),
+        ]));
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(CalculatorTool::new()));
+        let config = ExecutorConfig {
+            enable_adversarial: false,
+            tool_call_confidence: 0.8,
+            seed: Some(7),
+            ..Default::default()
+        };
+        let backend: Arc<dyn StorageBackend> = Arc::new(MemoryBackend::new());
+        let audit = Arc::new(AuditStore::new(backend.clone()));
+        let mut executor = AgentExecutor::new(llm, config, Arc::new(GenericGateMock))
+            .with_tools(Arc::new(ToolExecutor::new(registry)))
+            .with_transcripts(Arc::new(ArtifactStore::new(backend)));
+        executor.audit_store = Some(audit.clone());
+        let mut agent = Agent::new(AgentConfig::default());
+
+        let result = executor
+            .execute("test-tenant", &mut agent, "What is 6 * 7?", None, vec![])
+            .await
+            .unwrap();
+        let hash = result.transcript_hash.unwrap();
+        let chain = audit.get_chain("test-tenant").await.unwrap();
+        let executed = chain
+            .iter()
+            .find(|e| e.event_type == vex_core::audit::AuditEventType::AgentExecuted)
+            .unwrap();
+        assert_eq!(
+            executed.data["transcript"],
+            format!("sha256:{}", hash.to_hex())
+        );
+
+        // Replaying the stored transcript reproduces the ALLOW decision
+        let report = executor.replay_stored("test-tenant", &hash).await.unwrap();
+        assert!(report.is_faithful(), "{:?}", report.divergences);
+        assert_eq!(report.outcome.unwrap().gate_outcome, "ALLOW");
+
+        let mut transcript: Transcript = executor
+            .transcripts
+            .as_ref()
+            .unwrap()
+            .get("test-tenant", &hash)
+            .await
+            .unwrap()
+            .unwrap();
+        // LLM call, tool gate, tool call, LLM call, final gate
+        assert_eq!(transcript.entries.len(), 5);
+        assert!(matches!(
+            &transcript.entries[0],
+            TranscriptEntry::Llm { request, .. } if request.seed == Some(7)
+        ));
+        assert!(matches!(
+            &transcript.entries[1],
+            TranscriptEntry::Gate { confidence, .. } if *confidence == 0.8
+        ));
+
+        // A different recorded answer no longer passes the gate
+        if let TranscriptEntry::Llm { response, .. } = &mut transcript.entries[3] {
+            response.content = r#"{"final_answer": "I'm sorry, I cannot fulfill that."}"#.into();
+        }
+        let report = executor.replay(&transcript).await.unwrap();
+        assert!(report.error.unwrap().contains("REFUSAL_FILTER"));
+        let kinds: Vec<_> = report.divergences.iter().map(|d| d.kind).collect();
+        assert_eq!(kinds, vec![DivergenceKind::Gate, DivergenceKind::Outcome]);
+        assert_eq!(report.divergences[0].index, 4);
+    }
+
+    #[tokio::test]
+    async fn test_executor_tool_step_budget() {
+        use crate::gate::GenericGateMock;
+        use vex_llm::{CalculatorTool, MockProvider, ToolRegistry};
+
+        let call = r#"{"tool": "calculator", "arguments": {"expression": "1 + 1"}}"#;
+        let executor_with = |llm: MockProvider| {
+            let mut registry = ToolRegistry::new();
+            registry.register(Arc::new(CalculatorTool::new()));
+            let config = ExecutorConfig {
+                enable_adversarial: false,
+                max_tool_steps: 3,
+                ..Default::default()
+            };
+            AgentExecutor::new(Arc::new(llm), config, Arc::new(GenericGateMock))
+                .with_tools(Arc::new(ToolExecutor::new(registry)))
+        };
+
+        // A model that keeps calling tools past the budget fails the run
+        let executor = executor_with(MockProvider::constant(call));
+        let mut agent = Agent::new(AgentConfig::default());
+        let err = executor
+            .execute("test-tenant", &mut agent, "Loop forever", None, vec![])
+            .await
+            .unwrap_err();
+        assert!(err.contains("budget of 3 exhausted"), "{}", err);
+        assert!(!err.contains("arguments"));
+
+        // One last answer after the budget is accepted
+        let mut responses = vec![call.to_string(); 3];
+        responses.push(r#"{"final_answer": "2"}"#.to_string());
+        let executor = executor_with(MockProvider::new(responses));
+        let mut agent = Agent::new(AgentConfig::default());
+        let result = executor
+            .execute("test-tenant", &mut agent, "Add", None, vec![])
+            .await
+            .unwrap();
+        assert_eq!(result.tool_calls.len(), 3);
+        assert_eq!(result.response, "2");
+    }
+
+    #[tokio::test]
+    async fn test_executor_suspend_and_resume() {
+        use crate::gate::GenericGateMock;
+        use vex_core::segment::ContinuationPayload;
+        use vex_llm::MockProvider;
+        use vex_persist::backend::MemoryBackend;
+
+        let llm = Arc::new(MockProvider::constant("Deploying to production"));
+        let config = ExecutorConfig {
+            enable_adversarial: false,
+            ..Default::default()
+        };
+        let store = Arc::new(SuspensionStore::new(Arc::new(MemoryBackend::new())));
+        let executor = AgentExecutor::new(llm, config, Arc::new(GenericGateMock))
+            .with_suspensions(store.clone());
+        let mut agent = Agent::new(AgentConfig::default());
+
+        // A privileged action without a token is suspended, not lost
+        let err = executor
+            .execute(
+                "test-tenant",
+                &mut agent,
+                "Deploy",
+                None,
+                vec![Capability::Network],
+            )
+            .await
+            .unwrap_err();
+        let escalation_id = suspension::suspended_escalation_id(&err).unwrap();
+        let suspended = store
+            .get("test-tenant", escalation_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(suspended.response, "Deploying to production");
+
+        let token = |root: &str| {
+            let now = chrono::Utc::now();
+            vex_core::ContinuationToken {
+                payload: ContinuationPayload {
+                    schema: "chora.continuation.token.v1".to_string(),
+                    ledger_event_id: escalation_id.to_string(),
+                    aid: "aid".to_string(),
+                    source_capsule_root: root.to_string(),
+                    circuit_id: None,
+                    resolution_event_id: None,
+                    capabilities: vec!["Network".to_string()],
+                    nonce: "n".to_string(),
+                    iat: now.to_rfc3339(),
+                    exp: (now + chrono::Duration::hours(1)).to_rfc3339(),
+                    issuer: "chora".to_string(),
+                },
+                signature: "sig".to_string(),
+            }
+        };
+
+        // A token bound to another capsule cannot resume it
+        assert!(executor
+            .resume_stored("test-tenant", escalation_id, token("other-root"))
+            .await
+            .unwrap_err()
+            .contains("capsule root"));
+
+        let (resumed, result) = executor
+            .resume_stored("test-tenant", escalation_id, token(&suspended.capsule_root))
+            .await
+            .unwrap();
+        assert_eq!(resumed.id, agent.id);
+        assert_eq!(resumed.context.content, "Deploying to production");
+        assert_eq!(result.response, "Deploying to production");
+        assert!(result.evidence.unwrap().continuation_token.is_some());
+        assert!(store
+            .get("test-tenant", escalation_id)
+            .await
+            .unwrap()
+            .is_none());
+
+        // The execution was claimed: a second resume cannot commit it again
+        assert!(executor
+            .resume_stored("test-tenant", escalation_id, token(&suspended.capsule_root))
+            .await
+            .unwrap_err()
+            .contains("No execution suspended"));
+    }
 }
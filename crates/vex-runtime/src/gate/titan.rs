@@ -890,6 +890,9 @@ mod tests {
                 tokens_used: None,
                 latency_ms: 0,
                 trace_root: None,
+                tool_calls: Vec::new(),
+                provider: None,
+                cached: false,
             })
         }
     }
@@ -2,13 +2,37 @@
 //!
 //! Tokio-based agent orchestration and lifecycle management.
 
+pub mod anchoring;
 pub mod audit;
 pub mod executor;
 pub mod gate;
 pub mod orchestrator;
+pub mod run_context;
+pub mod suspension;
+pub mod tool_loop;
+pub mod topology;
+pub mod transcript;
 pub mod utils;
+pub mod workflow;
 
-pub use executor::{AgentExecutor, ExecutorConfig};
+pub use anchoring::{
+    AnchorPolicy, AnchorService, AnchorServiceError, BatchedAnchorReceipt, PendingAnchor,
+};
+pub use executor::{AgentExecutor, ExecutorConfig, PanelMember};
 
 pub use gate::{ChoraGate, Gate, GenericGateMock, HttpGate, TitanGate};
 pub use orchestrator::{Orchestrator, OrchestratorConfig};
+pub use run_context::{RunBudget, RunContext, RunError, StopReason};
+pub use suspension::{
+    suspended_escalation_id, SuspendedExecution, SuspensionStore, EXECUTION_SUSPENDED,
+};
+pub use tool_loop::{ToolCallOutcome, ToolCallRecord};
+pub use topology::{OrchestrationPlan, PlanError, PlanFormat, RoleSpec};
+pub use transcript::{
+    Divergence, DivergenceKind, ReplayReport, Transcript, TranscriptEntry, TranscriptInput,
+    TranscriptOutcome,
+};
+pub use workflow::{
+    EdgeCondition, NodeKind, Workflow, WorkflowEdge, WorkflowEngine, WorkflowError, WorkflowNode,
+    WorkflowResult,
+};
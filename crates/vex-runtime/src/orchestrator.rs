@@ -1,6 +1,6 @@
 //! Orchestrator - manages hierarchical agent networks
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
@@ -11,16 +11,23 @@ use vex_core::{
     StandardOperator,
 };
 
-use crate::executor::{AgentExecutor, ExecutionResult, ExecutorConfig};
+use crate::anchoring::{AnchorService, PendingAnchor};
+use crate::executor::{AgentExecutor, ExecutionResult, ExecutorConfig, PanelMember};
+use crate::run_context::{RunContext, StopReason};
+use crate::suspension::SuspensionStore;
+use crate::topology::{OrchestrationPlan, PlanError, RoleSpec};
 use vex_llm::LlmProvider;
+use vex_persist::{AgentStore, ContextStore, StorageBackend, StorageError};
 
 /// Configuration for the orchestrator
 #[derive(Debug, Clone)]
 pub struct OrchestratorConfig {
-    /// Maximum depth of agent hierarchy
+    /// Maximum depth of agent hierarchy (deeper plan roles run as leaves)
     pub max_depth: u8,
-    /// Number of agents per level
+    /// Maximum child agents spawned under one parent
     pub agents_per_level: usize,
+    /// Agent hierarchy built for each query
+    pub plan: OrchestrationPlan,
     /// Enable evolutionary selection
     pub enable_evolution: bool,
     /// Mutation rate for evolution
@@ -29,6 +36,8 @@ pub struct OrchestratorConfig {
     pub executor_config: ExecutorConfig,
     /// Maximum age for tracked agents before cleanup (prevents memory leaks)
     pub max_agent_age: Duration,
+    /// Agents kept per tenant in the agent store; the least fit are deleted beyond this
+    pub max_stored_agents: usize,
     /// Enable self-correcting genome evolution
     pub enable_self_correction: bool,
     /// Minimum fitness improvement to accept change
@@ -42,10 +51,12 @@ impl Default for OrchestratorConfig {
         Self {
             max_depth: 3,
             agents_per_level: 2,
+            plan: OrchestrationPlan::default(),
             enable_evolution: true,
             mutation_rate: 0.1,
             executor_config: ExecutorConfig::default(),
             max_agent_age: Duration::from_secs(3600), // 1 hour default
+            max_stored_agents: 256,
             enable_self_correction: false,
             improvement_threshold: 0.02,
             reflect_every_n_tasks: 5,
@@ -70,19 +81,31 @@ pub struct OrchestrationResult {
     pub agent_results: HashMap<Uuid, ExecutionResult>,
     /// Anchor receipts from blockchain backends
     pub anchor_receipts: Vec<AnchorReceipt>,
+    /// Handle resolving once the batched anchor service anchors `merkle_root`
+    pub pending_anchor: Option<PendingAnchor>,
     /// Total levels processed
     pub levels_processed: u8,
     /// Overall confidence
     pub confidence: f64,
     /// Evidence from the final decision gate
     pub evidence: Option<vex_core::audit::EvidenceCapsule>,
+    /// Why the run stopped early (results are partial)
+    pub stopped: Option<StopReason>,
+}
+
+/// Agents executed for one plan node and its subtree (children first, node last)
+struct NodeOutcome {
+    agents: Vec<(Agent, ExecutionResult)>,
+    levels: u8,
+    /// Whether the node itself ran (false if the run stopped first)
+    completed: bool,
 }
 
 /// Tracked agent with creation timestamp for TTL-based cleanup
 #[derive(Clone)]
 struct TrackedAgent {
     agent: Agent,
-    _tenant_id: String,
+    tenant_id: String,
     created_at: Instant,
 }
 
@@ -92,10 +115,18 @@ pub struct Orchestrator<L: LlmProvider + ?Sized> {
     pub config: OrchestratorConfig,
     /// All agents (id -> tracked agent with timestamp)
     agents: RwLock<HashMap<Uuid, TrackedAgent>>,
+    /// Durable agent population (agents and genomes)
+    agent_store: Option<Arc<AgentStore<dyn StorageBackend>>>,
+    /// Durable agent context
+    context_store: Option<Arc<ContextStore<dyn StorageBackend>>>,
+    /// Tenants whose persisted agents have been loaded into `agents`
+    loaded_tenants: RwLock<HashSet<String>>,
     /// Executor
     executor: AgentExecutor<L>,
     /// Anchoring backends (Blockchain, Cloud, etc)
     anchors: Vec<Arc<dyn AnchorBackend>>,
+    /// Batched background anchoring (preferred over inline `anchors`)
+    anchor_service: Option<AnchorService>,
     /// LLM backend (stored for future use)
     #[allow(dead_code)]
     llm: Arc<L>,
@@ -135,12 +166,17 @@ impl<L: LlmProvider + ?Sized> std::fmt::Debug for Orchestrator<L> {
 
 impl<L: LlmProvider + ?Sized + 'static> Orchestrator<L> {
     /// Create a new orchestrator
+    ///
+    /// Fails if the plan is invalid or a role fans out to more than
+    /// `agents_per_level` children.
     pub fn new(
         llm: Arc<L>,
         config: OrchestratorConfig,
         persistence_layer: Option<Arc<dyn vex_persist::EvolutionStore>>,
         gate: Arc<dyn crate::gate::Gate>,
-    ) -> Self {
+    ) -> Result<Self, PlanError> {
+        config.plan.validate()?;
+        config.plan.validate_fan_out(config.agents_per_level)?;
         let executor =
             AgentExecutor::new(llm.clone(), config.executor_config.clone(), gate.clone());
         let evolution_memory = if config.enable_self_correction {
@@ -153,11 +189,15 @@ impl<L: LlmProvider + ?Sized + 'static> Orchestrator<L> {
         } else {
             None
         };
-        Self {
+        Ok(Self {
             config,
             agents: RwLock::new(HashMap::new()),
+            agent_store: None,
+            context_store: None,
+            loaded_tenants: RwLock::new(HashSet::new()),
             executor,
             anchors: Vec::new(),
+            anchor_service: None,
             llm,
             evolution_memory,
             reflection_agent,
@@ -166,7 +206,7 @@ impl<L: LlmProvider + ?Sized + 'static> Orchestrator<L> {
             identity: None,
             gate,
             verifier: None,
-        }
+        })
     }
 
     /// Attach a ZK Verifier (Phase 4)
@@ -176,11 +216,59 @@ impl<L: LlmProvider + ?Sized + 'static> Orchestrator<L> {
         self
     }
 
+    /// Verify agent answers with a panel of providers (see [`AgentExecutor::with_panel`])
+    pub fn with_panel(mut self, panel: Vec<PanelMember>) -> Self {
+        self.executor = self.executor.clone().with_panel(panel);
+        self
+    }
+
+    /// Suspend escalated executions until approved (see [`AgentExecutor::with_suspensions`])
+    pub fn with_suspensions(mut self, store: Arc<SuspensionStore>) -> Self {
+        self.executor = self.executor.clone().with_suspensions(store);
+        self
+    }
+
+    /// Resume an execution suspended on `escalation_id` with its continuation token
+    ///
+    /// The resumed agent is tracked (and persisted) like any agent a request
+    /// produced. Only the suspended agent's step is finished; the rest of the
+    /// hierarchy it belonged to is not re-run.
+    pub async fn resume(
+        &self,
+        tenant_id: &str,
+        escalation_id: &str,
+        token: vex_core::ContinuationToken,
+    ) -> Result<ExecutionResult, String> {
+        let (agent, result) = self
+            .executor
+            .resume_stored(tenant_id, escalation_id, token)
+            .await?;
+        self.agents.write().await.insert(
+            agent.id,
+            TrackedAgent {
+                agent: agent.clone(),
+                tenant_id: tenant_id.to_string(),
+                created_at: Instant::now(),
+            },
+        );
+        self.save_agents(tenant_id, &[agent]).await;
+        Ok(result)
+    }
+
     /// Add an anchoring backend
+    ///
+    /// Backends added here are called inline on every request; use
+    /// [`Orchestrator::with_anchor_service`] to anchor batches in the background.
     pub fn add_anchor(&mut self, anchor: Arc<dyn AnchorBackend>) {
         self.anchors.push(anchor);
     }
 
+    /// Submit each run's Merkle root to a batched anchor service
+    pub fn with_anchor_service(mut self, service: AnchorService) -> Self {
+        self.anchor_service = Some(service);
+        self
+    }
+
     /// Attach a hardware-rooted identity and audit store (Phase 3)
     pub fn with_identity(
         mut self,
@@ -196,8 +284,167 @@ impl<L: LlmProvider + ?Sized + 'static> Orchestrator<L> {
         self
     }
 
+    /// Persist each tenant's agent population (agents, genomes and latest context)
+    ///
+    /// A tenant's stored agents are loaded on its first request, and every agent a
+    /// request creates or evolves is saved afterwards, so the population survives
+    /// restarts and is shared by orchestrators using the same backend.
+    pub fn with_agent_persistence(
+        mut self,
+        agent_store: Arc<AgentStore<dyn StorageBackend>>,
+        context_store: Arc<ContextStore<dyn StorageBackend>>,
+    ) -> Self {
+        self.agent_store = Some(agent_store);
+        self.context_store = Some(context_store);
+        self
+    }
+
+    /// Load (or reload) a tenant's persisted agents into memory
+    ///
+    /// Returns the number of agents loaded. Call this to pick up agents saved by
+    /// other replicas since the tenant was first loaded.
+    pub async fn load_agents(&self, tenant_id: &str) -> Result<usize, String> {
+        let Some(store) = &self.agent_store else {
+            return Ok(0);
+        };
+        let mut loaded = store
+            .load_all(tenant_id)
+            .await
+            .map_err(|e| format!("Failed to load agents: {}", e))?;
+        if let Some(contexts) = &self.context_store {
+            for agent in &mut loaded {
+                if let Some(context) = contexts
+                    .latest_by_agent(tenant_id, agent.id)
+                    .await
+                    .map_err(|e| format!("Failed to load agent context: {}", e))?
+                {
+                    agent.context = context;
+                }
+            }
+        }
+
+        let count = loaded.len();
+        let mut agents = self.agents.write().await;
+        for agent in loaded {
+            agents.insert(
+                agent.id,
+                TrackedAgent {
+                    agent,
+                    tenant_id: tenant_id.to_string(),
+                    created_at: Instant::now(),
+                },
+            );
+        }
+        drop(agents);
+        self.loaded_tenants
+            .write()
+            .await
+            .insert(tenant_id.to_string());
+        Ok(count)
+    }
+
+    /// Load a tenant's persisted agents if this orchestrator has not yet
+    async fn ensure_loaded(&self, tenant_id: &str) {
+        if self.agent_store.is_none() || self.loaded_tenants.read().await.contains(tenant_id) {
+            return;
+        }
+        if let Err(e) = self.load_agents(tenant_id).await {
+            tracing::warn!(tenant_id = %tenant_id, "{}", e);
+        }
+    }
+
+    /// Start `agent` from the genome of the tenant's fittest agent in the same role,
+    /// then apply the role's trait overrides
+    async fn seed_genome(&self, tenant_id: &str, spec: &RoleSpec, agent: &mut Agent) {
+        {
+            let agents = self.agents.read().await;
+            let fittest = agents
+                .values()
+                .filter(|t| t.tenant_id == tenant_id && t.agent.config.role == spec.role)
+                .max_by(|a, b| a.agent.fitness.total_cmp(&b.agent.fitness));
+            if let Some(tracked) = fittest {
+                agent.genome = tracked.agent.genome.clone();
+            }
+        }
+        spec.apply_genome(&mut agent.genome);
+    }
+
+    /// Save agents (and their latest context) to the configured stores, then trim
+    /// the stored population to `max_stored_agents`
+    ///
+    /// A context is only saved when it differs from the agent's latest stored one.
+    async fn save_agents(&self, tenant_id: &str, agents: &[Agent]) {
+        let Some(store) = &self.agent_store else {
+            return;
+        };
+        for agent in agents {
+            if let Err(e) = store.save(tenant_id, agent).await {
+                tracing::warn!(agent_id = %agent.id, "Failed to persist agent: {}", e);
+                continue;
+            }
+            if let Some(contexts) = &self.context_store {
+                let latest = contexts.latest_by_agent(tenant_id, agent.id).await;
+                let unchanged = matches!(
+                    &latest,
+                    Ok(Some(stored))
+                        if stored.id == agent.context.id && stored.hash == agent.context.hash
+                );
+                if unchanged {
+                    continue;
+                }
+                if let Err(e) = contexts.save(tenant_id, &agent.context).await {
+                    tracing::warn!(agent_id = %agent.id, "Failed to persist agent context: {}", e);
+                }
+            }
+        }
+
+        let keep: HashSet<Uuid> = agents.iter().map(|a| a.id).collect();
+        if let Err(e) = self.prune_stored_agents(tenant_id, &keep).await {
+            tracing::warn!(tenant_id = %tenant_id, "Failed to prune stored agents: {}", e);
+        }
+    }
+
+    /// Delete the least fit stored agents (and their contexts) beyond
+    /// `max_stored_agents`, never touching the agents in `keep`
+    ///
+    /// Returns the number of agents deleted.
+    async fn prune_stored_agents(
+        &self,
+        tenant_id: &str,
+        keep: &HashSet<Uuid>,
+    ) -> Result<usize, StorageError> {
+        let Some(store) = &self.agent_store else {
+            return Ok(0);
+        };
+        let stored = store.load_all(tenant_id).await?;
+        let excess = stored.len().saturating_sub(self.config.max_stored_agents);
+        if excess == 0 {
+            return Ok(0);
+        }
+
+        let mut candidates: Vec<&Agent> = stored.iter().filter(|a| !keep.contains(&a.id)).collect();
+        candidates.sort_by(|a, b| a.fitness.total_cmp(&b.fitness));
+        let evicted: Vec<Uuid> = candidates.iter().take(excess).map(|a| a.id).collect();
+        for id in &evicted {
+            store.delete(tenant_id, *id).await?;
+            if let Some(contexts) = &self.context_store {
+                contexts.delete_by_agent(tenant_id, *id).await?;
+            }
+        }
+
+        let mut agents = self.agents.write().await;
+        for id in &evicted {
+            agents.remove(id);
+        }
+        tracing::debug!(tenant_id = %tenant_id, evicted = evicted.len(), "Pruned stored agents");
+        Ok(evicted.len())
+    }
+
     /// Cleanup expired agents to prevent memory leaks
     /// Returns the number of agents removed
+    ///
+    /// Persisted agents are only evicted from memory; they stay in the agent store
+    /// and come back with [`Orchestrator::load_agents`].
     pub async fn cleanup_expired(&self) -> usize {
         let mut agents = self.agents.write().await;
         let before = agents.len();
@@ -226,111 +473,92 @@ impl<L: LlmProvider + ?Sized + 'static> Orchestrator<L> {
         intent_data: Option<vex_core::segment::IntentData>,
         capabilities: Vec<vex_llm::Capability>,
     ) -> Result<OrchestrationResult, String> {
-        // Create root agent
+        self.process_with_context(
+            &RunContext::new(),
+            tenant_id,
+            query,
+            intent_data,
+            capabilities,
+        )
+        .await
+    }
+
+    /// Process a query under a run context (cancellation, deadline, budget)
+    ///
+    /// If the run stops, agents that finished are still returned (and anchored) with
+    /// `stopped` set; the response is the root's answer if it ran, otherwise the
+    /// most confident finished agent's. Evolution and the final gate are skipped and
+    /// a `RunStopped` audit event records the reason.
+    pub async fn process_with_context(
+        &self,
+        ctx: &RunContext,
+        tenant_id: &str,
+        query: &str,
+        intent_data: Option<vex_core::segment::IntentData>,
+        capabilities: Vec<vex_llm::Capability>,
+    ) -> Result<OrchestrationResult, String> {
+        // New agents start from the tenant's persisted population
+        self.ensure_loaded(tenant_id).await;
+
+        // Create root agent from the plan
+        let plan = &self.config.plan;
         let root_config = AgentConfig {
-            name: "Root".to_string(),
-            role: "You are a strategic coordinator. Synthesize information from sub-agents into a coherent response.".to_string(),
+            name: plan.root.name.clone(),
+            role: plan.root.role.clone(),
             max_depth: self.config.max_depth,
-            spawn_shadow: true,
+            spawn_shadow: plan.root.spawn_shadow,
         };
         let mut root = Agent::new(root_config);
+        self.seed_genome(tenant_id, &plan.root, &mut root).await;
         let root_id = root.id;
 
-        // Spawn child agents for research
-        let child_configs = vec![
-            AgentConfig {
-                name: "Researcher".to_string(),
-                role: "You are a thorough researcher. Analyze the query and provide detailed findings.".to_string(),
-                max_depth: 1,
-                spawn_shadow: true,
-            },
-            AgentConfig {
-                name: "Critic".to_string(),
-                role: "You are a critical analyzer. Identify potential issues, edge cases, and weaknesses.".to_string(),
-                max_depth: 1,
-                spawn_shadow: true,
-            },
-        ];
-
-        // Execute child agents in parallel (no lock held during await)
-        let mut execution_futures = Vec::new();
-        for config in child_configs.into_iter().take(self.config.agents_per_level) {
-            let mut child = root.spawn_child(config);
-            let executor = self.executor.clone();
-            let query_str = query.to_string();
-            let caps = capabilities.clone();
-            let tenant = tenant_id.to_string();
-
-            execution_futures.push(tokio::spawn(async move {
-                let result = executor
-                    .execute(&tenant, &mut child, &query_str, None, caps)
-                    .await;
-                (child.id, child, result)
-            }));
-        }
-
-        let task_results = futures::future::join_all(execution_futures).await;
+        // Execute the hierarchy bottom-up (no lock held during await)
+        let outcome = self
+            .run_node(
+                ctx,
+                tenant_id,
+                query,
+                root,
+                &plan.root,
+                intent_data.clone(),
+                &capabilities,
+            )
+            .await?;
+        let levels_processed = outcome.levels;
 
-        // Re-acquire lock to update agents map
+        // Re-acquire lock to track agents and run evolution
         let mut agents = self.agents.write().await;
 
         let mut all_results: HashMap<Uuid, ExecutionResult> = HashMap::new();
-        let mut child_results = Vec::new();
-        for task_result in task_results {
-            let (child_id, child, result) = task_result.map_err(|e| e.to_string())?;
-            let execution_result: ExecutionResult = result?;
-
-            child_results.push((child_id, execution_result.clone()));
-            all_results.insert(child_id, execution_result);
+        let mut root_result = None;
+        for (agent, result) in outcome.agents {
+            if agent.id == root_id {
+                root_result = Some(result.clone());
+            }
+            all_results.insert(agent.id, result);
             agents.insert(
-                child_id,
+                agent.id,
                 TrackedAgent {
-                    agent: child,
-                    _tenant_id: tenant_id.to_string(),
+                    agent,
+                    tenant_id: tenant_id.to_string(),
                     created_at: Instant::now(),
                 },
             );
         }
-
-        // Drop lock before root synthesis
-        drop(agents);
-
-        // Synthesize child results at root level
-        let synthesis_prompt = format!(
-            "Based on the following research from your sub-agents, provide a comprehensive answer:\n\n\
-             Original Query: \"{}\"\n\n\
-             Researcher's Findings: \"{}\"\n\n\
-             Critic's Analysis: \"{}\"\n\n\
-             Synthesize these into a final, well-reasoned response.",
-            query,
-            child_results.first().map(|(_, r)| r.response.as_str()).unwrap_or("N/A"),
-            child_results.get(1).map(|(_, r)| r.response.as_str()).unwrap_or("N/A"),
-        );
-
-        let root_result = self
-            .executor
-            .execute(
-                tenant_id,
-                &mut root,
-                &synthesis_prompt,
-                intent_data.clone(),
-                capabilities.clone(),
-            )
-            .await?;
-        all_results.insert(root_id, root_result.clone());
-
-        // Re-acquire lock to update root and run evolution
-        let mut agents = self.agents.write().await;
-
-        // Insert root after children are handled
-        agents.insert(
-            root_id,
-            TrackedAgent {
-                agent: root,
-                _tenant_id: tenant_id.to_string(),
-                created_at: Instant::now(),
-            },
-        );
+        let stopped = ctx.stop_reason();
+        let root_result = match (root_result, &stopped) {
+            (Some(result), _) => Some(result),
+            (None, Some(_)) => None,
+            (None, None) => return Err("Root agent produced no result".to_string()),
+        };
+        let response = match &root_result {
+            Some(result) => result.response.clone(),
+            None => all_results
+                .values()
+                .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+                .map(|r| r.response.clone())
+                .unwrap_or_default(),
+        };
 
         // Build Merkle tree from all context packets
         let leaves: Vec<(String, Hash)> = all_results
@@ -341,10 +569,14 @@ impl<L: LlmProvider + ?Sized + 'static> Orchestrator<L> {
 
         // Calculate overall confidence
         let total_confidence: f64 = all_results.values().map(|r| r.confidence).sum();
-        let avg_confidence = total_confidence / all_results.len() as f64;
+        let avg_confidence = if all_results.is_empty() {
+            0.0
+        } else {
+            total_confidence / all_results.len() as f64
+        };
 
-        // Evolution step (if enabled)
-        if self.config.enable_evolution {
+        // Evolution step (if enabled; partial runs would skew fitness)
+        if self.config.enable_evolution && stopped.is_none() {
             if self.config.enable_self_correction {
                 self.evolve_agents_self_correcting(tenant_id, &mut agents, &all_results)
                     .await;
@@ -354,6 +586,14 @@ impl<L: LlmProvider + ?Sized + 'static> Orchestrator<L> {
             }
         }
 
+        // Persist this run's agents after evolution (no lock held during I/O)
+        let run_agents: Vec<Agent> = all_results
+            .keys()
+            .filter_map(|id| agents.get(id).map(|tracked| tracked.agent.clone()))
+            .collect();
+        drop(agents);
+        self.save_agents(tenant_id, &run_agents).await;
+
         // Build trace merkle tree from agent trace roots (ISO 42001 compliance)
         let trace_leaves: Vec<(String, Hash)> = all_results
             .iter()
@@ -363,7 +603,16 @@ impl<L: LlmProvider + ?Sized + 'static> Orchestrator<L> {
 
         // Anchoring Step (Neutral Authority Sync)
         let mut anchor_receipts = Vec::new();
+        let mut pending_anchor = None;
         if let Some(root_hash) = merkle_tree.root_hash() {
+            if let Some(service) = &self.anchor_service {
+                pending_anchor = Some(
+                    service
+                        .submit(tenant_id, root_hash.clone(), all_results.len() as u64)
+                        .await,
+                );
+            }
+
             let metadata = AnchorMetadata::new(tenant_id, all_results.len() as u64);
             for anchor in &self.anchors {
                 match anchor.anchor(root_hash, metadata.clone()).await {
@@ -373,21 +622,29 @@ impl<L: LlmProvider + ?Sized + 'static> Orchestrator<L> {
             }
         }
 
-        let gate_capsule = self
-            .gate
-            .execute_gate(
-                root_id,
-                query,
-                &root_result.response,
-                intent_data,
-                avg_confidence,
-                &capabilities,
-            )
-            .await;
+        let gate_capsule = match &stopped {
+            None => Some(
+                self.gate
+                    .execute_gate(
+                        root_id,
+                        query,
+                        &response,
+                        intent_data,
+                        avg_confidence,
+                        &capabilities,
+                    )
+                    .await,
+            ),
+            Some(reason) => {
+                self.record_stop(ctx, tenant_id, root_id, reason, all_results.len())
+                    .await;
+                None
+            }
+        };
 
         Ok(OrchestrationResult {
             root_agent_id: root_id,
-            response: root_result.response,
+            response,
             merkle_root: merkle_tree
                 .root_hash()
                 .cloned()
@@ -395,9 +652,164 @@ impl<L: LlmProvider + ?Sized + 'static> Orchestrator<L> {
             trace_root: trace_merkle.root_hash().cloned(),
             agent_results: all_results,
             anchor_receipts,
-            levels_processed: 2,
+            pending_anchor,
+            levels_processed,
             confidence: avg_confidence,
-            evidence: Some(gate_capsule),
+            evidence: gate_capsule,
+            stopped,
+        })
+    }
+
+    /// Audit why a run stopped early
+    async fn record_stop(
+        &self,
+        ctx: &RunContext,
+        tenant_id: &str,
+        root_id: Uuid,
+        reason: &StopReason,
+        completed_agents: usize,
+    ) {
+        tracing::warn!(
+            root_agent_id = %root_id,
+            completed_agents = completed_agents,
+            tokens_used = ctx.tokens_used(),
+            "Orchestration stopped early: {}",
+            reason
+        );
+        if let Some(store) = &self.audit_store {
+            if let Err(e) = store
+                .log(
+                    tenant_id,
+                    vex_core::audit::AuditEventType::RunStopped,
+                    vex_core::audit::ActorType::System("orchestrator".to_string()),
+                    Some(root_id),
+                    serde_json::json!({
+                        "stop": reason,
+                        "completed_agents": completed_agents,
+                        "tokens_used": ctx.tokens_used(),
+                        "cost_usd": ctx.cost_usd(),
+                    }),
+                    self.identity.as_ref().map(|id| id.as_ref()),
+                    None,
+                    None,
+                )
+                .await
+            {
+                tracing::warn!("Failed to audit stopped run: {}", e);
+            }
+        }
+    }
+
+    /// Execute one plan node: run its child roles concurrently (recursing while the
+    /// agent can still spawn), then run the node on the query (leaves) or on the
+    /// synthesis of its children's findings. Children run without the stream sink,
+    /// so only the root's answer is streamed.
+    #[allow(clippy::too_many_arguments)]
+    fn run_node<'a>(
+        &'a self,
+        ctx: &'a RunContext,
+        tenant_id: &'a str,
+        query: &'a str,
+        mut agent: Agent,
+        spec: &'a RoleSpec,
+        intent_data: Option<vex_core::segment::IntentData>,
+        capabilities: &'a [vex_llm::Capability],
+    ) -> futures::future::BoxFuture<'a, Result<NodeOutcome, String>> {
+        Box::pin(async move {
+            let mut subtree = Vec::new();
+            let mut findings = Vec::new();
+            let mut child_levels = 0;
+
+            if agent.can_spawn() && !spec.children.is_empty() {
+                let child_specs: Vec<&RoleSpec> = spec
+                    .children
+                    .iter()
+                    .flat_map(|child| std::iter::repeat_n(child, child.fan_out))
+                    .collect();
+                let child_ctx = ctx.without_stream();
+                let mut instance: HashMap<&str, usize> = HashMap::new();
+                let mut names = Vec::new();
+                let mut futures = Vec::new();
+                for child_spec in child_specs {
+                    let n = instance.entry(child_spec.name.as_str()).or_insert(0);
+                    *n += 1;
+                    let name = if child_spec.fan_out > 1 {
+                        format!("{} {}", child_spec.name, n)
+                    } else {
+                        child_spec.name.clone()
+                    };
+
+                    let mut child = agent.spawn_child(AgentConfig {
+                        name,
+                        role: child_spec.role.clone(),
+                        max_depth: agent.config.max_depth,
+                        spawn_shadow: child_spec.spawn_shadow,
+                    });
+                    self.seed_genome(tenant_id, child_spec, &mut child).await;
+                    names.push(child_spec.name.clone());
+                    futures.push(self.run_node(
+                        &child_ctx,
+                        tenant_id,
+                        query,
+                        child,
+                        child_spec,
+                        None,
+                        capabilities,
+                    ));
+                }
+
+                for (name, outcome) in names
+                    .into_iter()
+                    .zip(futures::future::join_all(futures).await)
+                {
+                    let outcome = outcome?;
+                    if let Some((_, result)) = outcome.agents.last().filter(|_| outcome.completed) {
+                        findings.push((name, result.response.clone()));
+                    }
+                    child_levels = child_levels.max(outcome.levels);
+                    subtree.extend(outcome.agents);
+                }
+            }
+
+            let prompt = if findings.is_empty() {
+                query.to_string()
+            } else {
+                spec.render_synthesis(query, &findings)
+            };
+            // A stopped run keeps whatever finished below this node
+            let stopped = |subtree| {
+                Ok(NodeOutcome {
+                    agents: subtree,
+                    levels: child_levels,
+                    completed: false,
+                })
+            };
+            if ctx.check().is_err() {
+                return stopped(subtree);
+            }
+            let result = match self
+                .executor
+                .execute_with_context(
+                    ctx,
+                    tenant_id,
+                    &mut agent,
+                    &prompt,
+                    intent_data,
+                    capabilities.to_vec(),
+                )
+                .await
+            {
+                Ok(result) => result,
+                Err(_) if ctx.stop_reason().is_some() => return stopped(subtree),
+                Err(e) => return Err(e),
+            };
+            subtree.push((agent, result));
+
+            Ok(NodeOutcome {
+                agents: subtree,
+                levels: child_levels + 1,
+                completed: true,
+            })
         })
     }
 
@@ -413,11 +825,12 @@ impl<L: LlmProvider + ?Sized + 'static> Orchestrator<L> {
         // Build population with fitness scores from actual agent genomes
         let population: Vec<(Genome, Fitness)> = agents
             .values()
+            .filter(|tracked| tracked.tenant_id == tenant_id)
             .map(|tracked| {
                 let fitness = results
                     .get(&tracked.agent.id)
                     .map(|r| r.confidence)
-                    .unwrap_or(0.5);
+                    .unwrap_or(tracked.agent.fitness);
                 (tracked.agent.genome.clone(), Fitness::new(fitness))
             })
             .collect();
@@ -757,6 +1170,9 @@ mod tests {
                 tokens_used: Some(10),
                 latency_ms: 10,
                 trace_root: None,
+                tool_calls: Vec::new(),
+                provider: None,
+                cached: false,
             })
         }
     }
@@ -767,7 +1183,7 @@ mod tests {
         let gate = Arc::new(crate::gate::GenericGateMock);
         let mut config = OrchestratorConfig::default();
         config.executor_config.enable_adversarial = false;
-        let orchestrator = Orchestrator::new(llm, config, None, gate);
+        let orchestrator = Orchestrator::new(llm, config, None, gate).unwrap();
 
         let result = orchestrator
             .process("test-tenant", "What is the meaning of life?", None, vec![])
@@ -778,4 +1194,387 @@ mod tests {
         assert!(result.confidence > 0.0);
         assert!(!result.agent_results.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_orchestrator_agent_persistence() {
+        use vex_persist::backend::MemoryBackend;
+
+        let backend: Arc<dyn StorageBackend> = Arc::new(MemoryBackend::new());
+        let agent_store = Arc::new(AgentStore::new(backend.clone()));
+        let context_store = Arc::new(ContextStore::new(backend));
+        let orchestrator = |max_stored_agents: usize| {
+            let mut config = OrchestratorConfig {
+                max_stored_agents,
+                ..Default::default()
+            };
+            config.executor_config.enable_adversarial = false;
+            Orchestrator::new(
+                Arc::new(MockLlm),
+                config,
+                None,
+                Arc::new(crate::gate::GenericGateMock),
+            )
+            .unwrap()
+            .with_agent_persistence(agent_store.clone(), context_store.clone())
+        };
+
+        let first = orchestrator(256);
+        let result = first
+            .process("test-tenant", "What is the meaning of life?", None, vec![])
+            .await
+            .unwrap();
+        let saved = agent_store.list("test-tenant").await.unwrap();
+        assert_eq!(saved.len(), result.agent_results.len());
+        let contexts = context_store.count("test-tenant").await.unwrap();
+        assert_eq!(contexts, saved.len());
+
+        // A fresh orchestrator (restart or another replica) resumes the population
+        let second = orchestrator(saved.len());
+        assert_eq!(
+            second.load_agents("test-tenant").await.unwrap(),
+            saved.len()
+        );
+        {
+            let agents = second.agents.read().await;
+            let root = &agents[&result.root_agent_id].agent;
+            assert_eq!(root.context.content, result.response);
+            assert_eq!(
+                root.genome.traits,
+                first.agents.read().await[&result.root_agent_id]
+                    .agent
+                    .genome
+                    .traits
+            );
+        }
+
+        // Re-saving unchanged agents does not copy their contexts again
+        let unchanged: Vec<Agent> = second
+            .agents
+            .read()
+            .await
+            .values()
+            .map(|t| t.agent.clone())
+            .collect();
+        second.save_agents("test-tenant", &unchanged).await;
+        assert_eq!(context_store.count("test-tenant").await.unwrap(), contexts);
+
+        // The stored population is capped: the previous run's agents make room
+        let next = second
+            .process("test-tenant", "And the second question?", None, vec![])
+            .await
+            .unwrap();
+        let stored = agent_store.list("test-tenant").await.unwrap();
+        assert_eq!(stored.len(), saved.len());
+        assert!(stored.contains(&next.root_agent_id));
+        assert!(!stored.contains(&result.root_agent_id));
+        assert_eq!(
+            context_store.count("test-tenant").await.unwrap(),
+            stored.len()
+        );
+        assert_eq!(second.agent_count().await, saved.len());
+
+        // Expiry only evicts from memory
+        let mut expiring = orchestrator(256);
+        expiring.config.max_agent_age = Duration::ZERO;
+        assert_eq!(
+            expiring.load_agents("test-tenant").await.unwrap(),
+            saved.len()
+        );
+        assert_eq!(expiring.cleanup_expired().await, saved.len());
+        assert_eq!(
+            agent_store.list("test-tenant").await.unwrap().len(),
+            saved.len()
+        );
+        assert_eq!(second.load_agents("other-tenant").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_orchestrator_seeds_agents_from_stored_population() {
+        use vex_persist::backend::MemoryBackend;
+
+        let backend: Arc<dyn StorageBackend> = Arc::new(MemoryBackend::new());
+        let agent_store = Arc::new(AgentStore::new(backend.clone()));
+        let context_store = Arc::new(ContextStore::new(backend));
+        let plan = OrchestrationPlan::default();
+        let researcher = &plan.root.children[0];
+
+        let stored = |fitness: f64, value: f64| {
+            let mut agent = Agent::new(AgentConfig {
+                name: researcher.name.clone(),
+                role: researcher.role.clone(),
+                ..Default::default()
+            });
+            agent.fitness = fitness;
+            agent.genome.traits[0] = value;
+            agent
+        };
+        let fittest = stored(0.9, 0.91);
+        let weaker = stored(0.2, 0.12);
+        for agent in [&fittest, &weaker] {
+            agent_store.save("test-tenant", agent).await.unwrap();
+        }
+
+        let mut config = OrchestratorConfig {
+            enable_evolution: false,
+            ..Default::default()
+        };
+        config.executor_config.enable_adversarial = false;
+        let orchestrator = Orchestrator::new(
+            Arc::new(MockLlm),
+            config,
+            None,
+            Arc::new(crate::gate::GenericGateMock),
+        )
+        .unwrap()
+        .with_agent_persistence(agent_store, context_store);
+
+        let result = orchestrator
+            .process("test-tenant", "What is the meaning of life?", None, vec![])
+            .await
+            .unwrap();
+
+        let agents = orchestrator.agents.read().await;
+        let spawned: Vec<&Agent> = result
+            .agent_results
+            .keys()
+            .map(|id| &agents[id].agent)
+            .filter(|a| a.config.role == researcher.role)
+            .collect();
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].genome.traits, fittest.genome.traits);
+    }
+
+    #[tokio::test]
+    async fn test_orchestrator_custom_plan() {
+        let plan = OrchestrationPlan::new(
+            "code-review",
+            RoleSpec::new("Lead", "You are the review lead.")
+                .with_children(vec![
+                    RoleSpec::new("Security", "You are a security reviewer.")
+                        .with_fan_out(2)
+                        .with_trait("skepticism", 0.9),
+                    RoleSpec::new("Style", "You review readability.").with_children(vec![
+                        RoleSpec::new("Naming", "You are a critic of naming."),
+                    ]),
+                ])
+                .with_synthesis_template("Merge: {{role:Security}} / {{role:Style}}"),
+        );
+
+        let mut config = OrchestratorConfig {
+            agents_per_level: 4,
+            plan,
+            ..Default::default()
+        };
+        config.executor_config.enable_adversarial = false;
+        config.enable_evolution = false;
+        let orchestrator = Orchestrator::new(
+            Arc::new(MockLlm),
+            config,
+            None,
+            Arc::new(crate::gate::GenericGateMock),
+        )
+        .unwrap();
+
+        let result = orchestrator
+            .process("test-tenant", "Review this diff", None, vec![])
+            .await
+            .unwrap();
+
+        // Lead + 2 Security + Style + Naming
+        assert_eq!(result.agent_results.len(), 5);
+        assert_eq!(result.levels_processed, 3);
+
+        let agents = orchestrator.agents.read().await;
+        let security: Vec<_> = agents
+            .values()
+            .filter(|t| t.agent.config.name.starts_with("Security"))
+            .collect();
+        assert_eq!(security.len(), 2);
+        assert_eq!(security[0].agent.genome.get_trait("skepticism"), Some(0.9));
+        let naming = agents
+            .values()
+            .find(|t| t.agent.config.name == "Naming")
+            .unwrap();
+        assert_eq!(naming.agent.depth, 2);
+    }
+
+    #[test]
+    fn test_orchestrator_rejects_wide_plan() {
+        let plan = OrchestrationPlan::new(
+            "wide",
+            RoleSpec::new("Lead", "You lead.").with_children(vec![
+                RoleSpec::new("A", "a").with_fan_out(2),
+                RoleSpec::new("B", "b"),
+            ]),
+        );
+        let config = OrchestratorConfig {
+            plan,
+            ..Default::default()
+        };
+        let err = Orchestrator::new(
+            Arc::new(MockLlm),
+            config,
+            None,
+            Arc::new(crate::gate::GenericGateMock),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("3 child agents"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_orchestrator_depth_limit() {
+        let plan = OrchestrationPlan::new(
+            "deep",
+            RoleSpec::new("A", "a").with_children(vec![
+                RoleSpec::new("B", "b").with_children(vec![RoleSpec::new("C", "c")])
+            ]),
+        );
+        let mut config = OrchestratorConfig {
+            max_depth: 1,
+            plan,
+            ..Default::default()
+        };
+        config.executor_config.enable_adversarial = false;
+        let orchestrator = Orchestrator::new(
+            Arc::new(MockLlm),
+            config,
+            None,
+            Arc::new(crate::gate::GenericGateMock),
+        )
+        .unwrap();
+
+        let result = orchestrator
+            .process("test-tenant", "q", None, vec![])
+            .await
+            .unwrap();
+        assert_eq!(result.levels_processed, 2);
+        assert_eq!(result.agent_results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_orchestrator_token_budget_partial_result() {
+        use vex_persist::backend::MemoryBackend;
+
+        let mut config = OrchestratorConfig::default();
+        config.executor_config.enable_adversarial = false;
+        let audit_store = Arc::new(vex_persist::AuditStore::new(
+            Arc::new(MemoryBackend::new()) as Arc<dyn vex_persist::StorageBackend>
+        ));
+        let identity = Arc::new(vex_hardware::api::AgentIdentity::new());
+        let orchestrator = Orchestrator::new(
+            Arc::new(MockLlm),
+            config,
+            None,
+            Arc::new(crate::gate::GenericGateMock),
+        )
+        .unwrap()
+        .with_identity(identity, audit_store.clone());
+
+        // Each call reports 10 tokens: both children run, the root synthesis is refused
+        let ctx = RunContext::new().with_token_budget(20);
+        let result = orchestrator
+            .process_with_context(&ctx, "test-tenant", "Test query", None, vec![])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.stopped,
+            Some(StopReason::TokenBudgetExhausted {
+                used: 20,
+                limit: 20
+            })
+        );
+        assert_eq!(result.agent_results.len(), 2);
+        assert!(!result.agent_results.contains_key(&result.root_agent_id));
+        assert!(result.evidence.is_none());
+        assert!(!result.response.is_empty());
+
+        let events = audit_store.get_chain("test-tenant").await.unwrap();
+        let stop = events
+            .iter()
+            .find(|e| e.event_type == vex_core::audit::AuditEventType::RunStopped)
+            .unwrap();
+        assert_eq!(stop.data["completed_agents"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_orchestrator_streams_root_only() {
+        let mut config = OrchestratorConfig::default();
+        config.executor_config.enable_adversarial = false;
+        let orchestrator = Orchestrator::new(
+            Arc::new(MockLlm),
+            config,
+            None,
+            Arc::new(crate::gate::GenericGateMock),
+        )
+        .unwrap();
+
+        let (tx, mut rx) = tokio::sync::broadcast::channel(256);
+        let ctx = RunContext::new().with_stream(tx);
+        let result = orchestrator
+            .process_with_context(&ctx, "test-tenant", "Test query", None, vec![])
+            .await
+            .unwrap();
+        assert!(result.agent_results.len() > 1);
+
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        let finishes = events
+            .iter()
+            .filter(|e| matches!(e, vex_llm::StreamEvent::Finish { .. }))
+            .count();
+        assert_eq!(finishes, 1);
+        assert!(matches!(
+            events.last(),
+            Some(vex_llm::StreamEvent::Finish { .. })
+        ));
+        let text: String = events
+            .iter()
+            .filter_map(|e| match e {
+                vex_llm::StreamEvent::Delta { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(text.starts_with("Synthesized response"), "{}", text);
+    }
+
+    #[tokio::test]
+    async fn test_orchestrator_batched_anchoring() {
+        let dir = tempfile::tempdir().unwrap();
+        let anchor = Arc::new(vex_anchor::FileAnchor::new(dir.path().join("anchors.json")));
+        let service =
+            AnchorService::new(vec![anchor], crate::anchoring::AnchorPolicy::default()).unwrap();
+
+        let mut config = OrchestratorConfig::default();
+        config.executor_config.enable_adversarial = false;
+        let orchestrator = Orchestrator::new(
+            Arc::new(MockLlm),
+            config,
+            None,
+            Arc::new(crate::gate::GenericGateMock),
+        )
+        .unwrap()
+        .with_anchor_service(service.clone());
+
+        let first = orchestrator
+            .process("test-tenant", "First query", None, vec![])
+            .await
+            .unwrap();
+        let second = orchestrator
+            .process("test-tenant", "Second query", None, vec![])
+            .await
+            .unwrap();
+        assert_eq!(service.pending("test-tenant").await, 2);
+
+        // Both runs are anchored under one aggregate root
+        service.flush_all().await;
+        let a = first.pending_anchor.unwrap().wait().await.unwrap();
+        let b = second.pending_anchor.unwrap().wait().await.unwrap();
+        assert_eq!(a.root, first.merkle_root);
+        assert_eq!(b.root, second.merkle_root);
+        assert_eq!(a.batch_root, b.batch_root);
+        assert!(a.verify() && b.verify());
+    }
 }
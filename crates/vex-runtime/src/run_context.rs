@@ -0,0 +1,428 @@
+//! Cancellation, deadlines and budgets for a single run
+//!
+//! A [`RunContext`] is shared (by clone) across everything a run does: the
+//! orchestrator hierarchy, each agent execution, the adversarial debate and
+//! every LLM call. LLM calls made through [`RunContext::complete`] race the
+//! provider against cancellation and the deadline, and charge their token usage
+//! to the run's budget. Once the context stops, the first [`StopReason`] is kept
+//! so callers can return partial results and record why the run ended.
+//!
+//! With a stream sink attached ([`RunContext::with_stream`]), calls made through
+//! [`RunContext::complete_streamed`] forward token deltas to the sink as they
+//! arrive.
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use crate::transcript::TranscriptRecorder;
+use vex_llm::{LlmError, LlmProvider, LlmRequest, LlmResponse, StreamCollector, StreamEvent};
+
+/// Why a run stopped before completing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum StopReason {
+    /// The caller cancelled the run
+    Cancelled,
+    /// The wall-clock deadline passed
+    DeadlineExceeded,
+    /// Token usage reached the budget
+    TokenBudgetExhausted { used: u64, limit: u64 },
+    /// Estimated cost reached the budget
+    CostBudgetExhausted { cost_usd: f64, limit_usd: f64 },
+}
+
+impl std::fmt::Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::DeadlineExceeded => write!(f, "deadline exceeded"),
+            Self::TokenBudgetExhausted { used, limit } => {
+                write!(f, "token budget exhausted ({}/{} tokens)", used, limit)
+            }
+            Self::CostBudgetExhausted {
+                cost_usd,
+                limit_usd,
+            } => write!(
+                f,
+                "cost budget exhausted (${:.4}/${:.4})",
+                cost_usd, limit_usd
+            ),
+        }
+    }
+}
+
+/// Errors from LLM calls made through a [`RunContext`]
+#[derive(Debug, Error)]
+pub enum RunError {
+    #[error("Run stopped: {0}")]
+    Stopped(StopReason),
+    #[error(transparent)]
+    Llm(#[from] LlmError),
+}
+
+/// Token and cost limits for a run
+#[derive(Debug, Clone, Default)]
+pub struct RunBudget {
+    /// Maximum tokens across all LLM calls
+    pub max_tokens: Option<u64>,
+    /// Maximum estimated spend in USD
+    pub max_cost_usd: Option<f64>,
+    /// Blended price used to estimate spend
+    pub usd_per_1k_tokens: f64,
+}
+
+/// Cancellation token, deadline and budget shared by one run
+#[derive(Debug, Clone, Default)]
+pub struct RunContext {
+    cancel: CancellationToken,
+    deadline: Option<Instant>,
+    budget: RunBudget,
+    tokens_used: Arc<AtomicU64>,
+    stopped: Arc<Mutex<Option<StopReason>>>,
+    transcript: Option<Arc<TranscriptRecorder>>,
+    stream: Option<broadcast::Sender<StreamEvent>>,
+}
+
+impl RunContext {
+    /// Unbounded context (never stops unless cancelled)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop the run at `deadline`
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stop the run `timeout` from now
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Stop the run once `max_tokens` have been used
+    pub fn with_token_budget(mut self, max_tokens: u64) -> Self {
+        self.budget.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Stop the run once estimated spend reaches `max_cost_usd`
+    pub fn with_cost_budget(mut self, max_cost_usd: f64, usd_per_1k_tokens: f64) -> Self {
+        self.budget.max_cost_usd = Some(max_cost_usd);
+        self.budget.usd_per_1k_tokens = usd_per_1k_tokens;
+        self
+    }
+
+    /// Use an externally owned cancellation token
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Record (or replay) every LLM call into `recorder`
+    pub(crate) fn with_transcript(mut self, recorder: Arc<TranscriptRecorder>) -> Self {
+        self.transcript = Some(recorder);
+        self
+    }
+
+    /// Forward the events of streamed calls to `sink`
+    pub fn with_stream(mut self, sink: broadcast::Sender<StreamEvent>) -> Self {
+        self.stream = Some(sink);
+        self
+    }
+
+    /// The same run (sharing cancellation, deadline and budget) without the
+    /// stream sink, for calls whose output should not reach the stream
+    pub fn without_stream(&self) -> Self {
+        Self {
+            stream: None,
+            ..self.clone()
+        }
+    }
+
+    pub(crate) fn transcript(&self) -> Option<&TranscriptRecorder> {
+        self.transcript.as_deref()
+    }
+
+    /// Token that cancels this run (clone it to cancel from elsewhere)
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+
+    /// Cancel the run; in-flight LLM calls are abandoned
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    pub fn budget(&self) -> &RunBudget {
+        &self.budget
+    }
+
+    /// Tokens charged to this run so far
+    pub fn tokens_used(&self) -> u64 {
+        self.tokens_used.load(Ordering::SeqCst)
+    }
+
+    /// Estimated spend so far in USD
+    pub fn cost_usd(&self) -> f64 {
+        self.tokens_used() as f64 / 1000.0 * self.budget.usd_per_1k_tokens
+    }
+
+    /// First reason the run stopped, if it has
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stopped.lock().ok().and_then(|s| s.clone())
+    }
+
+    fn stop(&self, reason: StopReason) -> StopReason {
+        match self.stopped.lock() {
+            Ok(mut stopped) => stopped.get_or_insert(reason).clone(),
+            Err(_) => reason,
+        }
+    }
+
+    /// Fail if the run has been cancelled, is past its deadline or is over budget
+    pub fn check(&self) -> Result<(), StopReason> {
+        if let Some(reason) = self.stop_reason() {
+            return Err(reason);
+        }
+        if self.cancel.is_cancelled() {
+            return Err(self.stop(StopReason::Cancelled));
+        }
+        if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            return Err(self.stop(StopReason::DeadlineExceeded));
+        }
+        if let Some(limit) = self.budget.max_tokens {
+            let used = self.tokens_used();
+            if used >= limit {
+                return Err(self.stop(StopReason::TokenBudgetExhausted { used, limit }));
+            }
+        }
+        if let Some(limit_usd) = self.budget.max_cost_usd {
+            let cost_usd = self.cost_usd();
+            if cost_usd >= limit_usd {
+                return Err(self.stop(StopReason::CostBudgetExhausted {
+                    cost_usd,
+                    limit_usd,
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    /// Charge tokens to the run's budget
+    pub fn record_usage(&self, tokens: u64) {
+        self.tokens_used.fetch_add(tokens, Ordering::SeqCst);
+    }
+
+    /// Call `llm` unless the run has stopped, abandoning the call on cancellation
+    /// or deadline.
+    ///
+    /// Usage comes from `tokens_used`; providers that do not report it are charged
+    /// an estimate of four bytes per token over the request and response. A call
+    /// that pushes the run over budget still returns its response; the next call
+    /// is refused.
+    ///
+    /// With a transcript attached, requests carry the transcript seed and each
+    /// call is recorded; when replaying, the recorded response is returned
+    /// instead of calling `llm`.
+    pub async fn complete<L: LlmProvider + ?Sized>(
+        &self,
+        llm: &L,
+        request: LlmRequest,
+    ) -> Result<LlmResponse, RunError> {
+        self.call(llm, request, None).await
+    }
+
+    /// Like [`complete`](Self::complete), but streams the call and forwards its
+    /// events to the stream sink, if one is attached. Replayed calls are not
+    /// forwarded.
+    pub async fn complete_streamed<L: LlmProvider + ?Sized>(
+        &self,
+        llm: &L,
+        request: LlmRequest,
+    ) -> Result<LlmResponse, RunError> {
+        self.call(llm, request, self.stream.as_ref()).await
+    }
+
+    async fn call<L: LlmProvider + ?Sized>(
+        &self,
+        llm: &L,
+        mut request: LlmRequest,
+        sink: Option<&broadcast::Sender<StreamEvent>>,
+    ) -> Result<LlmResponse, RunError> {
+        self.check().map_err(RunError::Stopped)?;
+        let request_bytes = request.input_len();
+
+        let recorder = self.transcript.as_deref();
+        if let Some(recorder) = recorder {
+            request.seed.get_or_insert(recorder.seed());
+            if recorder.is_replay() {
+                let response = recorder.replay_llm(&request)?;
+                self.record_usage(Self::usage(request_bytes, &response));
+                return Ok(response);
+            }
+        }
+        let recorded_request = recorder.map(|_| request.clone());
+
+        let deadline = async {
+            match self.deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        let response = tokio::select! {
+            _ = self.cancel.cancelled() => {
+                return Err(RunError::Stopped(self.stop(StopReason::Cancelled)));
+            }
+            _ = deadline => {
+                return Err(RunError::Stopped(self.stop(StopReason::DeadlineExceeded)));
+            }
+            response = Self::invoke(llm, request, sink) => response?,
+        };
+
+        if let (Some(recorder), Some(request)) = (recorder, recorded_request) {
+            recorder.record_llm(request, response.clone());
+        }
+        self.record_usage(Self::usage(request_bytes, &response));
+        Ok(response)
+    }
+
+    async fn invoke<L: LlmProvider + ?Sized>(
+        llm: &L,
+        request: LlmRequest,
+        sink: Option<&broadcast::Sender<StreamEvent>>,
+    ) -> Result<LlmResponse, LlmError> {
+        let Some(sink) = sink else {
+            return llm.complete(request).await;
+        };
+        let mut stream = llm.complete_stream(request).await?;
+        let mut collector = StreamCollector::new();
+        while let Some(event) = stream.next().await {
+            let event = event?;
+            collector.push(&event);
+            // Sending only fails when nobody is listening
+            let _ = sink.send(event);
+        }
+        Ok(collector.into_response())
+    }
+
+    fn usage(request_bytes: usize, response: &LlmResponse) -> u64 {
+        response
+            .tokens_used
+            .map(u64::from)
+            .unwrap_or(((request_bytes + response.content.len()) / 4) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    #[derive(Debug)]
+    struct SlowLlm;
+
+    #[async_trait]
+    impl LlmProvider for SlowLlm {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn complete(&self, _request: LlmRequest) -> Result<LlmResponse, LlmError> {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(LlmResponse {
+                content: "done".to_string(),
+                model: "slow".to_string(),
+                tokens_used: Some(100),
+                latency_ms: 50,
+                trace_root: None,
+                tool_calls: Vec::new(),
+                provider: None,
+                cached: false,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_context_budget_and_deadline() {
+        let ctx = RunContext::new().with_token_budget(150);
+        ctx.complete(&SlowLlm, LlmRequest::simple("a"))
+            .await
+            .unwrap();
+        ctx.complete(&SlowLlm, LlmRequest::simple("b"))
+            .await
+            .unwrap();
+        assert_eq!(ctx.tokens_used(), 200);
+        assert!(matches!(
+            ctx.complete(&SlowLlm, LlmRequest::simple("c")).await,
+            Err(RunError::Stopped(StopReason::TokenBudgetExhausted {
+                used: 200,
+                limit: 150
+            }))
+        ));
+
+        let ctx = RunContext::new().with_timeout(Duration::from_millis(10));
+        assert!(matches!(
+            ctx.complete(&SlowLlm, LlmRequest::simple("a")).await,
+            Err(RunError::Stopped(StopReason::DeadlineExceeded))
+        ));
+        assert_eq!(ctx.stop_reason(), Some(StopReason::DeadlineExceeded));
+    }
+
+    #[tokio::test]
+    async fn test_run_context_cancel_in_flight() {
+        let ctx = RunContext::new();
+        let canceller = ctx.cancellation_token();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            canceller.cancel();
+        });
+        assert!(matches!(
+            ctx.complete(&SlowLlm, LlmRequest::simple("a")).await,
+            Err(RunError::Stopped(StopReason::Cancelled))
+        ));
+        assert_eq!(ctx.tokens_used(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_run_context_stream_sink() {
+        let (tx, mut rx) = broadcast::channel(64);
+        let ctx = RunContext::new().with_stream(tx);
+        let llm = vex_llm::MockProvider::constant("streamed answer");
+
+        let response = ctx
+            .complete_streamed(&llm, LlmRequest::simple("a"))
+            .await
+            .unwrap();
+        assert_eq!(response.content, "streamed answer");
+        assert_eq!(ctx.tokens_used(), u64::from(response.tokens_used.unwrap()));
+
+        let mut text = String::new();
+        while let Ok(event) = rx.try_recv() {
+            if let StreamEvent::Delta { text: delta } = event {
+                text.push_str(&delta);
+            }
+        }
+        assert_eq!(text, "streamed answer");
+
+        // Plain calls are not forwarded
+        ctx.complete(&llm, LlmRequest::simple("b")).await.unwrap();
+        assert!(rx.try_recv().is_err());
+    }
+}
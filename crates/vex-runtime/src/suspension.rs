@@ -0,0 +1,354 @@
+//! Suspended executions awaiting human approval
+//!
+//! When the gate escalates an execution (outcome `ESCALATE`), or a privileged
+//! action arrives without a continuation token, an executor configured with a
+//! [`SuspensionStore`] persists everything needed to finish the run instead of
+//! failing it: the agent snapshot, inputs, the gated answer, the debate and
+//! the evidence capsule. The escalation is recorded in the coordination ledger
+//! so it shows up in `/api/v1/governance/escalations`.
+//!
+//! Once a reviewer issues a [`ContinuationToken`], [`AgentExecutor::resume`]
+//! finishes the run. The token must name the escalation as its
+//! `ledger_event_id` and be bound to the suspended capsule's root, so it cannot
+//! authorize any other execution.
+//!
+//! [`AgentExecutor::resume`]: crate::AgentExecutor::resume
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::tool_loop::ToolCallRecord;
+use vex_adversarial::Debate;
+use vex_core::audit::EvidenceCapsule;
+use vex_core::segment::{AuthorityData, IntentData};
+use vex_core::vep::{VepPacket, VepSegmentType};
+use vex_core::{Agent, ContinuationToken};
+use vex_llm::Capability;
+use vex_persist::{
+    CoordinationStore, PersistentCoordinationStore, StorageBackend, StorageError, StorageExt,
+};
+
+/// Error prefix for executions that were suspended rather than failed
+pub const EXECUTION_SUSPENDED: &str = "EXECUTION_SUSPENDED";
+
+/// Escalation ID from an executor error, if the execution was suspended
+pub fn suspended_escalation_id(error: &str) -> Option<&str> {
+    error
+        .strip_prefix(EXECUTION_SUSPENDED)?
+        .strip_prefix(": escalation ")?
+        .split_whitespace()
+        .next()
+}
+
+/// Root the capsule commits to: the VEP header root, or the capsule ID for
+/// gates that do not produce a VEP
+pub fn capsule_root(capsule: &EvidenceCapsule) -> String {
+    capsule
+        .vep_blob
+        .as_deref()
+        .and_then(|blob| VepPacket::new(blob).ok())
+        .map(|packet| hex::encode(packet.header().capsule_root))
+        .unwrap_or_else(|| capsule.capsule_id.clone())
+}
+
+/// Escalation the capsule belongs to: the CHORA escalation ID from the VEP
+/// authority pillar, then the ledger event of any attached token, then the
+/// capsule ID
+pub fn escalation_id(capsule: &EvidenceCapsule) -> String {
+    capsule
+        .vep_blob
+        .as_deref()
+        .and_then(|blob| VepPacket::new(blob).ok())
+        .and_then(|packet| {
+            let data = packet.get_segment_data(VepSegmentType::Authority)?;
+            serde_json::from_slice::<AuthorityData>(data).ok()
+        })
+        .and_then(|authority| authority.escalation_id)
+        .or_else(|| {
+            capsule
+                .continuation_token
+                .as_ref()
+                .map(|token| token.payload.ledger_event_id.clone())
+        })
+        .unwrap_or_else(|| capsule.capsule_id.clone())
+}
+
+/// An execution paused at the gate until a continuation token is issued
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspendedExecution {
+    pub escalation_id: String,
+    pub tenant_id: String,
+    /// Root of the capsule any continuation token must be bound to
+    pub capsule_root: String,
+    /// Agent state before the gate (updated by a successful resume)
+    pub agent: Agent,
+    pub prompt: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intent_data: Option<IntentData>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<Capability>,
+    /// The gated answer
+    pub response: String,
+    pub verified: bool,
+    pub confidence: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debate: Option<Debate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCallRecord>,
+    /// Gate decision that suspended the execution
+    pub evidence: EvidenceCapsule,
+    pub suspended_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SuspendedExecution {
+    /// Check that `token` continues this execution and is within its lifetime
+    pub fn check_token(&self, token: &ContinuationToken) -> Result<(), String> {
+        let payload = &token.payload;
+        if payload.ledger_event_id != self.escalation_id {
+            return Err(format!(
+                "AEM_GOVERNANCE_VIOLATION: Token resolves escalation {}, not {}",
+                payload.ledger_event_id, self.escalation_id
+            ));
+        }
+        if payload.source_capsule_root != self.capsule_root {
+            return Err(format!(
+                "AEM_GOVERNANCE_VIOLATION: Token is bound to capsule root {}, not {}",
+                payload.source_capsule_root, self.capsule_root
+            ));
+        }
+        payload
+            .validate_lifecycle(chrono::Utc::now())
+            .map_err(|e| format!("AEM_GOVERNANCE_VIOLATION: {}", e))
+    }
+}
+
+/// Suspended execution store for persistence
+///
+/// Suspensions are also recorded as escalations in the coordination ledger on
+/// the same backend.
+#[derive(Clone)]
+pub struct SuspensionStore {
+    backend: Arc<dyn StorageBackend>,
+    prefix: String,
+}
+
+impl std::fmt::Debug for SuspensionStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SuspensionStore")
+            .field("backend", &self.backend.name())
+            .finish()
+    }
+}
+
+impl SuspensionStore {
+    /// Create a new suspension store
+    pub fn new(backend: Arc<dyn StorageBackend>) -> Self {
+        Self {
+            backend,
+            prefix: "suspension:".to_string(),
+        }
+    }
+
+    fn key(&self, tenant_id: &str, escalation_id: &str) -> String {
+        format!("{}tenant:{}:{}", self.prefix, tenant_id, escalation_id)
+    }
+
+    /// Persist a suspended execution and record its escalation
+    pub async fn suspend(&self, suspended: &SuspendedExecution) -> Result<(), StorageError> {
+        self.backend
+            .set(
+                &self.key(&suspended.tenant_id, &suspended.escalation_id),
+                suspended,
+            )
+            .await?;
+        PersistentCoordinationStore::new(self.backend.clone())
+            .record_escalation(
+                &suspended.tenant_id,
+                suspended.escalation_id.clone(),
+                suspended.agent.id,
+                suspended.evidence.continuation_token.clone(),
+            )
+            .await
+    }
+
+    /// Load a suspended execution
+    pub async fn get(
+        &self,
+        tenant_id: &str,
+        escalation_id: &str,
+    ) -> Result<Option<SuspendedExecution>, StorageError> {
+        self.backend.get(&self.key(tenant_id, escalation_id)).await
+    }
+
+    /// Take a suspended execution out of the store so only one caller resumes it
+    ///
+    /// Returns None if it does not exist or another caller claimed it first. Put it
+    /// back with [`SuspensionStore::restore`] if the resume fails.
+    pub async fn claim(
+        &self,
+        tenant_id: &str,
+        escalation_id: &str,
+    ) -> Result<Option<SuspendedExecution>, StorageError> {
+        let Some(suspended) = self.get(tenant_id, escalation_id).await? else {
+            return Ok(None);
+        };
+        // Only the caller whose delete removes the key owns the execution
+        if self.remove(tenant_id, escalation_id).await? {
+            Ok(Some(suspended))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Return a claimed execution to the store (its escalation is already recorded)
+    pub async fn restore(&self, suspended: &SuspendedExecution) -> Result<(), StorageError> {
+        self.backend
+            .set(
+                &self.key(&suspended.tenant_id, &suspended.escalation_id),
+                suspended,
+            )
+            .await
+    }
+
+    /// Remove a suspended execution (after it resumed)
+    pub async fn remove(&self, tenant_id: &str, escalation_id: &str) -> Result<bool, StorageError> {
+        self.backend
+            .delete(&self.key(tenant_id, escalation_id))
+            .await
+    }
+
+    /// List a tenant's suspended executions
+    pub async fn list(&self, tenant_id: &str) -> Result<Vec<SuspendedExecution>, StorageError> {
+        let prefix = format!("{}tenant:{}:", self.prefix, tenant_id);
+        let mut out = Vec::new();
+        for key in self.backend.list_keys(&prefix).await? {
+            if let Some(suspended) = self.backend.get(&key).await? {
+                out.push(suspended);
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vex_core::segment::ContinuationPayload;
+    use vex_core::AgentConfig;
+    use vex_persist::backend::MemoryBackend;
+
+    fn capsule() -> EvidenceCapsule {
+        EvidenceCapsule {
+            capsule_id: "cap-1".to_string(),
+            outcome: "ESCALATE".to_string(),
+            reason_code: "HUMAN_REVIEW".to_string(),
+            witness_receipt: "receipt".to_string(),
+            nonce: 0,
+            magpie_source: None,
+            gate_sensors: vex_core::segment::SchemaValue(serde_json::Value::Null),
+            reproducibility_context: vex_core::segment::SchemaValue(serde_json::Value::Null),
+            resolution_vep_hash: None,
+            continuation_token: None,
+            intent_data: None,
+            vep_blob: None,
+        }
+    }
+
+    fn token(ledger_event_id: &str, root: &str, exp: chrono::Duration) -> ContinuationToken {
+        let now = chrono::Utc::now();
+        ContinuationToken {
+            payload: ContinuationPayload {
+                schema: "chora.continuation.token.v1".to_string(),
+                ledger_event_id: ledger_event_id.to_string(),
+                aid: "aid".to_string(),
+                source_capsule_root: root.to_string(),
+                circuit_id: None,
+                resolution_event_id: None,
+                capabilities: vec![],
+                nonce: "n".to_string(),
+                iat: now.to_rfc3339(),
+                exp: (now + exp).to_rfc3339(),
+                issuer: "chora".to_string(),
+            },
+            signature: "sig".to_string(),
+        }
+    }
+
+    fn suspended() -> SuspendedExecution {
+        let capsule = capsule();
+        SuspendedExecution {
+            escalation_id: escalation_id(&capsule),
+            tenant_id: "tenant".to_string(),
+            capsule_root: capsule_root(&capsule),
+            agent: Agent::new(AgentConfig::default()),
+            prompt: "Deploy".to_string(),
+            intent_data: None,
+            capabilities: vec![],
+            response: "Deploying".to_string(),
+            verified: false,
+            confidence: 0.8,
+            debate: None,
+            tool_calls: vec![],
+            evidence: capsule,
+            suspended_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_suspended_escalation_id() {
+        let err = format!(
+            "{}: escalation esc-1 awaiting continuation token (transcript ab12)",
+            EXECUTION_SUSPENDED
+        );
+        assert_eq!(suspended_escalation_id(&err), Some("esc-1"));
+        assert_eq!(
+            suspended_escalation_id("Gate Blocking: LOW_CONFIDENCE"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_token_binding() {
+        let suspended = suspended();
+        assert_eq!(suspended.capsule_root, "cap-1");
+        let hour = chrono::Duration::hours(1);
+
+        assert!(suspended
+            .check_token(&token("cap-1", "cap-1", hour))
+            .is_ok());
+        assert!(suspended
+            .check_token(&token("cap-1", "other-root", hour))
+            .unwrap_err()
+            .contains("capsule root"));
+        assert!(suspended
+            .check_token(&token("esc-2", "cap-1", hour))
+            .is_err());
+        assert!(suspended
+            .check_token(&token("cap-1", "cap-1", -hour))
+            .unwrap_err()
+            .contains("expired"));
+    }
+
+    #[tokio::test]
+    async fn test_suspension_store() {
+        let backend: Arc<dyn StorageBackend> = Arc::new(MemoryBackend::new());
+        let store = SuspensionStore::new(backend.clone());
+        let suspended = suspended();
+
+        store.suspend(&suspended).await.unwrap();
+        let loaded = store.get("tenant", "cap-1").await.unwrap().unwrap();
+        assert_eq!(loaded.agent.id, suspended.agent.id);
+        assert_eq!(store.list("tenant").await.unwrap().len(), 1);
+        assert!(store.list("other").await.unwrap().is_empty());
+
+        // The escalation is visible in the coordination ledger
+        let active = PersistentCoordinationStore::new(backend)
+            .list_active("tenant")
+            .await
+            .unwrap();
+        assert_eq!(active[0].escalation_id, "cap-1");
+
+        assert!(store.remove("tenant", "cap-1").await.unwrap());
+        assert!(store.get("tenant", "cap-1").await.unwrap().is_none());
+    }
+}
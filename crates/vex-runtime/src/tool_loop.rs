@@ -0,0 +1,231 @@
+//! Tool-calling protocol for the agent executor
+//!
+//! The executor advertises registered tools in the system prompt and expects the
+//! model to reply with a single JSON object per turn:
+//! - `{"tool": "<name>", "arguments": {...}}` to call a tool
+//! - `{"final_answer": "..."}` to finish
+//!
+//! The object must be the whole reply, optionally inside a Markdown code fence.
+//! Anything else, including prose that merely contains such an object, is treated
+//! as the final answer.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+use vex_core::Hash;
+use vex_llm::ToolRegistry;
+
+/// Maximum characters of tool output fed back to the model per call
+pub const MAX_OBSERVATION_CHARS: usize = 4096;
+
+/// What the model asked for in one turn
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModelAction {
+    Call { tool: String, arguments: Value },
+    Final(String),
+}
+
+/// The reply without surrounding whitespace and Markdown code fence
+fn strip_code_fence(output: &str) -> &str {
+    let trimmed = output.trim();
+    let Some(fenced) = trimmed
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+    else {
+        return trimmed;
+    };
+    // Drop the info string (e.g. `json`) on the opening line
+    match fenced.split_once('\n') {
+        Some((info, body)) if !info.contains('{') => body.trim(),
+        _ => fenced.trim(),
+    }
+}
+
+/// Parse a model reply into a tool call or a final answer
+pub fn parse_action(output: &str) -> ModelAction {
+    match serde_json::from_str::<Value>(strip_code_fence(output)).ok() {
+        Some(Value::Object(map)) => {
+            if let Some(tool) = map.get("tool").and_then(|v| v.as_str()) {
+                return ModelAction::Call {
+                    tool: tool.to_string(),
+                    arguments: map
+                        .get("arguments")
+                        .cloned()
+                        .unwrap_or_else(|| serde_json::json!({})),
+                };
+            }
+            match map.get("final_answer").and_then(|v| v.as_str()) {
+                Some(answer) => ModelAction::Final(answer.to_string()),
+                None => ModelAction::Final(output.to_string()),
+            }
+        }
+        _ => ModelAction::Final(output.to_string()),
+    }
+}
+
+/// System prompt section describing the available tools and reply format
+pub fn tool_instructions(registry: &ToolRegistry) -> String {
+    let mut definitions = registry.definitions();
+    // Stable ordering keeps prompts (and cache keys) deterministic
+    definitions.sort_by_key(|d| d.name);
+    let tools: Vec<Value> = definitions.iter().map(|d| d.to_openai_format()).collect();
+
+    format!(
+        "You can call tools. Available tools (OpenAI function format):\n{}\n\n\
+         Reply with exactly one JSON object per turn:\n\
+         - {{\"tool\": \"<name>\", \"arguments\": {{...}}}} to call a tool\n\
+         - {{\"final_answer\": \"<answer>\"}} when you are done",
+        serde_json::to_string_pretty(&tools).unwrap_or_else(|_| "[]".to_string())
+    )
+}
+
+/// User prompt for the next turn: the task plus the tool transcript so far
+pub fn turn_prompt(task: &str, transcript: &str) -> String {
+    if transcript.is_empty() {
+        task.to_string()
+    } else {
+        format!("{}\n\nTool calls so far:\n{}", task, transcript)
+    }
+}
+
+/// How a tool call ended
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallOutcome {
+    /// Tool ran and returned output
+    Executed,
+    /// Tool was unknown, rejected its input, failed or timed out
+    Failed,
+    /// Capability check or gate refused the call
+    Denied,
+}
+
+/// Record of one tool call made during execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    /// Zero-based loop step
+    pub step: usize,
+    pub tool: String,
+    pub arguments: Value,
+    pub outcome: ToolCallOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Hash from `ToolResult` for executed calls
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result_hash: Option<Hash>,
+    /// Gate outcome (ALLOW / HALT / ...) if the gate was consulted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gate_outcome: Option<String>,
+    /// Audit event recording this call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit_event_id: Option<Uuid>,
+}
+
+impl ToolCallRecord {
+    pub fn new(step: usize, tool: impl Into<String>, arguments: Value) -> Self {
+        Self {
+            step,
+            tool: tool.into(),
+            arguments,
+            outcome: ToolCallOutcome::Failed,
+            output: None,
+            error: None,
+            result_hash: None,
+            gate_outcome: None,
+            audit_event_id: None,
+        }
+    }
+
+    pub(crate) fn deny(mut self, reason: impl Into<String>) -> Self {
+        self.outcome = ToolCallOutcome::Denied;
+        self.error = Some(reason.into());
+        self
+    }
+
+    pub(crate) fn fail(mut self, error: impl Into<String>) -> Self {
+        self.outcome = ToolCallOutcome::Failed;
+        self.error = Some(error.into());
+        self
+    }
+
+    /// Hash of the JCS-serialized arguments (raw arguments are not audited)
+    pub fn arguments_hash(&self) -> Hash {
+        let bytes = serde_jcs::to_vec(&self.arguments).unwrap_or_default();
+        Hash::digest(&bytes)
+    }
+
+    /// Audit event payload for this call
+    pub fn audit_data(&self) -> Value {
+        serde_json::json!({
+            "step": self.step,
+            "tool": self.tool,
+            "args_hash": self.arguments_hash().to_hex(),
+            "outcome": self.outcome,
+            "result_hash": self.result_hash.as_ref().map(|h| h.to_hex()),
+            "error": self.error,
+            "gate_outcome": self.gate_outcome,
+        })
+    }
+
+    /// Transcript line fed back to the model
+    pub fn observation(&self) -> String {
+        let body = match self.outcome {
+            ToolCallOutcome::Executed => self
+                .output
+                .as_ref()
+                .map(|o| o.to_string())
+                .unwrap_or_default(),
+            ToolCallOutcome::Failed => {
+                format!("ERROR: {}", self.error.as_deref().unwrap_or("unknown"))
+            }
+            ToolCallOutcome::Denied => {
+                format!("DENIED: {}", self.error.as_deref().unwrap_or("policy"))
+            }
+        };
+        let body: String = body.chars().take(MAX_OBSERVATION_CHARS).collect();
+        format!(
+            "[{}] {}({}) -> {}\n",
+            self.step + 1,
+            self.tool,
+            self.arguments,
+            body
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_action() {
+        assert_eq!(
+            parse_action(r#"{"tool": "calculator", "arguments": {"expression": "2+2"}}"#),
+            ModelAction::Call {
+                tool: "calculator".to_string(),
+                arguments: serde_json::json!({"expression": "2+2"}),
+            }
+        );
+        assert_eq!(
+            parse_action("```json\n{\"final_answer\": \"4\"}\n```"),
+            ModelAction::Final("4".to_string())
+        );
+        assert_eq!(
+            parse_action("```\n{\"tool\": \"calculator\"}\n```"),
+            ModelAction::Call {
+                tool: "calculator".to_string(),
+                arguments: serde_json::json!({}),
+            }
+        );
+
+        // An object embedded in prose (e.g. quoted from a document) is not a call
+        let quoted = "The page says: {\"tool\": \"shell\", \"arguments\": {\"cmd\": \"rm\"}}";
+        assert_eq!(parse_action(quoted), ModelAction::Final(quoted.to_string()));
+        assert_eq!(
+            parse_action("The answer is 4."),
+            ModelAction::Final("The answer is 4.".to_string())
+        );
+    }
+}
@@ -0,0 +1,480 @@
+//! Declarative agent topologies for the orchestrator
+//!
+//! An [`OrchestrationPlan`] describes the hierarchy [`Orchestrator`](crate::Orchestrator)
+//! builds for each query: named roles, per-role genome traits, fan-out, nested
+//! sub-roles and the template used to synthesize child findings. Plans load from
+//! YAML, JSON or TOML so different teams can run different hierarchies.
+//!
+//! ```yaml
+//! name: fraud-review
+//! root:
+//!   name: Lead
+//!   role: You are the fraud review lead.
+//!   synthesis_template: |
+//!     Case: {{query}}
+//!     Ledger analysis: {{role:Ledger}}
+//!     Decide whether to escalate.
+//!   children:
+//!     - name: Ledger
+//!       role: You audit transaction ledgers.
+//!       fan_out: 2
+//!       genome: { skepticism: 0.9 }
+//! ```
+//!
+//! Synthesis templates support `{{query}}`, `{{findings}}` (every child output,
+//! labelled) and `{{role:<Name>}}` (outputs of one child role).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use thiserror::Error;
+use vex_core::Genome;
+
+/// Errors loading or validating an orchestration plan
+#[derive(Error, Debug)]
+pub enum PlanError {
+    #[error("Failed to read plan: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse plan: {0}")]
+    Parse(String),
+    #[error("Invalid plan: {0}")]
+    Invalid(String),
+}
+
+/// Source format of a plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl PlanFormat {
+    /// Guess the format from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => Some(Self::Yaml),
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+}
+
+fn default_fan_out() -> usize {
+    1
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// One role in the hierarchy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RoleSpec {
+    /// Agent name (also the key for `{{role:<Name>}}`)
+    pub name: String,
+    /// System prompt for agents in this role
+    pub role: String,
+    /// Number of agents spawned for this role
+    #[serde(default = "default_fan_out")]
+    pub fan_out: usize,
+    /// Genome trait overrides (trait name -> value in 0.0..=1.0)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub genome: BTreeMap<String, f64>,
+    /// Whether agents in this role get an adversarial shadow
+    #[serde(default = "default_true")]
+    pub spawn_shadow: bool,
+    /// Sub-roles whose findings this role synthesizes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<RoleSpec>,
+    /// Prompt template combining child findings (inner roles only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synthesis_template: Option<String>,
+}
+
+impl RoleSpec {
+    /// Leaf role with the given name and system prompt
+    pub fn new(name: impl Into<String>, role: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            role: role.into(),
+            fan_out: 1,
+            genome: BTreeMap::new(),
+            spawn_shadow: true,
+            children: Vec::new(),
+            synthesis_template: None,
+        }
+    }
+
+    pub fn with_children(mut self, children: Vec<RoleSpec>) -> Self {
+        self.children = children;
+        self
+    }
+
+    pub fn with_fan_out(mut self, fan_out: usize) -> Self {
+        self.fan_out = fan_out;
+        self
+    }
+
+    pub fn with_trait(mut self, name: impl Into<String>, value: f64) -> Self {
+        self.genome.insert(name.into(), value);
+        self
+    }
+
+    pub fn with_synthesis_template(mut self, template: impl Into<String>) -> Self {
+        self.synthesis_template = Some(template.into());
+        self
+    }
+
+    /// Apply this role's trait overrides to a genome
+    pub fn apply_genome(&self, genome: &mut Genome) {
+        for (name, value) in &self.genome {
+            genome.set_trait(name, *value);
+        }
+    }
+
+    /// Depth of the subtree rooted at this role (a leaf is 1)
+    pub fn depth(&self) -> usize {
+        1 + self.children.iter().map(|c| c.depth()).max().unwrap_or(0)
+    }
+
+    /// Number of agents spawned directly under this role
+    pub fn child_count(&self) -> usize {
+        self.children.iter().map(|c| c.fan_out).sum()
+    }
+
+    /// Largest [`RoleSpec::child_count`] anywhere in the subtree rooted at this role
+    pub fn max_child_count(&self) -> usize {
+        self.children
+            .iter()
+            .map(|c| c.max_child_count())
+            .fold(self.child_count(), usize::max)
+    }
+
+    /// Render the synthesis prompt from `(role name, output)` pairs of child agents
+    ///
+    /// Placeholders are substituted in one pass over the template, so a query or
+    /// finding containing `{{...}}` is copied verbatim rather than expanded.
+    pub fn render_synthesis(&self, query: &str, findings: &[(String, String)]) -> String {
+        let template = self
+            .synthesis_template
+            .as_deref()
+            .unwrap_or(DEFAULT_SYNTHESIS_TEMPLATE);
+
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            rendered.push_str(&rest[..start]);
+            let Some(len) = rest[start..].find("}}") else {
+                rest = &rest[start..];
+                break;
+            };
+            let placeholder = &rest[start + 2..start + len];
+            match self.placeholder_value(placeholder, query, findings) {
+                Some(value) => rendered.push_str(&value),
+                None => rendered.push_str(&rest[start..start + len + 2]),
+            }
+            rest = &rest[start + len + 2..];
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+
+    /// Value of one `{{...}}` placeholder (None leaves it as written)
+    fn placeholder_value(
+        &self,
+        placeholder: &str,
+        query: &str,
+        findings: &[(String, String)],
+    ) -> Option<String> {
+        match placeholder {
+            "query" => Some(query.to_string()),
+            "findings" => Some(
+                findings
+                    .iter()
+                    .map(|(name, output)| format!("{}: \"{}\"", name, output))
+                    .collect::<Vec<_>>()
+                    .join("\n\n"),
+            ),
+            _ => {
+                let role = placeholder.strip_prefix("role:")?;
+                if !self.children.iter().any(|c| c.name == role) {
+                    return None;
+                }
+                let outputs: Vec<&str> = findings
+                    .iter()
+                    .filter(|(name, _)| name == role)
+                    .map(|(_, output)| output.as_str())
+                    .collect();
+                Some(if outputs.is_empty() {
+                    "N/A".to_string()
+                } else {
+                    outputs.join("\n\n")
+                })
+            }
+        }
+    }
+
+    fn validate(&self, path: &str) -> Result<(), PlanError> {
+        let path = format!("{}/{}", path, self.name);
+        if self.name.trim().is_empty() {
+            return Err(PlanError::Invalid(format!("{}: role name is empty", path)));
+        }
+        if self.fan_out == 0 {
+            return Err(PlanError::Invalid(format!(
+                "{}: fan_out must be >= 1",
+                path
+            )));
+        }
+
+        let known_traits = Genome::new("").trait_names;
+        for (name, value) in &self.genome {
+            if !known_traits.contains(name) {
+                return Err(PlanError::Invalid(format!(
+                    "{}: unknown genome trait '{}' (expected one of {:?})",
+                    path, name, known_traits
+                )));
+            }
+            if !(0.0..=1.0).contains(value) {
+                return Err(PlanError::Invalid(format!(
+                    "{}: genome trait '{}' must be within 0.0..=1.0",
+                    path, name
+                )));
+            }
+        }
+
+        let mut names = std::collections::HashSet::new();
+        for child in &self.children {
+            if !names.insert(child.name.as_str()) {
+                return Err(PlanError::Invalid(format!(
+                    "{}: duplicate child role '{}'",
+                    path, child.name
+                )));
+            }
+        }
+
+        if let Some(template) = &self.synthesis_template {
+            if self.children.is_empty() {
+                return Err(PlanError::Invalid(format!(
+                    "{}: synthesis_template requires child roles",
+                    path
+                )));
+            }
+            for reference in template.split("{{role:").skip(1) {
+                let name = reference.split("}}").next().unwrap_or_default();
+                if !names.contains(name) {
+                    return Err(PlanError::Invalid(format!(
+                        "{}: template references unknown role '{}'",
+                        path, name
+                    )));
+                }
+            }
+        }
+
+        self.children.iter().try_for_each(|c| c.validate(&path))
+    }
+}
+
+/// Synthesis prompt used by inner roles without a template
+pub const DEFAULT_SYNTHESIS_TEMPLATE: &str =
+    "Based on the following findings from your sub-agents, provide a comprehensive answer:\n\n\
+     Original Query: \"{{query}}\"\n\n\
+     {{findings}}\n\n\
+     Synthesize these into a final, well-reasoned response.";
+
+/// Agent hierarchy the orchestrator builds for each query
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OrchestrationPlan {
+    pub name: String,
+    pub root: RoleSpec,
+}
+
+impl Default for OrchestrationPlan {
+    /// Coordinator synthesizing a Researcher and a Critic
+    fn default() -> Self {
+        Self {
+            name: "research".to_string(),
+            root: RoleSpec::new(
+                "Root",
+                "You are a strategic coordinator. Synthesize information from sub-agents into a coherent response.",
+            )
+            .with_children(vec![
+                RoleSpec::new(
+                    "Researcher",
+                    "You are a thorough researcher. Analyze the query and provide detailed findings.",
+                ),
+                RoleSpec::new(
+                    "Critic",
+                    "You are a critical analyzer. Identify potential issues, edge cases, and weaknesses.",
+                ),
+            ])
+            .with_synthesis_template(
+                "Based on the following research from your sub-agents, provide a comprehensive answer:\n\n\
+                 Original Query: \"{{query}}\"\n\n\
+                 Researcher's Findings: \"{{role:Researcher}}\"\n\n\
+                 Critic's Analysis: \"{{role:Critic}}\"\n\n\
+                 Synthesize these into a final, well-reasoned response.",
+            ),
+        }
+    }
+}
+
+impl OrchestrationPlan {
+    pub fn new(name: impl Into<String>, root: RoleSpec) -> Self {
+        Self {
+            name: name.into(),
+            root,
+        }
+    }
+
+    /// Parse and validate a plan
+    pub fn parse(source: &str, format: PlanFormat) -> Result<Self, PlanError> {
+        let plan: Self = match format {
+            PlanFormat::Json => {
+                serde_json::from_str(source).map_err(|e| PlanError::Parse(e.to_string()))?
+            }
+            PlanFormat::Yaml | PlanFormat::Toml => {
+                let file_format = match format {
+                    PlanFormat::Yaml => config::FileFormat::Yaml,
+                    _ => config::FileFormat::Toml,
+                };
+                config::Config::builder()
+                    .add_source(config::File::from_str(source, file_format))
+                    .build()
+                    .and_then(|c| c.try_deserialize())
+                    .map_err(|e| PlanError::Parse(e.to_string()))?
+            }
+        };
+        plan.validate()?;
+        Ok(plan)
+    }
+
+    /// Load a plan file, choosing the format from its extension
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, PlanError> {
+        let path = path.as_ref();
+        let format = PlanFormat::from_path(path).ok_or_else(|| {
+            PlanError::Parse(format!(
+                "Unsupported plan file extension: {}",
+                path.display()
+            ))
+        })?;
+        Self::parse(&std::fs::read_to_string(path)?, format)
+    }
+
+    /// Check names, fan-out, genome traits and template references
+    pub fn validate(&self) -> Result<(), PlanError> {
+        self.root.validate(&self.name)
+    }
+
+    /// Number of levels in the hierarchy (root only is 1)
+    pub fn depth(&self) -> usize {
+        self.root.depth()
+    }
+
+    /// Check that no role spawns more than `limit` child agents
+    pub fn validate_fan_out(&self, limit: usize) -> Result<(), PlanError> {
+        let widest = self.root.max_child_count();
+        if widest > limit {
+            return Err(PlanError::Invalid(format!(
+                "{}: a role spawns {} child agents, more than the limit of {}",
+                self.name, widest, limit
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAUD_PLAN: &str = r#"
+name: fraud-review
+root:
+  name: Lead
+  role: You are the fraud review lead.
+  synthesis_template: "Case: {{query}} | Ledger: {{role:Ledger}} | Network: {{role:Network}}"
+  children:
+    - name: Ledger
+      role: You audit transaction ledgers.
+      fan_out: 2
+      genome:
+        skepticism: 0.9
+    - name: Network
+      role: You map relationships between accounts.
+      children:
+        - name: Graph
+          role: You analyse transaction graphs.
+"#;
+
+    #[test]
+    fn test_plan_from_yaml() {
+        let plan = OrchestrationPlan::parse(FRAUD_PLAN, PlanFormat::Yaml).unwrap();
+        assert_eq!(plan.name, "fraud-review");
+        assert_eq!(plan.depth(), 3);
+
+        let ledger = &plan.root.children[0];
+        assert_eq!(ledger.fan_out, 2);
+        assert_eq!(ledger.genome.get("skepticism"), Some(&0.9));
+        assert!(ledger.spawn_shadow);
+
+        // JSON round trip yields the same plan
+        let json = serde_json::to_string(&plan).unwrap();
+        assert_eq!(
+            OrchestrationPlan::parse(&json, PlanFormat::Json).unwrap(),
+            plan
+        );
+
+        let prompt = plan.root.render_synthesis(
+            "Account 42",
+            &[
+                ("Ledger".to_string(), "a".to_string()),
+                ("Ledger".to_string(), "b".to_string()),
+            ],
+        );
+        assert_eq!(prompt, "Case: Account 42 | Ledger: a\n\nb | Network: N/A");
+    }
+
+    #[test]
+    fn test_plan_validation() {
+        let bad_trait = RoleSpec::new("Root", "r")
+            .with_children(vec![RoleSpec::new("A", "a").with_trait("charisma", 0.5)]);
+        assert!(OrchestrationPlan::new("p", bad_trait).validate().is_err());
+
+        let bad_template = RoleSpec::new("Root", "r")
+            .with_children(vec![RoleSpec::new("A", "a")])
+            .with_synthesis_template("{{role:B}}");
+        assert!(OrchestrationPlan::new("p", bad_template)
+            .validate()
+            .is_err());
+
+        assert!(OrchestrationPlan::default().validate().is_ok());
+
+        let wide = OrchestrationPlan::new(
+            "p",
+            RoleSpec::new("Root", "r").with_children(vec![
+                RoleSpec::new("A", "a").with_fan_out(2),
+                RoleSpec::new("B", "b"),
+            ]),
+        );
+        assert_eq!(wide.root.max_child_count(), 3);
+        assert!(wide.validate_fan_out(3).is_ok());
+        assert!(wide.validate_fan_out(2).is_err());
+    }
+
+    #[test]
+    fn test_synthesis_does_not_expand_user_text() {
+        let plan = OrchestrationPlan::default();
+        let prompt = plan.root.render_synthesis(
+            "Why {{role:Critic}}?",
+            &[
+                ("Researcher".to_string(), "see {{query}}".to_string()),
+                ("Critic".to_string(), "ok".to_string()),
+            ],
+        );
+        assert!(prompt.contains("Original Query: \"Why {{role:Critic}}?\""));
+        assert!(prompt.contains("Researcher's Findings: \"see {{query}}\""));
+        assert!(prompt.contains("Critic's Analysis: \"ok\""));
+    }
+}
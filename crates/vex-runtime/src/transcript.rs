@@ -0,0 +1,348 @@
+//! Replayable execution transcripts
+//!
+//! With transcripts enabled, [`AgentExecutor`](crate::AgentExecutor) records
+//! everything an execution depended on: the agent snapshot and inputs, the
+//! sampling seed, every `LlmRequest`/`LlmResponse` pair, each tool call and
+//! each gate decision, in order. The transcript is stored as a content-addressed
+//! artifact and its hash is linked from the `AgentExecuted` audit event.
+//!
+//! [`AgentExecutor::replay`](crate::AgentExecutor::replay) runs the same
+//! executor and gate code again, feeding recorded LLM responses and tool
+//! outputs instead of calling providers or tools, and reports every point where
+//! the replay diverges from the recording.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+
+use crate::tool_loop::ToolCallRecord;
+use vex_core::{Agent, Hash};
+use vex_llm::{Capability, LlmError, LlmRequest, LlmResponse};
+
+/// Inputs an execution started from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptInput {
+    /// Agent state before execution (context, fitness, genome)
+    pub agent: Agent,
+    pub prompt: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intent_data: Option<vex_core::segment::IntentData>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<Capability>,
+}
+
+/// One recorded step, in execution order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TranscriptEntry {
+    Llm {
+        request: LlmRequest,
+        response: LlmResponse,
+    },
+    ToolCall {
+        record: ToolCallRecord,
+    },
+    Gate {
+        /// Output (or tool call) submitted to the gate
+        action: String,
+        confidence: f64,
+        outcome: String,
+        reason_code: String,
+    },
+}
+
+impl TranscriptEntry {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Llm { .. } => "llm",
+            Self::ToolCall { .. } => "tool_call",
+            Self::Gate { .. } => "gate",
+        }
+    }
+}
+
+/// How the execution ended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptOutcome {
+    pub response: String,
+    pub verified: bool,
+    pub confidence: f64,
+    /// Final gate outcome (ALLOW / HALT / ...)
+    pub gate_outcome: String,
+}
+
+/// Full record of one agent execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transcript {
+    /// Seed sent with every LLM request
+    pub seed: u64,
+    pub input: TranscriptInput,
+    pub entries: Vec<TranscriptEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<TranscriptOutcome>,
+}
+
+impl Transcript {
+    /// Content hash under which the transcript is stored
+    pub fn content_hash(&self) -> Result<Hash, vex_persist::StorageError> {
+        vex_persist::content_hash(self)
+    }
+}
+
+/// What kind of mismatch a replay found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivergenceKind {
+    /// The replay made a different LLM request than recorded
+    LlmRequest,
+    /// A tool call differed (tool, arguments, capability or gate result)
+    ToolCall,
+    /// A gate returned a different decision
+    Gate,
+    /// The replay took a different step than recorded
+    StepMismatch,
+    /// Recorded steps the replay never reached
+    MissingEntry,
+    /// Steps the replay took beyond the recording
+    ExtraEntry,
+    /// Final response, verification, confidence or gate outcome differ
+    Outcome,
+}
+
+/// One mismatch between recording and replay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Divergence {
+    /// Entry index (entries.len() for outcome mismatches)
+    pub index: usize,
+    pub kind: DivergenceKind,
+    pub expected: Value,
+    pub actual: Value,
+}
+
+/// Result of replaying a transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayReport {
+    pub transcript_hash: Hash,
+    pub divergences: Vec<Divergence>,
+    /// Outcome reached by the replay (None if it failed before the gate)
+    pub outcome: Option<TranscriptOutcome>,
+    /// Error the replayed execution returned, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ReplayReport {
+    /// Whether the replay reproduced the recording exactly
+    pub fn is_faithful(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+fn to_value<T: Serialize + ?Sized>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+#[derive(Debug)]
+struct RecorderState {
+    entries: Vec<TranscriptEntry>,
+    divergences: Vec<Divergence>,
+    outcome: Option<TranscriptOutcome>,
+}
+
+/// Collects entries while an execution runs; in replay mode also serves
+/// recorded responses and tracks divergences.
+#[derive(Debug)]
+pub(crate) struct TranscriptRecorder {
+    seed: u64,
+    recorded: Option<Vec<TranscriptEntry>>,
+    state: Mutex<RecorderState>,
+}
+
+impl TranscriptRecorder {
+    pub(crate) fn record(seed: u64) -> Self {
+        Self::new(seed, None)
+    }
+
+    pub(crate) fn replay(transcript: &Transcript) -> Self {
+        Self::new(transcript.seed, Some(transcript.entries.clone()))
+    }
+
+    fn new(seed: u64, recorded: Option<Vec<TranscriptEntry>>) -> Self {
+        Self {
+            seed,
+            recorded,
+            state: Mutex::new(RecorderState {
+                entries: Vec::new(),
+                divergences: Vec::new(),
+                outcome: None,
+            }),
+        }
+    }
+
+    pub(crate) fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub(crate) fn is_replay(&self) -> bool {
+        self.recorded.is_some()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RecorderState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Recorded entry at the position the next entry will take
+    fn expected(&self, state: &RecorderState) -> Option<TranscriptEntry> {
+        self.recorded.as_ref()?.get(state.entries.len()).cloned()
+    }
+
+    /// Append an entry, comparing it with the recording when replaying
+    fn push(&self, entry: TranscriptEntry) {
+        let mut state = self.lock();
+        if self.recorded.is_some() {
+            let index = state.entries.len();
+            let divergence = match (self.expected(&state), &entry) {
+                (None, actual) => Some((DivergenceKind::ExtraEntry, Value::Null, to_value(actual))),
+                (Some(expected), actual) if expected.kind() != actual.kind() => Some((
+                    DivergenceKind::StepMismatch,
+                    to_value(&expected),
+                    to_value(actual),
+                )),
+                (
+                    Some(TranscriptEntry::Llm { request, .. }),
+                    TranscriptEntry::Llm {
+                        request: actual, ..
+                    },
+                ) if to_value(&request) != to_value(actual) => Some((
+                    DivergenceKind::LlmRequest,
+                    to_value(&request),
+                    to_value(actual),
+                )),
+                (
+                    Some(TranscriptEntry::ToolCall { record }),
+                    TranscriptEntry::ToolCall { record: actual },
+                ) if to_value(&record) != to_value(actual) => Some((
+                    DivergenceKind::ToolCall,
+                    to_value(&record),
+                    to_value(actual),
+                )),
+                (
+                    Some(TranscriptEntry::Gate {
+                        outcome,
+                        reason_code,
+                        ..
+                    }),
+                    TranscriptEntry::Gate {
+                        outcome: actual_outcome,
+                        reason_code: actual_reason,
+                        ..
+                    },
+                ) if &outcome != actual_outcome || &reason_code != actual_reason => Some((
+                    DivergenceKind::Gate,
+                    serde_json::json!({"outcome": outcome, "reason_code": reason_code}),
+                    serde_json::json!({"outcome": actual_outcome, "reason_code": actual_reason}),
+                )),
+                _ => None,
+            };
+            if let Some((kind, expected, actual)) = divergence {
+                state.divergences.push(Divergence {
+                    index,
+                    kind,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        state.entries.push(entry);
+    }
+
+    /// Recorded response for the next LLM call (replay only)
+    pub(crate) fn replay_llm(&self, request: &LlmRequest) -> Result<LlmResponse, LlmError> {
+        let expected = self.expected(&self.lock());
+        let response = match expected {
+            Some(TranscriptEntry::Llm { response, .. }) => Ok(response),
+            _ => Err(LlmError::NotAvailable),
+        };
+        if let Ok(response) = &response {
+            self.record_llm(request.clone(), response.clone());
+        }
+        response
+    }
+
+    pub(crate) fn record_llm(&self, request: LlmRequest, response: LlmResponse) {
+        self.push(TranscriptEntry::Llm { request, response });
+    }
+
+    /// Recorded tool call at the next position (replay only)
+    pub(crate) fn replay_tool(&self) -> Option<ToolCallRecord> {
+        match self.expected(&self.lock()) {
+            Some(TranscriptEntry::ToolCall { record }) => Some(record),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn record_tool(&self, record: &ToolCallRecord) {
+        let mut record = record.clone();
+        // Audit IDs differ between runs and are not part of the decision
+        record.audit_event_id = None;
+        self.push(TranscriptEntry::ToolCall { record });
+    }
+
+    pub(crate) fn record_gate(
+        &self,
+        action: &str,
+        confidence: f64,
+        capsule: &vex_core::audit::EvidenceCapsule,
+    ) {
+        self.push(TranscriptEntry::Gate {
+            action: action.to_string(),
+            confidence,
+            outcome: capsule.outcome.clone(),
+            reason_code: capsule.reason_code.clone(),
+        });
+    }
+
+    pub(crate) fn set_outcome(&self, outcome: TranscriptOutcome) {
+        self.lock().outcome = Some(outcome);
+    }
+
+    /// Build the transcript recorded so far
+    pub(crate) fn transcript(&self, input: TranscriptInput) -> Transcript {
+        let state = self.lock();
+        Transcript {
+            seed: self.seed,
+            input,
+            entries: state.entries.clone(),
+            outcome: state.outcome.clone(),
+        }
+    }
+
+    /// Finish a replay against `original`, returning all divergences
+    pub(crate) fn finish_replay(&self, original: &Transcript) -> Vec<Divergence> {
+        let state = self.lock();
+        let mut divergences = state.divergences.clone();
+
+        let replayed = state.entries.len();
+        if replayed < original.entries.len() {
+            divergences.push(Divergence {
+                index: replayed,
+                kind: DivergenceKind::MissingEntry,
+                expected: to_value(&original.entries[replayed..]),
+                actual: Value::Null,
+            });
+        }
+        if state.outcome != original.outcome {
+            divergences.push(Divergence {
+                index: original.entries.len(),
+                kind: DivergenceKind::Outcome,
+                expected: to_value(&original.outcome),
+                actual: to_value(&state.outcome),
+            });
+        }
+        divergences
+    }
+
+    pub(crate) fn outcome(&self) -> Option<TranscriptOutcome> {
+        self.lock().outcome.clone()
+    }
+}
@@ -0,0 +1,941 @@
+//! DAG workflow engine on top of [`AgentExecutor`]
+//!
+//! A [`Workflow`] is a directed acyclic graph whose nodes are agent executions,
+//! tool invocations, gate checks or escalations, and whose edges carry
+//! conditions on the source node's output:
+//!
+//! ```yaml
+//! name: claim-triage
+//! nodes:
+//!   - { id: assess, type: agent, role: You assess insurance claims. }
+//!   - { id: review, type: gate }
+//!   - { id: human, type: escalate, reason: "Low confidence on {{node:assess}}" }
+//! edges:
+//!   - { from: assess, to: review, when: { confidence_at_least: 0.6 } }
+//!   - { from: assess, to: human, when: { confidence_below: 0.6 } }
+//! ```
+//!
+//! A node runs once all its predecessors have finished and at least one
+//! incoming edge condition holds; otherwise it is skipped. Nodes whose
+//! dependencies are met run concurrently. With a [`WorkflowStore`] attached,
+//! step state is persisted after every wave so [`WorkflowEngine::resume`] can
+//! continue a crashed or failed run.
+//!
+//! Prompt, argument and reason templates support `{{input}}` and
+//! `{{node:<id>}}` (output of a finished node).
+
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::executor::AgentExecutor;
+use crate::tool_loop::{ToolCallOutcome, ToolCallRecord};
+use vex_core::{Agent, AgentConfig, ContextPacket, Hash, MerkleTree};
+use vex_llm::{Capability, LlmProvider};
+use vex_persist::{
+    StepRecord, StepStatus, StorageBackend, StorageError, WorkflowRunRecord, WorkflowStatus,
+    WorkflowStore,
+};
+
+/// Errors defining or running a workflow
+#[derive(Error, Debug)]
+pub enum WorkflowError {
+    #[error("Invalid workflow: {0}")]
+    Invalid(String),
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+    #[error("Workflow run {0} not found")]
+    NotFound(Uuid),
+    #[error("Node '{node}' failed: {error}")]
+    Node { node: String, error: String },
+}
+
+/// What a node does
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NodeKind {
+    /// Run a fresh agent with this role through the executor
+    Agent {
+        role: String,
+        /// Prompt template (defaults to the input plus predecessor outputs)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prompt: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        capabilities: Vec<Capability>,
+    },
+    /// Call a tool registered with the executor
+    Tool {
+        tool: String,
+        /// Arguments; string values are rendered as templates
+        #[serde(default)]
+        arguments: Value,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        capabilities: Vec<Capability>,
+    },
+    /// Submit predecessor outputs to the policy gate; the output is the gate outcome
+    Gate {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        capabilities: Vec<Capability>,
+    },
+    /// Hand the run to a human; the run finishes as `Escalated`
+    Escalate { reason: String },
+}
+
+/// One node of the graph
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkflowNode {
+    pub id: String,
+    #[serde(flatten)]
+    pub kind: NodeKind,
+}
+
+impl WorkflowNode {
+    pub fn agent(id: impl Into<String>, role: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            kind: NodeKind::Agent {
+                role: role.into(),
+                prompt: None,
+                capabilities: Vec::new(),
+            },
+        }
+    }
+
+    pub fn tool(id: impl Into<String>, tool: impl Into<String>, arguments: Value) -> Self {
+        Self {
+            id: id.into(),
+            kind: NodeKind::Tool {
+                tool: tool.into(),
+                arguments,
+                capabilities: Vec::new(),
+            },
+        }
+    }
+
+    pub fn gate(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            kind: NodeKind::Gate {
+                capabilities: Vec::new(),
+            },
+        }
+    }
+
+    pub fn escalate(id: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            kind: NodeKind::Escalate {
+                reason: reason.into(),
+            },
+        }
+    }
+
+    /// Set the prompt template of an agent node (no-op for other kinds)
+    pub fn with_prompt(mut self, template: impl Into<String>) -> Self {
+        if let NodeKind::Agent { prompt, .. } = &mut self.kind {
+            *prompt = Some(template.into());
+        }
+        self
+    }
+
+    /// Grant capabilities to this node (no-op for escalations)
+    pub fn with_capabilities(mut self, granted: Vec<Capability>) -> Self {
+        match &mut self.kind {
+            NodeKind::Agent { capabilities, .. }
+            | NodeKind::Tool { capabilities, .. }
+            | NodeKind::Gate { capabilities } => *capabilities = granted,
+            NodeKind::Escalate { .. } => {}
+        }
+        self
+    }
+}
+
+/// Condition on the source node's output
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeCondition {
+    #[default]
+    Always,
+    ConfidenceBelow(f64),
+    ConfidenceAtLeast(f64),
+    OutputContains(String),
+    /// Exact match, e.g. a gate outcome such as `ALLOW` or `HALT`
+    OutputEquals(String),
+}
+
+impl EdgeCondition {
+    /// Whether the edge is taken for a completed source step
+    pub fn holds(&self, step: &StepRecord) -> bool {
+        match self {
+            Self::Always => true,
+            Self::ConfidenceBelow(v) => step.confidence < *v,
+            Self::ConfidenceAtLeast(v) => step.confidence >= *v,
+            Self::OutputContains(s) => step.output.contains(s.as_str()),
+            Self::OutputEquals(s) => step.output.trim() == s,
+        }
+    }
+}
+
+/// Directed edge between two nodes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkflowEdge {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub when: EdgeCondition,
+}
+
+impl WorkflowEdge {
+    pub fn new(from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            when: EdgeCondition::Always,
+        }
+    }
+
+    pub fn when(mut self, condition: EdgeCondition) -> Self {
+        self.when = condition;
+        self
+    }
+}
+
+/// Workflow definition
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Workflow {
+    pub name: String,
+    pub nodes: Vec<WorkflowNode>,
+    #[serde(default)]
+    pub edges: Vec<WorkflowEdge>,
+}
+
+impl Workflow {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn with_node(mut self, node: WorkflowNode) -> Self {
+        self.nodes.push(node);
+        self
+    }
+
+    pub fn with_edge(mut self, edge: WorkflowEdge) -> Self {
+        self.edges.push(edge);
+        self
+    }
+
+    /// Get a node by ID
+    pub fn node(&self, id: &str) -> Option<&WorkflowNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    fn incoming<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a WorkflowEdge> + 'a {
+        self.edges.iter().filter(move |e| e.to == id)
+    }
+
+    /// Check node IDs, edge endpoints and acyclicity; returns node IDs in topological order
+    pub fn validate(&self) -> Result<Vec<String>, WorkflowError> {
+        if self.nodes.is_empty() {
+            return Err(WorkflowError::Invalid("workflow has no nodes".to_string()));
+        }
+
+        let mut in_degree: HashMap<&str, usize> = HashMap::new();
+        for node in &self.nodes {
+            if node.id.trim().is_empty() {
+                return Err(WorkflowError::Invalid("node ID is empty".to_string()));
+            }
+            if in_degree.insert(node.id.as_str(), 0).is_some() {
+                return Err(WorkflowError::Invalid(format!(
+                    "duplicate node '{}'",
+                    node.id
+                )));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for edge in &self.edges {
+            for end in [&edge.from, &edge.to] {
+                if !in_degree.contains_key(end.as_str()) {
+                    return Err(WorkflowError::Invalid(format!(
+                        "edge {} -> {} references unknown node '{}'",
+                        edge.from, edge.to, end
+                    )));
+                }
+            }
+            if !seen.insert((edge.from.as_str(), edge.to.as_str())) {
+                return Err(WorkflowError::Invalid(format!(
+                    "duplicate edge {} -> {}",
+                    edge.from, edge.to
+                )));
+            }
+            *in_degree.entry(edge.to.as_str()).or_default() += 1;
+        }
+
+        // Kahn's algorithm, seeded in declaration order for a stable result
+        let mut queue: VecDeque<&str> = self
+            .nodes
+            .iter()
+            .map(|n| n.id.as_str())
+            .filter(|id| in_degree[id] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(id) = queue.pop_front() {
+            order.push(id.to_string());
+            for edge in self.edges.iter().filter(|e| e.from == id) {
+                let degree = in_degree.entry(edge.to.as_str()).or_default();
+                *degree -= 1;
+                if *degree == 0 {
+                    queue.push_back(edge.to.as_str());
+                }
+            }
+        }
+
+        if order.len() != self.nodes.len() {
+            return Err(WorkflowError::Invalid(
+                "workflow contains a cycle".to_string(),
+            ));
+        }
+        Ok(order)
+    }
+}
+
+/// Result of a workflow run
+#[derive(Debug, Clone)]
+pub struct WorkflowResult {
+    pub run_id: Uuid,
+    pub status: WorkflowStatus,
+    /// Finished steps keyed by node ID
+    pub steps: BTreeMap<String, StepRecord>,
+    /// Merkle root over the context packets of all completed nodes
+    pub merkle_root: Hash,
+}
+
+impl WorkflowResult {
+    /// Output of a completed node
+    pub fn output(&self, node_id: &str) -> Option<&str> {
+        self.steps
+            .get(node_id)
+            .filter(|s| s.status == StepStatus::Completed)
+            .map(|s| s.output.as_str())
+    }
+
+    fn from_record(record: &WorkflowRunRecord) -> Self {
+        let leaves: Vec<(String, Hash)> = record
+            .steps
+            .values()
+            .filter_map(|s| {
+                s.context
+                    .as_ref()
+                    .map(|c| (s.node_id.clone(), c.hash.clone()))
+            })
+            .collect();
+        let merkle_tree = MerkleTree::from_leaves(leaves);
+
+        Self {
+            run_id: record.run_id,
+            status: record.status,
+            steps: record.steps.clone(),
+            merkle_root: merkle_tree
+                .root_hash()
+                .cloned()
+                .unwrap_or(Hash::digest(b"empty")),
+        }
+    }
+}
+
+/// Render `{{input}}` and `{{node:<id>}}` placeholders in one pass over the template, so
+/// placeholders inside substituted values are left as written
+fn render(template: &str, input: &str, steps: &BTreeMap<String, StepRecord>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let placeholder = &rest[start + 2..start + len];
+        match placeholder_value(placeholder, input, steps) {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Value of one `{{...}}` placeholder (None leaves it as written)
+fn placeholder_value<'a>(
+    placeholder: &str,
+    input: &'a str,
+    steps: &'a BTreeMap<String, StepRecord>,
+) -> Option<&'a str> {
+    if placeholder == "input" {
+        return Some(input);
+    }
+    let id = placeholder.strip_prefix("node:")?;
+    steps
+        .get(id)
+        .filter(|step| step.status == StepStatus::Completed)
+        .map(|step| step.output.as_str())
+}
+
+/// Render every string inside a JSON value
+fn render_value(value: &Value, input: &str, steps: &BTreeMap<String, StepRecord>) -> Value {
+    match value {
+        Value::String(s) => Value::String(render(s, input, steps)),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|v| render_value(v, input, steps))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), render_value(v, input, steps)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn completed(node_id: &str, output: String, confidence: f64, context: ContextPacket) -> StepRecord {
+    StepRecord {
+        node_id: node_id.to_string(),
+        status: StepStatus::Completed,
+        output,
+        confidence,
+        context: Some(context),
+        error: None,
+        finished_at: chrono::Utc::now(),
+    }
+}
+
+fn not_completed(node_id: &str, status: StepStatus, error: Option<String>) -> StepRecord {
+    StepRecord {
+        node_id: node_id.to_string(),
+        status,
+        output: String::new(),
+        confidence: 0.0,
+        context: None,
+        error,
+        finished_at: chrono::Utc::now(),
+    }
+}
+
+/// Runs [`Workflow`]s with an [`AgentExecutor`]
+pub struct WorkflowEngine<L: LlmProvider + ?Sized> {
+    executor: AgentExecutor<L>,
+    store: Option<Arc<WorkflowStore<dyn StorageBackend>>>,
+}
+
+impl<L: LlmProvider + ?Sized> std::fmt::Debug for WorkflowEngine<L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkflowEngine")
+            .field("executor", &self.executor)
+            .field("persistent", &self.store.is_some())
+            .finish()
+    }
+}
+
+impl<L: LlmProvider + ?Sized> WorkflowEngine<L> {
+    /// Create an engine that runs nodes through `executor`
+    pub fn new(executor: AgentExecutor<L>) -> Self {
+        Self {
+            executor,
+            store: None,
+        }
+    }
+
+    /// Persist step state so runs can be resumed
+    pub fn with_store(mut self, store: Arc<WorkflowStore<dyn StorageBackend>>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Start a new run of `workflow` on `input`
+    pub async fn run(
+        &self,
+        tenant_id: &str,
+        workflow: &Workflow,
+        input: &str,
+    ) -> Result<WorkflowResult, WorkflowError> {
+        workflow.validate()?;
+        let definition = serde_json::to_value(workflow)
+            .map_err(|e| WorkflowError::Invalid(format!("unserializable workflow: {}", e)))?;
+        let record = WorkflowRunRecord::new(&workflow.name, definition, input);
+        self.drive(tenant_id, workflow, record).await
+    }
+
+    /// Continue a persisted run from its last completed steps, retrying failed nodes
+    pub async fn resume(
+        &self,
+        tenant_id: &str,
+        run_id: Uuid,
+    ) -> Result<WorkflowResult, WorkflowError> {
+        let store = self.store.as_ref().ok_or_else(|| {
+            WorkflowError::Invalid("resume requires a workflow store".to_string())
+        })?;
+        let mut record = store
+            .load(tenant_id, run_id)
+            .await?
+            .ok_or(WorkflowError::NotFound(run_id))?;
+
+        if !record.status.is_resumable() {
+            return Ok(WorkflowResult::from_record(&record));
+        }
+
+        let workflow: Workflow = serde_json::from_value(record.definition.clone())
+            .map_err(|e| WorkflowError::Invalid(format!("stored definition: {}", e)))?;
+        record.steps.retain(|_, s| s.status != StepStatus::Failed);
+        tracing::info!(
+            run_id = %run_id,
+            workflow = %workflow.name,
+            finished_steps = record.steps.len(),
+            "Resuming workflow run"
+        );
+        self.drive(tenant_id, &workflow, record).await
+    }
+
+    async fn save(&self, tenant_id: &str, record: &WorkflowRunRecord) -> Result<(), WorkflowError> {
+        if let Some(store) = &self.store {
+            store.save(tenant_id, record).await?;
+        }
+        Ok(())
+    }
+
+    /// Run waves of ready nodes until nothing is left to run
+    async fn drive(
+        &self,
+        tenant_id: &str,
+        workflow: &Workflow,
+        mut record: WorkflowRunRecord,
+    ) -> Result<WorkflowResult, WorkflowError> {
+        let order = workflow.validate()?;
+        record.status = WorkflowStatus::Running;
+        self.save(tenant_id, &record).await?;
+
+        loop {
+            // Topological order lets skips cascade within a single pass
+            let mut ready = Vec::new();
+            for id in &order {
+                if record.steps.contains_key(id) {
+                    continue;
+                }
+                let incoming: Vec<&WorkflowEdge> = workflow.incoming(id).collect();
+                if incoming.iter().any(|e| !record.steps.contains_key(&e.from)) {
+                    continue;
+                }
+                let active = incoming.is_empty()
+                    || incoming.iter().any(|e| {
+                        let source = &record.steps[&e.from];
+                        source.status == StepStatus::Completed && e.when.holds(source)
+                    });
+                if active {
+                    ready.push(id.as_str());
+                } else {
+                    record
+                        .steps
+                        .insert(id.clone(), not_completed(id, StepStatus::Skipped, None));
+                }
+            }
+
+            if ready.is_empty() {
+                break;
+            }
+
+            let wave = ready.iter().filter_map(|id| workflow.node(id)).map(|node| {
+                let inputs: Vec<&StepRecord> = workflow
+                    .incoming(&node.id)
+                    .filter_map(|e| {
+                        let source = &record.steps[&e.from];
+                        (source.status == StepStatus::Completed && e.when.holds(source))
+                            .then_some(source)
+                    })
+                    .collect();
+                self.run_step(tenant_id, node, &record, inputs)
+            });
+            let steps = join_all(wave).await;
+
+            let mut failure = None;
+            for step in steps {
+                if step.status == StepStatus::Failed && failure.is_none() {
+                    failure = Some(WorkflowError::Node {
+                        node: step.node_id.clone(),
+                        error: step.error.clone().unwrap_or_default(),
+                    });
+                }
+                record.steps.insert(step.node_id.clone(), step);
+            }
+
+            if let Some(error) = failure {
+                record.status = WorkflowStatus::Failed;
+                self.save(tenant_id, &record).await?;
+                return Err(error);
+            }
+            self.save(tenant_id, &record).await?;
+        }
+
+        let escalated = record.steps.values().any(|s| {
+            s.status == StepStatus::Completed
+                && matches!(
+                    workflow.node(&s.node_id).map(|n| &n.kind),
+                    Some(NodeKind::Escalate { .. })
+                )
+        });
+        record.status = if escalated {
+            WorkflowStatus::Escalated
+        } else {
+            WorkflowStatus::Completed
+        };
+        self.save(tenant_id, &record).await?;
+
+        Ok(WorkflowResult::from_record(&record))
+    }
+
+    /// Execute one node given the completed predecessors whose edges were taken
+    async fn run_step(
+        &self,
+        tenant_id: &str,
+        node: &WorkflowNode,
+        record: &WorkflowRunRecord,
+        inputs: Vec<&StepRecord>,
+    ) -> StepRecord {
+        let input_confidence = if inputs.is_empty() {
+            1.0
+        } else {
+            inputs.iter().map(|s| s.confidence).sum::<f64>() / inputs.len() as f64
+        };
+        let predecessor_outputs = inputs
+            .iter()
+            .map(|s| format!("[{}]: {}", s.node_id, s.output))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        match &node.kind {
+            NodeKind::Agent {
+                role,
+                prompt,
+                capabilities,
+            } => {
+                let prompt = match prompt {
+                    Some(template) => render(template, &record.input, &record.steps),
+                    None if inputs.is_empty() => record.input.clone(),
+                    None => format!(
+                        "Task: {}\n\nPrevious steps:\n{}",
+                        record.input, predecessor_outputs
+                    ),
+                };
+                let mut agent = Agent::new(AgentConfig {
+                    name: node.id.clone(),
+                    role: role.clone(),
+                    ..Default::default()
+                });
+                match self
+                    .executor
+                    .execute(tenant_id, &mut agent, &prompt, None, capabilities.clone())
+                    .await
+                {
+                    Ok(result) => {
+                        completed(&node.id, result.response, result.confidence, result.context)
+                    }
+                    Err(e) => not_completed(&node.id, StepStatus::Failed, Some(e)),
+                }
+            }
+            NodeKind::Tool {
+                tool,
+                arguments,
+                capabilities,
+            } => {
+                let Some(tools) = &self.executor.tools else {
+                    return not_completed(
+                        &node.id,
+                        StepStatus::Failed,
+                        Some("No tool executor configured".to_string()),
+                    );
+                };
+                let agent = Agent::new(AgentConfig {
+                    name: node.id.clone(),
+                    role: format!("Workflow tool step '{}'", node.id),
+                    ..Default::default()
+                });
+                let call = ToolCallRecord::new(
+                    0,
+                    tool,
+                    render_value(arguments, &record.input, &record.steps),
+                );
+                let call = self
+                    .executor
+                    .run_tool_call(
+                        None,
+                        tenant_id,
+                        &agent,
+                        &record.input,
+                        tools,
+                        capabilities,
+                        call,
+                    )
+                    .await;
+
+                if call.outcome != ToolCallOutcome::Executed {
+                    return not_completed(&node.id, StepStatus::Failed, call.error);
+                }
+                let output = match call.output {
+                    Some(Value::String(s)) => s,
+                    Some(other) => other.to_string(),
+                    None => String::new(),
+                };
+                let mut context = ContextPacket::new(&output);
+                context.importance = input_confidence;
+                completed(&node.id, output, input_confidence, context)
+            }
+            NodeKind::Gate { capabilities } => {
+                let capsule = self
+                    .executor
+                    .gate()
+                    .execute_gate(
+                        Uuid::new_v4(),
+                        &record.input,
+                        &predecessor_outputs,
+                        None,
+                        input_confidence,
+                        capabilities,
+                    )
+                    .await;
+                let mut context = ContextPacket::new(&format!(
+                    "{} {} {}",
+                    capsule.outcome, capsule.reason_code, capsule.witness_receipt
+                ));
+                context.importance = input_confidence;
+                completed(&node.id, capsule.outcome, input_confidence, context)
+            }
+            NodeKind::Escalate { reason } => {
+                let reason = render(reason, &record.input, &record.steps);
+                if let Some(store) = &self.executor.audit_store {
+                    if let Err(e) = store
+                        .log(
+                            tenant_id,
+                            vex_core::audit::AuditEventType::Escalation,
+                            vex_core::audit::ActorType::System("workflow".to_string()),
+                            None,
+                            serde_json::json!({
+                                "run_id": record.run_id,
+                                "workflow": record.workflow_name,
+                                "node": node.id,
+                                "reason": reason,
+                            }),
+                            self.executor.identity.as_ref().map(|id| id.as_ref()),
+                            None,
+                            None,
+                        )
+                        .await
+                    {
+                        return not_completed(&node.id, StepStatus::Failed, Some(e.to_string()));
+                    }
+                }
+                let context = ContextPacket::new(&reason);
+                completed(&node.id, reason, input_confidence, context)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::ExecutorConfig;
+    use crate::gate::GenericGateMock;
+    use async_trait::async_trait;
+    use vex_llm::{LlmError, LlmRequest, LlmResponse};
+    use vex_persist::backend::MemoryBackend;
+
+    /// Echoes the prompt; fails prompts containing "boom" while `fail` is set
+    #[derive(Debug, Default)]
+    struct EchoLlm {
+        fail: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait]
+    impl LlmProvider for EchoLlm {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn complete(&self, request: LlmRequest) -> Result<LlmResponse, LlmError> {
+            if request.prompt.contains("boom")
+                && self.fail.load(std::sync::atomic::Ordering::SeqCst)
+            {
+                return Err(LlmError::ConnectionFailed("provider down".to_string()));
+            }
+            Ok(LlmResponse {
+                content: format!("echo: {}", request.prompt),
+                model: "echo".to_string(),
+                tokens_used: None,
+                latency_ms: 0,
+                trace_root: None,
+                tool_calls: Vec::new(),
+                provider: None,
+                cached: false,
+            })
+        }
+    }
+
+    fn engine(llm: Arc<EchoLlm>) -> WorkflowEngine<EchoLlm> {
+        let config = ExecutorConfig {
+            enable_adversarial: false,
+            ..Default::default()
+        };
+        WorkflowEngine::new(AgentExecutor::new(llm, config, Arc::new(GenericGateMock)))
+    }
+
+    #[test]
+    fn test_workflow_validation() {
+        let cyclic = Workflow::new("cyclic")
+            .with_node(WorkflowNode::agent("a", "r"))
+            .with_node(WorkflowNode::agent("b", "r"))
+            .with_edge(WorkflowEdge::new("a", "b"))
+            .with_edge(WorkflowEdge::new("b", "a"));
+        assert!(cyclic.validate().is_err());
+
+        let dangling = Workflow::new("dangling")
+            .with_node(WorkflowNode::agent("a", "r"))
+            .with_edge(WorkflowEdge::new("a", "missing"));
+        assert!(dangling.validate().is_err());
+
+        let parsed: Workflow = serde_json::from_value(serde_json::json!({
+            "name": "triage",
+            "nodes": [
+                {"id": "assess", "type": "agent", "role": "assessor"},
+                {"id": "human", "type": "escalate", "reason": "{{node:assess}}"}
+            ],
+            "edges": [{"from": "assess", "to": "human", "when": {"confidence_below": 0.6}}]
+        }))
+        .unwrap();
+        assert_eq!(parsed.validate().unwrap(), vec!["assess", "human"]);
+        assert_eq!(parsed.edges[0].when, EdgeCondition::ConfidenceBelow(0.6));
+    }
+
+    #[tokio::test]
+    async fn test_workflow_conditional_edges() {
+        // Non-adversarial execution reports confidence 0.5, so only the escalation branch runs
+        let workflow = Workflow::new("triage")
+            .with_node(WorkflowNode::agent("assess", "You assess claims."))
+            .with_node(WorkflowNode::gate("review"))
+            .with_node(WorkflowNode::agent("approve", "You approve claims."))
+            .with_node(WorkflowNode::escalate(
+                "human",
+                "Low confidence: {{node:assess}}",
+            ))
+            .with_edge(
+                WorkflowEdge::new("assess", "review").when(EdgeCondition::ConfidenceAtLeast(0.6)),
+            )
+            .with_edge(
+                WorkflowEdge::new("review", "approve")
+                    .when(EdgeCondition::OutputEquals("ALLOW".to_string())),
+            )
+            .with_edge(
+                WorkflowEdge::new("assess", "human").when(EdgeCondition::ConfidenceBelow(0.6)),
+            );
+
+        let result = engine(Arc::new(EchoLlm::default()))
+            .run("test-tenant", &workflow, "Claim #42")
+            .await
+            .unwrap();
+
+        assert_eq!(result.status, WorkflowStatus::Escalated);
+        assert_eq!(result.steps["review"].status, StepStatus::Skipped);
+        assert_eq!(result.steps["approve"].status, StepStatus::Skipped);
+        assert_eq!(
+            result.output("human"),
+            Some("Low confidence: echo: Claim #42")
+        );
+
+        let expected = MerkleTree::from_leaves(vec![
+            (
+                "assess".to_string(),
+                result.steps["assess"]
+                    .context
+                    .as_ref()
+                    .unwrap()
+                    .hash
+                    .clone(),
+            ),
+            (
+                "human".to_string(),
+                result.steps["human"].context.as_ref().unwrap().hash.clone(),
+            ),
+        ]);
+        assert_eq!(&result.merkle_root, expected.root_hash().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_workflow_input_cannot_inject_placeholders() {
+        let workflow = Workflow::new("triage")
+            .with_node(WorkflowNode::agent("assess", "You assess claims."))
+            .with_node(WorkflowNode::escalate("human", "Claim: {{input}}"))
+            .with_edge(WorkflowEdge::new("assess", "human"));
+
+        let result = engine(Arc::new(EchoLlm::default()))
+            .run("test-tenant", &workflow, "{{node:assess}}")
+            .await
+            .unwrap();
+
+        assert_eq!(result.output("assess"), Some("echo: {{node:assess}}"));
+        assert_eq!(result.output("human"), Some("Claim: {{node:assess}}"));
+    }
+
+    #[tokio::test]
+    async fn test_workflow_resume_after_failure() {
+        let backend: Arc<dyn StorageBackend> = Arc::new(MemoryBackend::new());
+        let store = Arc::new(WorkflowStore::new(backend));
+        let llm = Arc::new(EchoLlm::default());
+        llm.fail.store(true, std::sync::atomic::Ordering::SeqCst);
+        let engine = engine(llm.clone()).with_store(store.clone());
+
+        let workflow = Workflow::new("pipeline")
+            .with_node(WorkflowNode::agent("research", "researcher"))
+            .with_node(WorkflowNode::agent("write", "writer").with_prompt("boom {{node:research}}"))
+            .with_edge(WorkflowEdge::new("research", "write"));
+
+        let err = engine
+            .run("test-tenant", &workflow, "topic")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WorkflowError::Node { ref node, .. } if node == "write"));
+
+        let pending = store.list_resumable("test-tenant").await.unwrap();
+        assert_eq!(pending.len(), 1);
+        let run_id = pending[0].run_id;
+        let research = pending[0].steps["research"].clone();
+
+        llm.fail.store(false, std::sync::atomic::Ordering::SeqCst);
+        let result = engine.resume("test-tenant", run_id).await.unwrap();
+
+        assert_eq!(result.run_id, run_id);
+        assert_eq!(result.status, WorkflowStatus::Completed);
+        // The completed step is reused, not re-executed
+        assert_eq!(
+            result.steps["research"].context.as_ref().unwrap().id,
+            research.context.unwrap().id
+        );
+        assert_eq!(result.output("write"), Some("echo: boom echo: topic"));
+        assert!(store
+            .list_resumable("test-tenant")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -42,6 +42,9 @@ impl LlmProvider for MockLlm {
                 tokens_used: Some(10),
                 latency_ms: 10,
                 trace_root: None,
+                tool_calls: Vec::new(),
+                provider: None,
+                cached: false,
             });
         }
         Ok(LlmResponse {
@@ -50,6 +53,9 @@ impl LlmProvider for MockLlm {
             tokens_used: Some(10),
             latency_ms: 10,
             trace_root: None,
+            tool_calls: Vec::new(),
+            provider: None,
+            cached: false,
         })
     }
 }
@@ -101,7 +107,7 @@ async fn test_consolidation_flow() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let gate = Arc::new(vex_runtime::gate::GenericGateMock);
-    let orchestrator = Orchestrator::new(llm, config, Some(store.clone()), gate);
+    let orchestrator = Orchestrator::new(llm, config, Some(store.clone()), gate).unwrap();
 
     // 3. Fill Memory manually (> 50)
     for _i in 0..75 {
@@ -28,6 +28,9 @@ impl LlmProvider for MockLlm {
             tokens_used: None,
             latency_ms: 0,
             trace_root: None,
+            tool_calls: Vec::new(),
+            provider: None,
+            cached: false,
         })
     }
 }
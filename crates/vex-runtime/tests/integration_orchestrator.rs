@@ -45,6 +45,7 @@ async fn test_orchestrator_chora_gate_flow() {
             None, // No evolution store for now
             gate.clone() as Arc<dyn Gate>,
         )
+        .unwrap()
         .with_identity(identity.clone(), audit_store),
     );
 
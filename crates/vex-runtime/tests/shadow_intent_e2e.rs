@@ -51,6 +51,7 @@ async fn test_shadow_intent_high_assurance_e2e() {
             None,
             gate.clone() as Arc<dyn Gate>,
         )
+        .unwrap()
         .with_identity(identity.clone(), audit_store)
         .with_verifier(prover.clone()), // Executor needs the verifier for the "AEM Trap"
     );
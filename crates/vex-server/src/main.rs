@@ -8,7 +8,7 @@ use anyhow::Result;
 use axum::middleware;
 use std::sync::Arc;
 use tower_http::compression::CompressionLayer;
-use vex_api::jobs::agent::{AgentExecutionJob, AgentJobPayload};
+use vex_api::jobs::agent::{AgentExecutionJob, AgentJobPayload, AgentResumeJob};
 use vex_api::middleware::{
     auth_middleware, body_limit_layer, cors_layer, rate_limit_middleware, request_id_middleware,
     security_headers_middleware, timeout_layer, tracing_middleware,
@@ -17,8 +17,8 @@ use vex_api::routes::api_router;
 use vex_api::state::AppState;
 use vex_api::ServerConfig;
 use vex_llm::{
-    CachedProvider, DeepSeekProvider, LlmProvider, Metrics, MockProvider, OpenAIProvider,
-    ResilientProvider,
+    AnthropicProvider, CachedProvider, DeepSeekProvider, LlmCacheBackend, LlmProvider, Metrics,
+    MockProvider, OpenAIProvider, PersistentLlmCache, ResilientProvider,
 };
 use vex_queue::{QueueBackend, WorkerConfig, WorkerPool};
 
@@ -121,22 +121,37 @@ async fn main() -> Result<()> {
     // Use dynamic dispatch for the worker pool backend
     let worker_pool = WorkerPool::new_with_arc(queue_backend, WorkerConfig::default());
 
+    // LLM response cache shared by every replica on this database (no idle
+    // expiry: the persistent cache only honors the ttl)
+    let llm_cache: Arc<dyn LlmCacheBackend> = Arc::new(PersistentLlmCache::new(
+        db.clone(),
+        vex_llm::LlmCacheConfig {
+            tti: None,
+            ..Default::default()
+        },
+    ));
+
     // REAL Intelligence Layer: Bypass the broken router!
     let llm: Arc<dyn LlmProvider> = if let Ok(key) = std::env::var("GROQ_API_KEY") {
         tracing::info!("Initializing Real Groq Provider (Fast+Free)");
         let base = GroqProvider::new(&key, "llama-3.3-70b-versatile");
         let resilient = ResilientProvider::new(base, vex_llm::LlmCircuitConfig::conservative());
-        Arc::new(CachedProvider::wrap(resilient))
+        Arc::new(CachedProvider::with_backend(resilient, llm_cache.clone()))
     } else if let Ok(key) = std::env::var("DEEPSEEK_API_KEY") {
         tracing::info!("Initializing Real DeepSeek Provider");
         let base = DeepSeekProvider::chat(&key);
         let resilient = ResilientProvider::new(base, vex_llm::LlmCircuitConfig::conservative());
-        Arc::new(CachedProvider::wrap(resilient))
+        Arc::new(CachedProvider::with_backend(resilient, llm_cache.clone()))
     } else if let Ok(key) = std::env::var("OPENAI_API_KEY") {
         tracing::info!("Initializing Real OpenAI Provider (GPT-4)");
         let base = OpenAIProvider::gpt4(&key);
         let resilient = ResilientProvider::new(base, vex_llm::LlmCircuitConfig::conservative());
-        Arc::new(CachedProvider::wrap(resilient))
+        Arc::new(CachedProvider::with_backend(resilient, llm_cache.clone()))
+    } else if let Ok(key) = std::env::var("ANTHROPIC_API_KEY") {
+        tracing::info!("Initializing Real Anthropic Provider (Claude Sonnet)");
+        let base = AnthropicProvider::sonnet(&key);
+        let resilient = ResilientProvider::new(base, vex_llm::LlmCircuitConfig::conservative());
+        Arc::new(CachedProvider::with_backend(resilient, llm_cache.clone()))
     } else {
         tracing::warn!("No LLM API keys found. Falling back to Mock Provider.");
         Arc::new(MockProvider::smart())
@@ -144,6 +159,7 @@ async fn main() -> Result<()> {
 
     // Create shared result store
     let result_store = vex_api::jobs::new_result_store();
+    let job_streams = vex_api::jobs::JobStreams::new();
 
     // Create FileAnchor for audit logging (Legacy/Fallback)
     let _file_anchor: Arc<dyn vex_anchor::AnchorBackend> =
@@ -219,9 +235,12 @@ async fn main() -> Result<()> {
         vex_runtime::OrchestratorConfig::default(),
         Some(evolution_store.clone()),
         gate.clone(),
+    )?;
+    let orchestrator = Arc::new(
+        base_orchestrator
+            .with_identity(identity.clone(), audit_store.clone())
+            .with_suspensions(Arc::new(vex_runtime::SuspensionStore::new(db.clone()))),
     );
-    let orchestrator =
-        Arc::new(base_orchestrator.with_identity(identity.clone(), audit_store.clone()));
 
     tracing::info!("⚓ Hardware Identity Active: {}", identity.agent_id);
     tracing::info!("🛡️ Cognitive Orchestrator initialized (Unified Signing)");
@@ -233,6 +252,7 @@ async fn main() -> Result<()> {
     let evolution_store_clone = evolution_store.clone();
     let gate_clone = gate.clone();
     let orchestrator_clone = orchestrator.clone();
+    let job_streams_clone = job_streams.clone();
     worker_pool.register_job_factory("agent_execution", move |payload| {
         let job_payload: AgentJobPayload =
             serde_json::from_value(payload).unwrap_or_else(|_| AgentJobPayload {
@@ -244,20 +264,35 @@ async fn main() -> Result<()> {
                 max_debate_rounds: 3,
                 tenant_id: None,
                 capabilities: vec![],
+                stream_id: None,
             });
         let job_id = uuid::Uuid::new_v4();
         let db_concrete = db_for_factory.clone();
         let evo_store = evolution_store_clone.clone();
 
-        Box::new(AgentExecutionJob::new(
-            job_id,
-            job_payload,
-            llm_clone.clone(),
+        Box::new(
+            AgentExecutionJob::new(
+                job_id,
+                job_payload,
+                llm_clone.clone(),
+                result_store_clone.clone(),
+                db_concrete as Arc<dyn vex_persist::StorageBackend>,
+                None, // Anchor handled by AuditStore now
+                evo_store,
+                gate_clone.clone(),
+                orchestrator_clone.clone(),
+            )
+            .with_streams(job_streams_clone.clone()),
+        )
+    });
+
+    let result_store_clone = result_store.clone();
+    let orchestrator_clone = orchestrator.clone();
+    worker_pool.register_job_factory("agent_resume", move |payload| {
+        Box::new(AgentResumeJob::new(
+            uuid::Uuid::new_v4(),
+            payload,
             result_store_clone.clone(),
-            db_concrete as Arc<dyn vex_persist::StorageBackend>,
-            None, // Anchor handled by AuditStore now
-            evo_store,
-            gate_clone.clone(),
             orchestrator_clone.clone(),
         ))
     });
@@ -277,7 +312,8 @@ async fn main() -> Result<()> {
         gate.clone(),
         orchestrator.clone(),
         bridge,
-    );
+    )
+    .with_job_streams(job_streams);
 
     let mut app = api_router(app_state.clone());
 
@@ -370,6 +406,10 @@ impl vex_llm::LlmProvider for GroqProvider {
         "groq"
     }
 
+    fn model(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn is_available(&self) -> bool {
         // Simple connectivity check
         self.client
@@ -392,10 +432,11 @@ impl vex_llm::LlmProvider for GroqProvider {
             .bearer_auth(&self.api_key)
             .json(&serde_json::json!({
                 "model": self.model,
-                "messages": [
-                    {"role": "system", "content": request.system},
-                    {"role": "user", "content": request.prompt}
-                ],
+                "messages": request
+                    .conversation()
+                    .iter()
+                    .map(vex_llm::openai_compat::ChatMessage::from)
+                    .collect::<Vec<_>>(),
                 "temperature": request.temperature,
             }))
             .send()
@@ -425,6 +466,9 @@ impl vex_llm::LlmProvider for GroqProvider {
             tokens_used: tokens,
             latency_ms: start.elapsed().as_millis() as u64,
             trace_root: None,
+            tool_calls: Vec::new(),
+            provider: None,
+            cached: false,
         })
     }
 }
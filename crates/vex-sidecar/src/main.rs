@@ -13,7 +13,7 @@ use tracing::{error, info};
 use vex_core::segment::{
     AuthorityData, Capsule, CryptoData, IdentityData, IntentData, SchemaValue, WitnessData,
 };
-use vex_core::VEP_MAGIC;
+use vex_core::{VepBuilder, VEP_MAGIC, VEP_VERSION_V3};
 use vex_hardware::api::AgentIdentity;
 
 #[derive(Clone)]
@@ -62,7 +62,9 @@ async fn proxy_handler(
 
     if is_vep {
         info!("Request is already VEP enveloped. Forwarding to target...");
-        return forward_request(&state.target_url, headers, body_bytes).await;
+        return forward_request(&state.target_url, headers, body_bytes)
+            .await
+            .into_response();
     }
 
     info!(
@@ -161,13 +163,30 @@ async fn proxy_handler(
         &capsule.capsule_root[..8]
     );
 
-    // 8. Build VEP binary (Legacy TLV for now, but with hardened capsule)
-    // In a real implementation, we would use the VEP builder.
-    let capsule_json = serde_json::to_vec(&capsule).unwrap();
+    // 8. Build VEP binary (header AID is the hardware public key)
+    let header_aid: [u8; 32] = match hex::decode(state.identity.public_key_hex())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+    {
+        Some(aid) => aid,
+        None => {
+            error!("Hardware public key is not a 32-byte key");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Invalid agent identity").into_response();
+        }
+    };
+    let vep_binary = match VepBuilder::from_capsule_as(&capsule, header_aid, VEP_VERSION_V3)
+        .and_then(|builder| builder.build())
+    {
+        Ok(binary) => binary,
+        Err(e) => {
+            error!("VEP encoding failed: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "VEP encoding failed").into_response();
+        }
+    };
 
-    // For now, we'll just return the capsule JSON as the body to the target
-    // in this simulation path.
-    forward_request(&state.target_url, headers, Bytes::from(capsule_json)).await
+    forward_request(&state.target_url, headers, Bytes::from(vep_binary))
+        .await
+        .into_response()
 }
 
 async fn forward_request(target_url: &str, headers: HeaderMap, body: Bytes) -> impl IntoResponse {
@@ -110,6 +110,9 @@ async fn main() {
             tokens_used: None,
             latency_ms: 0,
             trace_root: None,
+            tool_calls: Vec::new(),
+            provider: None,
+            cached: false,
         }
     });
 
@@ -138,6 +141,9 @@ async fn main() {
             tokens_used: None,
             latency_ms: 0,
             trace_root: None,
+            tool_calls: Vec::new(),
+            provider: None,
+            cached: false,
         });
 
     println!("   ⚠️  Red Team Findings:");
@@ -157,6 +163,9 @@ async fn main() {
         tokens_used: None,
         latency_ms: 0,
         trace_root: None,
+        tool_calls: Vec::new(),
+        provider: None,
+        cached: false,
     });
 
     println!("   ✅ Blue Team Defense:");
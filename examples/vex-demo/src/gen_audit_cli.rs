@@ -49,6 +49,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let export = AuditExport {
         events: events.clone(),
         merkle_root: tree.root_hash().map(|h| h.to_string()),
+        hash_algorithm: tree.algorithm(),
+        key_history: Vec::new(),
         exported_at: Utc::now(),
         verified: true,
     };